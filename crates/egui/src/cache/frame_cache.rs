@@ -33,6 +33,11 @@ impl<Value, Computer> FrameCache<Value, Computer> {
         }
     }
 
+    /// The computer, e.g. to use what it loaded for other things than the cached values.
+    pub fn computer(&self) -> &Computer {
+        &self.computer
+    }
+
     /// Must be called once per frame to clear the cache.
     pub fn evict_cache(&mut self) {
         let current_generation = self.generation;
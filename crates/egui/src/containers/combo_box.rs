@@ -1,9 +1,14 @@
 use epaint::Shape;
 
 use crate::{
-    epaint, style::WidgetVisuals, vec2, Align2, Context, Id, InnerResponse, NumExt, Painter,
-    PopupCloseBehavior, Rect, Response, ScrollArea, Sense, Stroke, TextStyle, TextWrapMode, Ui,
-    UiBuilder, Vec2, WidgetInfo, WidgetText, WidgetType,
+    containers::filter_list::{
+        find_match, navigate, show_matches, ListAction, ListKeys, MatchCache,
+    },
+    epaint,
+    style::WidgetVisuals,
+    vec2, Align2, Context, Id, InnerResponse, NumExt, Painter, PopupCloseBehavior, Rect, Response,
+    ScrollArea, Sense, Stroke, TextEdit, TextStyle, TextWrapMode, Ui, UiBuilder, Vec2, WidgetInfo,
+    WidgetText, WidgetType,
 };
 
 #[allow(unused_imports)] // Documentation
@@ -204,13 +209,30 @@ impl ComboBox {
         ui: &mut Ui,
         menu_contents: impl FnOnce(&mut Ui) -> R,
     ) -> InnerResponse<Option<R>> {
-        self.show_ui_dyn(ui, Box::new(menu_contents))
+        self.show_ui_dyn(
+            ui,
+            Box::new(|ui, height| {
+                ScrollArea::vertical()
+                    .max_height(height)
+                    .show(ui, |ui| {
+                        // Often the button is very narrow, which means this popup
+                        // is also very narrow. Having wrapping on would therefore
+                        // result in labels that wrap very early.
+                        // Instead, we turn it off by default so that the labels
+                        // expand the width of the menu.
+                        ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                        menu_contents(ui)
+                    })
+                    .inner
+            }),
+        )
     }
 
+    /// `menu_contents` is given the maximum height of the menu.
     fn show_ui_dyn<'c, R>(
         self,
         ui: &mut Ui,
-        menu_contents: Box<dyn FnOnce(&mut Ui) -> R + 'c>,
+        menu_contents: Box<dyn FnOnce(&mut Ui, f32) -> R + 'c>,
     ) -> InnerResponse<Option<R>> {
         let Self {
             id_salt,
@@ -296,6 +318,122 @@ impl ComboBox {
         response
     }
 
+    /// Show a list of items with the given selected index, with a text field at the top of the menu
+    /// for filtering the items.
+    ///
+    /// Only the items that contain the filter text (ignoring case) are shown, with the matching part highlighted.
+    /// Use the arrow keys to move through them, Enter to pick one and Escape to close the menu.
+    /// Only the visible rows are laid out, so this works well for thousands of items.
+    ///
+    /// The items are shown as plain text, so any formatting of the [`WidgetText`] is lost.
+    ///
+    /// Unless [`Self::close_behavior`] is set, the menu is only closed by clicking outside of it.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// let fonts: Vec<String> = (0..500).map(|i| format!("Font {i}")).collect();
+    /// let mut selected = 0;
+    /// egui::ComboBox::from_label("Font").show_index_filtered(
+    ///     ui,
+    ///     &mut selected,
+    ///     fonts.len(),
+    ///     |i| &fonts[i]
+    /// );
+    /// # });
+    /// ```
+    pub fn show_index_filtered<Text: Into<WidgetText>>(
+        mut self,
+        ui: &mut Ui,
+        selected: &mut usize,
+        len: usize,
+        get: impl Fn(usize) -> Text,
+    ) -> Response {
+        let button_id = ui.make_persistent_id(self.id_salt);
+        let popup_id = Self::widget_to_popup_id(button_id);
+        let filter_id = popup_id.with("filter");
+        let edit_id = popup_id.with("filter_edit");
+
+        self.close_behavior
+            .get_or_insert(PopupCloseBehavior::CloseOnClickOutside);
+        let slf = self.selected_text(get(*selected));
+
+        let mut changed = false;
+
+        let mut response = slf
+            .show_ui_dyn(
+                ui,
+                Box::new(|ui, height| {
+                    let loaded = ui.data_mut(|d| d.get_temp::<FilterState>(filter_id));
+                    let is_new = loaded.is_none();
+                    let mut state = loaded.unwrap_or(FilterState {
+                        query: String::new(),
+                        cursor: Some(*selected),
+                        matches: MatchCache::default(),
+                    });
+
+                    let keys = if ui.memory(|mem| mem.has_focus(edit_id)) {
+                        ListKeys::consume(ui)
+                    } else {
+                        ListKeys::default()
+                    };
+
+                    let edit_response = ui.add(
+                        TextEdit::singleline(&mut state.query)
+                            .id(edit_id)
+                            .hint_text("Filter")
+                            .desired_width(f32::INFINITY),
+                    );
+                    if is_new {
+                        edit_response.request_focus();
+                    }
+
+                    let matches = state.matches.matches(&state.query, len, |index| {
+                        let text: WidgetText = get(index).into();
+                        find_match(text.text(), &state.query)
+                    });
+
+                    let mut picked = None;
+                    match navigate(keys, &mut state.cursor, matches) {
+                        Some(ListAction::Accept(index)) => picked = Some(index),
+                        Some(ListAction::Cancel) => ui.memory_mut(|mem| mem.close_popup()),
+                        None => {}
+                    }
+
+                    let list_height = (height - ui.min_rect().height()).at_least(0.0);
+                    let clicked = show_matches(
+                        ui,
+                        list_height,
+                        matches,
+                        state.cursor,
+                        keys.moved(),
+                        |index| {
+                            let text: WidgetText = get(index).into();
+                            text.text().to_owned()
+                        },
+                    );
+
+                    if let Some(index) = picked.or(clicked) {
+                        *selected = index;
+                        changed = true;
+                        ui.memory_mut(|mem| mem.close_popup());
+                    }
+
+                    ui.data_mut(|d| d.insert_temp(filter_id, state));
+                }),
+            )
+            .response;
+
+        if !Self::is_open(ui.ctx(), button_id) {
+            // Start over with an empty filter next time the menu is opened.
+            ui.data_mut(|d| d.remove::<FilterState>(filter_id));
+        }
+
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+
     /// Check if the [`ComboBox`] with the given id has its popup menu currently opened.
     pub fn is_open(ctx: &Context, id: Id) -> bool {
         ctx.memory(|m| m.is_popup_open(Self::widget_to_popup_id(id)))
@@ -307,12 +445,24 @@ impl ComboBox {
     }
 }
 
+/// What the user typed into the filter of [`ComboBox::show_index_filtered`].
+#[derive(Clone, Debug)]
+struct FilterState {
+    query: String,
+
+    /// The item that is picked with Enter.
+    cursor: Option<usize>,
+
+    /// The items that match the query.
+    matches: MatchCache,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn combo_box_dyn<'c, R>(
     ui: &mut Ui,
     button_id: Id,
     selected_text: WidgetText,
-    menu_contents: Box<dyn FnOnce(&mut Ui) -> R + 'c>,
+    menu_contents: Box<dyn FnOnce(&mut Ui, f32) -> R + 'c>,
     icon: Option<IconPainter>,
     wrap_mode: Option<TextWrapMode>,
     close_behavior: Option<PopupCloseBehavior>,
@@ -414,20 +564,7 @@ fn combo_box_dyn<'c, R>(
         &button_response,
         above_or_below,
         close_behavior,
-        |ui| {
-            ScrollArea::vertical()
-                .max_height(height)
-                .show(ui, |ui| {
-                    // Often the button is very narrow, which means this popup
-                    // is also very narrow. Having wrapping on would therefore
-                    // result in labels that wrap very early.
-                    // Instead, we turn it off by default so that the labels
-                    // expand the width of the menu.
-                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
-                    menu_contents(ui)
-                })
-                .inner
-        },
+        |ui| menu_contents(ui, height),
     );
 
     InnerResponse {
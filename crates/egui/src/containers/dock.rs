@@ -0,0 +1,732 @@
+//! Tabs in resizable split panes and floating windows, rearranged with drag-and-drop. See [`DockArea`].
+
+use std::hash::Hash;
+
+use crate::{
+    tabs::TabDragPayload, vec2, CursorIcon, DragAndDrop, Id, LayerId, Order, Pos2, Rect, Response,
+    Sense, Stroke, TabViewer, Tabs, Ui, UiBuilder, Window,
+};
+
+/// How a [`DockNode::Split`] divides its area.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SplitDirection {
+    /// The children are side by side.
+    Horizontal,
+
+    /// The children are on top of each other.
+    Vertical,
+}
+
+/// A node in the tree of a [`DockState`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum DockNode<Tab> {
+    /// A group of tabs, shown with [`Tabs`].
+    Leaf { id: Id, tabs: Vec<Tab> },
+
+    /// Two nodes next to each other.
+    Split {
+        direction: SplitDirection,
+
+        /// How much of the area goes to the first child, in 0-1.
+        fraction: f32,
+
+        children: Box<[DockNode<Tab>; 2]>,
+    },
+}
+
+impl<Tab> DockNode<Tab> {
+    fn leaf_tabs_mut(&mut self, leaf_id: Id) -> Option<&mut Vec<Tab>> {
+        match self {
+            Self::Leaf { id, tabs } => (*id == leaf_id).then_some(tabs),
+            Self::Split { children, .. } => {
+                let [first, second] = children.as_mut();
+                first
+                    .leaf_tabs_mut(leaf_id)
+                    .or_else(|| second.leaf_tabs_mut(leaf_id))
+            }
+        }
+    }
+
+    fn first_leaf_id(&self) -> Id {
+        match self {
+            Self::Leaf { id, .. } => *id,
+            Self::Split { children, .. } => children[0].first_leaf_id(),
+        }
+    }
+
+    fn for_each_tab<'a>(&'a self, f: &mut dyn FnMut(&'a Tab)) {
+        match self {
+            Self::Leaf { tabs, .. } => tabs.iter().for_each(f),
+            Self::Split { children, .. } => {
+                children[0].for_each_tab(f);
+                children[1].for_each_tab(f);
+            }
+        }
+    }
+
+    /// Put `tabs` on the given `side` of the leaf with id `leaf_id`, in a new leaf with id `new_id`.
+    ///
+    /// Returns the tabs back if the leaf wasn't found.
+    fn insert(
+        &mut self,
+        leaf_id: Id,
+        side: DockSide,
+        tabs: Vec<Tab>,
+        new_id: Id,
+    ) -> Option<Vec<Tab>> {
+        match self {
+            Self::Leaf {
+                id,
+                tabs: leaf_tabs,
+            } if *id == leaf_id => {
+                let (direction, new_first) = match side {
+                    DockSide::Center => {
+                        leaf_tabs.extend(tabs);
+                        return None;
+                    }
+                    DockSide::Left => (SplitDirection::Horizontal, true),
+                    DockSide::Right => (SplitDirection::Horizontal, false),
+                    DockSide::Top => (SplitDirection::Vertical, true),
+                    DockSide::Bottom => (SplitDirection::Vertical, false),
+                };
+                let new_leaf = Self::Leaf { id: new_id, tabs };
+                let old_leaf = std::mem::replace(
+                    self,
+                    Self::Leaf {
+                        id: Id::NULL,
+                        tabs: vec![],
+                    },
+                );
+                let children = if new_first {
+                    [new_leaf, old_leaf]
+                } else {
+                    [old_leaf, new_leaf]
+                };
+                *self = Self::Split {
+                    direction,
+                    fraction: 0.5,
+                    children: Box::new(children),
+                };
+                None
+            }
+            Self::Leaf { .. } => Some(tabs),
+            Self::Split { children, .. } => {
+                let [first, second] = children.as_mut();
+                let tabs = first.insert(leaf_id, side, tabs, new_id)?;
+                second.insert(leaf_id, side, tabs, new_id)
+            }
+        }
+    }
+
+    /// Remove leaves without tabs, and splits with only one child left.
+    fn without_empty_leaves(self) -> Option<Self> {
+        match self {
+            Self::Leaf { id, tabs } => (!tabs.is_empty()).then_some(Self::Leaf { id, tabs }),
+            Self::Split {
+                direction,
+                fraction,
+                children,
+            } => {
+                let [first, second] = *children;
+                match (first.without_empty_leaves(), second.without_empty_leaves()) {
+                    (Some(first), Some(second)) => Some(Self::Split {
+                        direction,
+                        fraction,
+                        children: Box::new([first, second]),
+                    }),
+                    (Some(node), None) | (None, Some(node)) => Some(node),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+}
+
+/// Where to put tabs that are dropped on a group of tabs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockSide {
+    /// Add them to the group.
+    Center,
+
+    /// Split the group, with the new tabs to the left.
+    Left,
+
+    /// Split the group, with the new tabs to the right.
+    Right,
+
+    /// Split the group, with the new tabs on top.
+    Top,
+
+    /// Split the group, with the new tabs at the bottom.
+    Bottom,
+}
+
+impl DockSide {
+    /// Which side of `rect` is `pos` closest to, or the center if it is far from all of them?
+    ///
+    /// Over the tab bar at the top of `rect` it is always the center,
+    /// so that tabs dropped there are added to the group.
+    fn from_pos(rect: Rect, tab_bar_height: f32, pos: Pos2) -> Self {
+        if pos.y < rect.top() + tab_bar_height {
+            return Self::Center;
+        }
+
+        let rel = (pos - rect.min) / rect.size();
+        let sides = [
+            (rel.x, Self::Left),
+            (1.0 - rel.x, Self::Right),
+            (rel.y, Self::Top),
+            (1.0 - rel.y, Self::Bottom),
+        ];
+        let (distance, side) = sides
+            .into_iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap_or((1.0, Self::Center));
+        if distance < 0.25 {
+            side
+        } else {
+            Self::Center
+        }
+    }
+
+    /// Where the new tabs would end up.
+    fn preview_rect(self, rect: Rect) -> Rect {
+        let half = 0.5 * rect.size();
+        match self {
+            Self::Center => rect,
+            Self::Left => Rect::from_min_size(rect.min, vec2(half.x, rect.height())),
+            Self::Right => Rect::from_min_max(rect.min + vec2(half.x, 0.0), rect.max),
+            Self::Top => Rect::from_min_size(rect.min, vec2(rect.width(), half.y)),
+            Self::Bottom => Rect::from_min_max(rect.min + vec2(0.0, half.y), rect.max),
+        }
+    }
+}
+
+/// A group of tabs in its own [`Window`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FloatingTabs<Tab> {
+    pub id: Id,
+    pub tabs: Vec<Tab>,
+
+    /// Where the window was last.
+    pub pos: Pos2,
+}
+
+/// The layout of a [`DockArea`]: a tree of split panes with tabs, plus floating windows.
+///
+/// Keep this in your app state. With the `serde` feature (enabled by `persistence`)
+/// it can be saved, so that the layout survives restarts.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct DockState<Tab> {
+    root: Option<DockNode<Tab>>,
+    floating: Vec<FloatingTabs<Tab>>,
+    next_id: u64,
+}
+
+impl<Tab> Default for DockState<Tab> {
+    fn default() -> Self {
+        Self {
+            root: None,
+            floating: vec![],
+            next_id: 0,
+        }
+    }
+}
+
+impl<Tab> DockState<Tab> {
+    /// All the tabs in one group.
+    pub fn new(tabs: Vec<Tab>) -> Self {
+        let mut state = Self::default();
+        if !tabs.is_empty() {
+            let id = state.new_id();
+            state.root = Some(DockNode::Leaf { id, tabs });
+        }
+        state
+    }
+
+    fn new_id(&mut self) -> Id {
+        self.next_id += 1;
+        Id::new(("dock_node", self.next_id))
+    }
+
+    /// The docked tabs, if any.
+    pub fn root(&self) -> Option<&DockNode<Tab>> {
+        self.root.as_ref()
+    }
+
+    pub fn root_mut(&mut self) -> Option<&mut DockNode<Tab>> {
+        self.root.as_mut()
+    }
+
+    /// The tabs in floating windows.
+    pub fn floating(&self) -> &[FloatingTabs<Tab>] {
+        &self.floating
+    }
+
+    /// All tabs, docked and floating.
+    pub fn tabs(&self) -> Vec<&Tab> {
+        let mut tabs = vec![];
+        if let Some(root) = &self.root {
+            root.for_each_tab(&mut |tab| tabs.push(tab));
+        }
+        for floating in &self.floating {
+            tabs.extend(&floating.tabs);
+        }
+        tabs
+    }
+
+    /// Add a tab to the first group of docked tabs.
+    pub fn push_tab(&mut self, tab: Tab) {
+        match &mut self.root {
+            Some(root) => {
+                let leaf_id = root.first_leaf_id();
+                if let Some(tabs) = root.leaf_tabs_mut(leaf_id) {
+                    tabs.push(tab);
+                }
+            }
+            None => *self = Self::new(vec![tab]),
+        }
+    }
+
+    /// Put `tabs` next to, or in, the group of tabs with id `leaf_id`.
+    ///
+    /// Returns the id of the group the tabs ended up in,
+    /// or `None` if there is no such group.
+    pub fn split(&mut self, leaf_id: Id, side: DockSide, tabs: Vec<Tab>) -> Option<Id> {
+        let new_id = self.new_id();
+        let root = self.root.as_mut()?;
+        if root.insert(leaf_id, side, tabs, new_id).is_some() {
+            None
+        } else if side == DockSide::Center {
+            Some(leaf_id)
+        } else {
+            Some(new_id)
+        }
+    }
+
+    /// Put `tabs` in a new floating window.
+    pub fn add_floating(&mut self, tabs: Vec<Tab>, pos: Pos2) -> Id {
+        let id = self.new_id();
+        self.floating.push(FloatingTabs { id, tabs, pos });
+        id
+    }
+
+    fn take_tabs(&mut self, source: DragSource, index: Option<usize>) -> Vec<Tab> {
+        let tabs = match source {
+            DragSource::Leaf(leaf_id) => self
+                .root
+                .as_mut()
+                .and_then(|root| root.leaf_tabs_mut(leaf_id)),
+            DragSource::Floating(i) => self.floating.get_mut(i).map(|floating| &mut floating.tabs),
+        };
+        match (tabs, index) {
+            (Some(tabs), Some(index)) if index < tabs.len() => vec![tabs.remove(index)],
+            (Some(tabs), None) => std::mem::take(tabs),
+            _ => vec![],
+        }
+    }
+
+    fn remove_empty(&mut self) {
+        self.root = self.root.take().and_then(DockNode::without_empty_leaves);
+        self.floating.retain(|floating| !floating.tabs.is_empty());
+    }
+}
+
+/// Where dragged tabs come from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DragSource {
+    Leaf(Id),
+    Floating(usize),
+}
+
+/// Where dragged tabs can be dropped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DropTarget {
+    Leaf(Id, DockSide),
+    Floating(usize),
+
+    /// There are no docked tabs yet.
+    Root,
+}
+
+/// A group of tabs that was shown this frame.
+struct ShownGroup {
+    source: DragSource,
+
+    /// The id of the [`Tabs`] container.
+    tabs_id: Id,
+    rect: Rect,
+}
+
+/// IDE-style docking of tabs.
+///
+/// Each group of tabs is shown with [`Tabs`].
+/// Drag a tab onto another group to move it there, or near the edge of a group to split it.
+/// Drag a tab outside of the dock area to put it in a floating window,
+/// and drag the window back by its title bar to dock it again.
+///
+/// The layout is stored in a [`DockState`], and the tabs are shown by a [`TabViewer`].
+///
+/// ```
+/// struct MyTabs;
+///
+/// impl egui::TabViewer for MyTabs {
+///     type Tab = String;
+///
+///     fn id(&mut self, tab: &mut String) -> egui::Id {
+///         egui::Id::new(tab.as_str())
+///     }
+///
+///     fn title(&mut self, tab: &mut String) -> egui::WidgetText {
+///         tab.as_str().into()
+///     }
+///
+///     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut String) {
+///         ui.label(format!("Contents of {tab}"));
+///     }
+/// }
+///
+/// # egui::__run_test_ui(|ui| {
+/// let mut dock_state = egui::DockState::new(vec!["Files".to_owned(), "Editor".to_owned()]);
+/// egui::DockArea::new("my_dock").show(ui, &mut dock_state, &mut MyTabs);
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+#[derive(Clone, Debug)]
+pub struct DockArea {
+    id_salt: Id,
+}
+
+impl DockArea {
+    pub fn new(id_salt: impl Hash) -> Self {
+        Self {
+            id_salt: Id::new(id_salt),
+        }
+    }
+
+    /// Fills all available space.
+    pub fn show<V: TabViewer>(
+        self,
+        ui: &mut Ui,
+        state: &mut DockState<V::Tab>,
+        viewer: &mut V,
+    ) -> Response {
+        let id = ui.make_persistent_id(self.id_salt);
+        let dock_rect = ui.available_rect_before_wrap();
+
+        let mut groups = vec![];
+        if let Some(root) = &mut state.root {
+            show_node(ui, id.with("root"), root, dock_rect, viewer, &mut groups);
+        }
+
+        let mut window_drag = None;
+        for (i, floating) in state.floating.iter_mut().enumerate() {
+            let title = floating
+                .tabs
+                .first_mut()
+                .map(|tab| viewer.title(tab).text().to_owned())
+                .unwrap_or_default();
+            let window = Window::new(title)
+                .id(id.with(floating.id))
+                .default_pos(floating.pos)
+                .default_size(vec2(300.0, 200.0))
+                .show(ui.ctx(), |ui| {
+                    let tabs = Tabs::new(floating.id);
+                    groups.push(ShownGroup {
+                        source: DragSource::Floating(i),
+                        tabs_id: tabs.id(ui),
+                        rect: ui.max_rect(),
+                    });
+                    tabs.show(ui, &mut floating.tabs, viewer);
+                });
+            if let Some(window) = window {
+                let response = window.response;
+                floating.pos = response.rect.min;
+                if let Some(group) = groups.last_mut() {
+                    group.rect = response.rect;
+                }
+                if response.dragged() || response.drag_stopped() {
+                    window_drag = Some((i, response.drag_stopped()));
+                }
+            }
+        }
+
+        let ctx = ui.ctx().clone();
+
+        // What is being dragged:
+        let payload = DragAndDrop::payload::<TabDragPayload>(&ctx);
+        let dragged = if let Some(payload) = &payload {
+            groups
+                .iter()
+                .find(|group| group.tabs_id == payload.tabs_id)
+                .map(|group| (group.source, Some(payload.index), false))
+        } else {
+            window_drag.map(|(i, released)| (DragSource::Floating(i), None, released))
+        };
+
+        if let (Some((source, index, window_released)), Some(pointer)) =
+            (dragged, ctx.pointer_latest_pos())
+        {
+            let bar_height = ui.spacing().interact_size.y;
+            let over_own_tab_bar = groups.iter().any(|group| {
+                group.source == source
+                    && index.is_some()
+                    && Rect::from_min_size(group.rect.min, vec2(group.rect.width(), bar_height))
+                        .contains(pointer)
+            });
+
+            // Floating windows are shown last and are on top, so check them first:
+            let mut target = None;
+            for group in groups.iter().rev() {
+                if !group.rect.contains(pointer) {
+                    continue;
+                }
+                let is_source = group.source == source;
+                match group.source {
+                    DragSource::Floating(i) => {
+                        if is_source && index.is_none() {
+                            continue; // The window being dragged
+                        }
+                        if !is_source {
+                            target = Some((DropTarget::Floating(i), group.rect));
+                        }
+                    }
+                    DragSource::Leaf(leaf_id) => {
+                        let side = DockSide::from_pos(group.rect, bar_height, pointer);
+                        if !(is_source && (side == DockSide::Center || over_own_tab_bar)) {
+                            target = Some((
+                                DropTarget::Leaf(leaf_id, side),
+                                side.preview_rect(group.rect),
+                            ));
+                        }
+                    }
+                }
+                break;
+            }
+            if state.root.is_none() && dock_rect.contains(pointer) && target.is_none() {
+                target = Some((DropTarget::Root, dock_rect));
+            }
+
+            if let Some((_, preview_rect)) = target {
+                let painter =
+                    ctx.layer_painter(LayerId::new(Order::Foreground, id.with("preview")));
+                let selection = ui.visuals().selection;
+                painter.rect(
+                    preview_rect.shrink(2.0),
+                    ui.visuals().window_rounding,
+                    selection.bg_fill.gamma_multiply(0.5),
+                    selection.stroke,
+                );
+            }
+
+            let released =
+                window_released || (index.is_some() && ctx.input(|i| i.pointer.any_released()));
+            if released && !over_own_tab_bar {
+                if let Some((target, _)) = target {
+                    let tabs = state.take_tabs(source, index);
+                    match target {
+                        DropTarget::Leaf(leaf_id, side) => {
+                            state.split(leaf_id, side, tabs);
+                        }
+                        DropTarget::Floating(i) => state.floating[i].tabs.extend(tabs),
+                        DropTarget::Root => {
+                            *state = {
+                                let mut new_state = DockState::new(tabs);
+                                new_state.floating = std::mem::take(&mut state.floating);
+                                new_state.next_id = state.next_id;
+                                new_state
+                            }
+                        }
+                    }
+                } else if index.is_some()
+                    && !dock_rect.contains(pointer)
+                    && !groups.iter().any(|group| group.rect.contains(pointer))
+                {
+                    // Dropped outside: undock into a floating window.
+                    let tabs = state.take_tabs(source, index);
+                    state.add_floating(tabs, pointer - vec2(20.0, 10.0));
+                }
+                DragAndDrop::clear_payload(&ctx);
+                state.remove_empty();
+                ctx.request_repaint();
+            }
+        }
+
+        state.remove_empty();
+
+        ui.allocate_rect(dock_rect, Sense::hover())
+    }
+}
+
+/// Show a node of the dock tree in `rect`.
+///
+/// `path_id` identifies the position of the node in the tree.
+fn show_node<V: TabViewer>(
+    ui: &mut Ui,
+    path_id: Id,
+    node: &mut DockNode<V::Tab>,
+    rect: Rect,
+    viewer: &mut V,
+    groups: &mut Vec<ShownGroup>,
+) {
+    match node {
+        DockNode::Leaf { id, tabs } => {
+            let mut leaf_ui = ui.new_child(UiBuilder::new().id_salt(*id).max_rect(rect));
+            leaf_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+            let tabs_container = Tabs::new(*id);
+            groups.push(ShownGroup {
+                source: DragSource::Leaf(*id),
+                tabs_id: tabs_container.id(&leaf_ui),
+                rect,
+            });
+            tabs_container.show(&mut leaf_ui, tabs, viewer);
+        }
+
+        DockNode::Split {
+            direction,
+            fraction,
+            children,
+        } => {
+            let gap = ui.spacing().item_spacing;
+            let (first_rect, second_rect, separator) = match direction {
+                SplitDirection::Horizontal => {
+                    let width = (rect.width() - gap.x).max(0.0);
+                    let x = rect.left() + *fraction * width;
+                    (
+                        Rect::from_min_max(rect.min, Pos2::new(x, rect.bottom())),
+                        Rect::from_min_max(Pos2::new(x + gap.x, rect.top()), rect.max),
+                        x + 0.5 * gap.x,
+                    )
+                }
+                SplitDirection::Vertical => {
+                    let height = (rect.height() - gap.y).max(0.0);
+                    let y = rect.top() + *fraction * height;
+                    (
+                        Rect::from_min_max(rect.min, Pos2::new(rect.right(), y)),
+                        Rect::from_min_max(Pos2::new(rect.left(), y + gap.y), rect.max),
+                        y + 0.5 * gap.y,
+                    )
+                }
+            };
+
+            let [first, second] = children.as_mut();
+            show_node(ui, path_id.with(0), first, first_rect, viewer, groups);
+            show_node(ui, path_id.with(1), second, second_rect, viewer, groups);
+
+            // Resize on top of the contents, like panels do:
+            let grab_radius = ui.style().interaction.resize_grab_radius_side;
+            let resize_rect = match direction {
+                SplitDirection::Horizontal => {
+                    Rect::from_x_y_ranges(separator..=separator, rect.y_range())
+                        .expand2(vec2(grab_radius, 0.0))
+                }
+                SplitDirection::Vertical => {
+                    Rect::from_x_y_ranges(rect.x_range(), separator..=separator)
+                        .expand2(vec2(0.0, grab_radius))
+                }
+            };
+            let resize_response = ui.interact(resize_rect, path_id.with("resize"), Sense::drag());
+            if let (true, Some(pointer)) = (
+                resize_response.dragged(),
+                resize_response.interact_pointer_pos(),
+            ) {
+                let t = match direction {
+                    SplitDirection::Horizontal => (pointer.x - rect.left()) / rect.width(),
+                    SplitDirection::Vertical => (pointer.y - rect.top()) / rect.height(),
+                };
+                *fraction = t.clamp(0.1, 0.9);
+            }
+
+            let stroke = if resize_response.dragged() {
+                ui.style().visuals.widgets.active.fg_stroke // highly visible
+            } else if resize_response.hovered() {
+                ui.style().visuals.widgets.hovered.fg_stroke // highly visible
+            } else {
+                Stroke::NONE
+            };
+            if resize_response.dragged() || resize_response.hovered() {
+                ui.ctx().set_cursor_icon(match direction {
+                    SplitDirection::Horizontal => CursorIcon::ResizeHorizontal,
+                    SplitDirection::Vertical => CursorIcon::ResizeVertical,
+                });
+            }
+            match direction {
+                SplitDirection::Horizontal => {
+                    ui.painter().vline(separator, rect.y_range(), stroke);
+                }
+                SplitDirection::Vertical => {
+                    ui.painter().hline(rect.x_range(), separator, stroke);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf_tabs(node: &DockNode<u32>) -> Vec<Vec<u32>> {
+        match node {
+            DockNode::Leaf { tabs, .. } => vec![tabs.clone()],
+            DockNode::Split { children, .. } => {
+                let mut tabs = leaf_tabs(&children[0]);
+                tabs.extend(leaf_tabs(&children[1]));
+                tabs
+            }
+        }
+    }
+
+    #[test]
+    fn dock_side_from_pos() {
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0));
+        let side = |x: f32, y: f32| DockSide::from_pos(rect, 20.0, Pos2::new(x, y));
+        assert_eq!(side(50.0, 50.0), DockSide::Center);
+        assert_eq!(side(10.0, 50.0), DockSide::Left);
+        assert_eq!(side(90.0, 50.0), DockSide::Right);
+        assert_eq!(side(50.0, 22.0), DockSide::Top);
+        assert_eq!(side(50.0, 90.0), DockSide::Bottom);
+
+        // The tab bar adds the tabs to the group, even near the corners:
+        assert_eq!(side(50.0, 10.0), DockSide::Center);
+        assert_eq!(side(2.0, 2.0), DockSide::Center);
+    }
+
+    #[test]
+    fn split_and_merge() {
+        let mut state = DockState::new(vec![1, 2, 3]);
+        let first = state.root().unwrap().first_leaf_id();
+
+        let right = state.split(first, DockSide::Right, vec![4]).unwrap();
+        state.split(right, DockSide::Top, vec![5]).unwrap();
+        assert_eq!(
+            leaf_tabs(state.root().unwrap()),
+            vec![vec![1, 2, 3], vec![5], vec![4]]
+        );
+
+        assert_eq!(state.split(right, DockSide::Center, vec![6]), Some(right));
+        assert_eq!(
+            leaf_tabs(state.root().unwrap()),
+            vec![vec![1, 2, 3], vec![5], vec![4, 6]]
+        );
+
+        // Moving the only tab out of a group removes the group:
+        let tabs = state.take_tabs(DragSource::Leaf(right), None);
+        assert_eq!(tabs, vec![4, 6]);
+        state.add_floating(tabs, Pos2::ZERO);
+        state.remove_empty();
+        assert_eq!(
+            leaf_tabs(state.root().unwrap()),
+            vec![vec![1, 2, 3], vec![5]]
+        );
+        assert_eq!(state.floating()[0].tabs, vec![4, 6]);
+
+        let mut all: Vec<u32> = state.tabs().into_iter().copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, vec![1, 2, 3, 4, 5, 6]);
+    }
+}
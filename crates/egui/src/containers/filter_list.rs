@@ -0,0 +1,310 @@
+//! The filtered list of items shown by [`crate::ComboBox::show_index_filtered`]
+//! and [`crate::TextEdit::show_with_completions`].
+
+use std::ops::Range;
+
+use crate::{
+    text::{LayoutJob, TextFormat},
+    Color32, Key, Modifiers, NumExt as _, Rect, ScrollArea, Stroke, TextStyle, TextWrapMode, Ui,
+    WidgetText,
+};
+
+/// An item that matches the query.
+#[derive(Clone, Debug)]
+pub(crate) struct Match {
+    /// Which item.
+    pub index: usize,
+
+    /// Where in the text of the item the query was found, in bytes.
+    pub range: Range<usize>,
+}
+
+/// Case-insensitive search for `query` in `text`.
+///
+/// Returns the byte range of the first occurrence.
+/// An empty query matches everything.
+pub(crate) fn find_match(text: &str, query: &str) -> Option<Range<usize>> {
+    text.char_indices()
+        .map(|(start, _)| start)
+        .chain(std::iter::once(text.len()))
+        .find_map(|start| {
+            let mut chars = text[start..].chars();
+            let mut end = start;
+            for q in query.chars() {
+                let c = chars.next()?;
+                if !c.to_lowercase().eq(q.to_lowercase()) {
+                    return None;
+                }
+                end += c.len_utf8();
+            }
+            Some(start..end)
+        })
+}
+
+/// The matches of the last query, so that the items are only searched again when the query changes.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MatchCache {
+    /// The query, and the number of items that were searched.
+    key: Option<(String, usize)>,
+
+    matches: Vec<Match>,
+}
+
+impl MatchCache {
+    /// The items, out of `len`, where `find` finds the `query`.
+    ///
+    /// The items are assumed not to change as long as their number stays the same.
+    pub fn matches(
+        &mut self,
+        query: &str,
+        len: usize,
+        find: impl Fn(usize) -> Option<Range<usize>>,
+    ) -> &[Match] {
+        if self.key.as_ref().map(|(q, l)| (q.as_str(), *l)) != Some((query, len)) {
+            self.matches = (0..len)
+                .filter_map(|index| {
+                    Some(Match {
+                        index,
+                        range: find(index)?,
+                    })
+                })
+                .collect();
+            self.key = Some((query.to_owned(), len));
+        }
+        &self.matches
+    }
+}
+
+/// The keys used to move through the list.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ListKeys {
+    pub up: bool,
+    pub down: bool,
+    pub enter: bool,
+    pub escape: bool,
+}
+
+impl ListKeys {
+    /// Consume the keys, so that the text field the user is typing in doesn't act on them.
+    ///
+    /// Call this before showing the text field.
+    pub fn consume(ui: &Ui) -> Self {
+        ui.input_mut(|i| Self {
+            up: i.consume_key(Modifiers::NONE, Key::ArrowUp),
+            down: i.consume_key(Modifiers::NONE, Key::ArrowDown),
+            enter: i.consume_key(Modifiers::NONE, Key::Enter),
+            escape: i.consume_key(Modifiers::NONE, Key::Escape),
+        })
+    }
+
+    /// Did the cursor move, so that it should be scrolled into view?
+    pub fn moved(&self) -> bool {
+        self.up || self.down
+    }
+}
+
+/// What the user did with the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ListAction {
+    /// Pick this item.
+    Accept(usize),
+
+    /// Close the list without picking anything.
+    Cancel,
+}
+
+/// Move the `cursor` (the index of an item) up and down through the `matches`.
+///
+/// The cursor is always kept on one of the matches, if there are any.
+pub(crate) fn navigate(
+    keys: ListKeys,
+    cursor: &mut Option<usize>,
+    matches: &[Match],
+) -> Option<ListAction> {
+    let mut position = cursor
+        .and_then(|cursor| matches.iter().position(|m| m.index == cursor))
+        .or((!matches.is_empty()).then_some(0));
+
+    if let Some(position) = &mut position {
+        if keys.down {
+            *position = (*position + 1).at_most(matches.len() - 1);
+        }
+        if keys.up {
+            *position = position.saturating_sub(1);
+        }
+    }
+    *cursor = position.map(|position| matches[position].index);
+
+    if keys.escape {
+        Some(ListAction::Cancel)
+    } else if keys.enter {
+        cursor.map(ListAction::Accept)
+    } else {
+        None
+    }
+}
+
+/// Show the matches in a scroll area, only laying out the visible rows.
+///
+/// `text` is the text of an item.
+/// The row under the `cursor` is shown as selected, and the matching part of each item is highlighted.
+///
+/// Returns the index of the item that was clicked, if any.
+pub(crate) fn show_matches(
+    ui: &mut Ui,
+    max_height: f32,
+    matches: &[Match],
+    cursor: Option<usize>,
+    scroll_to_cursor: bool,
+    text: impl Fn(usize) -> String,
+) -> Option<usize> {
+    let row_height = (ui.text_style_height(&TextStyle::Button)
+        + 2.0 * ui.spacing().button_padding.y)
+        .at_least(ui.spacing().interact_size.y);
+    let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+
+    let mut clicked = None;
+
+    ScrollArea::vertical().max_height(max_height).show_rows(
+        ui,
+        row_height,
+        matches.len(),
+        |ui, row_range| {
+            // Don't wrap long items, let them widen the list instead.
+            ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+
+            if scroll_to_cursor {
+                if let Some(row) = matches.iter().position(|m| Some(m.index) == cursor) {
+                    let top = ui.max_rect().top()
+                        + (row as f32 - row_range.start as f32) * row_height_with_spacing;
+                    let rect =
+                        Rect::from_x_y_ranges(ui.max_rect().x_range(), top..=top + row_height);
+                    ui.scroll_to_rect(rect, None);
+                }
+            }
+
+            for m in &matches[row_range] {
+                let label = highlighted(ui, &text(m.index), m.range.clone());
+                if ui
+                    .selectable_label(Some(m.index) == cursor, label)
+                    .clicked()
+                {
+                    clicked = Some(m.index);
+                }
+            }
+        },
+    );
+
+    clicked
+}
+
+/// The text, with the matching `range` in the strong text color and underlined.
+fn highlighted(ui: &Ui, text: &str, range: Range<usize>) -> WidgetText {
+    let font_id = TextStyle::Button.resolve(ui.style());
+    let strong_color = ui.visuals().strong_text_color();
+
+    // Placeholder: use the color of the selectable label.
+    let normal = TextFormat::simple(font_id, Color32::PLACEHOLDER);
+    let strong = TextFormat {
+        color: strong_color,
+        underline: Stroke::new(1.0, strong_color),
+        ..normal.clone()
+    };
+
+    let mut job = LayoutJob::default();
+    for (part, format) in [
+        (&text[..range.start], &normal),
+        (&text[range.clone()], &strong),
+        (&text[range.end..], &normal),
+    ] {
+        if !part.is_empty() {
+            job.append(part, 0.0, format.clone());
+        }
+    }
+    job.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_match() {
+        assert_eq!(find_match("Helvetica", ""), Some(0..0));
+        assert_eq!(find_match("Helvetica", "vet"), Some(3..6));
+        assert_eq!(find_match("Helvetica", "HEL"), Some(0..3));
+        assert_eq!(find_match("Helvetica", "ca"), Some(7..9));
+        assert_eq!(find_match("Helvetica", "cab"), None);
+        assert_eq!(find_match("Ærøskøbing", "RØ"), Some(2..5));
+        assert_eq!(find_match("", "a"), None);
+    }
+
+    #[test]
+    fn test_match_cache() {
+        let items = ["apple", "banana", "cherry"];
+        let searched = std::cell::Cell::new(0);
+        let mut cache = MatchCache::default();
+        let mut matches = |query: &str, len: usize| -> Vec<usize> {
+            let find = |index: usize| {
+                searched.set(searched.get() + 1);
+                find_match(items[index], query)
+            };
+            cache
+                .matches(query, len, find)
+                .iter()
+                .map(|m| m.index)
+                .collect()
+        };
+
+        assert_eq!(matches("an", 3), [1]);
+        assert_eq!(searched.get(), 3);
+
+        // Not searched again for the same query:
+        assert_eq!(matches("an", 3), [1]);
+        assert_eq!(searched.get(), 3);
+
+        assert_eq!(matches("e", 3), [0, 2]);
+        assert_eq!(matches("e", 2), [0]);
+        assert_eq!(searched.get(), 8);
+    }
+
+    #[test]
+    fn test_navigate() {
+        let matches: Vec<Match> = [3, 5, 8]
+            .into_iter()
+            .map(|index| Match { index, range: 0..0 })
+            .collect();
+        let down = ListKeys {
+            down: true,
+            ..Default::default()
+        };
+        let up = ListKeys {
+            up: true,
+            ..Default::default()
+        };
+        let enter = ListKeys {
+            enter: true,
+            ..Default::default()
+        };
+
+        // The cursor snaps to the first match:
+        let mut cursor = Some(4);
+        assert_eq!(navigate(ListKeys::default(), &mut cursor, &matches), None);
+        assert_eq!(cursor, Some(3));
+
+        navigate(down, &mut cursor, &matches);
+        navigate(down, &mut cursor, &matches);
+        navigate(down, &mut cursor, &matches);
+        assert_eq!(cursor, Some(8));
+
+        navigate(up, &mut cursor, &matches);
+        assert_eq!(
+            navigate(enter, &mut cursor, &matches),
+            Some(ListAction::Accept(5))
+        );
+
+        let mut cursor = Some(5);
+        assert_eq!(navigate(enter, &mut cursor, &[]), None);
+        assert_eq!(cursor, None);
+    }
+}
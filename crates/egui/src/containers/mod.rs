@@ -5,6 +5,8 @@
 pub(crate) mod area;
 pub mod collapsing_header;
 mod combo_box;
+pub mod dock;
+pub(crate) mod filter_list;
 pub mod frame;
 pub mod modal;
 pub mod panel;
@@ -12,18 +14,23 @@ pub mod popup;
 pub(crate) mod resize;
 pub mod scroll_area;
 mod sides;
+pub mod tabs;
+pub mod tree_view;
 pub(crate) mod window;
 
 pub use {
     area::{Area, AreaState},
     collapsing_header::{CollapsingHeader, CollapsingResponse},
     combo_box::*,
+    dock::{DockArea, DockNode, DockSide, DockState, FloatingTabs, SplitDirection},
     frame::Frame,
     modal::{Modal, ModalResponse},
     panel::{CentralPanel, SidePanel, TopBottomPanel},
     popup::*,
     resize::Resize,
-    scroll_area::ScrollArea,
+    scroll_area::{ScrollArea, VisibleCells},
     sides::Sides,
+    tabs::{TabViewer, Tabs, TabsResponse, TabsState},
+    tree_view::{TreeView, TreeViewDrop, TreeViewNodes, TreeViewResponse, TreeViewState},
     window::Window,
 };
@@ -1,5 +1,7 @@
 #![allow(clippy::needless_range_loop)]
 
+use std::{ops::Range, sync::Arc};
+
 use crate::{
     emath, epaint, lerp, pass_state, pos2, remap, remap_clamp, vec2, Context, Id, NumExt, Pos2,
     Rangef, Rect, Sense, Ui, UiBuilder, UiKind, UiStackInfo, Vec2, Vec2b,
@@ -96,6 +98,119 @@ pub struct ScrollAreaOutput<R> {
     pub inner_rect: Rect,
 }
 
+/// The part of a grid that is currently visible in [`ScrollArea::show_cells`].
+#[derive(Clone, Debug)]
+pub struct VisibleCells {
+    /// The rows that are (at least partially) visible.
+    pub rows: Range<usize>,
+
+    /// The columns that are (at least partially) visible.
+    pub cols: Range<usize>,
+
+    /// Screen position of the top left corner of the whole grid.
+    origin: Pos2,
+
+    /// Offsets of the visible rows, from `rows.start` to `rows.end` (inclusive).
+    row_offsets: Vec<f32>,
+
+    /// Offsets of the visible columns, from `cols.start` to `cols.end` (inclusive).
+    col_offsets: Vec<f32>,
+}
+
+impl VisibleCells {
+    /// The screen rectangle of a visible cell.
+    ///
+    /// # Panics
+    /// If the cell is outside of [`Self::rows`] or [`Self::cols`].
+    pub fn cell_rect(&self, row: usize, col: usize) -> Rect {
+        let r = row - self.rows.start;
+        let c = col - self.cols.start;
+        Rect::from_min_max(
+            self.origin + vec2(self.col_offsets[c], self.row_offsets[r]),
+            self.origin + vec2(self.col_offsets[c + 1], self.row_offsets[r + 1]),
+        )
+    }
+
+    /// All visible `(row, col)` pairs, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rows
+            .clone()
+            .flat_map(move |row| self.cols.clone().map(move |col| (row, col)))
+    }
+
+    /// The screen rectangle covering all visible cells.
+    pub fn rect(&self) -> Rect {
+        let first = |offsets: &[f32]| offsets.first().copied().unwrap_or_default();
+        let last = |offsets: &[f32]| offsets.last().copied().unwrap_or_default();
+        Rect::from_min_max(
+            self.origin + vec2(first(&self.col_offsets), first(&self.row_offsets)),
+            self.origin + vec2(last(&self.col_offsets), last(&self.row_offsets)),
+        )
+    }
+}
+
+/// Prefix sums of the sizes of all rows (or columns) of [`ScrollArea::show_cells`].
+///
+/// Cached in temporary memory, since building it touches every row.
+#[derive(Clone, Debug)]
+struct AxisSizes {
+    /// `offsets[i]` is where item `i` starts. The last element is the total size.
+    ///
+    /// Summed in `f64`, since with a million fractional sizes an `f32` sum drifts by whole points.
+    offsets: Vec<f64>,
+}
+
+impl AxisSizes {
+    fn new(count: usize, size: impl Fn(usize) -> f32) -> Self {
+        let mut offsets = Vec::with_capacity(count + 1);
+        let mut offset = 0.0;
+        offsets.push(offset);
+        for i in 0..count {
+            offset += size(i).at_least(0.0) as f64;
+            offsets.push(offset);
+        }
+        Self { offsets }
+    }
+
+    fn load_or_new(ctx: &Context, id: Id, count: usize, size: impl Fn(usize) -> f32) -> Arc<Self> {
+        if let Some(cached) = ctx.data(|d| d.get_temp::<Arc<Self>>(id)) {
+            if cached.count() == count {
+                return cached;
+            }
+        }
+        // Note: `size` may read from the context, so we must not hold a lock while calling it.
+        let sizes = Arc::new(Self::new(count, size));
+        ctx.data_mut(|d| d.insert_temp(id, sizes.clone()));
+        sizes
+    }
+
+    fn count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn total(&self) -> f32 {
+        self.offsets[self.count()] as f32
+    }
+
+    /// The offsets of the given items, and of the end of the last one.
+    fn offsets(&self, items: Range<usize>) -> Vec<f32> {
+        self.offsets[items.start..=items.end]
+            .iter()
+            .map(|&offset| offset as f32)
+            .collect()
+    }
+
+    /// The items overlapping the given range.
+    fn visible(&self, range: Rangef) -> Range<usize> {
+        let (min, max) = (range.min as f64, range.max as f64);
+        // The number of items ending before the range:
+        let start = self.offsets[1..].partition_point(|&end| end <= min);
+        // The number of items starting before the end of the range:
+        let end = self.offsets[..self.count()].partition_point(|&start| start < max);
+        start.min(end)..end
+    }
+}
+
 /// Indicate whether the horizontal and vertical scroll bars must be always visible, hidden or visible when needed.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -781,6 +896,91 @@ impl ScrollArea {
         })
     }
 
+    /// Efficiently show only the visible cells of a large two-dimensional grid.
+    ///
+    /// Rows and columns can have different sizes, given by `row_height` and `col_width`
+    /// (which should include any spacing between cells).
+    /// These are only called when the number of rows or columns change,
+    /// since the resulting layout is cached.
+    /// If the sizes change for any other reason, call [`Self::clear_cell_size_cache`].
+    ///
+    /// Use [`VisibleCells::cell_rect`] to position each cell.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// egui::ScrollArea::both().show_cells(
+    ///     ui,
+    ///     1_000_000,
+    ///     500,
+    ///     |row| if row % 10 == 0 { 40.0 } else { 20.0 },
+    ///     |_col| 80.0,
+    ///     |ui, cells| {
+    ///         for (row, col) in cells.cells() {
+    ///             ui.put(cells.cell_rect(row, col), egui::Label::new(format!("{row}, {col}")));
+    ///         }
+    ///     },
+    /// );
+    /// # });
+    /// ```
+    pub fn show_cells<R>(
+        self,
+        ui: &mut Ui,
+        total_rows: usize,
+        total_cols: usize,
+        row_height: impl Fn(usize) -> f32,
+        col_width: impl Fn(usize) -> f32,
+        add_contents: impl FnOnce(&mut Ui, &VisibleCells) -> R,
+    ) -> ScrollAreaOutput<R> {
+        let mut prepared = self.begin(ui);
+        let id = prepared.id;
+        let inner_rect = prepared.inner_rect;
+        let viewport = prepared.viewport;
+
+        let rows = AxisSizes::load_or_new(ui.ctx(), id.with("__row_sizes"), total_rows, row_height);
+        let cols = AxisSizes::load_or_new(ui.ctx(), id.with("__col_sizes"), total_cols, col_width);
+
+        let content_ui = &mut prepared.content_ui;
+        content_ui.set_min_size(vec2(cols.total(), rows.total()));
+
+        let visible_rows = rows.visible(viewport.y_range());
+        let visible_cols = cols.visible(viewport.x_range());
+        let cells = VisibleCells {
+            origin: content_ui.max_rect().min,
+            row_offsets: rows.offsets(visible_rows.clone()),
+            col_offsets: cols.offsets(visible_cols.clone()),
+            rows: visible_rows,
+            cols: visible_cols,
+        };
+
+        let inner = content_ui
+            .allocate_new_ui(UiBuilder::new().max_rect(cells.rect()), |viewport_ui| {
+                // Make sure we get consistent IDs:
+                viewport_ui.skip_ahead_auto_ids(cells.rows.start * total_cols + cells.cols.start);
+                add_contents(viewport_ui, &cells)
+            })
+            .inner;
+
+        let (content_size, state) = prepared.end(ui);
+        ScrollAreaOutput {
+            inner,
+            id,
+            state,
+            content_size,
+            inner_rect,
+        }
+    }
+
+    /// Forget the cached row heights and column widths of [`Self::show_cells`],
+    /// so that they are recomputed next pass.
+    ///
+    /// `id` is [`ScrollAreaOutput::id`].
+    pub fn clear_cell_size_cache(ctx: &Context, id: Id) {
+        ctx.data_mut(|d| {
+            d.remove::<Arc<AxisSizes>>(id.with("__row_sizes"));
+            d.remove::<Arc<AxisSizes>>(id.with("__col_sizes"));
+        });
+    }
+
     /// This can be used to only paint the visible part of the contents.
     ///
     /// `add_contents` is given the viewport rectangle, which is the relative view of the content.
@@ -1283,3 +1483,44 @@ impl Prepared {
         (content_size, state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_axis_range() {
+        let sizes = AxisSizes::new(5, |i| if i == 2 { 30.0 } else { 10.0 });
+        assert_eq!(sizes.offsets, vec![0.0, 10.0, 20.0, 50.0, 60.0, 70.0]);
+        assert_eq!(sizes.total(), 70.0);
+
+        assert_eq!(sizes.visible(Rangef::new(0.0, 10.0)), 0..1);
+        assert_eq!(sizes.visible(Rangef::new(5.0, 25.0)), 0..3);
+        assert_eq!(sizes.visible(Rangef::new(20.0, 50.0)), 2..3);
+        assert_eq!(sizes.visible(Rangef::new(65.0, 200.0)), 4..5);
+        assert_eq!(sizes.visible(Rangef::new(100.0, 200.0)), 5..5);
+
+        let empty = AxisSizes::new(0, |_| 10.0);
+        assert_eq!(empty.visible(Rangef::new(0.0, 100.0)), 0..0);
+    }
+
+    #[test]
+    fn million_fractional_rows() {
+        let count = 1_000_000;
+        let height = 20.3_f32;
+        let sizes = AxisSizes::new(count, |_| height);
+
+        // Every offset is as close as an `f32` gets, without drifting:
+        for i in [1, 1_000, 500_000, 999_999, count] {
+            let exact = i as f64 * height as f64;
+            assert_eq!(sizes.offsets(i..i)[0], exact as f32, "offset of row {i}");
+        }
+        assert_eq!(sizes.total(), (count as f64 * height as f64) as f32);
+
+        let row = 900_000;
+        let top = sizes.offsets(row..row)[0];
+        let visible = sizes.visible(Rangef::new(top + 1.0, top + 100.0));
+        assert_eq!(visible.start, row);
+        assert_eq!(visible.len(), 5);
+    }
+}
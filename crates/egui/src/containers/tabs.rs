@@ -0,0 +1,632 @@
+//! A row of tabs, each showing different contents. See [`Tabs`].
+
+use std::hash::Hash;
+
+use crate::{
+    pos2, vec2, Align2, Context, CursorIcon, DragAndDrop, Id, InnerResponse, Key, Modifiers,
+    PointerButton, PopupCloseBehavior, Rect, Response, Rounding, Sense, TextStyle, TextWrapMode,
+    Ui, UiBuilder, Vec2, WidgetInfo, WidgetText, WidgetType,
+};
+
+/// How to show the tabs of a [`Tabs`] container.
+///
+/// You implement this for your own type, which has access to whatever the tabs need.
+pub trait TabViewer {
+    /// Your representation of a tab.
+    type Tab;
+
+    /// Uniquely identifies a tab. Must not change while the tab is open,
+    /// even if its [`Self::title`] does.
+    ///
+    /// Two tabs with the same title need different ids.
+    fn id(&mut self, tab: &mut Self::Tab) -> Id;
+
+    /// The text shown on the tab.
+    fn title(&mut self, tab: &mut Self::Tab) -> WidgetText;
+
+    /// Show the contents of the active tab.
+    fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab);
+
+    /// Does this tab have a close button?
+    fn closable(&mut self, tab: &mut Self::Tab) -> bool {
+        let _ = tab;
+        true
+    }
+
+    /// Called when the user closes a tab.
+    ///
+    /// Return `false` to keep the tab open, e.g. to first ask about unsaved changes.
+    fn on_close(&mut self, tab: &mut Self::Tab) -> bool {
+        let _ = tab;
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub(crate) struct InnerState {
+    active: Option<Id>,
+}
+
+/// Which tab of a [`Tabs`] is active.
+///
+/// This is stored in [`crate::Memory`], like [`crate::collapsing_header::CollapsingState`].
+#[derive(Clone, Debug)]
+pub struct TabsState {
+    id: Id,
+    state: InnerState,
+}
+
+impl TabsState {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| {
+            d.get_persisted::<InnerState>(id)
+                .map(|state| Self { id, state })
+        })
+    }
+
+    pub fn store(&self, ctx: &Context) {
+        ctx.data_mut(|d| d.insert_persisted(self.id, self.state));
+    }
+
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// The [`TabViewer::id`] of the active tab.
+    pub fn active(&self) -> Option<Id> {
+        self.state.active
+    }
+
+    pub fn set_active(&mut self, tab_id: Option<Id>) {
+        self.state.active = tab_id;
+    }
+}
+
+/// What is being dragged when reordering tabs.
+pub(crate) struct TabDragPayload {
+    pub tabs_id: Id,
+    pub index: usize,
+}
+
+/// Returned by [`Tabs::show`].
+pub struct TabsResponse {
+    /// The response of the whole container, tab bar and contents.
+    pub response: Response,
+
+    /// The [`TabViewer::id`] of the active tab, if any.
+    pub active: Option<Id>,
+
+    /// Did the user switch to another tab this frame?
+    pub changed: bool,
+}
+
+/// A tab bar on top of the contents of the active tab.
+///
+/// * Click a tab to make it active, or use ctrl+tab and ctrl+shift+tab.
+/// * Drag tabs to reorder them.
+/// * Close tabs with their close button, or with a middle click.
+/// * Tabs that don't fit are listed in a dropdown at the end of the tab bar.
+///
+/// The tabs are stored in a `Vec` owned by you, which is reordered and shrunk as the user moves and closes tabs.
+///
+/// ```
+/// struct MyTabs;
+///
+/// impl egui::TabViewer for MyTabs {
+///     type Tab = String;
+///
+///     fn id(&mut self, tab: &mut String) -> egui::Id {
+///         egui::Id::new(tab.as_str())
+///     }
+///
+///     fn title(&mut self, tab: &mut String) -> egui::WidgetText {
+///         tab.as_str().into()
+///     }
+///
+///     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut String) {
+///         ui.label(format!("Contents of {tab}"));
+///     }
+/// }
+///
+/// # egui::__run_test_ui(|ui| {
+/// let mut tabs = vec!["First".to_owned(), "Second".to_owned()];
+/// egui::Tabs::new("my_tabs").show(ui, &mut tabs, &mut MyTabs);
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+#[derive(Clone, Debug)]
+pub struct Tabs {
+    id_salt: Id,
+    reorderable: bool,
+    keyboard_cycling: bool,
+}
+
+impl Tabs {
+    pub fn new(id_salt: impl Hash) -> Self {
+        Self {
+            id_salt: Id::new(id_salt),
+            reorderable: true,
+            keyboard_cycling: true,
+        }
+    }
+
+    /// Can the tabs be reordered by dragging them?
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// Switch tabs with ctrl+tab and ctrl+shift+tab
+    /// when the keyboard focus is within the container, or it is hovered.
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn keyboard_cycling(mut self, keyboard_cycling: bool) -> Self {
+        self.keyboard_cycling = keyboard_cycling;
+        self
+    }
+
+    /// The id of the container when shown in `ui`, e.g. [`TabDragPayload::tabs_id`].
+    pub(crate) fn id(&self, ui: &Ui) -> Id {
+        ui.make_persistent_id(self.id_salt)
+    }
+
+    pub fn show<V: TabViewer>(
+        self,
+        ui: &mut Ui,
+        tabs: &mut Vec<V::Tab>,
+        viewer: &mut V,
+    ) -> TabsResponse {
+        let id = self.id(ui);
+        let InnerResponse {
+            inner: (active, changed),
+            response,
+        } = ui.scope_builder(
+            UiBuilder::new().id_salt(self.id_salt).sense(Sense::hover()),
+            |ui| self.show_inside(ui, id, tabs, viewer),
+        );
+        TabsResponse {
+            response,
+            active,
+            changed,
+        }
+    }
+
+    /// Returns the active tab, and if it changed.
+    fn show_inside<V: TabViewer>(
+        self,
+        ui: &mut Ui,
+        id: Id,
+        tabs: &mut Vec<V::Tab>,
+        viewer: &mut V,
+    ) -> (Option<Id>, bool) {
+        let Self {
+            id_salt: _,
+            reorderable,
+            keyboard_cycling,
+        } = self;
+
+        let mut state = TabsState::load(ui.ctx(), id).unwrap_or(TabsState {
+            id,
+            state: InnerState::default(),
+        });
+
+        let tab_ids: Vec<Id> = tabs.iter_mut().map(|tab| viewer.id(tab)).collect();
+        let prev_active = state
+            .active()
+            .and_then(|active| tab_ids.iter().position(|&tab_id| tab_id == active));
+        let mut active = prev_active.or((!tabs.is_empty()).then_some(0));
+
+        if keyboard_cycling && !tabs.is_empty() && has_keyboard_attention(ui) {
+            let n = tabs.len();
+            let cycled = ui.input_mut(|i| {
+                if i.consume_key(Modifiers::CTRL | Modifiers::SHIFT, Key::Tab) {
+                    active = active.map(|a| (a + n - 1) % n);
+                    true
+                } else if i.consume_key(Modifiers::CTRL, Key::Tab) {
+                    active = active.map(|a| (a + 1) % n);
+                    true
+                } else {
+                    false
+                }
+            });
+            if cycled {
+                // The key switched tabs, so it shouldn't also move the focus to the next widget:
+                ui.memory_mut(|mem| mem.cancel_focus_move());
+            }
+        }
+
+        // The tab bar:
+        let spacing = ui.spacing().item_spacing.x;
+        let padding = ui.spacing().button_padding;
+        let icon_width = ui.spacing().icon_width;
+        let bar_height = ui.spacing().interact_size.y;
+
+        let mut titles = Vec::with_capacity(tabs.len());
+        let mut widths = Vec::with_capacity(tabs.len());
+        let mut closable = Vec::with_capacity(tabs.len());
+        for tab in tabs.iter_mut() {
+            let galley = viewer.title(tab).into_galley(
+                ui,
+                Some(TextWrapMode::Extend),
+                f32::INFINITY,
+                TextStyle::Button,
+            );
+            let can_close = viewer.closable(tab);
+            let close_width = if can_close {
+                ui.spacing().icon_spacing + icon_width
+            } else {
+                0.0
+            };
+            widths.push(2.0 * padding.x + galley.size().x + close_width);
+            titles.push(galley);
+            closable.push(can_close);
+        }
+
+        let (bar_rect, _) =
+            ui.allocate_exact_size(vec2(ui.available_width(), bar_height), Sense::hover());
+        let overflow_width = bar_height;
+        let visible = visible_tabs(&widths, bar_rect.width(), overflow_width, spacing, active);
+
+        let mut clicked = None;
+        let mut to_close = None;
+        let mut tab_rects = Vec::with_capacity(visible.len());
+
+        let mut x = bar_rect.left();
+        for &index in &visible {
+            let rect =
+                Rect::from_min_size(pos2(x, bar_rect.top()), vec2(widths[index], bar_height));
+            x += widths[index] + spacing;
+            tab_rects.push(rect);
+
+            let tab_id = id.with(tab_ids[index]);
+            let sense = if reorderable {
+                Sense::click_and_drag()
+            } else {
+                Sense::click()
+            };
+            let response = ui.interact(rect, tab_id, sense);
+            let is_active = active == Some(index);
+            let galley = titles[index].clone();
+            response.widget_info(|| {
+                WidgetInfo::selected(
+                    WidgetType::SelectableLabel,
+                    ui.is_enabled(),
+                    is_active,
+                    galley.text(),
+                )
+            });
+
+            let close_response = closable[index].then(|| {
+                let close_rect = Rect::from_center_size(
+                    pos2(rect.right() - padding.x - 0.5 * icon_width, rect.center().y),
+                    Vec2::splat(icon_width),
+                );
+                ui.interact(close_rect, tab_id.with("close"), Sense::click())
+            });
+
+            if ui.is_rect_visible(rect) {
+                let visuals = ui.style().interact_selectable(&response, is_active);
+                if is_active || response.hovered() {
+                    let rounding = Rounding {
+                        ne: visuals.rounding.ne,
+                        nw: visuals.rounding.nw,
+                        ..Default::default()
+                    };
+                    ui.painter()
+                        .rect(rect, rounding, visuals.weak_bg_fill, visuals.bg_stroke);
+                }
+                let text_pos = pos2(
+                    rect.left() + padding.x,
+                    rect.center().y - 0.5 * galley.size().y,
+                );
+                ui.painter().galley(text_pos, galley, visuals.text_color());
+
+                if let Some(close_response) = &close_response {
+                    let visuals = ui.style().interact(close_response);
+                    let rect = close_response.rect.shrink(2.0).expand(visuals.expansion);
+                    let stroke = visuals.fg_stroke;
+                    ui.painter()
+                        .line_segment([rect.left_top(), rect.right_bottom()], stroke);
+                    ui.painter()
+                        .line_segment([rect.right_top(), rect.left_bottom()], stroke);
+                }
+            }
+
+            if close_response.is_some_and(|r| r.clicked())
+                || (closable[index] && response.clicked_by(PointerButton::Middle))
+            {
+                to_close = Some(index);
+            } else if response.clicked() || response.drag_started() {
+                clicked = Some(index);
+            }
+
+            if reorderable && response.drag_started() {
+                DragAndDrop::set_payload(ui.ctx(), TabDragPayload { tabs_id: id, index });
+            }
+        }
+
+        ui.painter().hline(
+            bar_rect.x_range(),
+            bar_rect.bottom(),
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+
+        // Tabs that didn't fit:
+        if visible.len() < tabs.len() {
+            let button_rect = Rect::from_min_max(
+                pos2(bar_rect.right() - overflow_width, bar_rect.top()),
+                bar_rect.max,
+            );
+            let button_response = ui.interact(button_rect, id.with("overflow"), Sense::click());
+            button_response.widget_info(|| {
+                WidgetInfo::labeled(WidgetType::ComboBox, ui.is_enabled(), "More tabs")
+            });
+            let visuals = ui.style().interact(&button_response);
+            ui.painter().text(
+                button_rect.center(),
+                Align2::CENTER_CENTER,
+                "⏷",
+                TextStyle::Button.resolve(ui.style()),
+                visuals.text_color(),
+            );
+
+            let popup_id = id.with("overflow_popup");
+            if button_response.clicked() {
+                ui.memory_mut(|mem| mem.toggle_popup(popup_id));
+            }
+            crate::popup_below_widget(
+                ui,
+                popup_id,
+                &button_response,
+                PopupCloseBehavior::CloseOnClick,
+                |ui| {
+                    ui.set_min_width(100.0);
+                    for (index, tab) in tabs.iter_mut().enumerate() {
+                        if !visible.contains(&index)
+                            && ui.selectable_label(false, viewer.title(tab)).clicked()
+                        {
+                            clicked = Some(index);
+                        }
+                    }
+                },
+            );
+        }
+
+        // Reordering by drag-and-drop:
+        let mut moved = None;
+        if reorderable {
+            if let (Some(payload), Some(pointer)) = (
+                DragAndDrop::payload::<TabDragPayload>(ui.ctx()),
+                ui.ctx().pointer_hover_pos(),
+            ) {
+                if payload.tabs_id == id && bar_rect.contains(pointer) && !tab_rects.is_empty() {
+                    ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
+
+                    // Insert before the first tab whose center is to the right of the pointer:
+                    let slot = tab_rects
+                        .iter()
+                        .position(|rect| pointer.x < rect.center().x)
+                        .unwrap_or(tab_rects.len());
+                    let target = insertion_index(&visible, slot);
+                    let line_x = if slot < tab_rects.len() {
+                        tab_rects[slot].left() - 0.5 * spacing
+                    } else {
+                        tab_rects[tab_rects.len() - 1].right() + 0.5 * spacing
+                    };
+                    ui.painter()
+                        .vline(line_x, bar_rect.y_range(), ui.visuals().selection.stroke);
+
+                    if ui.input(|i| i.pointer.any_released()) {
+                        DragAndDrop::clear_payload(ui.ctx());
+                        moved = Some((payload.index, target));
+                    }
+                }
+            }
+        }
+
+        if let Some(index) = clicked {
+            active = Some(index);
+        }
+
+        // Contents of the active tab:
+        if let Some(index) = active {
+            let tab = &mut tabs[index];
+            ui.push_id(tab_ids[index], |ui| viewer.ui(ui, tab));
+        }
+
+        let mut active_id = active.map(|index| tab_ids[index]);
+
+        if let Some(index) = to_close {
+            if viewer.on_close(&mut tabs[index]) {
+                tabs.remove(index);
+                if active == Some(index) {
+                    // Activate a neighbor:
+                    let neighbor = if index + 1 < tab_ids.len() {
+                        Some(index + 1)
+                    } else {
+                        index.checked_sub(1)
+                    };
+                    active_id = neighbor.map(|i| tab_ids[i]);
+                }
+                ui.ctx().request_repaint();
+            }
+        } else if let Some((from, to)) = moved {
+            if from < tabs.len() && from != to && from + 1 != to {
+                let tab = tabs.remove(from);
+                tabs.insert(if from < to { to - 1 } else { to }, tab);
+                ui.ctx().request_repaint();
+            }
+        }
+
+        let changed = active_id != prev_active.map(|index| tab_ids[index]);
+        state.set_active(active_id);
+        state.store(ui.ctx());
+
+        (active_id, changed)
+    }
+}
+
+/// Does the keyboard focus lie within this [`Ui`], or is the pointer over it?
+fn has_keyboard_attention(ui: &Ui) -> bool {
+    let ctx = ui.ctx();
+    let container = ui.response();
+    match ctx.memory(|mem| mem.focused()) {
+        Some(focused) => ctx
+            .read_response(focused)
+            .is_some_and(|focused| container.rect.contains_rect(focused.rect)),
+        None => container.contains_pointer(),
+    }
+}
+
+/// Where in the tabs to insert a tab dropped before the `slot`th of the `visible` tabs.
+///
+/// Hidden tabs may lie between two visible ones,
+/// so the dropped tab goes right after the visible tab to the left of where it was dropped.
+fn insertion_index(visible: &[usize], slot: usize) -> usize {
+    match slot.checked_sub(1) {
+        Some(left) => visible[left] + 1,
+        None => visible.first().copied().unwrap_or(0),
+    }
+}
+
+/// Which tabs fit in the available width, always including the active one.
+///
+/// If not all fit, room is left for the overflow button.
+fn visible_tabs(
+    widths: &[f32],
+    available_width: f32,
+    overflow_width: f32,
+    spacing: f32,
+    active: Option<usize>,
+) -> Vec<usize> {
+    let total: f32 = widths.iter().sum::<f32>() + spacing * widths.len().saturating_sub(1) as f32;
+    if total <= available_width {
+        return (0..widths.len()).collect();
+    }
+
+    let available_width = available_width - overflow_width - spacing;
+    let mut visible = vec![];
+    let mut used = 0.0;
+    for (index, &width) in widths.iter().enumerate() {
+        if used + width > available_width {
+            break;
+        }
+        used += width + spacing;
+        visible.push(index);
+    }
+
+    if let Some(active) = active {
+        if !visible.contains(&active) {
+            // Make room for the active tab at the end:
+            while let Some(&last) = visible.last() {
+                if used + widths[active] <= available_width {
+                    break;
+                }
+                used -= widths[last] + spacing;
+                visible.pop();
+            }
+            visible.push(active);
+        }
+    }
+
+    visible
+}
+
+#[test]
+fn test_visible_tabs() {
+    let widths = [50.0, 50.0, 50.0, 50.0];
+    assert_eq!(
+        visible_tabs(&widths, 200.0, 20.0, 0.0, Some(3)),
+        [0, 1, 2, 3]
+    );
+    assert_eq!(visible_tabs(&widths, 190.0, 20.0, 0.0, Some(0)), [0, 1, 2]);
+    assert_eq!(visible_tabs(&widths, 190.0, 20.0, 0.0, Some(3)), [0, 1, 3]);
+    assert_eq!(visible_tabs(&widths, 60.0, 20.0, 0.0, Some(2)), [2]);
+}
+
+#[test]
+fn test_insertion_index() {
+    let visible = [0, 1, 4];
+    assert_eq!(insertion_index(&visible, 0), 0);
+    assert_eq!(insertion_index(&visible, 1), 1);
+    assert_eq!(
+        insertion_index(&visible, 2),
+        2,
+        "After tab 1, not before tab 4"
+    );
+    assert_eq!(insertion_index(&visible, 3), 5);
+    assert_eq!(insertion_index(&[3], 0), 3);
+}
+
+#[test]
+fn ctrl_tab_switches_tabs_without_moving_focus() {
+    struct Viewer;
+
+    impl TabViewer for Viewer {
+        type Tab = &'static str;
+
+        fn id(&mut self, tab: &mut Self::Tab) -> Id {
+            Id::new(*tab)
+        }
+
+        fn title(&mut self, tab: &mut Self::Tab) -> WidgetText {
+            (*tab).into()
+        }
+
+        fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
+            let _ = ui.button(*tab);
+        }
+    }
+
+    let ctx = Context::default();
+    let mut tabs = vec!["a", "b"];
+    let mut run = |input: crate::RawInput| {
+        let mut active = None;
+        let _ = ctx.run(input, |ctx| {
+            crate::CentralPanel::default().show(ctx, |ui| {
+                active = Tabs::new("tabs").show(ui, &mut tabs, &mut Viewer).active;
+                let _ = ui.button("after");
+            });
+        });
+        active
+    };
+
+    assert_eq!(run(Default::default()), Some(Id::new("a")));
+    // Focus the first tab:
+    let focus = crate::Event::Key {
+        key: Key::Tab,
+        physical_key: None,
+        pressed: true,
+        repeat: false,
+        modifiers: Modifiers::NONE,
+    };
+    run(crate::RawInput {
+        events: vec![focus],
+        ..Default::default()
+    });
+    let focused = ctx.memory(|mem| mem.focused());
+    assert!(focused.is_some());
+
+    let ctrl_tab = crate::Event::Key {
+        key: Key::Tab,
+        physical_key: None,
+        pressed: true,
+        repeat: false,
+        modifiers: Modifiers::CTRL,
+    };
+    let active = run(crate::RawInput {
+        events: vec![ctrl_tab],
+        ..Default::default()
+    });
+    assert_eq!(active, Some(Id::new("b")));
+    assert_eq!(
+        ctx.memory(|mem| mem.focused()),
+        focused,
+        "Ctrl+Tab must not move the focus to the next widget"
+    );
+}
@@ -0,0 +1,922 @@
+//! A tree of selectable, collapsible rows. See [`TreeView`].
+
+use std::hash::Hash;
+use std::sync::Arc;
+
+use ahash::HashSet;
+
+use crate::{
+    collapsing_header::paint_default_icon, pos2, vec2, Context, CursorIcon, DragAndDrop, Event,
+    EventFilter, Id, Key, Modifiers, NumExt as _, Rect, Response, ScrollArea, Sense, TextStyle,
+    TextWrapMode, Ui, WidgetInfo, WidgetText, WidgetType,
+};
+
+/// The nodes shown by a [`TreeView`].
+///
+/// The tree view doesn't own the nodes, it only asks for the ones it needs to show:
+/// the roots, and the children of the nodes on the visible rows.
+///
+/// The visible rows are remembered between frames, and only asked for again
+/// when a node is expanded or collapsed, or when you call [`TreeView::nodes_changed`].
+///
+/// ```
+/// struct FileTree {
+///     children: std::collections::BTreeMap<String, Vec<String>>,
+/// }
+///
+/// impl egui::TreeViewNodes for FileTree {
+///     type NodeId = String;
+///
+///     fn roots(&self) -> Vec<String> {
+///         vec!["/".to_owned()]
+///     }
+///
+///     fn children(&self, node: &String) -> Vec<String> {
+///         self.children.get(node).cloned().unwrap_or_default()
+///     }
+///
+///     fn label(&self, node: &String) -> egui::WidgetText {
+///         node.as_str().into()
+///     }
+/// }
+/// ```
+pub trait TreeViewNodes {
+    /// Uniquely identifies a node in the tree.
+    ///
+    /// This is stored in the [`TreeViewState`], so it should be cheap to clone.
+    type NodeId: Clone + Eq + Hash + Send + Sync + 'static;
+
+    /// The top-level nodes, in order.
+    fn roots(&self) -> Vec<Self::NodeId>;
+
+    /// The children of a node, in order.
+    fn children(&self, node: &Self::NodeId) -> Vec<Self::NodeId>;
+
+    /// What to show on the row of a node.
+    fn label(&self, node: &Self::NodeId) -> WidgetText;
+
+    /// Can other nodes be dropped into this one, to become its children?
+    ///
+    /// Return `false` for leaves, like files in a file tree.
+    fn accepts_children(&self, node: &Self::NodeId) -> bool {
+        let _ = node;
+        true
+    }
+}
+
+/// Which nodes of a [`TreeView`] are expanded and selected.
+///
+/// Stored in egui memory, and returned in [`TreeViewResponse`].
+#[derive(Clone, Debug)]
+pub struct TreeViewState<NodeId> {
+    expanded: HashSet<NodeId>,
+    selected: HashSet<NodeId>,
+
+    /// Where a shift-click range selection starts.
+    anchor: Option<NodeId>,
+
+    /// The row that is moved by the arrow keys.
+    cursor: Option<NodeId>,
+
+    /// The visible rows, unless they need to be flattened again.
+    rows: Option<Arc<Vec<Row<NodeId>>>>,
+}
+
+impl<NodeId> Default for TreeViewState<NodeId> {
+    fn default() -> Self {
+        Self {
+            expanded: Default::default(),
+            selected: Default::default(),
+            anchor: None,
+            cursor: None,
+            rows: None,
+        }
+    }
+}
+
+impl<NodeId: Clone + Eq + Hash + Send + Sync + 'static> TreeViewState<NodeId> {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_temp(id))
+    }
+
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.insert_temp(id, self));
+    }
+
+    pub fn is_expanded(&self, node: &NodeId) -> bool {
+        self.expanded.contains(node)
+    }
+
+    pub fn set_expanded(&mut self, node: NodeId, expanded: bool) {
+        let changed = if expanded {
+            self.expanded.insert(node)
+        } else {
+            self.expanded.remove(&node)
+        };
+        if changed {
+            self.rows = None;
+        }
+    }
+
+    pub fn is_selected(&self, node: &NodeId) -> bool {
+        self.selected.contains(node)
+    }
+
+    /// The selected nodes, in no particular order.
+    pub fn selected(&self) -> impl Iterator<Item = &NodeId> {
+        self.selected.iter()
+    }
+
+    /// Replace the selection.
+    pub fn set_selected(&mut self, nodes: impl IntoIterator<Item = NodeId>) {
+        self.selected = nodes.into_iter().collect();
+        self.anchor = None;
+    }
+
+    /// The node that has keyboard focus within the tree, if any.
+    pub fn cursor(&self) -> Option<&NodeId> {
+        self.cursor.as_ref()
+    }
+
+    /// The visible rows, flattened again only if they changed since last time.
+    fn rows<N: TreeViewNodes<NodeId = NodeId>>(&mut self, nodes: &N) -> Arc<Vec<Row<NodeId>>> {
+        if let Some(rows) = &self.rows {
+            return rows.clone();
+        }
+        let rows = Arc::new(flatten(nodes, self));
+        self.rows = Some(rows.clone());
+        rows
+    }
+
+    fn select_only(&mut self, node: &NodeId) {
+        self.selected.clear();
+        self.selected.insert(node.clone());
+        self.anchor = Some(node.clone());
+    }
+
+    fn toggle_selected(&mut self, node: &NodeId) {
+        if !self.selected.remove(node) {
+            self.selected.insert(node.clone());
+        }
+        self.anchor = Some(node.clone());
+    }
+
+    /// Select all rows between the anchor and `rows[index]`.
+    fn select_range(&mut self, rows: &[Row<NodeId>], index: usize, add: bool) {
+        let anchor = self
+            .anchor
+            .as_ref()
+            .and_then(|anchor| rows.iter().position(|row| &row.node == anchor))
+            .unwrap_or(index);
+        if !add {
+            self.selected.clear();
+        }
+        let range = anchor.min(index)..=anchor.max(index);
+        self.selected
+            .extend(rows[range].iter().map(|row| row.node.clone()));
+    }
+
+    /// Update the selection as a result of the user clicking `rows[index]`,
+    /// or moving the cursor there with the keyboard.
+    fn select_with_modifiers(
+        &mut self,
+        rows: &[Row<NodeId>],
+        index: usize,
+        modifiers: Modifiers,
+        multi_select: bool,
+        is_click: bool,
+    ) {
+        let node = &rows[index].node;
+        self.cursor = Some(node.clone());
+        if multi_select && modifiers.shift {
+            self.select_range(rows, index, modifiers.command);
+        } else if multi_select && modifiers.command {
+            if is_click {
+                self.toggle_selected(node);
+            }
+            // With the keyboard, command moves the cursor without changing the selection.
+        } else {
+            self.select_only(node);
+        }
+    }
+}
+
+/// A visible row of a [`TreeView`].
+#[derive(Clone, Debug)]
+struct Row<NodeId> {
+    node: NodeId,
+    depth: usize,
+
+    /// Index of the row of the parent node.
+    parent: Option<usize>,
+
+    has_children: bool,
+}
+
+/// All visible rows, in order.
+fn flatten<N: TreeViewNodes>(nodes: &N, state: &TreeViewState<N::NodeId>) -> Vec<Row<N::NodeId>> {
+    fn add_rows<N: TreeViewNodes>(
+        nodes: &N,
+        state: &TreeViewState<N::NodeId>,
+        children: Vec<N::NodeId>,
+        depth: usize,
+        parent: Option<usize>,
+        rows: &mut Vec<Row<N::NodeId>>,
+    ) {
+        for node in children {
+            let children = nodes.children(&node);
+            let index = rows.len();
+            let expanded = state.is_expanded(&node);
+            rows.push(Row {
+                node,
+                depth,
+                parent,
+                has_children: !children.is_empty(),
+            });
+            if expanded {
+                add_rows(nodes, state, children, depth + 1, Some(index), rows);
+            }
+        }
+    }
+
+    let mut rows = vec![];
+    add_rows(nodes, state, nodes.roots(), 0, None, &mut rows);
+    rows
+}
+
+/// Where dragged nodes should go, relative to the row they are dropped on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropPosition {
+    Before,
+    Into,
+    After,
+}
+
+/// What is being dragged in a [`TreeView`].
+struct TreeViewDragPayload<NodeId> {
+    tree_id: Id,
+    nodes: Vec<NodeId>,
+}
+
+/// Some nodes were dragged and dropped in a [`TreeView`].
+///
+/// It is up to you to actually move them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeViewDrop<NodeId> {
+    /// The nodes that were dragged, in tree order.
+    pub nodes: Vec<NodeId>,
+
+    /// The new parent of the nodes. `None` means they become roots.
+    pub parent: Option<NodeId>,
+
+    /// Where among the current children of [`Self::parent`] the nodes should be inserted.
+    ///
+    /// This is the index before the dragged nodes are removed from their old place.
+    pub index: usize,
+}
+
+/// Returned by [`TreeView::show`].
+pub struct TreeViewResponse<NodeId> {
+    /// The response of the whole tree view. Has focus when the tree reacts to the keyboard.
+    pub response: Response,
+
+    /// The expanded and selected nodes.
+    pub state: TreeViewState<NodeId>,
+
+    /// Did the user change the selection this frame?
+    pub selection_changed: bool,
+
+    /// A node that was double-clicked, or had enter pressed on it.
+    pub activated: Option<NodeId>,
+
+    /// Nodes that were dragged and dropped this frame.
+    ///
+    /// After moving them, call [`TreeView::nodes_changed`] next frame.
+    pub dropped: Option<TreeViewDrop<NodeId>>,
+}
+
+/// A tree of collapsible rows, e.g. for files or a scene graph.
+///
+/// Only the visible rows are laid out, so it stays fast with many thousands of nodes.
+///
+/// * Click selects a row; ctrl/cmd-click toggles it, and shift-click selects a range.
+/// * The arrow keys move the selection; left and right collapse and expand.
+/// * Rows can be dragged onto other rows, which is reported in [`TreeViewResponse::dropped`].
+///
+/// The nodes come from a [`TreeViewNodes`].
+///
+/// ```
+/// # struct MyTree;
+/// # impl egui::TreeViewNodes for MyTree {
+/// #     type NodeId = usize;
+/// #     fn roots(&self) -> Vec<usize> { vec![0] }
+/// #     fn children(&self, node: &usize) -> Vec<usize> { if *node < 10 { vec![2 * node + 1, 2 * node + 2] } else { vec![] } }
+/// #     fn label(&self, node: &usize) -> egui::WidgetText { node.to_string().into() }
+/// # }
+/// # egui::__run_test_ui(|ui| {
+/// let response = egui::TreeView::new("my_tree").show(ui, &MyTree);
+/// if let Some(drop) = response.dropped {
+///     // Move `drop.nodes` to `drop.parent` …
+/// }
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+#[derive(Clone, Debug)]
+pub struct TreeView {
+    id_salt: Id,
+    row_height: Option<f32>,
+    indent: Option<f32>,
+    max_height: f32,
+    multi_select: bool,
+    drag_and_drop: bool,
+    nodes_changed: bool,
+}
+
+impl TreeView {
+    pub fn new(id_salt: impl Hash) -> Self {
+        Self {
+            id_salt: Id::new(id_salt),
+            row_height: None,
+            indent: None,
+            max_height: f32::INFINITY,
+            multi_select: true,
+            drag_and_drop: true,
+            nodes_changed: false,
+        }
+    }
+
+    /// Height of each row.
+    ///
+    /// Default: [`crate::Spacing::interact_size`]`.y`.
+    #[inline]
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// How far each level is indented.
+    ///
+    /// Default: [`crate::Spacing::indent`].
+    #[inline]
+    pub fn indent(mut self, indent: f32) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// The tree view will scroll when taller than this.
+    ///
+    /// Default: [`f32::INFINITY`], i.e. as much space as is available.
+    #[inline]
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    /// Can more than one node be selected?
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+
+    /// Can rows be dragged onto other rows?
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn drag_and_drop(mut self, drag_and_drop: bool) -> Self {
+        self.drag_and_drop = drag_and_drop;
+        self
+    }
+
+    /// Set to `true` when the nodes have changed since last frame,
+    /// e.g. when some were added, removed or moved.
+    ///
+    /// The tree view remembers which rows to show, and only asks the [`TreeViewNodes`] again
+    /// when a node is expanded or collapsed, or when this is set.
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn nodes_changed(mut self, nodes_changed: bool) -> Self {
+        self.nodes_changed = nodes_changed;
+        self
+    }
+
+    pub fn show<N: TreeViewNodes>(self, ui: &mut Ui, nodes: &N) -> TreeViewResponse<N::NodeId> {
+        let Self {
+            id_salt,
+            row_height,
+            indent,
+            max_height,
+            multi_select,
+            drag_and_drop,
+            nodes_changed,
+        } = self;
+
+        let id = ui.make_persistent_id(id_salt);
+        let row_height = row_height.unwrap_or_else(|| ui.spacing().interact_size.y);
+        let indent = indent.unwrap_or_else(|| ui.spacing().indent);
+
+        let mut state = TreeViewState::load(ui.ctx(), id).unwrap_or_default();
+        if nodes_changed {
+            state.rows = None;
+        }
+        let mut rows = state.rows(nodes);
+
+        let mut selection_changed = false;
+        let mut activated = None;
+        let mut scroll_to_cursor = false;
+
+        let has_focus = ui.memory(|mem| mem.has_focus(id));
+        if has_focus {
+            let event_filter = EventFilter {
+                horizontal_arrows: true,
+                vertical_arrows: true,
+                ..Default::default()
+            };
+            ui.memory_mut(|mem| mem.set_focus_lock_filter(id, event_filter));
+
+            let events = ui.input(|i| i.filtered_events(&event_filter));
+            for event in events {
+                let Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } = event
+                else {
+                    continue;
+                };
+                if rows.is_empty() {
+                    break;
+                }
+
+                let cursor = state
+                    .cursor
+                    .as_ref()
+                    .and_then(|cursor| rows.iter().position(|row| &row.node == cursor));
+                let last = rows.len() - 1;
+
+                let new_cursor = match key {
+                    Key::ArrowUp => Some(cursor.map_or(0, |i| i.saturating_sub(1))),
+                    Key::ArrowDown => Some(cursor.map_or(0, |i| (i + 1).min(last))),
+                    Key::Home => Some(0),
+                    Key::End => Some(last),
+                    Key::ArrowRight => cursor.and_then(|i| {
+                        let row = &rows[i];
+                        if !row.has_children {
+                            None
+                        } else if state.is_expanded(&row.node) {
+                            Some((i + 1).min(last))
+                        } else {
+                            state.set_expanded(row.node.clone(), true);
+                            None
+                        }
+                    }),
+                    Key::ArrowLeft => cursor.and_then(|i| {
+                        let row = &rows[i];
+                        if row.has_children && state.is_expanded(&row.node) {
+                            state.set_expanded(row.node.clone(), false);
+                            None
+                        } else {
+                            row.parent
+                        }
+                    }),
+                    Key::Space => {
+                        if let Some(i) = cursor {
+                            if multi_select && modifiers.command {
+                                state.toggle_selected(&rows[i].node);
+                            } else {
+                                state.select_only(&rows[i].node);
+                            }
+                            selection_changed = true;
+                        }
+                        None
+                    }
+                    Key::Enter => {
+                        activated = cursor.map(|i| rows[i].node.clone());
+                        None
+                    }
+                    _ => None,
+                };
+
+                if let Some(index) = new_cursor {
+                    let modifiers = if matches!(key, Key::ArrowLeft | Key::ArrowRight) {
+                        Modifiers::NONE
+                    } else {
+                        modifiers
+                    };
+                    state.select_with_modifiers(&rows, index, modifiers, multi_select, false);
+                    selection_changed |= !modifiers.command || modifiers.shift;
+                    scroll_to_cursor = true;
+                }
+
+                // Expanding or collapsing changes the visible rows:
+                rows = state.rows(nodes);
+            }
+        }
+
+        let mut dropped = None;
+        let mut clicked_row = None;
+        let mut toggled_row = None;
+        let mut request_focus = false;
+
+        #[cfg(feature = "accesskit")]
+        ui.ctx().accesskit_node_builder(id, |builder| {
+            builder.set_role(accesskit::Role::Tree);
+        });
+
+        let scroll_output = ui.ctx().clone().with_accessibility_parent(id, || {
+            ScrollArea::vertical()
+                .id_salt(id.with("scroll"))
+                .max_height(max_height)
+                .auto_shrink([false, true])
+                .show_rows(ui, row_height, rows.len(), |ui, row_range| {
+                    let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+                    let first_row_top = ui.max_rect().top();
+                    let first_row = row_range.start;
+                    let row_top = |index: usize| {
+                        first_row_top + (index as f32 - first_row as f32) * row_height_with_spacing
+                    };
+
+                    if scroll_to_cursor {
+                        let cursor = state
+                            .cursor
+                            .as_ref()
+                            .and_then(|cursor| rows.iter().position(|row| &row.node == cursor));
+                        if let Some(index) = cursor {
+                            let top = row_top(index);
+                            let rect = Rect::from_x_y_ranges(
+                                ui.max_rect().x_range(),
+                                top..=top + row_height,
+                            );
+                            ui.scroll_to_rect(rect, None);
+                        }
+                    }
+
+                    for index in row_range {
+                        let row = &rows[index];
+                        let rect = Rect::from_min_size(
+                            pos2(ui.max_rect().left(), row_top(index)),
+                            vec2(ui.available_width(), row_height),
+                        );
+                        ui.allocate_rect(rect, Sense::hover());
+
+                        let row_id = id.with(&row.node);
+                        let sense = if drag_and_drop {
+                            Sense::click_and_drag()
+                        } else {
+                            Sense::click()
+                        };
+                        let response = ui.interact(rect, row_id, sense);
+
+                        let expanded = state.is_expanded(&row.node);
+                        let selected = state.is_selected(&row.node);
+                        let is_cursor = state.cursor.as_ref() == Some(&row.node);
+
+                        let icon_width = ui.spacing().icon_width;
+                        let icon_rect = Rect::from_center_size(
+                            pos2(
+                                rect.left() + row.depth as f32 * indent + 0.5 * icon_width,
+                                rect.center().y,
+                            ),
+                            vec2(icon_width, icon_width),
+                        );
+                        let icon_response = row
+                            .has_children
+                            .then(|| ui.interact(icon_rect, row_id.with("expand"), Sense::click()));
+
+                        let text_left = icon_rect.right() + ui.spacing().icon_spacing;
+                        let galley = nodes.label(&row.node).into_galley(
+                            ui,
+                            Some(TextWrapMode::Truncate),
+                            (rect.right() - text_left).at_least(0.0),
+                            TextStyle::Button,
+                        );
+
+                        response.widget_info(|| {
+                            WidgetInfo::selected(
+                                WidgetType::TreeItem,
+                                ui.is_enabled(),
+                                selected,
+                                galley.text(),
+                            )
+                        });
+                        #[cfg(feature = "accesskit")]
+                        ui.ctx().accesskit_node_builder(row_id, |builder| {
+                            builder.set_level(row.depth + 1);
+                            if row.has_children {
+                                builder.set_expanded(expanded);
+                            }
+                        });
+
+                        if ui.is_rect_visible(rect) {
+                            let visuals = ui.style().interact_selectable(&response, selected);
+                            if selected || response.hovered() || (has_focus && is_cursor) {
+                                let stroke = if has_focus && is_cursor {
+                                    ui.visuals().selection.stroke
+                                } else {
+                                    visuals.bg_stroke
+                                };
+                                let fill = if selected || response.hovered() {
+                                    visuals.weak_bg_fill
+                                } else {
+                                    crate::Color32::TRANSPARENT
+                                };
+                                ui.painter().rect(rect, visuals.rounding, fill, stroke);
+                            }
+
+                            if let Some(icon_response) = &icon_response {
+                                let openness = ui
+                                    .ctx()
+                                    .animate_bool_responsive(row_id.with("expand"), expanded);
+                                paint_default_icon(ui, openness, icon_response);
+                            }
+
+                            let text_pos = pos2(text_left, rect.center().y - 0.5 * galley.size().y);
+                            ui.painter().galley(text_pos, galley, visuals.text_color());
+                        }
+
+                        if icon_response.as_ref().is_some_and(|r| r.clicked()) {
+                            toggled_row = Some(index);
+                        } else if response.double_clicked() {
+                            if row.has_children {
+                                toggled_row = Some(index);
+                            }
+                            activated = Some(row.node.clone());
+                        } else if response.clicked() {
+                            clicked_row = Some(index);
+                        }
+                        if response.clicked() || response.drag_started() {
+                            request_focus = true;
+                        }
+
+                        if drag_and_drop {
+                            if response.drag_started() {
+                                let nodes = if selected {
+                                    rows.iter()
+                                        .filter(|row| state.is_selected(&row.node))
+                                        .map(|row| row.node.clone())
+                                        .collect()
+                                } else {
+                                    vec![row.node.clone()]
+                                };
+                                DragAndDrop::set_payload(
+                                    ui.ctx(),
+                                    TreeViewDragPayload { tree_id: id, nodes },
+                                );
+                            }
+
+                            if let Some(drop) =
+                                drop_target(ui, nodes, &rows, index, &response, id, indent)
+                            {
+                                dropped = Some(drop);
+                            }
+                        }
+                    }
+                })
+        });
+
+        if let Some(index) = clicked_row {
+            let modifiers = ui.input(|i| i.modifiers);
+            state.select_with_modifiers(&rows, index, modifiers, multi_select, true);
+            selection_changed = true;
+        }
+        if let Some(index) = toggled_row {
+            let node = rows[index].node.clone();
+            let expanded = state.is_expanded(&node);
+            state.set_expanded(node, !expanded);
+        }
+
+        let response = ui.interact(
+            scroll_output.inner_rect,
+            id,
+            Sense::focusable_noninteractive(),
+        );
+        if request_focus {
+            response.request_focus();
+        }
+        response.widget_info(|| WidgetInfo::new(WidgetType::Tree));
+
+        state.clone().store(ui.ctx(), id);
+
+        TreeViewResponse {
+            response,
+            state,
+            selection_changed,
+            activated,
+            dropped,
+        }
+    }
+}
+
+/// Paint where the nodes being dragged over `rows[index]` would end up,
+/// and return where they were dropped, if they were.
+fn drop_target<N: TreeViewNodes>(
+    ui: &Ui,
+    nodes: &N,
+    rows: &[Row<N::NodeId>],
+    index: usize,
+    response: &Response,
+    tree_id: Id,
+    indent: f32,
+) -> Option<TreeViewDrop<N::NodeId>> {
+    let payload = response.dnd_hover_payload::<TreeViewDragPayload<N::NodeId>>()?;
+    if payload.tree_id != tree_id {
+        return None;
+    }
+    let pointer = ui.ctx().pointer_interact_pos()?;
+
+    // Don't allow dropping nodes into themselves:
+    let mut ancestor = Some(index);
+    while let Some(i) = ancestor {
+        if payload.nodes.contains(&rows[i].node) {
+            return None;
+        }
+        ancestor = rows[i].parent;
+    }
+
+    let row = &rows[index];
+    let rect = response.rect;
+    let t = (pointer.y - rect.top()) / rect.height();
+    let position = if nodes.accepts_children(&row.node) && 0.25 < t && t < 0.75 {
+        DropPosition::Into
+    } else if t < 0.5 {
+        DropPosition::Before
+    } else {
+        DropPosition::After
+    };
+
+    ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
+    let stroke = ui.visuals().selection.stroke;
+    let left = rect.left() + row.depth as f32 * indent;
+    let gap = 0.5 * ui.spacing().item_spacing.y;
+    match position {
+        DropPosition::Before => {
+            ui.painter()
+                .hline(left..=rect.right(), rect.top() - gap, stroke);
+        }
+        DropPosition::Into => {
+            ui.painter().rect_stroke(rect, 2.0, stroke);
+        }
+        DropPosition::After => {
+            ui.painter()
+                .hline(left..=rect.right(), rect.bottom() + gap, stroke);
+        }
+    }
+
+    let payload: Arc<TreeViewDragPayload<N::NodeId>> = response.dnd_release_payload()?;
+
+    let (parent, index) = if position == DropPosition::Into {
+        let index = nodes.children(&row.node).len();
+        (Some(row.node.clone()), index)
+    } else {
+        let parent = row.parent.map(|parent| rows[parent].node.clone());
+        let siblings = parent
+            .as_ref()
+            .map_or_else(|| nodes.roots(), |parent| nodes.children(parent));
+        let index = siblings
+            .iter()
+            .position(|sibling| sibling == &row.node)
+            .unwrap_or(siblings.len());
+        let index = if position == DropPosition::After {
+            index + 1
+        } else {
+            index
+        };
+        (parent, index)
+    };
+
+    Some(TreeViewDrop {
+        nodes: payload.nodes.clone(),
+        parent,
+        index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Node `n` has children `2n + 1` and `2n + 2`, up to 6.
+    struct BinaryTree;
+
+    impl TreeViewNodes for BinaryTree {
+        type NodeId = usize;
+
+        fn roots(&self) -> Vec<usize> {
+            vec![0]
+        }
+
+        fn children(&self, node: &usize) -> Vec<usize> {
+            if *node < 3 {
+                vec![2 * node + 1, 2 * node + 2]
+            } else {
+                vec![]
+            }
+        }
+
+        fn label(&self, node: &usize) -> WidgetText {
+            node.to_string().into()
+        }
+    }
+
+    fn visible_nodes(state: &TreeViewState<usize>) -> Vec<usize> {
+        flatten(&BinaryTree, state)
+            .into_iter()
+            .map(|row| row.node)
+            .collect()
+    }
+
+    #[test]
+    fn flatten_expanded_nodes() {
+        let mut state = TreeViewState::default();
+        assert_eq!(visible_nodes(&state), vec![0]);
+
+        state.set_expanded(0, true);
+        state.set_expanded(2, true);
+        assert_eq!(visible_nodes(&state), vec![0, 1, 2, 5, 6]);
+
+        let rows = flatten(&BinaryTree, &state);
+        assert_eq!(rows[3].depth, 2);
+        assert_eq!(rows[3].parent, Some(2));
+        assert!(rows[1].has_children);
+        assert!(!rows[4].has_children);
+    }
+
+    #[test]
+    fn range_selection() {
+        let mut state = TreeViewState::default();
+        state.set_expanded(0, true);
+        state.set_expanded(2, true);
+        let rows = flatten(&BinaryTree, &state);
+
+        let click = |state: &mut TreeViewState<usize>, index, modifiers| {
+            state.select_with_modifiers(&rows, index, modifiers, true, true);
+            let mut selected: Vec<usize> = state.selected().copied().collect();
+            selected.sort_unstable();
+            selected
+        };
+
+        assert_eq!(click(&mut state, 1, Modifiers::NONE), vec![1]);
+        assert_eq!(click(&mut state, 3, Modifiers::SHIFT), vec![1, 2, 5]);
+        assert_eq!(click(&mut state, 0, Modifiers::COMMAND), vec![0, 1, 2, 5]);
+        assert_eq!(click(&mut state, 2, Modifiers::COMMAND), vec![0, 1, 5]);
+        assert_eq!(click(&mut state, 4, Modifiers::NONE), vec![6]);
+        assert_eq!(state.cursor(), Some(&6));
+    }
+
+    /// A [`BinaryTree`] that counts how often it is asked for children.
+    #[derive(Default)]
+    struct CountingTree(std::cell::Cell<usize>);
+
+    impl TreeViewNodes for CountingTree {
+        type NodeId = usize;
+
+        fn roots(&self) -> Vec<usize> {
+            BinaryTree.roots()
+        }
+
+        fn children(&self, node: &usize) -> Vec<usize> {
+            self.0.set(self.0.get() + 1);
+            BinaryTree.children(node)
+        }
+
+        fn label(&self, node: &usize) -> WidgetText {
+            BinaryTree.label(node)
+        }
+    }
+
+    #[test]
+    fn rows_are_only_flattened_when_they_change() {
+        let ctx = Context::default();
+        let tree = CountingTree::default();
+        let mut id = Id::NULL;
+        let mut run = |nodes_changed: bool| {
+            let _ = ctx.run(Default::default(), |ctx| {
+                crate::CentralPanel::default().show(ctx, |ui| {
+                    id = TreeView::new("tree")
+                        .nodes_changed(nodes_changed)
+                        .show(ui, &tree)
+                        .response
+                        .id;
+                });
+            });
+            (id, tree.0.take())
+        };
+
+        let (id, children_calls) = run(false);
+        assert_eq!(children_calls, 1, "Only the root is visible");
+        assert_eq!(run(false).1, 0);
+
+        let mut state = TreeViewState::<usize>::load(&ctx, id).unwrap();
+        state.set_expanded(0, true);
+        state.store(&ctx, id);
+        assert_eq!(run(false).1, 3, "The root and its two children");
+        assert_eq!(run(false).1, 0);
+
+        assert_eq!(run(true).1, 3);
+        assert_eq!(run(false).1, 0);
+    }
+}
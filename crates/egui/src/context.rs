@@ -564,7 +564,7 @@ impl ContextImpl {
 
         if let Some(font_definitions) = self.memory.new_font_definitions.take() {
             // New font definition loaded, so we need to reload all fonts.
-            self.fonts.clear();
+            clear_fonts(&self.tex_manager, &mut self.fonts);
             self.font_definitions = font_definitions;
             #[cfg(feature = "log")]
             log::trace!("Loading new font definitions");
@@ -573,7 +573,7 @@ impl ContextImpl {
         if !self.memory.add_fonts.is_empty() {
             let fonts = self.memory.add_fonts.drain(..);
             for font in fonts {
-                self.fonts.clear(); // recreate all the fonts
+                clear_fonts(&self.tex_manager, &mut self.fonts); // recreate all the fonts
                 for family in font.families {
                     let fam = self
                         .font_definitions
@@ -757,6 +757,7 @@ impl Default for Context {
 
         // Register built-in plugins:
         crate::debug_text::register(&ctx);
+        crate::toasts::register(&ctx);
         crate::text_selection::LabelSelectionState::register(&ctx);
         crate::DragAndDrop::register(&ctx);
 
@@ -1405,6 +1406,20 @@ impl Context {
         crate::debug_text::print(self, text);
     }
 
+    /// Show a transient notification, stacked with the others at a corner of the screen.
+    ///
+    /// Returns the id of the toast, which can be passed to [`crate::toasts::dismiss`].
+    ///
+    /// ```
+    /// # let ctx = egui::Context::default();
+    /// ctx.notify(egui::Toast::success("Saved"));
+    /// ```
+    ///
+    /// This is just a convenience for calling [`crate::toasts::notify`].
+    pub fn notify(&self, toast: crate::Toast) -> Id {
+        crate::toasts::notify(self, toast)
+    }
+
     /// What operating system are we running on?
     ///
     /// When compiling natively, this is
@@ -2300,10 +2315,7 @@ impl ContextImpl {
 
         if let Some(fonts) = self.fonts.get(&pixels_per_point.into()) {
             let tex_mngr = &mut self.tex_manager.0.write();
-            if let Some(font_image_delta) = fonts.font_image_delta() {
-                // A partial font atlas update, e.g. a new glyph has been entered.
-                tex_mngr.set(TextureId::default(), font_image_delta);
-            }
+            upload_font_atlas_pages(tex_mngr, fonts);
 
             if 1 < self.fonts.len() {
                 // We have multiple different `pixels_per_point`,
@@ -2465,7 +2477,7 @@ impl ContextImpl {
             .values()
             .map(|v| v.input.pixels_per_point.into())
             .collect();
-        self.fonts.retain(|pixels_per_point, _| {
+        self.fonts.retain(|pixels_per_point, fonts| {
             if active_pixels_per_point.contains(pixels_per_point) {
                 true
             } else {
@@ -2474,6 +2486,7 @@ impl ContextImpl {
                     "Freeing Fonts with pixels_per_point={} because it is no longer needed",
                     pixels_per_point.into_inner()
                 );
+                free_font_atlas_pages(&mut self.tex_manager.0.write(), fonts);
                 false
             }
         });
@@ -2490,6 +2503,56 @@ impl ContextImpl {
     }
 }
 
+/// Upload the changes to all pages of the font atlas.
+///
+/// The first page is uploaded to [`TextureId::default`], the others get textures of their own.
+fn upload_font_atlas_pages(tex_mngr: &mut epaint::TextureManager, fonts: &Fonts) {
+    let atlas = fonts.texture_atlas();
+    let mut atlas = atlas.lock();
+
+    for (page, delta) in atlas.take_page_deltas() {
+        if let Some(texture_id) = atlas.page_texture_id(page) {
+            // A partial font atlas update, e.g. a new glyph has been entered.
+            tex_mngr.set(texture_id, delta);
+        } else {
+            // A new page always starts with a full delta.
+            let texture_id = tex_mngr.alloc(
+                format!("egui_font_texture_{page}"),
+                delta.image,
+                delta.options,
+            );
+            atlas.set_page_texture_id(page, texture_id);
+        }
+    }
+
+    for texture_id in atlas.take_freed_textures() {
+        tex_mngr.free(texture_id);
+    }
+}
+
+/// Drop all fonts, and free the textures of their atlas pages.
+fn clear_fonts(
+    tex_manager: &WrappedTextureManager,
+    fonts: &mut std::collections::BTreeMap<OrderedFloat<f32>, Fonts>,
+) {
+    let tex_mngr = &mut tex_manager.0.write();
+    for fonts in std::mem::take(fonts).values() {
+        free_font_atlas_pages(tex_mngr, fonts);
+    }
+}
+
+/// Free the textures of the font atlas pages of [`Fonts`] we no longer use.
+fn free_font_atlas_pages(tex_mngr: &mut epaint::TextureManager, fonts: &Fonts) {
+    let atlas = fonts.texture_atlas();
+    let mut atlas = atlas.lock();
+
+    // The first page is always `TextureId::default()`, which we keep.
+    let page_textures = atlas.take_page_texture_ids().into_iter().skip(1);
+    for texture_id in page_textures.chain(atlas.take_freed_textures()) {
+        tex_mngr.free(texture_id);
+    }
+}
+
 impl Context {
     /// Tessellate the given shapes into triangle meshes.
     ///
@@ -2521,21 +2584,22 @@ impl Context {
                     .1
                     .texture_atlas()
             };
-            let (font_tex_size, prepared_discs) = {
+            let (font_tex_size, font_pages, prepared_discs) = {
                 let atlas = texture_atlas.lock();
-                (atlas.size(), atlas.prepared_discs())
+                (atlas.size(), atlas.texture_pages(), atlas.prepared_discs())
             };
 
             let paint_stats = PaintStats::from_shapes(&shapes);
             let clipped_primitives = {
                 profiling::scope!("tessellator::tessellate_shapes");
-                tessellator::Tessellator::new(
+                let mut tessellator = tessellator::Tessellator::new(
                     pixels_per_point,
                     tessellation_options,
                     font_tex_size,
                     prepared_discs,
-                )
-                .tessellate_shapes(shapes)
+                );
+                tessellator.set_font_pages(font_pages);
+                tessellator.tessellate_shapes(shapes)
             };
             ctx.paint_stats = paint_stats.with_clipped_primitives(&clipped_primitives);
             clipped_primitives
@@ -704,6 +704,8 @@ impl WidgetInfo {
             WidgetType::CollapsingHeader => "collapsing header",
             WidgetType::ProgressIndicator => "progress indicator",
             WidgetType::Window => "window",
+            WidgetType::Tree => "tree",
+            WidgetType::TreeItem => "tree item",
             WidgetType::Label | WidgetType::Other => "",
         };
 
@@ -431,6 +431,7 @@ pub mod response;
 mod sense;
 pub mod style;
 pub mod text_selection;
+pub mod toasts;
 mod ui;
 mod ui_builder;
 mod ui_stack;
@@ -462,7 +463,7 @@ pub use emath::{
 };
 pub use epaint::{
     mutex,
-    text::{FontData, FontDefinitions, FontFamily, FontId, FontTweak},
+    text::{FontData, FontDefinitions, FontFamily, FontId, FontTweak, FontWeight},
     textures::{TextureFilter, TextureOptions, TextureWrapMode, TexturesDelta},
     ClippedPrimitive, ColorImage, FontImage, ImageData, Margin, Mesh, PaintCallback,
     PaintCallbackInfo, Rounding, Shadow, Shape, Stroke, TextureHandle, TextureId,
@@ -472,7 +473,8 @@ pub mod text {
     pub use crate::text_selection::{CCursorRange, CursorRange};
     pub use epaint::text::{
         cursor::CCursor, FontData, FontDefinitions, FontFamily, Fonts, Galley, LayoutJob,
-        LayoutSection, TextFormat, TextWrapping, TAB_SIZE,
+        LayoutSection, Placeholder, TextDecoration, TextDecorationLine, TextDecorationStyle,
+        TextFormat, TextWrapping, TAB_SIZE,
     };
 }
 
@@ -501,6 +503,7 @@ pub use self::{
     sense::Sense,
     style::{FontSelection, Spacing, Style, TextStyle, Visuals},
     text::{Galley, TextFormat},
+    toasts::{Toast, ToastLevel},
     ui::Ui,
     ui_builder::UiBuilder,
     ui_stack::*,
@@ -674,6 +677,12 @@ pub enum WidgetType {
 
     Window,
 
+    /// A [`TreeView`].
+    Tree,
+
+    /// A row in a [`TreeView`].
+    TreeItem,
+
     /// If you cannot fit any of the above slots.
     ///
     /// If this is something you think should be added, file an issue.
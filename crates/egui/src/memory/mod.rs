@@ -973,6 +973,13 @@ impl Memory {
         self.focus()?.top_modal_layer()
     }
 
+    /// Don't move the keyboard focus this pass, e.g. because a widget
+    /// used the key that would have moved it (like Ctrl+Tab in [`crate::Tabs`]).
+    #[inline(always)]
+    pub(crate) fn cancel_focus_move(&mut self) {
+        self.focus_mut().reset_focus();
+    }
+
     /// Stop editing the active [`TextEdit`](crate::TextEdit) (if any).
     #[inline(always)]
     pub fn stop_text_input(&mut self) {
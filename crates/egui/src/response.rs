@@ -1031,6 +1031,8 @@ impl Response {
             WidgetType::ColorButton => Role::ColorWell,
             WidgetType::ProgressIndicator => Role::ProgressIndicator,
             WidgetType::Window => Role::Window,
+            WidgetType::Tree => Role::Tree,
+            WidgetType::TreeItem => Role::TreeItem,
             WidgetType::Other => Role::Unknown,
         });
         if !info.enabled {
@@ -1049,7 +1051,9 @@ impl Response {
         if let Some(value) = info.value {
             builder.set_numeric_value(value);
         }
-        if let Some(selected) = info.selected {
+        if let (WidgetType::TreeItem, Some(selected)) = (info.typ, info.selected) {
+            builder.set_selected(selected);
+        } else if let Some(selected) = info.selected {
             builder.set_toggled(if selected {
                 Toggled::True
             } else {
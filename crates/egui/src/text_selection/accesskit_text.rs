@@ -50,7 +50,11 @@ pub fn update_accesskit_for_text_widget(
                     x1: rect.max.x.into(),
                     y1: rect.max.y.into(),
                 });
-                builder.set_text_direction(accesskit::TextDirection::LeftToRight);
+                builder.set_text_direction(if row.is_rtl {
+                    accesskit::TextDirection::RightToLeft
+                } else {
+                    accesskit::TextDirection::LeftToRight
+                });
                 // TODO(mwcampbell): Set more node fields for the row
                 // once AccessKit adapters expose text formatting info.
 
@@ -74,7 +78,12 @@ pub fn update_accesskit_for_text_widget(
                     let old_len = value.len();
                     value.push(glyph.chr);
                     character_lengths.push((value.len() - old_len) as _);
-                    character_positions.push(glyph.pos.x - row.rect.min.x);
+                    // Positions are measured in the direction of the text:
+                    character_positions.push(if row.is_rtl {
+                        row.rect.max.x - glyph.max_x()
+                    } else {
+                        glyph.pos.x - row.rect.min.x
+                    });
                     character_widths.push(glyph.advance_width);
                 }
 
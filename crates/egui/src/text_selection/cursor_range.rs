@@ -132,7 +132,11 @@ impl CursorRange {
             }
 
             Key::ArrowLeft | Key::ArrowRight if modifiers.is_none() && !self.is_empty() => {
-                if key == Key::ArrowLeft {
+                let is_rtl = galley
+                    .rows
+                    .get(self.primary.rcursor.row)
+                    .is_some_and(|row| row.is_rtl);
+                if (key == Key::ArrowLeft) != is_rtl {
                     *self = Self::one(self.sorted_cursors()[0]);
                 } else {
                     *self = Self::one(self.sorted_cursors()[1]);
@@ -321,27 +325,35 @@ fn move_single_cursor(
         }
         return;
     }
+
+    // In a right-to-left paragraph, the start of the row and the previous word are to the right:
+    let is_rtl = galley
+        .rows
+        .get(cursor.rcursor.row)
+        .is_some_and(|row| row.is_rtl);
+
     match key {
-        Key::ArrowLeft => {
-            if modifiers.alt || modifiers.ctrl {
-                // alt on mac, ctrl on windows
+        Key::ArrowLeft | Key::ArrowRight if modifiers.alt || modifiers.ctrl => {
+            // alt on mac, ctrl on windows
+            if (key == Key::ArrowLeft) != is_rtl {
                 *cursor = galley.from_ccursor(ccursor_previous_word(galley, cursor.ccursor));
-            } else if modifiers.mac_cmd {
-                *cursor = galley.cursor_begin_of_row(cursor);
             } else {
-                *cursor = galley.cursor_left_one_character(cursor);
+                *cursor = galley.from_ccursor(ccursor_next_word(galley, cursor.ccursor));
             }
         }
-        Key::ArrowRight => {
-            if modifiers.alt || modifiers.ctrl {
-                // alt on mac, ctrl on windows
-                *cursor = galley.from_ccursor(ccursor_next_word(galley, cursor.ccursor));
-            } else if modifiers.mac_cmd {
-                *cursor = galley.cursor_end_of_row(cursor);
+        Key::ArrowLeft | Key::ArrowRight if modifiers.mac_cmd => {
+            if (key == Key::ArrowLeft) != is_rtl {
+                *cursor = galley.cursor_begin_of_row(cursor);
             } else {
-                *cursor = galley.cursor_right_one_character(cursor);
+                *cursor = galley.cursor_end_of_row(cursor);
             }
         }
+        Key::ArrowLeft => {
+            *cursor = galley.cursor_left_one_character_visually(cursor);
+        }
+        Key::ArrowRight => {
+            *cursor = galley.cursor_right_one_character_visually(cursor);
+        }
         Key::ArrowUp => {
             if modifiers.command {
                 // mac and windows behavior
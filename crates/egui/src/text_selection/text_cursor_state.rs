@@ -152,7 +152,8 @@ impl TextCursorState {
     }
 }
 
-fn select_word_at(text: &str, ccursor: CCursor) -> CCursorRange {
+/// The word at the cursor, e.g. for selecting it with a double-click.
+pub fn select_word_at(text: &str, ccursor: CCursor) -> CCursorRange {
     if ccursor.index == 0 {
         CCursorRange::two(ccursor, ccursor_next_word(text, ccursor))
     } else {
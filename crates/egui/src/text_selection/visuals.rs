@@ -32,62 +32,113 @@ pub fn paint_text_selection(
 
     for ri in min.row..=max.row {
         let row = &mut galley.rows[ri];
-        let left = if ri == min.row {
-            row.x_offset(min.column)
+        let newline_size = if ri != max.row && row.ends_with_newline {
+            row.height() / 2.0 // visualize that we select the newline
         } else {
-            row.rect.left()
+            0.0
         };
-        let right = if ri == max.row {
-            row.x_offset(max.column)
+
+        let x_ranges = if row.has_rtl() {
+            let first_column = if ri == min.row { min.column } else { 0 };
+            let last_column = if ri == max.row {
+                max.column
+            } else {
+                row.char_count_excluding_newline()
+            };
+            selected_x_ranges(row, first_column..last_column, newline_size)
         } else {
-            let newline_size = if row.ends_with_newline {
-                row.height() / 2.0 // visualize that we select the newline
+            let left = if ri == min.row {
+                row.x_offset(min.column)
             } else {
-                0.0
+                row.rect.left()
             };
-            row.rect.right() + newline_size
+            let right = if ri == max.row {
+                row.x_offset(max.column)
+            } else {
+                row.rect.right() + newline_size
+            };
+            vec![(left, right)]
         };
 
-        let rect = Rect::from_min_max(pos2(left, row.min_y()), pos2(right, row.max_y()));
-        let mesh = &mut row.visuals.mesh;
-
-        // Time to insert the selection rectangle into the row mesh.
-        // It should be on top (after) of any background in the galley,
-        // but behind (before) any glyphs. The row visuals has this information:
-        let glyph_index_start = row.visuals.glyph_index_start;
-
-        // Start by appending the selection rectangle to end of the mesh, as two triangles (= 6 indices):
-        let num_indices_before = mesh.indices.len();
-        mesh.add_colored_rect(rect, color);
-        assert_eq!(num_indices_before + 6, mesh.indices.len());
-
-        // Copy out the new triangles:
-        let selection_triangles = [
-            mesh.indices[num_indices_before],
-            mesh.indices[num_indices_before + 1],
-            mesh.indices[num_indices_before + 2],
-            mesh.indices[num_indices_before + 3],
-            mesh.indices[num_indices_before + 4],
-            mesh.indices[num_indices_before + 5],
-        ];
-
-        // Move every old triangle forwards by 6 indices to make room for the new triangle:
-        for i in (glyph_index_start..num_indices_before).rev() {
-            mesh.indices.swap(i, i + 6);
+        for (left, right) in x_ranges {
+            let rect = Rect::from_min_max(pos2(left, row.min_y()), pos2(right, row.max_y()));
+            let mesh = &mut row.visuals.mesh;
+
+            // Time to insert the selection rectangle into the row mesh.
+            // It should be on top (after) of any background in the galley,
+            // but behind (before) any glyphs. The row visuals has this information:
+            let glyph_index_start = row.visuals.glyph_index_start;
+
+            // Start by appending the selection rectangle to end of the mesh, as two triangles (= 6 indices):
+            let num_indices_before = mesh.indices.len();
+            mesh.add_colored_rect(rect, color);
+            assert_eq!(num_indices_before + 6, mesh.indices.len());
+
+            // Copy out the new triangles:
+            let selection_triangles = [
+                mesh.indices[num_indices_before],
+                mesh.indices[num_indices_before + 1],
+                mesh.indices[num_indices_before + 2],
+                mesh.indices[num_indices_before + 3],
+                mesh.indices[num_indices_before + 4],
+                mesh.indices[num_indices_before + 5],
+            ];
+
+            // Move every old triangle forwards by 6 indices to make room for the new triangle:
+            for i in (glyph_index_start..num_indices_before).rev() {
+                mesh.indices.swap(i, i + 6);
+            }
+            // Put the new triangle in place:
+            mesh.indices[glyph_index_start..glyph_index_start + 6]
+                .clone_from_slice(&selection_triangles);
+
+            row.visuals.mesh_bounds = mesh.calc_bounds();
+
+            if let Some(new_vertex_indices) = &mut new_vertex_indices {
+                new_vertex_indices.push(RowVertexIndices {
+                    row: ri,
+                    vertex_indices: selection_triangles,
+                });
+            }
         }
-        // Put the new triangle in place:
-        mesh.indices[glyph_index_start..glyph_index_start + 6]
-            .clone_from_slice(&selection_triangles);
+    }
+}
 
-        row.visuals.mesh_bounds = mesh.calc_bounds();
+/// With mixed-direction text, a selection of consecutive characters
+/// can be split into several pieces on screen.
+///
+/// Returns the x ranges of the selected glyphs, from left to right.
+fn selected_x_ranges(
+    row: &epaint::text::Row,
+    columns: std::ops::Range<usize>,
+    newline_size: f32,
+) -> Vec<(f32, f32)> {
+    let mut x_ranges: Vec<(f32, f32)> = vec![];
+    let mut previous_selected = false;
+    for &glyph_index in row.visual_order().iter() {
+        let selected = columns.contains(&glyph_index);
+        if selected {
+            let glyph = &row.glyphs[glyph_index];
+            let left = glyph.pos.x;
+            let right = glyph.max_x();
+            match x_ranges.last_mut() {
+                Some(x_range) if previous_selected => x_range.1 = right,
+                _ => x_ranges.push((left, right)),
+            }
+        }
+        previous_selected = selected;
+    }
 
-        if let Some(new_vertex_indices) = &mut new_vertex_indices {
-            new_vertex_indices.push(RowVertexIndices {
-                row: ri,
-                vertex_indices: selection_triangles,
-            });
+    if 0.0 < newline_size {
+        // The newline is at the end of the row, as seen in the paragraph direction:
+        if row.is_rtl {
+            x_ranges.insert(0, (row.rect.left() - newline_size, row.rect.left()));
+        } else {
+            x_ranges.push((row.rect.right(), row.rect.right() + newline_size));
         }
     }
+
+    x_ranges
 }
 
 /// Paint one end of the selection, e.g. the primary cursor.
@@ -0,0 +1,397 @@
+//! Toast notifications: short, transient messages like "Saved" or "Connection lost".
+//!
+//! Toasts are shown with [`Context::notify`] and stack up at a configurable corner of the screen
+//! (see [`ToastOptions::anchor`]).
+//! Each toast disappears after its timeout, unless the user hovers it,
+//! which pauses the timer.
+//!
+//! This is a built-in plugin in egui, so no setup is needed:
+//!
+//! ```
+//! # egui::__run_test_ctx(|ctx| {
+//! ctx.notify(egui::Toast::success("Saved"));
+//! ctx.notify(egui::Toast::error("Connection lost").duration(None)); // stays until closed
+//!
+//! egui::toasts::options_mut(ctx, |options| options.anchor = egui::Align2::LEFT_TOP);
+//! # });
+//! ```
+
+use std::time::Duration;
+
+use crate::{
+    Align, Align2, Area, Button, Color32, Context, Frame, Id, Label, Order, RichText, UiKind, Vec2,
+    Visuals, WidgetText,
+};
+
+/// Register this plugin on the given egui context,
+/// so that it will be called every pass.
+///
+/// This is a built-in plugin in egui,
+/// meaning [`Context`] calls this from its `Default` implementation,
+/// so this is marked as `pub(crate)`.
+pub(crate) fn register(ctx: &Context) {
+    ctx.on_end_pass("toasts", std::sync::Arc::new(State::end_pass));
+}
+
+/// Show a toast. Returns the id of the toast, which can be passed to [`dismiss`].
+///
+/// Usually called via [`Context::notify`].
+pub fn notify(ctx: &Context, toast: Toast) -> Id {
+    ctx.data_mut(|data| {
+        let state = data.get_temp_mut_or_default::<State>(state_id());
+        state.counter += 1;
+        let id = state_id().with(state.counter);
+        state.entries.push(Entry {
+            id,
+            remaining: toast.duration.map(|duration| duration.as_secs_f32()),
+            toast,
+            dismissed: false,
+            is_new: true,
+            height: 0.0,
+        });
+        id
+    })
+}
+
+/// Start fading out the toast with the given id.
+pub fn dismiss(ctx: &Context, id: Id) {
+    ctx.data_mut(|data| {
+        let state = data.get_temp_mut_or_default::<State>(state_id());
+        for entry in &mut state.entries {
+            if entry.id == id {
+                entry.dismissed = true;
+            }
+        }
+    });
+}
+
+/// Start fading out all toasts.
+pub fn dismiss_all(ctx: &Context) {
+    ctx.data_mut(|data| {
+        let state = data.get_temp_mut_or_default::<State>(state_id());
+        for entry in &mut state.entries {
+            entry.dismissed = true;
+        }
+    });
+}
+
+/// The number of toasts currently on screen, including the ones fading out.
+pub fn count(ctx: &Context) -> usize {
+    ctx.data(|data| {
+        data.get_temp::<State>(state_id())
+            .map_or(0, |state| state.entries.len())
+    })
+}
+
+/// Change how toasts are laid out.
+pub fn options_mut<R>(ctx: &Context, writer: impl FnOnce(&mut ToastOptions) -> R) -> R {
+    ctx.data_mut(|data| writer(&mut data.get_temp_mut_or_default::<State>(state_id()).options))
+}
+
+fn state_id() -> Id {
+    Id::new("egui_toasts")
+}
+
+// ----------------------------------------------------------------------------
+
+/// How severe a [`Toast`] is. Decides the icon, and how urgently it is announced to screen readers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ToastLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Info => "ℹ",
+            Self::Success => "✔",
+            Self::Warning => "⚠",
+            Self::Error => "🗙",
+        }
+    }
+
+    fn color(self, visuals: &Visuals) -> Color32 {
+        match self {
+            Self::Info => visuals.hyperlink_color,
+            Self::Success => Color32::from_rgb(0x40, 0xb0, 0x40),
+            Self::Warning => visuals.warn_fg_color,
+            Self::Error => visuals.error_fg_color,
+        }
+    }
+}
+
+/// A transient notification, shown with [`Context::notify`].
+#[derive(Clone)]
+pub struct Toast {
+    text: WidgetText,
+    level: ToastLevel,
+    duration: Option<Duration>,
+    closable: bool,
+}
+
+impl Toast {
+    /// A toast that disappears after four seconds.
+    pub fn new(level: ToastLevel, text: impl Into<WidgetText>) -> Self {
+        Self {
+            text: text.into(),
+            level,
+            duration: Some(Duration::from_secs(4)),
+            closable: true,
+        }
+    }
+
+    pub fn info(text: impl Into<WidgetText>) -> Self {
+        Self::new(ToastLevel::Info, text)
+    }
+
+    pub fn success(text: impl Into<WidgetText>) -> Self {
+        Self::new(ToastLevel::Success, text)
+    }
+
+    pub fn warning(text: impl Into<WidgetText>) -> Self {
+        Self::new(ToastLevel::Warning, text)
+    }
+
+    pub fn error(text: impl Into<WidgetText>) -> Self {
+        Self::new(ToastLevel::Error, text)
+    }
+
+    /// How long until the toast disappears by itself.
+    ///
+    /// `None` means it stays until closed by the user or with [`dismiss`].
+    /// The timer is paused while the toast is hovered.
+    #[inline]
+    pub fn duration(mut self, duration: Option<Duration>) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Show a close button on the toast? Default: `true`.
+    #[inline]
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+
+    #[inline]
+    pub fn level(&self) -> ToastLevel {
+        self.level
+    }
+}
+
+/// Where and how toasts are shown. See [`options_mut`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToastOptions {
+    /// Which corner (or edge) of the screen the toasts stack up from.
+    ///
+    /// Toasts anchored at the bottom grow upwards, all others grow downwards.
+    /// Default: [`Align2::RIGHT_BOTTOM`].
+    pub anchor: Align2,
+
+    /// Distance from the screen edge.
+    pub margin: Vec2,
+
+    /// Vertical space between toasts.
+    pub spacing: f32,
+
+    /// The text of toasts wider than this is wrapped.
+    pub max_width: f32,
+}
+
+impl Default for ToastOptions {
+    fn default() -> Self {
+        Self {
+            anchor: Align2::RIGHT_BOTTOM,
+            margin: Vec2::splat(16.0),
+            spacing: 8.0,
+            max_width: 320.0,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct Entry {
+    id: Id,
+    toast: Toast,
+
+    /// Seconds left until we start fading out, if any.
+    remaining: Option<f32>,
+
+    /// Fading out, and removed once invisible.
+    dismissed: bool,
+
+    /// Not yet shown, so the appear-animation still needs to start at zero.
+    is_new: bool,
+
+    /// Height last pass, used for stacking.
+    height: f32,
+}
+
+#[derive(Clone, Default)]
+struct State {
+    entries: Vec<Entry>,
+    options: ToastOptions,
+    counter: u64,
+}
+
+impl State {
+    fn end_pass(ctx: &Context) {
+        let state = ctx.data_mut(|data| data.remove_temp::<Self>(state_id()));
+        if let Some(mut state) = state {
+            state.show(ctx);
+            ctx.data_mut(|data| data.insert_temp(state_id(), state));
+        }
+    }
+
+    fn show(&mut self, ctx: &Context) {
+        let Self {
+            entries, options, ..
+        } = self;
+
+        if entries.is_empty() {
+            return;
+        }
+
+        let anchor = options.anchor;
+        let anchor_pos = anchor.pos_in_rect(&ctx.screen_rect().shrink2(options.margin));
+        let stack_dir = if anchor.y() == Align::Max { -1.0 } else { 1.0 };
+        let slide_dir = if anchor.x() == Align::Min { -1.0 } else { 1.0 };
+        let animation_time = ctx.style().animation_time;
+        let dt = ctx.input(|i| i.unstable_dt);
+
+        let mut offset = 0.0;
+
+        entries.retain_mut(|entry| {
+            let appear_id = entry.id.with("appear");
+            let appear = if entry.is_new {
+                // Start at zero, so we animate towards one from now on:
+                entry.is_new = false;
+                ctx.animate_value_with_time(appear_id, 0.0, animation_time)
+            } else {
+                let target = if entry.dismissed { 0.0 } else { 1.0 };
+                ctx.animate_value_with_time(appear_id, target, animation_time)
+            };
+
+            if entry.dismissed && appear == 0.0 {
+                return false;
+            }
+
+            let y = ctx.animate_value_with_time(entry.id.with("offset"), offset, animation_time);
+            offset += entry.height + options.spacing;
+
+            let slide = (1.0 - appear) * 2.0 * options.spacing * slide_dir;
+            let pos = anchor_pos + Vec2::new(slide, stack_dir * y);
+
+            let mut close = false;
+            let area_response = Area::new(entry.id)
+                .kind(UiKind::Popup)
+                .order(Order::Foreground)
+                .fixed_pos(pos)
+                .pivot(anchor)
+                .constrain(true)
+                .interactable(!entry.dismissed)
+                .show(ctx, |ui| {
+                    ui.multiply_opacity(appear);
+                    ui.set_max_width(options.max_width);
+
+                    Frame::popup(ui.style()).show(ui, |ui| {
+                        let level = entry.toast.level;
+
+                        #[cfg(feature = "accesskit")]
+                        ui.ctx().accesskit_node_builder(entry.id, |builder| {
+                            builder.set_role(if level == ToastLevel::Error {
+                                accesskit::Role::Alert
+                            } else {
+                                accesskit::Role::Status
+                            });
+                            builder.set_live(match level {
+                                ToastLevel::Info | ToastLevel::Success => accesskit::Live::Polite,
+                                ToastLevel::Warning | ToastLevel::Error => {
+                                    accesskit::Live::Assertive
+                                }
+                            });
+                            builder.set_label(entry.toast.text.text());
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(level.icon()).color(level.color(ui.visuals())));
+                            ui.add(Label::new(entry.toast.text.clone()).wrap());
+                            if entry.toast.closable
+                                && ui.add(Button::new("🗙").frame(false)).clicked()
+                            {
+                                close = true;
+                            }
+                        });
+                    });
+                });
+
+            entry.height = area_response.response.rect.height();
+            if close {
+                entry.dismissed = true;
+            }
+
+            let paused = area_response.response.contains_pointer();
+            if let Some(remaining) = &mut entry.remaining {
+                if !entry.dismissed && !paused {
+                    *remaining -= dt;
+                    if *remaining <= 0.0 {
+                        entry.dismissed = true;
+                    } else {
+                        ctx.request_repaint_after(Duration::from_secs_f32(*remaining));
+                    }
+                }
+            }
+
+            true
+        });
+
+        if entries.iter().any(|entry| entry.dismissed) {
+            // Keep the fade-out going, even if the timer ran out during an idle pass.
+            ctx.request_repaint();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RawInput;
+
+    #[test]
+    fn toasts_expire() {
+        let ctx = Context::default();
+        let run = |time: f64| {
+            let input = RawInput {
+                time: Some(time),
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |_ctx| {});
+        };
+
+        run(0.0);
+        ctx.notify(Toast::info("Short").duration(Some(Duration::from_secs(1))));
+        ctx.notify(Toast::warning("Sticky").duration(None));
+
+        run(0.1);
+        run(0.5);
+        assert_eq!(count(&ctx), 2);
+
+        for i in 0..10 {
+            run(1.5 + 0.1 * i as f64);
+        }
+        assert_eq!(count(&ctx), 1, "The short toast should have faded out");
+
+        dismiss_all(&ctx);
+        for i in 0..10 {
+            run(3.0 + 0.1 * i as f64);
+        }
+        assert_eq!(count(&ctx), 0);
+    }
+}
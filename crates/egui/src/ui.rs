@@ -1840,7 +1840,7 @@ impl Ui {
         Label::new(text.into().small()).ui(self)
     }
 
-    /// Show text that stand out a bit (e.g. slightly brighter).
+    /// Show text that stand out a bit (brighter and bold).
     ///
     /// Shortcut for `ui.label(RichText::new(text).strong())`
     pub fn strong(&mut self, text: impl Into<RichText>) -> Response {
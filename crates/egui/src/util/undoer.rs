@@ -130,6 +130,11 @@ where
         self.flux.is_some()
     }
 
+    /// The latest undo point, if any.
+    pub(crate) fn latest(&self) -> Option<&State> {
+        self.undos.back()
+    }
+
     pub fn undo(&mut self, current_state: &State) -> Option<&State> {
         if self.has_undo(current_state) {
             self.flux = None;
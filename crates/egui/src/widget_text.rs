@@ -3,8 +3,9 @@ use std::{borrow::Cow, sync::Arc};
 use emath::GuiRounding as _;
 
 use crate::{
-    text::{LayoutJob, TextWrapping},
-    Align, Color32, FontFamily, FontSelection, Galley, Style, TextStyle, TextWrapMode, Ui, Visuals,
+    text::{LayoutJob, TextDecoration, TextWrapping},
+    Align, Color32, FontFamily, FontSelection, FontWeight, Galley, Style, TextStyle, TextWrapMode,
+    Ui, Visuals,
 };
 
 /// Text and optional style choices for it.
@@ -32,11 +33,13 @@ pub struct RichText {
     text_style: Option<TextStyle>,
     background_color: Color32,
     text_color: Option<Color32>,
+    weight: Option<FontWeight>,
     code: bool,
     strong: bool,
     weak: bool,
     strikethrough: bool,
     underline: bool,
+    decorations: Vec<TextDecoration>,
     italics: bool,
     raised: bool,
 }
@@ -204,13 +207,30 @@ impl RichText {
         self.text_style(TextStyle::Monospace)
     }
 
-    /// Extra strong text (stronger color).
+    /// Extra strong text (stronger color, and bold).
     #[inline]
     pub fn strong(mut self) -> Self {
         self.strong = true;
         self
     }
 
+    /// Select how bold the text is.
+    ///
+    /// This overrides the bold of [`Self::strong`].
+    #[inline]
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Bold text.
+    ///
+    /// Shortcut for `.weight(FontWeight::BOLD)`.
+    #[inline]
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::BOLD)
+    }
+
     /// Extra weak text (fainter color).
     #[inline]
     pub fn weak(mut self) -> Self {
@@ -236,6 +256,15 @@ impl RichText {
         self
     }
 
+    /// Draw a line over, under or through the text, e.g. a wavy underline for errors.
+    ///
+    /// Can be called several times to add several decorations.
+    #[inline]
+    pub fn decoration(mut self, decoration: TextDecoration) -> Self {
+        self.decorations.push(decoration);
+        self
+    }
+
     /// Tilt the characters to the right.
     #[inline]
     pub fn italics(mut self) -> Self {
@@ -365,11 +394,13 @@ impl RichText {
             text_style,
             background_color,
             text_color: _, // already used by `get_text_color`
+            weight,
             code,
-            strong: _, // already used by `get_text_color`
-            weak: _,   // already used by `get_text_color`
+            strong,
+            weak: _, // already used by `get_text_color`
             strikethrough,
             underline,
+            decorations,
             italics,
             raised,
         } = self;
@@ -417,6 +448,12 @@ impl RichText {
             default_valign
         };
 
+        let weight = weight.unwrap_or(if strong {
+            FontWeight::BOLD
+        } else {
+            FontWeight::NORMAL
+        });
+
         (
             text,
             crate::text::TextFormat {
@@ -425,9 +462,11 @@ impl RichText {
                 line_height,
                 color: text_color,
                 background: background_color,
+                weight,
                 italics,
                 underline,
                 strikethrough,
+                decorations,
                 valign,
             },
         )
@@ -771,3 +810,24 @@ impl From<Arc<Galley>> for WidgetText {
         Self::Galley(galley)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight_of(text: RichText) -> FontWeight {
+        let (_, format) =
+            text.into_text_and_format(&Style::default(), FontSelection::Default, Align::Min);
+        format.weight
+    }
+
+    #[test]
+    fn strong_text_is_bold() {
+        assert_eq!(weight_of(RichText::new("x")), FontWeight::NORMAL);
+        assert_eq!(weight_of(RichText::new("x").strong()), FontWeight::BOLD);
+        assert_eq!(
+            weight_of(RichText::new("x").strong().weight(FontWeight::LIGHT)),
+            FontWeight::LIGHT
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
 use crate::{
-    epaint, pos2, text_selection, vec2, Align, Direction, FontSelection, Galley, Pos2, Response,
-    Sense, Stroke, TextWrapMode, Ui, Widget, WidgetInfo, WidgetText, WidgetType,
+    epaint, pos2, text_selection, vec2, Align, Color32, CursorIcon, Direction, FontSelection,
+    Galley, Pos2, Response, Sense, Stroke, TextWrapMode, Ui, Widget, WidgetInfo, WidgetText,
+    WidgetType,
 };
 
 use self::text_selection::LabelSelectionState;
@@ -23,6 +24,10 @@ use self::text_selection::LabelSelectionState;
 ///
 /// For full control of the text you can use [`crate::text::LayoutJob`]
 /// as argument to [`Self::new`].
+///
+/// Sections of a [`crate::text::LayoutJob`] can be tagged, e.g. to make links within wrapped text,
+/// or be placeholders for inline images and widgets.
+/// See [`Self::show_spans`].
 #[must_use = "You should put this widget in a ui with `ui.add(widget);`"]
 pub struct Label {
     text: WidgetText,
@@ -153,6 +158,7 @@ impl Label {
 
         if let WidgetText::Galley(galley) = self.text {
             // If the user said "use this specific galley", then just use it:
+            let sense = sense_tags(&galley.job, sense);
             let (rect, response) = ui.allocate_exact_size(galley.size(), sense);
             let pos = match galley.job.halign {
                 Align::LEFT => rect.left_top(),
@@ -167,6 +173,8 @@ impl Label {
             .text
             .into_layout_job(ui.style(), FontSelection::Default, valign);
 
+        let sense = sense_tags(&layout_job, sense);
+
         let available_width = ui.available_width();
 
         let wrap_mode = self.wrap_mode.unwrap_or_else(|| ui.wrap_mode());
@@ -239,8 +247,81 @@ impl Label {
     }
 }
 
-impl Widget for Label {
-    fn ui(self, ui: &mut Ui) -> Response {
+/// Tagged spans of text must be clickable.
+fn sense_tags(job: &epaint::text::LayoutJob, sense: Sense) -> Sense {
+    if job.has_tags() {
+        sense.union(Sense::click())
+    } else {
+        sense
+    }
+}
+
+/// The result of [`Label::show_spans`].
+pub struct LabelResponse {
+    /// The response of the whole label.
+    pub response: Response,
+
+    /// The [`epaint::text::LayoutSection::tag`] of the span under the mouse, if any.
+    pub hovered_tag: Option<u64>,
+
+    /// The [`epaint::text::LayoutSection::tag`] of the span that was clicked, if any.
+    pub clicked_tag: Option<u64>,
+
+    /// Where the galley was painted.
+    pub galley_pos: Pos2,
+
+    /// The laid out text.
+    pub galley: Arc<Galley>,
+}
+
+impl LabelResponse {
+    /// Where the [`epaint::text::Placeholder`] of the given section ended up, in screen coordinates.
+    ///
+    /// Use it to paint an [`crate::Image`] there (see [`crate::Image::paint_at`]),
+    /// or to put a widget there with [`Ui::put`].
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui::text::{LayoutJob, Placeholder, TextFormat};
+    ///
+    /// let mut job = LayoutJob::default();
+    /// job.append("Press ", 0.0, TextFormat::default());
+    /// let button = job.append_placeholder(Placeholder::new(egui::vec2(40.0, 18.0)), TextFormat::default());
+    /// job.append(" to continue.", 0.0, TextFormat::default());
+    ///
+    /// let label = egui::Label::new(job).show_spans(ui);
+    /// if let Some(rect) = label.placeholder_rect(button) {
+    ///     ui.put(rect, egui::Button::new("OK").small());
+    /// }
+    /// # });
+    /// ```
+    pub fn placeholder_rect(&self, section_index: usize) -> Option<crate::Rect> {
+        let rect = self.galley.placeholder_rect(section_index)?;
+        Some(rect.translate(self.galley_pos.to_vec2()))
+    }
+}
+
+impl Label {
+    /// Show the label, and report which tagged span of text is hovered or clicked.
+    ///
+    /// Hovering a tagged span underlines it, and shows [`CursorIcon::PointingHand`].
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui::text::{LayoutJob, TextFormat};
+    ///
+    /// let link = egui::Id::new("manual").value();
+    /// let mut job = LayoutJob::default();
+    /// job.append("Please read the ", 0.0, TextFormat::default());
+    /// job.append_tagged("manual", 0.0, TextFormat::default(), link);
+    /// job.append(" before you start.", 0.0, TextFormat::default());
+    ///
+    /// if egui::Label::new(job).show_spans(ui).clicked_tag == Some(link) {
+    ///     /* … */
+    /// }
+    /// # });
+    /// ```
+    pub fn show_spans(self, ui: &mut Ui) -> LabelResponse {
         // Interactive = the uses asked to sense interaction.
         // We DON'T want to have the color respond just because the text is selectable;
         // the cursor is enough to communicate that.
@@ -252,6 +333,11 @@ impl Widget for Label {
         response
             .widget_info(|| WidgetInfo::labeled(WidgetType::Label, ui.is_enabled(), galley.text()));
 
+        let hovered_tag = response
+            .hover_pos()
+            .and_then(|pos| galley.tag_at(pos - galley_pos));
+        let clicked_tag = hovered_tag.filter(|_| response.clicked());
+
         if ui.is_rect_visible(response.rect) {
             if galley.elided {
                 // Show the full (non-elided) text on hover:
@@ -270,24 +356,66 @@ impl Widget for Label {
                 Stroke::NONE
             };
 
+            if let Some(tag) = hovered_tag {
+                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                paint_span_underline(ui, &galley, galley_pos, tag, response_color);
+            }
+
             let selectable = selectable.unwrap_or_else(|| ui.style().interaction.selectable_labels);
             if selectable {
                 LabelSelectionState::label_text_selection(
                     ui,
                     &response,
                     galley_pos,
-                    galley,
+                    galley.clone(),
                     response_color,
                     underline,
                 );
             } else {
                 ui.painter().add(
-                    epaint::TextShape::new(galley_pos, galley, response_color)
+                    epaint::TextShape::new(galley_pos, galley.clone(), response_color)
                         .with_underline(underline),
                 );
             }
         }
 
-        response
+        LabelResponse {
+            response,
+            hovered_tag,
+            clicked_tag,
+            galley_pos,
+            galley,
+        }
+    }
+}
+
+/// Underline every row of the span with the given tag, in the color of its text.
+fn paint_span_underline(
+    ui: &Ui,
+    galley: &Galley,
+    galley_pos: Pos2,
+    tag: u64,
+    fallback_color: Color32,
+) {
+    let color = galley
+        .job
+        .sections
+        .iter()
+        .find(|section| section.tag == Some(tag))
+        .map(|section| section.format.color)
+        .filter(|color| *color != Color32::PLACEHOLDER)
+        .unwrap_or(fallback_color);
+    let stroke = Stroke::new(1.0, color);
+
+    for rect in galley.tag_rects(tag) {
+        let rect = rect.translate(galley_pos.to_vec2());
+        ui.painter()
+            .hline(rect.x_range(), rect.bottom() - 0.5, stroke);
+    }
+}
+
+impl Widget for Label {
+    fn ui(self, ui: &mut Ui) -> Response {
+        self.show_spans(ui).response
     }
 }
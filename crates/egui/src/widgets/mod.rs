@@ -32,14 +32,14 @@ pub use self::{
         FrameDurations, Image, ImageFit, ImageOptions, ImageSize, ImageSource,
     },
     image_button::ImageButton,
-    label::Label,
+    label::{Label, LabelResponse},
     progress_bar::ProgressBar,
     radio_button::RadioButton,
     selected_label::SelectableLabel,
     separator::Separator,
     slider::{Slider, SliderClamping, SliderOrientation},
     spinner::Spinner,
-    text_edit::{TextBuffer, TextEdit},
+    text_edit::{SpellChecker, TextBuffer, TextEdit, TextRope},
 };
 
 // ----------------------------------------------------------------------------
@@ -1,22 +1,35 @@
-use std::sync::Arc;
+use std::{cell::RefCell, ops::Range, sync::Arc};
 
 use emath::Rect;
 use epaint::text::{cursor::CCursor, Galley, LayoutJob};
 
 use crate::{
+    containers::filter_list::{
+        find_match, navigate, show_matches, ListAction, ListKeys, Match, MatchCache,
+    },
     epaint,
     os::OperatingSystem,
     output::OutputEvent,
     response, text_selection,
     text_selection::{
-        text_cursor_state::cursor_rect, visuals::paint_text_selection, CCursorRange, CursorRange,
+        text_cursor_state::{cursor_rect, select_word_at, slice_char_range},
+        visuals::paint_text_selection,
+        CCursorRange, CursorRange,
     },
-    vec2, Align, Align2, Color32, Context, CursorIcon, Event, EventFilter, FontSelection, Id,
-    ImeEvent, Key, KeyboardShortcut, Margin, Modifiers, NumExt, Response, Sense, Shape, TextBuffer,
-    TextStyle, TextWrapMode, Ui, Vec2, Widget, WidgetInfo, WidgetText, WidgetWithState,
+    vec2, Align, Align2, Area, Color32, Context, CursorIcon, Event, EventFilter, FontSelection,
+    Frame, Id, ImeEvent, Key, KeyboardShortcut, Margin, Modifiers, NumExt, Order, Pos2, Rangef,
+    Response, Sense, Shape, TextBuffer, TextStyle, TextWrapMode, Ui, UiKind, Vec2, Widget,
+    WidgetInfo, WidgetText, WidgetWithState,
 };
 
-use super::{TextEditOutput, TextEditState};
+use super::{
+    find::{find_bar, FindState},
+    spell_check::{apply_correction, suggestions_menu, SpellChecker, SpellingState},
+    text_window::{
+        self, paragraph_at, paragraphs_char_range, visible_paragraphs, ParagraphWindow, TextWindow,
+    },
+    TextEditOutput, TextEditState,
+};
 
 /// A text region that the user can edit the contents of.
 ///
@@ -86,6 +99,8 @@ pub struct TextEdit<'t> {
     char_limit: usize,
     return_key: Option<KeyboardShortcut>,
     background_color: Option<Color32>,
+    find_bar: bool,
+    spell_checker: Option<&'t dyn SpellChecker>,
 }
 
 impl WidgetWithState for TextEdit<'_> {
@@ -145,6 +160,8 @@ impl<'t> TextEdit<'t> {
             char_limit: usize::MAX,
             return_key: Some(KeyboardShortcut::new(Modifiers::NONE, Key::Enter)),
             background_color: None,
+            find_bar: false,
+            spell_checker: None,
         }
     }
 
@@ -256,6 +273,9 @@ impl<'t> TextEdit<'t> {
     /// The arguments is the enclosing [`Ui`] (so you can access e.g. [`Ui::fonts`]),
     /// the text and the wrap width.
     ///
+    /// For large texts that aren't stored in one piece, like a [`super::TextRope`],
+    /// the text is only the paragraphs around the visible ones (see [`TextEditOutput::galley_char_offset`]).
+    ///
     /// ```
     /// # egui::__run_test_ui(|ui| {
     /// # let mut my_code = String::new();
@@ -349,6 +369,32 @@ impl<'t> TextEdit<'t> {
         self
     }
 
+    /// Default is `false`. If set to `true`, Ctrl+F (or Cmd+F on Mac) opens a bar for finding text,
+    /// and Ctrl+H one for finding and replacing it.
+    ///
+    /// All the matches are highlighted, and Enter and Shift+Enter go to the next and previous one.
+    /// The bar has toggles for matching case and whole words,
+    /// and for regular expressions if the `regex` feature is enabled.
+    ///
+    /// Has no effect on a [`Self::password`] field.
+    #[inline]
+    pub fn find_bar(mut self, find_bar: bool) -> Self {
+        self.find_bar = find_bar;
+        self
+    }
+
+    /// Check the spelling of the words in the text, and give the misspelled ones a wavy underline.
+    ///
+    /// Right-clicking a misspelled word shows a menu with the replacements from [`SpellChecker::suggest`].
+    /// See [`SpellChecker`] for an example.
+    ///
+    /// Has no effect on a [`Self::password`] field.
+    #[inline]
+    pub fn spell_checker(mut self, spell_checker: &'t dyn SpellChecker) -> Self {
+        self.spell_checker = Some(spell_checker);
+        self
+    }
+
     /// Sets the limit for the amount of characters can be entered
     ///
     /// This only works for singleline [`TextEdit`]
@@ -416,7 +462,21 @@ impl TextEdit<'_> {
     /// }
     /// # });
     /// ```
-    pub fn show(self, ui: &mut Ui) -> TextEditOutput {
+    pub fn show(mut self, ui: &mut Ui) -> TextEditOutput {
+        let find_output = if self.find_bar && !self.password {
+            let id = self.id_up_front(ui);
+            Some(find_bar(ui, id, self.text))
+        } else {
+            None
+        };
+
+        let spell_checker = self.spell_checker.filter(|_| !self.password);
+        let mut corrected = false;
+        if spell_checker.is_some() {
+            let id = self.id_up_front(ui);
+            corrected = apply_correction(ui.ctx(), id, self.text);
+        }
+
         let is_mutable = self.text.is_mutable();
         let frame = self.frame;
         let where_to_put_background = ui.painter().add(Shape::Noop);
@@ -463,6 +523,180 @@ impl TextEdit<'_> {
             ui.painter().set(where_to_put_background, shape);
         }
 
+        if let Some(find_output) = find_output {
+            if find_output.changed {
+                output.response.mark_changed();
+            }
+            if let Some(scroll_to) = find_output.scroll_to {
+                let rect = match_rect(&output, scroll_to, find_output.scroll_to_paragraph);
+                ui.scroll_to_rect(rect, Some(Align::Center));
+            }
+        }
+
+        if let Some(spell_checker) = spell_checker {
+            if corrected {
+                output.response.mark_changed();
+            }
+            suggestions_menu(ui, &output, spell_checker);
+        }
+
+        output
+    }
+
+    /// We sometimes need the id before showing the [`TextEdit`], e.g. to load its state.
+    fn id_up_front(&mut self, ui: &Ui) -> Id {
+        let id = self.id.unwrap_or_else(|| match self.id_salt {
+            Some(id_salt) => ui.make_persistent_id(id_salt),
+            None => ui.next_auto_id(),
+        });
+        self.id = Some(id);
+        id
+    }
+
+    /// Show the [`TextEdit`] with a popup below the text cursor, suggesting completions
+    /// for the word in front of the cursor.
+    ///
+    /// The word is everything back to the previous whitespace.
+    /// The completions that contain the word (ignoring case) are shown, with the matching part highlighted.
+    /// Use the arrow keys to move through them, Enter to replace the word with one, and Escape to hide them.
+    /// Only the visible rows are laid out, so there can be many completions.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// # let mut my_string = String::new();
+    /// let fruits = ["apple", "apricot", "banana", "blueberry", "cherry"];
+    /// let output = egui::TextEdit::singleline(&mut my_string).show_with_completions(ui, &fruits);
+    /// if output.response.changed() {
+    ///     // The user typed something, or picked a completion.
+    /// }
+    /// # });
+    /// ```
+    pub fn show_with_completions(
+        mut self,
+        ui: &mut Ui,
+        completions: &[impl AsRef<str>],
+    ) -> TextEditOutput {
+        // We need the id up front, to know where the text cursor was last frame.
+        let id = self.id_up_front(ui);
+
+        let popup_id = id.with("completions");
+        let mut completion_state: CompletionState =
+            ui.data_mut(|d| d.get_temp(popup_id)).unwrap_or_default();
+
+        let word = TextEditState::load(ui.ctx(), id)
+            .and_then(|state| state.cursor.char_range())
+            .filter(|range| range.primary.index == range.secondary.index)
+            .map_or(0..0, |range| {
+                word_before(&self.text.text(), range.primary.index)
+            });
+        let query = self.text.char_range_cow(word.clone()).into_owned();
+
+        // Pressing a completion takes the focus from the text edit,
+        // so we keep the popup open until the click is over.
+        let is_active = ui.memory(|mem| mem.has_focus(id))
+            || (completion_state.is_open
+                && ui.input(|i| i.pointer.any_down() || i.pointer.any_released()));
+
+        let matches: &[Match] = if is_active
+            && !query.is_empty()
+            && completion_state.dismissed.as_ref() != Some(&query)
+        {
+            completion_state
+                .matches
+                .matches(&query, completions.len(), |index| {
+                    let completion = completions[index].as_ref();
+                    if completion == query {
+                        None
+                    } else {
+                        find_match(completion, &query)
+                    }
+                })
+        } else {
+            &[]
+        };
+        completion_state.is_open = !matches.is_empty();
+
+        let mut accepted = None;
+        if completion_state.is_open {
+            let keys = ListKeys::consume(ui);
+            match navigate(keys, &mut completion_state.cursor, matches) {
+                Some(ListAction::Accept(index)) => accepted = Some(index),
+                Some(ListAction::Cancel) => completion_state.dismissed = Some(query.clone()),
+                None => {}
+            }
+
+            let cursor = completion_state.cursor;
+            let clicked = Area::new(popup_id)
+                .kind(UiKind::Popup)
+                .order(Order::Foreground)
+                .fixed_pos(completion_state.pos)
+                .show(ui.ctx(), |ui| {
+                    Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            let max_height = ui.spacing().combo_height;
+                            show_matches(ui, max_height, matches, cursor, keys.moved(), |index| {
+                                completions[index].as_ref().to_owned()
+                            })
+                        })
+                        .inner
+                })
+                .inner;
+            accepted = accepted.or(clicked);
+        }
+
+        if let Some(index) = accepted {
+            let completion = completions[index].as_ref();
+            self.text.delete_char_range(word.clone());
+            let inserted = self.text.insert_text(completion, word.start);
+
+            let mut state = TextEditState::load(ui.ctx(), id).unwrap_or_default();
+            state
+                .cursor
+                .set_char_range(Some(CCursorRange::one(CCursor::new(word.start + inserted))));
+            state.text_version += 1;
+            state.store(ui.ctx(), id);
+            ui.memory_mut(|mem| mem.request_focus(id));
+
+            // Don't suggest the completion we just picked.
+            completion_state.dismissed = Some(completion.to_owned());
+            completion_state.is_open = false;
+        }
+
+        // Escape should hide the completions, not take the focus from the text edit.
+        self.event_filter.escape |= completion_state.is_open;
+
+        let mut output = self.show(ui);
+        if accepted.is_some() {
+            output.response.mark_changed();
+        }
+
+        if let Some(cursor_range) = &output.cursor_range {
+            let galley = &output.galley;
+            let mut pos = output.galley_pos
+                + galley
+                    .pos_from_cursor(&cursor_range.primary)
+                    .left_bottom()
+                    .to_vec2();
+            if let Some(to_global) = ui.ctx().layer_transform_to_global(ui.layer_id()) {
+                pos = to_global * pos;
+            }
+            completion_state.pos = pos;
+
+            // The popup we showed was for the word as it was before this frame's typing.
+            let new_word = word_before(galley.text(), cursor_range.primary.ccursor.index);
+            let new_query: String = galley
+                .text()
+                .chars()
+                .skip(new_word.start)
+                .take(new_word.len())
+                .collect();
+            if is_active && new_query != query {
+                ui.ctx().request_repaint();
+            }
+        }
+
+        ui.data_mut(|d| d.insert_temp(popup_id, completion_state));
+
         output
     }
 
@@ -491,6 +725,8 @@ impl TextEdit<'_> {
             char_limit,
             return_key,
             background_color: _,
+            find_bar,
+            spell_checker,
         } = self;
 
         let text_color = text_color
@@ -498,8 +734,6 @@ impl TextEdit<'_> {
             // .unwrap_or_else(|| ui.style().interact(&response).text_color()); // too bright
             .unwrap_or_else(|| ui.visuals().widgets.inactive.text_color());
 
-        let prev_text = text.as_str().to_owned();
-
         let font_id = font_selection.resolve(ui.style());
         let row_height = ui.fonts(|f| f.row_height(&font_id));
         const MIN_WIDTH: f32 = 24.0; // Never make a [`TextEdit`] more narrow than this.
@@ -524,7 +758,153 @@ impl TextEdit<'_> {
 
         let layouter = layouter.unwrap_or(&mut default_layouter);
 
-        let mut galley = layouter(ui, text.as_str(), wrap_width);
+        // We need the state before the layout, to know which part of a large text to lay out.
+        let id = id.unwrap_or_else(|| {
+            if let Some(id_salt) = id_salt {
+                ui.make_persistent_id(id_salt)
+            } else {
+                ui.next_auto_id() // Since we are only storing the cursor a persistent Id is not super important
+            }
+        });
+        let mut state = TextEditState::load(ui.ctx(), id).unwrap_or_default();
+
+        // Large buffers (like a `TextRope`) that aren't stored in one piece
+        // only get the paragraphs around the visible ones laid out:
+        let mut undone = false;
+        let mut window = (multiline && text.as_contiguous_str().is_none()).then(|| {
+            let has_focus = interactive && ui.memory(|mem| mem.has_focus(id));
+            if has_focus {
+                // Undo and redo here, because they may need to change text outside of the window:
+                let undos: Vec<bool> = ui.input(|i| {
+                    i.events
+                        .iter()
+                        .filter_map(|event| match event {
+                            Event::Key {
+                                key,
+                                pressed: true,
+                                modifiers,
+                                ..
+                            } if is_redo_shortcut(*key, *modifiers) => Some(true),
+                            Event::Key {
+                                key,
+                                pressed: true,
+                                modifiers,
+                                ..
+                            } if is_undo_shortcut(*key, *modifiers) => Some(false),
+                            _ => None,
+                        })
+                        .collect()
+                });
+                for redo in undos {
+                    let ranges = text_window::undo(
+                        text,
+                        &mut state.window_undoer.lock(),
+                        state.char_ranges(),
+                        redo,
+                    );
+                    if let Some(ranges) = ranges {
+                        state.set_char_ranges(ranges);
+                        undone = true;
+                    }
+                }
+            }
+
+            let char_width = ui.fonts(|f| f.glyph_width(&font_id, '0'));
+            let paragraph_height =
+                text_window::paragraph_height(text, row_height, wrap_width / char_width);
+            let text_top = ui.cursor().top() + margin.topf();
+            let prev = state.paragraph_window.clone();
+            let clip_rect = ui.clip_rect();
+            let mut visible = visible_paragraphs(
+                Rangef::new(clip_rect.top() - text_top, clip_rect.bottom() - text_top),
+                &prev,
+                state.paragraph_window_height,
+                paragraph_height,
+            );
+
+            let is_typing = has_focus
+                && ui.input(|i| {
+                    i.events.iter().any(|event| {
+                        matches!(
+                            event,
+                            Event::Key { pressed: true, .. }
+                                | Event::Text(_)
+                                | Event::Paste(_)
+                                | Event::Ime(_)
+                        )
+                    })
+                });
+            if let (true, Some(cursor)) = (is_typing, state.cursor.char_range()) {
+                let prev_chars = paragraphs_char_range(text, prev.clone());
+                if !(prev_chars.start..=prev_chars.end).contains(&cursor.primary.index) {
+                    // Bring the cursor into the window, so that the typing goes to the right place.
+                    let paragraph = paragraph_at(text, cursor.primary.index);
+                    visible = paragraph..paragraph + 1;
+                }
+            }
+
+            ParagraphWindow::new(text, prev, visible, paragraph_height)
+        });
+
+        // Underline the misspelled words:
+        let mut spelling = spell_checker.filter(|_| !password).map(|checker| {
+            (
+                checker,
+                SpellingState::load(ui.ctx(), id).unwrap_or_default(),
+            )
+        });
+        let mut underlining_layouter;
+        let layouter: &mut dyn FnMut(&Ui, &str, f32) -> Arc<Galley> =
+            if let Some((checker, spelling)) = &mut spelling {
+                underlining_layouter = move |ui: &Ui, text: &str, wrap_width: f32| {
+                    spelling.underline(ui, layouter(ui, text, wrap_width), *checker)
+                };
+                &mut underlining_layouter
+            } else {
+                layouter
+            };
+
+        // Highlight the matches of the find bar:
+        let find = FindState::load(ui.ctx(), id)
+            .filter(|find| find_bar && !password && find.is_open && !find.query.text.is_empty());
+        let char_offset = window.as_ref().map_or(0, |window| window.char_range.start);
+        let mut highlighting_layouter;
+        let layouter: &mut dyn FnMut(&Ui, &str, f32) -> Arc<Galley> = if let Some(find) = find {
+            highlighting_layouter = move |ui: &Ui, text: &str, wrap_width: f32| {
+                find.highlight(ui, layouter(ui, text, wrap_width), char_offset)
+            };
+            &mut highlighting_layouter
+        } else {
+            layouter
+        };
+
+        let mut text_window;
+        let window_edits = RefCell::new(Vec::new());
+        let mut undo_checkpoint = None;
+        let ranges_before_edits = state.char_ranges();
+        let text: &mut dyn TextBuffer = if let Some(window) = &mut window {
+            if interactive && ui.memory(|mem| mem.has_focus(id)) {
+                undo_checkpoint = window.undo_checkpoint(
+                    &state.window_undoer.lock(),
+                    text,
+                    ranges_before_edits.clone(),
+                );
+            }
+            window.enter(&mut state);
+            text_window = TextWindow::new(text, window, &window_edits);
+            &mut text_window
+        } else {
+            text
+        };
+
+        let prev_text = text.text().into_owned();
+
+        let mut galley = layouter(ui, &prev_text, wrap_width);
+
+        let window_top = window.as_ref().map_or(0.0, |window| window.top());
+        let text_height = window.as_ref().map_or(galley.size().y, |window| {
+            window.text_height(galley.size().y)
+        });
 
         let desired_inner_width = if clip_text {
             wrap_width // visual clipping with scroll in singleline input.
@@ -532,19 +912,11 @@ impl TextEdit<'_> {
             galley.size().x.max(wrap_width)
         };
         let desired_height = (desired_height_rows.at_least(1) as f32) * row_height;
-        let desired_inner_size = vec2(desired_inner_width, galley.size().y.max(desired_height));
+        let desired_inner_size = vec2(desired_inner_width, text_height.max(desired_height));
         let desired_outer_size = (desired_inner_size + margin.sum()).at_least(min_size);
-        let (auto_id, outer_rect) = ui.allocate_space(desired_outer_size);
+        let (_, outer_rect) = ui.allocate_space(desired_outer_size);
         let rect = outer_rect - margin; // inner rect (excluding frame/margin).
-
-        let id = id.unwrap_or_else(|| {
-            if let Some(id_salt) = id_salt {
-                ui.make_persistent_id(id_salt)
-            } else {
-                auto_id // Since we are only storing the cursor a persistent Id is not super important
-            }
-        });
-        let mut state = TextEditState::load(ui.ctx(), id).unwrap_or_default();
+        let galley_origin = rect.min + vec2(0.0, window_top);
 
         // On touch screens (e.g. mobile in `eframe` web), should
         // dragging select text, or scroll the enclosing [`ScrollArea`] (if any)?
@@ -563,6 +935,9 @@ impl TextEdit<'_> {
             Sense::hover()
         };
         let mut response = ui.interact(outer_rect, id, sense);
+        if undone {
+            response.mark_changed();
+        }
         response.intrinsic_size = Some(Vec2::new(desired_width, desired_outer_size.y));
 
         // Don't sent `OutputEvent::Clicked` when a user presses the space bar
@@ -580,7 +955,7 @@ impl TextEdit<'_> {
 
                 let singleline_offset = vec2(state.singleline_offset, 0.0);
                 let cursor_at_pointer =
-                    galley.cursor_from_pos(pointer_pos - rect.min + singleline_offset);
+                    galley.cursor_from_pos(pointer_pos - galley_origin + singleline_offset);
 
                 if ui.visuals().text_cursor.preview
                     && response.hovered()
@@ -588,18 +963,39 @@ impl TextEdit<'_> {
                 {
                     // text cursor preview:
                     let cursor_rect =
-                        cursor_rect(rect.min, &galley, &cursor_at_pointer, row_height);
+                        cursor_rect(galley_origin, &galley, &cursor_at_pointer, row_height);
                     text_selection::visuals::paint_cursor_end(&painter, ui.visuals(), cursor_rect);
                 }
 
                 let is_being_dragged = ui.ctx().is_being_dragged(response.id);
-                let did_interact = state.cursor.pointer_interaction(
-                    ui,
-                    &response,
-                    cursor_at_pointer,
-                    &galley,
-                    is_being_dragged,
-                );
+                let did_interact = if ui.input(|i| i.modifiers.alt) {
+                    let press_origin = ui.input(|i| i.pointer.press_origin());
+                    add_cursors_with_pointer(
+                        ui,
+                        &mut state,
+                        &response,
+                        &galley,
+                        pointer_pos - galley_origin + singleline_offset,
+                        press_origin.map(|origin| origin - galley_origin + singleline_offset),
+                        is_being_dragged,
+                    )
+                } else {
+                    let did_interact = state.cursor.pointer_interaction(
+                        ui,
+                        &response,
+                        cursor_at_pointer,
+                        &galley,
+                        is_being_dragged,
+                    );
+                    if did_interact {
+                        state.extra_cursors.clear();
+                        if let Some(window) = &mut window {
+                            window.outside_ranges.clear();
+                            window.primary_outside = false;
+                        }
+                    }
+                    did_interact
+                };
 
                 if did_interact || response.clicked() {
                     ui.memory_mut(|mem| mem.request_focus(response.id));
@@ -615,9 +1011,13 @@ impl TextEdit<'_> {
 
         let mut cursor_range = None;
         let prev_cursor_range = state.cursor.range(&galley);
+        // When scrolled away from the cursor in a large text, it isn't in the laid out window.
+        let cursor_outside_window = window.as_ref().is_some_and(|window| window.primary_outside)
+            && state.cursor.char_range().is_none();
         if interactive && ui.memory(|mem| mem.has_focus(id)) {
             ui.memory_mut(|mem| mem.set_focus_lock_filter(id, event_filter));
-
+        }
+        if interactive && ui.memory(|mem| mem.has_focus(id)) && !cursor_outside_window {
             let default_cursor_range = if cursor_at_end {
                 CursorRange::one(galley.end())
             } else {
@@ -638,6 +1038,7 @@ impl TextEdit<'_> {
                 char_limit,
                 event_filter,
                 return_key,
+                window.is_none(),
             );
 
             if changed {
@@ -646,10 +1047,14 @@ impl TextEdit<'_> {
             cursor_range = Some(new_cursor_range);
         }
 
-        let mut galley_pos = align
-            .align_size_within_rect(galley.size(), rect)
-            .intersect(rect) // limit pos to the response rect area
-            .min;
+        let mut galley_pos = if window.is_some() {
+            galley_origin
+        } else {
+            align
+                .align_size_within_rect(galley.size(), rect)
+                .intersect(rect) // limit pos to the response rect area
+                .min
+        };
         let align_offset = rect.left() - galley_pos.x;
 
         // Visual clipping for singleline text editor with text larger than width
@@ -689,7 +1094,7 @@ impl TextEdit<'_> {
         };
 
         if ui.is_rect_visible(rect) {
-            if text.as_str().is_empty() && !hint_text.is_empty() {
+            if text.is_empty() && !hint_text.is_empty() {
                 let hint_text_color = ui.visuals().weak_text_color();
                 let hint_text_font_id = hint_text_font.unwrap_or(font_id.into());
                 let galley = if multiline {
@@ -721,11 +1126,15 @@ impl TextEdit<'_> {
                     // Add text selection rectangles to the galley:
                     paint_text_selection(&mut galley, ui.visuals(), &cursor_range, None);
                 }
+                for extra_cursor in &state.extra_cursors {
+                    let extra_range = cursor_range_from_ccursor_range(&galley, *extra_cursor);
+                    paint_text_selection(&mut galley, ui.visuals(), &extra_range, None);
+                }
             }
 
             // Allocate additional space if edits were made this frame that changed the size. This is important so that,
             // if there's a ScrollArea, it can properly scroll to the cursor.
-            let extra_size = galley.size() - rect.size();
+            let extra_size = galley.size() + vec2(0.0, window_top) - rect.size();
             if extra_size.x > 0.0 || extra_size.y > 0.0 {
                 ui.allocate_rect(
                     Rect::from_min_size(outer_rect.max, extra_size),
@@ -763,6 +1172,20 @@ impl TextEdit<'_> {
                                 primary_cursor_rect,
                                 now - state.last_interaction_time,
                             );
+                            for extra_cursor in &state.extra_cursors {
+                                let extra_cursor_rect = cursor_rect(
+                                    galley_pos,
+                                    &galley,
+                                    &galley.from_ccursor(extra_cursor.primary),
+                                    row_height,
+                                );
+                                text_selection::visuals::paint_text_cursor(
+                                    ui,
+                                    &painter,
+                                    extra_cursor_rect,
+                                    now - state.last_interaction_time,
+                                );
+                            }
                         }
 
                         // Set IME output (in screen coords) when text is editable and visible
@@ -792,14 +1215,37 @@ impl TextEdit<'_> {
             ui.input_mut(|i| i.events.retain(|e| !matches!(e, Event::Ime(_))));
         }
 
+        if let Some(window) = &window {
+            window.leave(&mut state, &window_edits.borrow());
+            state.paragraph_window =
+                window.paragraphs.start..window.paragraphs.start + text.paragraph_count();
+            state.paragraph_window_height = galley.size().y;
+            state.paragraph_height = window.paragraph_height;
+            if interactive && ui.memory(|mem| mem.has_focus(id)) {
+                window.feed_undoer(
+                    &mut state.window_undoer.lock(),
+                    ui.input(|i| i.time),
+                    undo_checkpoint,
+                    (ranges_before_edits, &prev_text),
+                    (state.char_ranges(), text.text().into_owned()),
+                );
+            }
+        }
+
+        if response.changed() {
+            state.text_version += 1;
+        }
         state.clone().store(ui.ctx(), id);
+        if let Some((_, spelling)) = spelling {
+            spelling.store(ui.ctx(), id);
+        }
 
         if response.changed() {
             response.widget_info(|| {
                 WidgetInfo::text_edit(
                     ui.is_enabled(),
                     mask_if_password(password, prev_text.as_str()),
-                    mask_if_password(password, text.as_str()),
+                    mask_if_password(password, &text.text()),
                 )
             });
         } else if selection_changed {
@@ -809,7 +1255,7 @@ impl TextEdit<'_> {
             let info = WidgetInfo::text_selection_changed(
                 ui.is_enabled(),
                 char_range,
-                mask_if_password(password, text.as_str()),
+                mask_if_password(password, &text.text()),
             );
             response.output_event(OutputEvent::TextSelectionChanged(info));
         } else {
@@ -817,7 +1263,7 @@ impl TextEdit<'_> {
                 WidgetInfo::text_edit(
                     ui.is_enabled(),
                     mask_if_password(password, prev_text.as_str()),
-                    mask_if_password(password, text.as_str()),
+                    mask_if_password(password, &text.text()),
                 )
             });
         }
@@ -846,6 +1292,7 @@ impl TextEdit<'_> {
             response,
             galley,
             galley_pos,
+            galley_char_offset: window.map_or(0, |window| window.char_range.start),
             text_clip_rect,
             state,
             cursor_range,
@@ -853,6 +1300,36 @@ impl TextEdit<'_> {
     }
 }
 
+/// Where the match of the find bar is on screen, to scroll to it.
+///
+/// For a large text, the match may be outside the laid out window,
+/// and we can only estimate where its `paragraph` is.
+fn match_rect(output: &TextEditOutput, char_range: Range<usize>, paragraph: Option<usize>) -> Rect {
+    let galley = &output.galley;
+    let galley_chars =
+        output.galley_char_offset..=output.galley_char_offset + galley.end().ccursor.index;
+    if let (Some(paragraph), false) = (paragraph, galley_chars.contains(&char_range.start)) {
+        let window = &output.state.paragraph_window;
+        let paragraph_height = output.state.paragraph_height;
+        let y = if paragraph < window.start {
+            output.galley_pos.y - (window.start - paragraph) as f32 * paragraph_height
+        } else {
+            output.galley_pos.y
+                + galley.size().y
+                + paragraph.saturating_sub(window.end) as f32 * paragraph_height
+        };
+        return Rect::from_x_y_ranges(output.text_clip_rect.x_range(), y..=y + paragraph_height);
+    }
+
+    let to_local = |index: usize| {
+        let index = index.saturating_sub(output.galley_char_offset);
+        galley.pos_from_ccursor(CCursor::new(index))
+    };
+    to_local(char_range.start)
+        .union(to_local(char_range.end))
+        .translate(output.galley_pos.to_vec2())
+}
+
 fn mask_if_password(is_password: bool, text: &str) -> String {
     fn mask_password(text: &str) -> String {
         std::iter::repeat(epaint::text::PASSWORD_REPLACEMENT_CHAR)
@@ -867,10 +1344,43 @@ fn mask_if_password(is_password: bool, text: &str) -> String {
     }
 }
 
+/// The completions popup of [`TextEdit::show_with_completions`].
+#[derive(Clone, Debug, Default)]
+struct CompletionState {
+    /// Was the popup shown?
+    is_open: bool,
+
+    /// Where to show the popup, below the text cursor.
+    pos: Pos2,
+
+    /// The completion that is picked with Enter.
+    cursor: Option<usize>,
+
+    /// Don't suggest completions for this word,
+    /// because the user pressed Escape, or just picked it.
+    dismissed: Option<String>,
+
+    /// The completions that match the word before the text cursor.
+    matches: MatchCache,
+}
+
+/// The char range of the word that ends at `char_index`: everything back to the previous whitespace.
+fn word_before(text: &str, char_index: usize) -> Range<usize> {
+    let preceding: Vec<char> = text.chars().take(char_index).collect();
+    let word_len = preceding
+        .iter()
+        .rev()
+        .take_while(|c| !c.is_whitespace())
+        .count();
+    preceding.len() - word_len..preceding.len()
+}
+
 // ----------------------------------------------------------------------------
 
 /// Check for (keyboard) events to edit the cursor and/or text.
-#[allow(clippy::too_many_arguments)]
+///
+/// Edits are applied to the primary cursor range and to all the extra ones.
+#[allow(clippy::fn_params_excessive_bools, clippy::too_many_arguments)]
 fn events(
     ui: &crate::Ui,
     state: &mut TextEditState,
@@ -885,17 +1395,25 @@ fn events(
     char_limit: usize,
     event_filter: EventFilter,
     return_key: Option<KeyboardShortcut>,
+    handle_undo: bool,
 ) -> (bool, CursorRange) {
     let os = ui.ctx().os();
 
     let mut cursor_range = state.cursor.range(galley).unwrap_or(default_cursor_range);
+    let mut extra_ranges: Vec<CursorRange> = state
+        .extra_cursors
+        .iter()
+        .map(|ccursor_range| cursor_range_from_ccursor_range(galley, *ccursor_range))
+        .collect();
 
     // We feed state to the undoer both before and after handling input
     // so that the undoer creates automatic saves even when there are no events for a while.
-    state.undoer.lock().feed_state(
-        ui.input(|i| i.time),
-        &(cursor_range.as_ccursor_range(), text.as_str().to_owned()),
-    );
+    if handle_undo {
+        state.undoer.lock().feed_state(
+            ui.input(|i| i.time),
+            &undo_state(&cursor_range, &extra_ranges, text),
+        );
+    }
 
     let copy_if_not_password = |ui: &Ui, text: String| {
         if !password {
@@ -916,31 +1434,49 @@ fn events(
     for event in &events {
         let did_mutate_text = match event {
             // First handle events that only changes the selection cursor, not the text:
-            event if cursor_range.on_event(os, event, galley, id) => None,
+            event if cursor_range.on_event(os, event, galley, id) => {
+                for extra_range in &mut extra_ranges {
+                    extra_range.on_event(os, event, galley, id);
+                }
+                None
+            }
 
             Event::Copy => {
-                if cursor_range.is_empty() {
-                    None
-                } else {
-                    copy_if_not_password(ui, cursor_range.slice_str(text.as_str()).to_owned());
-                    None
+                let selected = selected_text(&text.text(), &cursor_range, &extra_ranges);
+                if !selected.is_empty() {
+                    copy_if_not_password(ui, selected);
                 }
+                None
             }
             Event::Cut => {
-                if cursor_range.is_empty() {
+                let selected = selected_text(&text.text(), &cursor_range, &extra_ranges);
+                if selected.is_empty() {
                     None
                 } else {
-                    copy_if_not_password(ui, cursor_range.slice_str(text.as_str()).to_owned());
-                    Some(CCursorRange::one(text.delete_selected(&cursor_range)))
+                    copy_if_not_password(ui, selected);
+                    edit_each(text, &cursor_range, &extra_ranges, |text, range, _| {
+                        Some(CCursorRange::one(text.delete_selected(range)))
+                    })
                 }
             }
             Event::Paste(text_to_insert) => {
                 if !text_to_insert.is_empty() {
-                    let mut ccursor = text.delete_selected(&cursor_range);
-
-                    text.insert_text_at(&mut ccursor, text_to_insert, char_limit);
-
-                    Some(CCursorRange::one(ccursor))
+                    // If there is a line for each cursor (e.g. from copying with as many cursors),
+                    // each cursor gets its own line:
+                    let lines: Vec<&str> = text_to_insert.split('\n').collect();
+                    let one_line_each =
+                        !extra_ranges.is_empty() && lines.len() == 1 + extra_ranges.len();
+
+                    edit_each(text, &cursor_range, &extra_ranges, |text, range, index| {
+                        let text_to_insert = if one_line_each {
+                            lines[index]
+                        } else {
+                            text_to_insert.as_str()
+                        };
+                        let mut ccursor = text.delete_selected(range);
+                        text.insert_text_at(&mut ccursor, text_to_insert, char_limit);
+                        Some(CCursorRange::one(ccursor))
+                    })
                 } else {
                     None
                 }
@@ -948,11 +1484,11 @@ fn events(
             Event::Text(text_to_insert) => {
                 // Newlines are handled by `Key::Enter`.
                 if !text_to_insert.is_empty() && text_to_insert != "\n" && text_to_insert != "\r" {
-                    let mut ccursor = text.delete_selected(&cursor_range);
-
-                    text.insert_text_at(&mut ccursor, text_to_insert, char_limit);
-
-                    Some(CCursorRange::one(ccursor))
+                    edit_each(text, &cursor_range, &extra_ranges, |text, range, _| {
+                        let mut ccursor = text.delete_selected(range);
+                        text.insert_text_at(&mut ccursor, text_to_insert, char_limit);
+                        Some(CCursorRange::one(ccursor))
+                    })
                 } else {
                     None
                 }
@@ -962,8 +1498,8 @@ fn events(
                 pressed: true,
                 modifiers,
                 ..
-            } if multiline => {
-                let mut ccursor = text.delete_selected(&cursor_range);
+            } if multiline => edit_each(text, &cursor_range, &extra_ranges, |text, range, _| {
+                let mut ccursor = text.delete_selected(range);
                 if modifiers.shift {
                     // TODO(emilk): support removing indentation over a selection?
                     text.decrease_indentation(&mut ccursor);
@@ -971,7 +1507,7 @@ fn events(
                     text.insert_text_at(&mut ccursor, "\t", char_limit);
                 }
                 Some(CCursorRange::one(ccursor))
-            }
+            }),
             Event::Key {
                 key,
                 pressed: true,
@@ -982,53 +1518,63 @@ fn events(
             }) =>
             {
                 if multiline {
-                    let mut ccursor = text.delete_selected(&cursor_range);
-                    text.insert_text_at(&mut ccursor, "\n", char_limit);
-                    // TODO(emilk): if code editor, auto-indent by same leading tabs, + one if the lines end on an opening bracket
-                    Some(CCursorRange::one(ccursor))
+                    edit_each(text, &cursor_range, &extra_ranges, |text, range, _| {
+                        let mut ccursor = text.delete_selected(range);
+                        text.insert_text_at(&mut ccursor, "\n", char_limit);
+                        // TODO(emilk): if code editor, auto-indent by same leading tabs, + one if the lines end on an opening bracket
+                        Some(CCursorRange::one(ccursor))
+                    })
                 } else {
                     ui.memory_mut(|mem| mem.surrender_focus(id)); // End input with enter
                     break;
                 }
             }
 
+            // Large texts are undone before laying them out, in `TextEdit::show_content`.
             Event::Key {
                 key,
                 pressed: true,
                 modifiers,
                 ..
-            } if (modifiers.matches_logically(Modifiers::COMMAND) && *key == Key::Y)
-                || (modifiers.matches_logically(Modifiers::SHIFT | Modifiers::COMMAND)
-                    && *key == Key::Z) =>
-            {
-                if let Some((redo_ccursor_range, redo_txt)) = state
-                    .undoer
-                    .lock()
-                    .redo(&(cursor_range.as_ccursor_range(), text.as_str().to_owned()))
-                {
-                    text.replace_with(redo_txt);
-                    Some(*redo_ccursor_range)
+            } if is_redo_shortcut(*key, *modifiers) => {
+                let redo = if handle_undo {
+                    let current_state = undo_state(&cursor_range, &extra_ranges, text);
+                    state.undoer.lock().redo(&current_state).cloned()
                 } else {
                     None
-                }
+                };
+                redo.map(|(redo_ccursor_ranges, redo_txt)| {
+                    text.replace_with(&redo_txt);
+                    redo_ccursor_ranges
+                })
             }
 
             Event::Key {
-                key: Key::Z,
+                key,
                 pressed: true,
                 modifiers,
                 ..
-            } if modifiers.matches_logically(Modifiers::COMMAND) => {
-                if let Some((undo_ccursor_range, undo_txt)) = state
-                    .undoer
-                    .lock()
-                    .undo(&(cursor_range.as_ccursor_range(), text.as_str().to_owned()))
-                {
-                    text.replace_with(undo_txt);
-                    Some(*undo_ccursor_range)
+            } if is_undo_shortcut(*key, *modifiers) => {
+                let undo = if handle_undo {
+                    let current_state = undo_state(&cursor_range, &extra_ranges, text);
+                    state.undoer.lock().undo(&current_state).cloned()
                 } else {
                     None
-                }
+                };
+                undo.map(|(undo_ccursor_ranges, undo_txt)| {
+                    text.replace_with(&undo_txt);
+                    undo_ccursor_ranges
+                })
+            }
+
+            Event::Key {
+                key: Key::D,
+                pressed: true,
+                modifiers,
+                ..
+            } if multiline && modifiers.matches_logically(Modifiers::COMMAND) => {
+                select_next_occurrence(&text.text(), galley, &mut cursor_range, &mut extra_ranges);
+                None
             }
 
             Event::Key {
@@ -1036,10 +1582,14 @@ fn events(
                 key,
                 pressed: true,
                 ..
-            } => check_for_mutating_key_press(os, &cursor_range, text, galley, modifiers, *key),
+            } => edit_each(text, &cursor_range, &extra_ranges, |text, range, _| {
+                check_for_mutating_key_press(os, range, text, galley, modifiers, *key)
+            }),
 
             Event::Ime(ime_event) => match ime_event {
                 ImeEvent::Enabled => {
+                    // IME only works with a single cursor.
+                    extra_ranges.clear();
                     state.ime_enabled = true;
                     state.ime_cursor_range = cursor_range;
                     None
@@ -1056,7 +1606,7 @@ fn events(
                             text.insert_text_at(&mut ccursor, text_mark, char_limit);
                         }
                         state.ime_cursor_range = cursor_range;
-                        Some(CCursorRange::two(start_cursor, ccursor))
+                        Some(vec![CCursorRange::two(start_cursor, ccursor)])
                     }
                 }
                 ImeEvent::Commit(prediction) => {
@@ -1071,10 +1621,10 @@ fn events(
                         {
                             let mut ccursor = text.delete_selected(&cursor_range);
                             text.insert_text_at(&mut ccursor, prediction, char_limit);
-                            Some(CCursorRange::one(ccursor))
+                            Some(vec![CCursorRange::one(ccursor)])
                         } else {
                             let ccursor = cursor_range.primary.ccursor;
-                            Some(CCursorRange::one(ccursor))
+                            Some(vec![CCursorRange::one(ccursor)])
                         }
                     }
                 }
@@ -1087,30 +1637,267 @@ fn events(
             _ => None,
         };
 
-        if let Some(new_ccursor_range) = did_mutate_text {
+        if let Some(new_ccursor_ranges) = did_mutate_text {
             any_change = true;
 
             // Layout again to avoid frame delay, and to keep `text` and `galley` in sync.
-            *galley = layouter(ui, text.as_str(), wrap_width);
-
-            // Set cursor_range using new galley:
-            cursor_range = CursorRange {
-                primary: galley.from_ccursor(new_ccursor_range.primary),
-                secondary: galley.from_ccursor(new_ccursor_range.secondary),
-            };
+            *galley = layouter(ui, &text.text(), wrap_width);
+
+            // Set the cursor ranges using new galley:
+            let mut new_ranges = new_ccursor_ranges
+                .into_iter()
+                .map(|ccursor_range| cursor_range_from_ccursor_range(galley, ccursor_range));
+            if let Some(new_cursor_range) = new_ranges.next() {
+                cursor_range = new_cursor_range;
+            }
+            extra_ranges = new_ranges.collect();
         }
+
+        remove_overlapping_ranges(&cursor_range, &mut extra_ranges);
     }
 
     state.cursor.set_range(Some(cursor_range));
-
-    state.undoer.lock().feed_state(
-        ui.input(|i| i.time),
-        &(cursor_range.as_ccursor_range(), text.as_str().to_owned()),
-    );
+    state.extra_cursors = extra_ranges
+        .iter()
+        .map(|range| range.as_ccursor_range())
+        .collect();
+
+    if handle_undo {
+        state.undoer.lock().feed_state(
+            ui.input(|i| i.time),
+            &undo_state(&cursor_range, &extra_ranges, text),
+        );
+    }
 
     (any_change, cursor_range)
 }
 
+fn cursor_range_from_ccursor_range(galley: &Galley, ccursor_range: CCursorRange) -> CursorRange {
+    CursorRange {
+        primary: galley.from_ccursor(ccursor_range.primary),
+        secondary: galley.from_ccursor(ccursor_range.secondary),
+    }
+}
+
+fn is_undo_shortcut(key: Key, modifiers: Modifiers) -> bool {
+    key == Key::Z
+        && modifiers.matches_logically(Modifiers::COMMAND)
+        && !is_redo_shortcut(key, modifiers)
+}
+
+fn is_redo_shortcut(key: Key, modifiers: Modifiers) -> bool {
+    (modifiers.matches_logically(Modifiers::COMMAND) && key == Key::Y)
+        || (modifiers.matches_logically(Modifiers::SHIFT | Modifiers::COMMAND) && key == Key::Z)
+}
+
+/// What we store in the [`super::TextEditUndoer`]: all cursor ranges, primary first, and the text.
+fn undo_state(
+    cursor_range: &CursorRange,
+    extra_ranges: &[CursorRange],
+    text: &dyn TextBuffer,
+) -> (Vec<CCursorRange>, String) {
+    let ranges = std::iter::once(cursor_range)
+        .chain(extra_ranges)
+        .map(|range| range.as_ccursor_range())
+        .collect();
+    (ranges, text.text().into_owned())
+}
+
+/// The text selected by all cursor ranges, in the order they appear in the text, one per line.
+fn selected_text(text: &str, cursor_range: &CursorRange, extra_ranges: &[CursorRange]) -> String {
+    let mut char_ranges: Vec<Range<usize>> = std::iter::once(cursor_range)
+        .chain(extra_ranges)
+        .filter(|range| !range.is_empty())
+        .map(|range| range.as_sorted_char_range())
+        .collect();
+    char_ranges.sort_by_key(|char_range| char_range.start);
+    char_ranges
+        .into_iter()
+        .map(|char_range| slice_char_range(text, char_range))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Apply `edit` to the primary cursor range and to all the extra ones.
+///
+/// The ranges are edited from the last one in the text to the first,
+/// so that each edit leaves the ranges before it where they are.
+/// `edit` is also given the index of the range in text order.
+///
+/// Returns the new ranges, primary first, if any of the edits returned a new range.
+fn edit_each(
+    text: &mut dyn TextBuffer,
+    cursor_range: &CursorRange,
+    extra_ranges: &[CursorRange],
+    mut edit: impl FnMut(&mut dyn TextBuffer, &CursorRange, usize) -> Option<CCursorRange>,
+) -> Option<Vec<CCursorRange>> {
+    if extra_ranges.is_empty() {
+        return edit(text, cursor_range, 0).map(|ccursor_range| vec![ccursor_range]);
+    }
+
+    let ranges: Vec<&CursorRange> = std::iter::once(cursor_range).chain(extra_ranges).collect();
+    let mut text_order: Vec<usize> = (0..ranges.len()).collect();
+    text_order.sort_by_key(|&i| ranges[i].as_sorted_char_range().start);
+
+    let mut new_ranges: Vec<CCursorRange> = ranges
+        .iter()
+        .map(|range| range.as_ccursor_range())
+        .collect();
+    let mut any_edit = false;
+    let mut num_chars = text.char_count();
+
+    for (index, &i) in text_order.iter().enumerate().rev() {
+        let Some(new_range) = edit(text, ranges[i], index) else {
+            continue;
+        };
+        any_edit = true;
+        new_ranges[i] = new_range;
+
+        // The ranges after this one move along with the text:
+        let new_num_chars = text.char_count();
+        for &j in &text_order[index + 1..] {
+            let range = &mut new_ranges[j];
+            for ccursor in [&mut range.primary, &mut range.secondary] {
+                ccursor.index = (ccursor.index + new_num_chars).saturating_sub(num_chars);
+            }
+        }
+        num_chars = new_num_chars;
+    }
+
+    any_edit.then_some(new_ranges)
+}
+
+/// Remove the extra cursor ranges that overlap the primary one, or each other.
+///
+/// This happens when e.g. several cursors are moved to the start of the text.
+fn remove_overlapping_ranges(cursor_range: &CursorRange, extra_ranges: &mut Vec<CursorRange>) {
+    let mut kept = vec![cursor_range.as_sorted_char_range()];
+    extra_ranges.retain(|range| {
+        let range = range.as_sorted_char_range();
+        let overlaps = kept
+            .iter()
+            .any(|k| k.start == range.start || (k.start < range.end && range.start < k.end));
+        if !overlaps {
+            kept.push(range);
+        }
+        !overlaps
+    });
+}
+
+/// Ctrl+D: select the word at the cursor, or else add a selection at the next occurrence of the selected text.
+///
+/// The new selection becomes the primary one.
+fn select_next_occurrence(
+    text: &str,
+    galley: &Galley,
+    cursor_range: &mut CursorRange,
+    extra_ranges: &mut Vec<CursorRange>,
+) {
+    if cursor_range.is_empty() {
+        let word = select_word_at(text, cursor_range.primary.ccursor);
+        *cursor_range = cursor_range_from_ccursor_range(galley, word);
+        return;
+    }
+
+    let needle = cursor_range.slice_str(text);
+    let selected = cursor_range.as_sorted_char_range();
+
+    // The char index of each match, counted in a single pass over the text:
+    let mut counted = (0, 0); // (byte index, char index)
+    let match_starts: Vec<usize> = text
+        .match_indices(needle)
+        .map(|(byte_index, _)| {
+            let (counted_bytes, counted_chars) = counted;
+            counted = (
+                byte_index,
+                counted_chars + text[counted_bytes..byte_index].chars().count(),
+            );
+            counted.1
+        })
+        .collect();
+
+    // Search forwards from the primary selection, wrapping around at the end:
+    let first_after = match_starts.partition_point(|&start| start < selected.end);
+    let (before, after) = match_starts.split_at(first_after);
+    let next = after.iter().chain(before).find_map(|&start| {
+        let char_range = start..start + selected.len();
+        let is_selected = std::iter::once(&*cursor_range)
+            .chain(extra_ranges.iter())
+            .any(|range| range.as_sorted_char_range() == char_range);
+        (!is_selected).then_some(char_range)
+    });
+
+    if let Some(char_range) = next {
+        extra_ranges.push(*cursor_range);
+        *cursor_range = cursor_range_from_ccursor_range(
+            galley,
+            CCursorRange::two(CCursor::new(char_range.start), CCursor::new(char_range.end)),
+        );
+    }
+}
+
+/// Alt+click adds a cursor, and Alt+drag selects a rectangular block of text,
+/// with one cursor range per row.
+///
+/// `pointer_pos` and `press_origin` are relative to the galley.
+///
+/// Returns `true` if there was interaction.
+fn add_cursors_with_pointer(
+    ui: &Ui,
+    state: &mut TextEditState,
+    response: &Response,
+    galley: &Galley,
+    pointer_pos: Vec2,
+    press_origin: Option<Vec2>,
+    is_being_dragged: bool,
+) -> bool {
+    if response.hovered() && ui.input(|i| i.pointer.primary_pressed()) {
+        let cursor = galley.cursor_from_pos(pointer_pos);
+        let index = cursor.ccursor.index;
+        let is_caret_here =
+            |range: &CCursorRange| range.primary.index == index && range.secondary.index == index;
+
+        // Don't add a second caret where there already is one:
+        if !state.cursor.char_range().is_some_and(|range| is_caret_here(&range)) {
+            state.extra_cursors.retain(|range| !is_caret_here(range));
+            if let Some(ccursor_range) = state.cursor.char_range() {
+                state.extra_cursors.push(ccursor_range);
+            }
+            state.cursor.set_range(Some(CursorRange::one(cursor)));
+        }
+        true
+    } else if let (true, Some(press_origin)) = (
+        is_being_dragged && ui.input(|i| i.pointer.primary_down()),
+        press_origin,
+    ) {
+        let from = galley.cursor_from_pos(press_origin).rcursor.row;
+        let to = galley.cursor_from_pos(pointer_pos).rcursor.row;
+        let rows: Vec<usize> = if from <= to {
+            (from..=to).collect()
+        } else {
+            (to..=from).rev().collect()
+        };
+
+        // The row under the pointer comes last, and becomes the primary range:
+        let mut ranges: Vec<CCursorRange> = rows
+            .into_iter()
+            .map(|row| {
+                let y = galley.rows[row].rect.center().y;
+                CCursorRange::two(
+                    galley.cursor_from_pos(vec2(press_origin.x, y)).ccursor,
+                    galley.cursor_from_pos(vec2(pointer_pos.x, y)).ccursor,
+                )
+            })
+            .collect();
+        let primary = ranges.pop();
+        state.cursor.set_char_range(primary);
+        state.extra_cursors = ranges;
+        true
+    } else {
+        false
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 fn remove_ime_incompatible_events(events: &mut Vec<Event>) {
@@ -1207,3 +1994,41 @@ fn check_for_mutating_key_press(
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use epaint::text::cursor::Cursor;
+
+    fn caret(index: usize) -> CursorRange {
+        CursorRange::one(Cursor {
+            ccursor: CCursor::new(index),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_edit_each() {
+        let mut text = String::from("ab cd ef");
+
+        // Type at three carets, the primary one in the middle:
+        let new_ranges = edit_each(
+            &mut text,
+            &caret(5),
+            &[caret(8), caret(2)],
+            |text, range, _| {
+                let mut ccursor = text.delete_selected(range);
+                text.insert_text_at(&mut ccursor, "!!", usize::MAX);
+                Some(CCursorRange::one(ccursor))
+            },
+        );
+        assert_eq!(text, "ab!! cd!! ef!!");
+
+        let indices: Vec<usize> = new_ranges
+            .unwrap()
+            .iter()
+            .map(|range| range.primary.index)
+            .collect();
+        assert_eq!(indices, vec![9, 14, 4]);
+    }
+}
@@ -0,0 +1,729 @@
+//! The find-and-replace bar of [`crate::TextEdit::find_bar`].
+
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    containers::filter_list::find_match,
+    text::{CCursor, CCursorRange, LayoutJob, LayoutSection, TextFormat},
+    text_selection::text_cursor_state::is_word_char,
+    Align, Button, Context, Frame, Galley, Id, Key, LayerId, Layout, Modifiers, TextEdit, Ui,
+    UiBuilder,
+};
+
+use super::{
+    text_window::{self, paragraph_at},
+    TextBuffer, TextEditState,
+};
+
+/// What to search for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct FindQuery {
+    pub text: String,
+    pub match_case: bool,
+    pub whole_word: bool,
+
+    /// Is [`Self::text`] a regular expression?
+    #[cfg(feature = "regex")]
+    pub regex: bool,
+}
+
+enum Searcher<'q> {
+    Plain(&'q FindQuery),
+
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Searcher<'_> {
+    /// The first match at or after byte `start`.
+    fn find_at(&self, text: &str, start: usize) -> Option<Range<usize>> {
+        match self {
+            Self::Plain(query) => {
+                let found = if query.match_case {
+                    let offset = text[start..].find(query.text.as_str())?;
+                    offset..offset + query.text.len()
+                } else {
+                    find_match(&text[start..], &query.text)?
+                };
+                Some(start + found.start..start + found.end)
+            }
+
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.find_at(text, start).map(|m| m.range()),
+        }
+    }
+}
+
+impl FindQuery {
+    #[cfg_attr(not(feature = "regex"), allow(clippy::unnecessary_wraps))]
+    fn searcher(&self) -> Result<Searcher<'_>, String> {
+        #[cfg(feature = "regex")]
+        if self.regex {
+            return regex::RegexBuilder::new(&self.text)
+                .case_insensitive(!self.match_case)
+                .build()
+                .map(Searcher::Regex)
+                .map_err(|err| err.to_string());
+        }
+
+        Ok(Searcher::Plain(self))
+    }
+
+    /// The byte ranges of all matches in `text`, in order.
+    ///
+    /// Empty matches (e.g. of the regular expression `^`) are skipped.
+    /// Returns an error if the regular expression is invalid.
+    pub fn find_all(&self, text: &str) -> Result<Vec<Range<usize>>, String> {
+        if self.text.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let searcher = self.searcher()?;
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while start <= text.len() {
+            let Some(found) = searcher.find_at(text, start) else {
+                break;
+            };
+            if !found.is_empty() && (!self.whole_word || is_whole_word(text, &found)) {
+                start = found.end;
+                matches.push(found);
+            } else {
+                // Try again from the next character:
+                let next_char_len = text[found.start..].chars().next().map_or(1, char::len_utf8);
+                start = found.start + next_char_len;
+            }
+        }
+        Ok(matches)
+    }
+
+    /// What to replace each of the `matches` in `text` with.
+    ///
+    /// With a regular expression, `$1` or `${name}` in the `replacement` become what the group captured.
+    #[cfg_attr(not(feature = "regex"), allow(unused_variables, clippy::unused_self))]
+    pub fn replacements(
+        &self,
+        text: &str,
+        matches: &[Range<usize>],
+        replacement: &str,
+    ) -> Vec<String> {
+        #[cfg(feature = "regex")]
+        if let Ok(Searcher::Regex(regex)) = self.searcher() {
+            return matches
+                .iter()
+                .map(|range| {
+                    let mut expanded = String::new();
+                    if let Some(captures) = regex.captures_at(text, range.start) {
+                        captures.expand(replacement, &mut expanded);
+                    }
+                    expanded
+                })
+                .collect();
+        }
+
+        vec![replacement.to_owned(); matches.len()]
+    }
+}
+
+fn is_whole_word(text: &str, range: &Range<usize>) -> bool {
+    !text[..range.start]
+        .chars()
+        .next_back()
+        .is_some_and(is_word_char)
+        && !text[range.end..].chars().next().is_some_and(is_word_char)
+}
+
+/// Convert byte ranges in `text`, in order and not overlapping, to char ranges.
+fn to_char_ranges(text: &str, byte_ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut byte_index = 0;
+    let mut char_index = 0;
+    byte_ranges
+        .iter()
+        .map(|range| {
+            char_index += text[byte_index..range.start].chars().count();
+            let start = char_index;
+            char_index += text[range.clone()].chars().count();
+            byte_index = range.end;
+            start..char_index
+        })
+        .collect()
+}
+
+/// Change the format of the text in the byte `ranges` (in order and not overlapping),
+/// with `style` getting the index of the range.
+pub(super) fn restyle(
+    job: &LayoutJob,
+    ranges: &[Range<usize>],
+    mut style: impl FnMut(usize, &mut TextFormat),
+) -> LayoutJob {
+    let mut sections = Vec::with_capacity(job.sections.len() + 2 * ranges.len());
+    for section in &job.sections {
+        if section.placeholder.is_some() || section.byte_range.is_empty() {
+            sections.push(section.clone());
+            continue;
+        }
+
+        let mut leading_space = section.leading_space;
+        let mut push = |byte_range: Range<usize>, range_index: Option<usize>| {
+            let mut format = section.format.clone();
+            if let Some(range_index) = range_index {
+                style(range_index, &mut format);
+            }
+            sections.push(LayoutSection {
+                leading_space: std::mem::take(&mut leading_space),
+                byte_range,
+                format,
+                ..section.clone()
+            });
+        };
+
+        let Range { start, end } = section.byte_range;
+        let mut piece_start = start;
+        let first = ranges.partition_point(|range| range.end <= start);
+        for (range_index, range) in ranges.iter().enumerate().skip(first) {
+            if end <= range.start {
+                break;
+            }
+            let styled = range.start.max(piece_start)..range.end.min(end);
+            if piece_start < styled.start {
+                push(piece_start..styled.start, None);
+            }
+            piece_start = styled.end;
+            push(styled, Some(range_index));
+        }
+        if piece_start < end {
+            push(piece_start..end, None);
+        }
+    }
+
+    LayoutJob {
+        sections,
+        ..job.clone()
+    }
+}
+
+/// The state of the find bar, stored in temporary memory.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FindState {
+    pub is_open: bool,
+
+    /// Show the second row, with the replacement?
+    pub show_replace: bool,
+
+    pub query: FindQuery,
+
+    pub replacement: String,
+
+    /// The char range of the match we went to last.
+    pub current: Option<Range<usize>>,
+
+    /// See [`Self::matches`].
+    matches: Option<FindMatches>,
+}
+
+/// The matches of a query, kept until the query or the text changes.
+#[derive(Clone, Debug)]
+struct FindMatches {
+    query: FindQuery,
+
+    /// The [`TextEditState::text_version`] and the char count of the text that was searched.
+    text_version: (u64, usize),
+
+    char_matches: Result<Vec<Range<usize>>, String>,
+}
+
+impl FindState {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_temp(id.with("find")))
+    }
+
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.insert_temp(id.with("find"), self));
+    }
+
+    /// The char ranges of the matches of the query in `text`, or why the query is invalid.
+    ///
+    /// Searching a large text takes a while, so this is only done when the query or the text changed.
+    fn matches(
+        &mut self,
+        text: &dyn TextBuffer,
+        text_version: u64,
+    ) -> Result<Vec<Range<usize>>, String> {
+        let text_version = (text_version, text.char_count());
+        if let Some(matches) = &self.matches {
+            if matches.query == self.query && matches.text_version == text_version {
+                return matches.char_matches.clone();
+            }
+        }
+
+        let full_text = text.text();
+        let char_matches = self
+            .query
+            .find_all(&full_text)
+            .map(|byte_matches| to_char_ranges(&full_text, &byte_matches));
+        self.matches = Some(FindMatches {
+            query: self.query.clone(),
+            text_version,
+            char_matches: char_matches.clone(),
+        });
+        char_matches
+    }
+
+    /// Highlight the matches in a `galley` of the text edit,
+    /// whose first character is at `char_offset` in the whole text.
+    pub fn highlight(&self, ui: &Ui, galley: Arc<Galley>, char_offset: usize) -> Arc<Galley> {
+        let text = galley.text();
+        let Ok(matches) = self.query.find_all(text) else {
+            return galley;
+        };
+        if matches.is_empty() {
+            return galley;
+        }
+
+        let current = self.current.as_ref().and_then(|current| {
+            Some(current.start.checked_sub(char_offset)?..current.end - char_offset)
+        });
+        let match_color = ui.visuals().selection.bg_fill.gamma_multiply(0.4);
+        let current_color = ui.visuals().warn_fg_color.gamma_multiply(0.5);
+
+        let char_matches = to_char_ranges(text, &matches);
+        let job = restyle(&galley.job, &matches, |i, format| {
+            format.background = if Some(&char_matches[i]) == current.as_ref() {
+                current_color
+            } else {
+                match_color
+            };
+        });
+        ui.fonts(|f| f.layout_job(job))
+    }
+}
+
+/// What [`find_bar`] did.
+#[derive(Default)]
+pub(crate) struct FindBarOutput {
+    /// Was the text changed by a replacement?
+    pub changed: bool,
+
+    /// Scroll to this match, given as a char range.
+    pub scroll_to: Option<Range<usize>>,
+
+    /// The paragraph of [`Self::scroll_to`], for large texts that are only laid out in part.
+    pub scroll_to_paragraph: Option<usize>,
+}
+
+/// Open the find bar of the [`TextEdit`] with the given `id` on Ctrl+F or Ctrl+H,
+/// and show it over the top right corner of where the text edit was last frame.
+///
+/// Call this before showing the text edit.
+pub(crate) fn find_bar(ui: &mut Ui, id: Id, text: &mut dyn TextBuffer) -> FindBarOutput {
+    let mut output = FindBarOutput::default();
+    let mut find = FindState::load(ui.ctx(), id).unwrap_or_default();
+    let mut state = TextEditState::load(ui.ctx(), id).unwrap_or_default();
+
+    let query_id = id.with("find_query");
+    let replacement_id = id.with("find_replacement");
+    let has_focus = ui.memory(|mem| {
+        mem.has_focus(id) || mem.has_focus(query_id) || mem.has_focus(replacement_id)
+    });
+
+    if has_focus {
+        let (find_pressed, replace_pressed) = ui.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::COMMAND, Key::F),
+                i.consume_key(Modifiers::COMMAND, Key::H),
+            )
+        });
+        if find_pressed || replace_pressed {
+            if !find.is_open {
+                find.current = None;
+            }
+            find.is_open = true;
+            find.show_replace = replace_pressed;
+
+            // Look for the selected text:
+            if let Some(cursor) = state.cursor.char_range() {
+                let [min, max] = cursor.sorted();
+                let selected = text.char_range_cow(min.index..max.index);
+                if !selected.is_empty() && !selected.contains('\n') {
+                    find.query.text = selected.into_owned();
+                }
+            }
+
+            let focus = if replace_pressed && !find.query.text.is_empty() {
+                replacement_id
+            } else {
+                query_id
+            };
+            ui.memory_mut(|mem| mem.request_focus(focus));
+        }
+    }
+
+    if !find.is_open {
+        find.store(ui.ctx(), id);
+        return output;
+    }
+
+    let Some(rect) = ui.ctx().read_response(id).map(|response| response.rect) else {
+        // We show the bar where the text edit was last frame.
+        ui.ctx().request_repaint();
+        find.store(ui.ctx(), id);
+        return output;
+    };
+
+    let (char_matches, error) = match find.matches(text, state.text_version) {
+        Ok(char_matches) => (char_matches, None),
+        Err(error) => (Vec::new(), Some(error)),
+    };
+    let current_index = find
+        .current
+        .as_ref()
+        .and_then(|current| char_matches.iter().position(|m| m == current));
+
+    // Paint the bar above the text:
+    let layer_id = LayerId::new(ui.layer_id().order, id.with("find_bar"));
+    ui.ctx().set_sublayer(ui.layer_id(), layer_id);
+    let mut bar_ui = ui.new_child(
+        UiBuilder::new()
+            .id_salt(id.with("find_bar"))
+            .layer_id(layer_id)
+            .max_rect(rect.intersect(ui.clip_rect()).shrink(4.0))
+            .layout(Layout::top_down(Align::Max)),
+    );
+
+    let query_before = find.query.clone();
+    let mut step = None; // `Some(true)` for the next match, `Some(false)` for the previous one.
+    let mut replace = false;
+    let mut replace_all = false;
+    let mut close = false;
+
+    Frame::popup(bar_ui.style()).show(&mut bar_ui, |ui| {
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                TextEdit::singleline(&mut find.query.text)
+                    .id(query_id)
+                    .hint_text("Find")
+                    .desired_width(160.0),
+            );
+            if response.lost_focus() {
+                if ui.input(|i| i.key_pressed(Key::Enter)) {
+                    step = Some(!ui.input(|i| i.modifiers.shift));
+                    ui.memory_mut(|mem| mem.request_focus(query_id));
+                }
+                close |= ui.input(|i| i.key_pressed(Key::Escape));
+            }
+
+            ui.toggle_value(&mut find.query.match_case, "Aa")
+                .on_hover_text("Match case");
+            ui.toggle_value(&mut find.query.whole_word, "ab")
+                .on_hover_text("Match whole word");
+            #[cfg(feature = "regex")]
+            ui.toggle_value(&mut find.query.regex, ".*")
+                .on_hover_text("Use regular expression");
+
+            if let Some(error) = &error {
+                ui.colored_label(ui.visuals().error_fg_color, "Invalid")
+                    .on_hover_text(error);
+            } else if let Some(index) = current_index {
+                ui.label(format!("{} of {}", index + 1, char_matches.len()));
+            } else if char_matches.is_empty() {
+                ui.weak("No results");
+            } else {
+                ui.label(format!("{} matches", char_matches.len()));
+            }
+
+            if ui
+                .button("⏶")
+                .on_hover_text("Previous match (Shift+Enter)")
+                .clicked()
+            {
+                step = Some(false);
+            }
+            if ui.button("⏷").on_hover_text("Next match (Enter)").clicked() {
+                step = Some(true);
+            }
+            if ui.button("🗙").on_hover_text("Close (Escape)").clicked() {
+                close = true;
+            }
+        });
+
+        if find.show_replace {
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    TextEdit::singleline(&mut find.replacement)
+                        .id(replacement_id)
+                        .hint_text("Replace")
+                        .desired_width(160.0),
+                );
+                if response.lost_focus() {
+                    if ui.input(|i| i.key_pressed(Key::Enter)) {
+                        replace = true;
+                        ui.memory_mut(|mem| mem.request_focus(replacement_id));
+                    }
+                    close |= ui.input(|i| i.key_pressed(Key::Escape));
+                }
+
+                let can_replace = text.is_mutable() && !char_matches.is_empty();
+                if ui
+                    .add_enabled(can_replace, Button::new("Replace"))
+                    .clicked()
+                {
+                    replace = true;
+                }
+                if ui
+                    .add_enabled(can_replace, Button::new("Replace all"))
+                    .clicked()
+                {
+                    replace_all = true;
+                }
+            });
+        }
+    });
+
+    let cursor_start = state
+        .cursor
+        .char_range()
+        .map_or(0, |cursor| cursor.sorted()[0].index);
+    let from = find
+        .current
+        .as_ref()
+        .map_or(cursor_start, |current| current.start);
+    let next_match = |from: usize, inclusive: bool| {
+        char_matches
+            .iter()
+            .find(|m| m.start > from || (inclusive && m.start == from))
+            .or(char_matches.first())
+            .cloned()
+    };
+
+    let mut go_to = None;
+    if let Some(forward) = step {
+        go_to = if forward {
+            next_match(from, find.current.is_none())
+        } else {
+            char_matches
+                .iter()
+                .rev()
+                .find(|m| m.start < from)
+                .or(char_matches.last())
+                .cloned()
+        };
+    }
+
+    if (replace || replace_all) && text.is_mutable() {
+        let targets: Vec<usize> = if replace_all {
+            (0..char_matches.len()).collect()
+        } else {
+            current_index.into_iter().collect()
+        };
+
+        if targets.is_empty() {
+            // Nothing selected to replace yet, so go to the first match:
+            go_to = next_match(from, true);
+        } else {
+            // The replacements may refer to the whole text (like `^` in a regex):
+            let full_text = text.text();
+            let byte_matches = find.query.find_all(&full_text).unwrap_or_default();
+            let target_bytes: Vec<Range<usize>> =
+                targets.iter().map(|&i| byte_matches[i].clone()).collect();
+            let replacements =
+                find.query
+                    .replacements(&full_text, &target_bytes, &find.replacement);
+            drop(full_text);
+
+            // All the replacements are one undo step:
+            if text.as_contiguous_str().is_some() {
+                let undo_state = (state.char_ranges(), text.text().into_owned());
+                state.undoer.lock().add_undo(&undo_state);
+            } else {
+                let first = char_matches[targets[0]].start;
+                let last = char_matches[*targets.last().unwrap_or(&0)].end;
+                text_window::add_undo_before_edit(
+                    &mut state.window_undoer.lock(),
+                    text,
+                    state.char_ranges(),
+                    first..last,
+                );
+            }
+
+            for (&i, replacement) in targets.iter().zip(&replacements).rev() {
+                text.delete_char_range(char_matches[i].clone());
+                text.insert_text(replacement, char_matches[i].start);
+            }
+            output.changed = true;
+            state.text_version += 1;
+
+            // Continue after the (last) replaced match:
+            let last = *targets.last().unwrap_or(&0);
+            let chars_removed: usize = targets.iter().map(|&i| char_matches[i].len()).sum();
+            let chars_added: usize = replacements.iter().map(|r| r.chars().count()).sum();
+            let after = char_matches[last].end - chars_removed + chars_added;
+            state.set_char_ranges([CCursorRange::one(CCursor::new(after))]);
+            find.current = None;
+
+            if replace && !replace_all {
+                let char_matches = find.matches(text, state.text_version).unwrap_or_default();
+                go_to = char_matches
+                    .iter()
+                    .find(|m| m.start >= after)
+                    .or(char_matches.first())
+                    .cloned();
+            }
+        }
+    }
+
+    if find.query != query_before {
+        // Search as you type, from where we were:
+        let char_matches = find.matches(text, state.text_version).unwrap_or_default();
+        find.current = char_matches
+            .iter()
+            .find(|m| m.start >= cursor_start)
+            .or(char_matches.first())
+            .cloned();
+        output.scroll_to = find.current.clone();
+
+        // The match count is of the old query.
+        ui.ctx().request_repaint();
+    }
+
+    if let Some(go_to) = go_to {
+        state.set_char_ranges([CCursorRange::two(
+            CCursor::new(go_to.start),
+            CCursor::new(go_to.end),
+        )]);
+        find.current = Some(go_to.clone());
+        output.scroll_to = Some(go_to);
+    }
+
+    if close {
+        find.is_open = false;
+        find.current = None;
+        ui.memory_mut(|mem| mem.request_focus(id));
+    }
+
+    if let Some(scroll_to) = &output.scroll_to {
+        if text.as_contiguous_str().is_none() {
+            output.scroll_to_paragraph = Some(paragraph_at(text, scroll_to.start));
+        }
+    }
+
+    state.store(ui.ctx(), id);
+    find.store(ui.ctx(), id);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color32;
+
+    fn find_all<'a>(query: &FindQuery, text: &'a str) -> Vec<&'a str> {
+        query
+            .find_all(text)
+            .unwrap()
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_find_all() {
+        let text = "Cat catalog, CAT! bobcat cat_";
+        let mut query = FindQuery {
+            text: "cat".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(find_all(&query, text), ["Cat", "cat", "CAT", "cat", "cat"]);
+
+        query.match_case = true;
+        assert_eq!(find_all(&query, text), ["cat", "cat", "cat"]);
+
+        query.match_case = false;
+        query.whole_word = true;
+        assert_eq!(find_all(&query, text), ["Cat", "CAT"]);
+
+        assert_eq!(
+            to_char_ranges(text, &query.find_all(text).unwrap()),
+            [0..3, 13..16]
+        );
+        assert_eq!(
+            to_char_ranges("Ø Ø", std::slice::from_ref(&(3..5))),
+            vec![(2..3)]
+        );
+    }
+
+    #[test]
+    fn test_cached_matches() {
+        let mut text = crate::TextRope::from("cat dog cat");
+        let mut find = FindState::default();
+        find.query.text = "cat".to_owned();
+        assert_eq!(find.matches(&text, 0), Ok(vec![0..3, 8..11]));
+
+        // The text edit changed the text:
+        text.insert_text("cat ", 0);
+        assert_eq!(find.matches(&text, 1), Ok(vec![0..3, 4..7, 12..15]));
+
+        // Changed without telling us, but the length changed:
+        text.delete_char_range(0..4);
+        assert_eq!(find.matches(&text, 1), Ok(vec![0..3, 8..11]));
+
+        find.query.text = "dog".to_owned();
+        assert_eq!(
+            find.matches(&text, 1).unwrap(),
+            std::slice::from_ref(&(4..7))
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_find_regex() {
+        let text = "width: 12, height: 345";
+        let query = FindQuery {
+            text: r"(\w+): (\d+)".to_owned(),
+            regex: true,
+            ..Default::default()
+        };
+        let matches = query.find_all(text).unwrap();
+        assert_eq!(matches, [0..9, 11..22]);
+        assert_eq!(
+            query.replacements(text, &matches, "$2 $1"),
+            ["12 width", "345 height"]
+        );
+
+        let invalid = FindQuery {
+            text: "(".to_owned(),
+            regex: true,
+            ..Default::default()
+        };
+        assert!(invalid.find_all(text).is_err());
+    }
+
+    #[test]
+    fn test_restyle() {
+        let mut job = LayoutJob::default();
+        job.append("hello ", 0.0, TextFormat::default());
+        job.append("world", 0.0, TextFormat::default());
+        let job = restyle(&job, std::slice::from_ref(&(3..8)), |_, format| {
+            format.background = Color32::RED;
+        });
+
+        let pieces: Vec<(&str, Color32)> = job
+            .sections
+            .iter()
+            .map(|section| {
+                (
+                    &job.text[section.byte_range.clone()],
+                    section.format.background,
+                )
+            })
+            .collect();
+        assert_eq!(
+            pieces,
+            [
+                ("hel", Color32::TRANSPARENT),
+                ("lo ", Color32::RED),
+                ("wo", Color32::RED),
+                ("rld", Color32::TRANSPARENT),
+            ]
+        );
+    }
+}
@@ -1,9 +1,13 @@
 mod builder;
+mod find;
 mod output;
+mod spell_check;
 mod state;
 mod text_buffer;
+mod text_rope;
+mod text_window;
 
 pub use {
     crate::text_selection::TextCursorState, builder::TextEdit, output::TextEditOutput,
-    state::TextEditState, text_buffer::TextBuffer,
+    spell_check::SpellChecker, state::TextEditState, text_buffer::TextBuffer, text_rope::TextRope,
 };
@@ -13,6 +13,11 @@ pub struct TextEditOutput {
     /// Where the text in [`Self::galley`] ended up on the screen.
     pub galley_pos: crate::Pos2,
 
+    /// The index of the first character of [`Self::galley`] in the text.
+    ///
+    /// Only large texts (see [`crate::TextRope`]) are laid out in part, so this is usually zero.
+    pub galley_char_offset: usize,
+
     /// The text was clipped to this rectangle when painted.
     pub text_clip_rect: crate::Rect,
 
@@ -0,0 +1,342 @@
+//! Spell checking for [`crate::TextEdit::spell_checker`].
+
+use std::{collections::HashSet, hash::BuildHasher, ops::Range, sync::Arc};
+
+use crate::{
+    text::{CCursor, CCursorRange, TextDecoration},
+    text_selection::text_cursor_state::byte_index_from_char_index,
+    Context, Galley, Id, Pos2, Stroke, TextBuffer, Ui,
+};
+
+use super::{find::restyle, text_window, TextEditOutput, TextEditState};
+
+/// At most this many suggestions are shown for a misspelled word.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Checks the spelling of the words in a [`crate::TextEdit`], see [`crate::TextEdit::spell_checker`].
+///
+/// The dictionary is up to the app.
+/// A [`HashSet`] of correctly spelled words is a [`SpellChecker`] that suggests similar words:
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use std::collections::HashSet;
+///
+/// let dictionary: HashSet<String> = ["hello", "world"].map(String::from).into();
+/// let mut text = String::from("Helo world");
+/// ui.add(egui::TextEdit::multiline(&mut text).spell_checker(&dictionary));
+/// # });
+/// ```
+pub trait SpellChecker {
+    /// Is this word spelled correctly?
+    ///
+    /// A word is only checked when it first shows up in the text, not every frame.
+    fn check(&self, word: &str) -> bool;
+
+    /// Replacements for a misspelled word, best first.
+    ///
+    /// Called when the word is right-clicked.
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let _ = word;
+        Vec::new()
+    }
+}
+
+/// The correctly spelled words, in lowercase unless they are always capitalized (like names).
+impl<S: BuildHasher> SpellChecker for HashSet<String, S> {
+    fn check(&self, word: &str) -> bool {
+        self.contains(word) || self.contains(&word.to_lowercase())
+    }
+
+    /// The words at most two edits away, closest first.
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let lowercase = word.to_lowercase();
+        let mut suggestions: Vec<(usize, &String)> = self
+            .iter()
+            .filter_map(|candidate| Some((edit_distance(&lowercase, candidate, 2)?, candidate)))
+            .collect();
+        suggestions.sort();
+
+        let capitalize = word.starts_with(char::is_uppercase);
+        suggestions
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, suggestion)| {
+                let mut chars = suggestion.chars();
+                match chars.next() {
+                    Some(first) if capitalize => first.to_uppercase().chain(chars).collect(),
+                    _ => suggestion.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The number of characters to insert, delete or change to turn `a` into `b`,
+/// if it is at most `max`.
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let change = prev_row[j] + usize::from(a_char != b_char);
+            row[j + 1] = change.min(prev_row[j + 1] + 1).min(row[j] + 1);
+        }
+        if row.iter().all(|&distance| distance > max) {
+            return None;
+        }
+        std::mem::swap(&mut prev_row, &mut row);
+    }
+    Some(prev_row[b.len()]).filter(|&distance| distance <= max)
+}
+
+/// The byte ranges of the words in `text` to check the spelling of.
+///
+/// Words are runs of letters, including apostrophes between them (like "don't").
+/// Words with digits in them (like "mp3") are skipped.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let is_apostrophe = |c: char| c == '\'' || c == '’';
+    let is_word_char = |c: char| c.is_alphanumeric() || is_apostrophe(c);
+
+    let mut words = Vec::new();
+    let mut word_start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (word_start, is_word_char(c)) {
+            (None, true) => word_start = Some(i),
+            (Some(start), false) => {
+                word_start = None;
+                let run = &text[start..i];
+                let word = run.trim_matches(is_apostrophe);
+                if !word.is_empty() && !word.contains(char::is_numeric) {
+                    let start = start + run.len() - run.trim_start_matches(is_apostrophe).len();
+                    words.push(start..start + word.len());
+                }
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// A misspelled word in the text.
+#[derive(Clone, Debug)]
+struct Misspelling {
+    char_range: Range<usize>,
+    word: String,
+}
+
+/// The spell checking state of a [`crate::TextEdit`], stored in temporary memory.
+#[derive(Clone, Default)]
+pub(crate) struct SpellingState {
+    /// Whether each of the words in the text is spelled correctly,
+    /// so that only new words need to be checked.
+    checked: ahash::HashMap<String, bool>,
+
+    /// The misspelled word the suggestions menu is open for, and the suggestions.
+    menu: Option<(Misspelling, Vec<String>)>,
+
+    /// The suggestion picked in the menu, to put in place of the misspelled word.
+    correction: Option<(Misspelling, String)>,
+}
+
+impl SpellingState {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_temp(id.with("spelling")))
+    }
+
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.insert_temp(id.with("spelling"), self));
+    }
+
+    /// Give the misspelled words in a `galley` of the text edit a wavy underline.
+    pub fn underline(
+        &mut self,
+        ui: &Ui,
+        galley: Arc<Galley>,
+        checker: &dyn SpellChecker,
+    ) -> Arc<Galley> {
+        let text = galley.text();
+        let mut checked = ahash::HashMap::default();
+        let mut misspelled = Vec::new();
+        for range in words(text) {
+            let word = &text[range.clone()];
+            let is_correct = if let Some(&is_correct) = checked.get(word) {
+                is_correct
+            } else {
+                let is_correct = self
+                    .checked
+                    .get(word)
+                    .copied()
+                    .unwrap_or_else(|| checker.check(word));
+                checked.insert(word.to_owned(), is_correct);
+                is_correct
+            };
+            if !is_correct {
+                misspelled.push(range);
+            }
+        }
+        // Forget the words that are no longer in the text:
+        self.checked = checked;
+
+        if misspelled.is_empty() {
+            return galley;
+        }
+        let stroke = Stroke::new(1.0, ui.visuals().error_fg_color);
+        let job = restyle(&galley.job, &misspelled, |_, format| {
+            format
+                .decorations
+                .push(TextDecoration::wavy_underline(stroke));
+        });
+        ui.fonts(|f| f.layout_job(job))
+    }
+}
+
+/// Put the suggestion that was picked in the menu last frame in place of the misspelled word.
+///
+/// Call this before showing the text edit. Returns `true` if the text changed.
+pub(crate) fn apply_correction(ctx: &Context, id: Id, text: &mut dyn TextBuffer) -> bool {
+    let Some(mut spelling) = SpellingState::load(ctx, id) else {
+        return false;
+    };
+    let Some((misspelling, suggestion)) = spelling.correction.take() else {
+        return false;
+    };
+    spelling.store(ctx, id);
+
+    let char_range = misspelling.char_range;
+    if !text.is_mutable() || text.char_range_cow(char_range.clone()) != misspelling.word.as_str() {
+        // The text changed since the menu was opened.
+        return false;
+    }
+
+    let mut state = TextEditState::load(ctx, id).unwrap_or_default();
+    if text.as_contiguous_str().is_some() {
+        let undo_state = (state.char_ranges(), text.text().into_owned());
+        state.undoer.lock().add_undo(&undo_state);
+    } else {
+        text_window::add_undo_before_edit(
+            &mut state.window_undoer.lock(),
+            text,
+            state.char_ranges(),
+            char_range.clone(),
+        );
+    }
+
+    text.delete_char_range(char_range.clone());
+    let inserted = text.insert_text(&suggestion, char_range.start);
+    state.set_char_ranges([CCursorRange::one(CCursor::new(char_range.start + inserted))]);
+    state.text_version += 1;
+    state.store(ctx, id);
+    true
+}
+
+/// Show a menu with suggestions when a misspelled word is right-clicked.
+///
+/// Call this after showing the text edit.
+pub(crate) fn suggestions_menu(ui: &Ui, output: &TextEditOutput, checker: &dyn SpellChecker) {
+    let id = output.response.id;
+    let mut spelling = SpellingState::load(ui.ctx(), id).unwrap_or_default();
+
+    if output.response.secondary_clicked() {
+        spelling.menu = ui
+            .input(|i| i.pointer.interact_pos())
+            .and_then(|pos| misspelling_at(output, pos, checker))
+            .map(|misspelling| {
+                let suggestions = checker.suggest(&misspelling.word);
+                (misspelling, suggestions)
+            });
+    }
+
+    if let Some((misspelling, suggestions)) = spelling.menu.take() {
+        let mut picked = None;
+        output.response.context_menu(|ui| {
+            if suggestions.is_empty() {
+                ui.weak("No suggestions");
+            }
+            for suggestion in suggestions.iter().take(MAX_SUGGESTIONS) {
+                if ui.button(suggestion).clicked() {
+                    picked = Some(suggestion.clone());
+                    ui.close_menu();
+                }
+            }
+        });
+
+        if let Some(suggestion) = picked {
+            spelling.correction = Some((misspelling, suggestion));
+            ui.ctx().request_repaint();
+        } else if output.response.context_menu_opened() {
+            spelling.menu = Some((misspelling, suggestions));
+        }
+    }
+
+    spelling.store(ui.ctx(), id);
+}
+
+/// The misspelled word at `pos` on the screen, if any.
+fn misspelling_at(
+    output: &TextEditOutput,
+    pos: Pos2,
+    checker: &dyn SpellChecker,
+) -> Option<Misspelling> {
+    let galley = &output.galley;
+    let text = galley.text();
+    let cursor = galley.cursor_from_pos(pos - output.galley_pos);
+    let byte_index = byte_index_from_char_index(text, cursor.ccursor.index);
+    let range = words(text)
+        .into_iter()
+        .find(|range| range.start <= byte_index && byte_index <= range.end)?;
+
+    let word = &text[range.clone()];
+    if checker.check(word) {
+        return None;
+    }
+    let start = output.galley_char_offset + text[..range.start].chars().count();
+    Some(Misspelling {
+        char_range: start..start + word.chars().count(),
+        word: word.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        let text = "Don't 'quote' mp3s, naïve—or 42 ''";
+        let words: Vec<&str> = words(text).into_iter().map(|range| &text[range]).collect();
+        assert_eq!(words, ["Don't", "quote", "naïve", "or"]);
+    }
+
+    #[test]
+    fn test_word_list() {
+        let dictionary: HashSet<String> = ["hello", "help", "world", "word", "London"]
+            .map(String::from)
+            .into();
+
+        assert!(dictionary.check("hello"));
+        assert!(dictionary.check("Hello"));
+        assert!(dictionary.check("London"));
+        assert!(!dictionary.check("london"));
+        assert!(!dictionary.check("helo"));
+
+        assert_eq!(dictionary.suggest("helo"), ["hello", "help"]);
+        assert_eq!(dictionary.suggest("Wrld"), ["World", "Word"]);
+        assert!(dictionary.suggest("xyzzy").is_empty());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance("kitten", "sitting", 2), None);
+        assert_eq!(edit_distance("", "ab", 2), Some(2));
+        assert_eq!(edit_distance("naïve", "naive", 2), Some(1));
+    }
+}
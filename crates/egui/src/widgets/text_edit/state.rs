@@ -7,7 +7,12 @@ use crate::{
     Context, Galley, Id,
 };
 
-pub type TextEditUndoer = crate::util::undoer::Undoer<(CCursorRange, String)>;
+use super::text_window::WindowUndoer;
+
+/// Undo/redo for a [`crate::TextEdit`].
+///
+/// Each state holds all the cursor ranges (see [`TextEditState::char_ranges`]) and the text.
+pub type TextEditUndoer = crate::util::undoer::Undoer<(Vec<CCursorRange>, String)>;
 
 /// The text edit state stored between frames.
 ///
@@ -37,6 +42,10 @@ pub struct TextEditState {
     /// Controls the text selection.
     pub cursor: TextCursorState,
 
+    /// More cursors, in addition to [`Self::cursor`], e.g. from Alt+click or Ctrl+D.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) extra_cursors: Vec<CCursorRange>,
+
     /// Wrapped in Arc for cheaper clones.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) undoer: Arc<Mutex<TextEditUndoer>>,
@@ -53,6 +62,27 @@ pub struct TextEditState {
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) singleline_offset: f32,
 
+    /// Which paragraphs of a large text were laid out, see [`crate::TextRope`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) paragraph_window: std::ops::Range<usize>,
+
+    /// How tall the laid out paragraphs of a large text were.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) paragraph_window_height: f32,
+
+    /// The estimated height of each paragraph of a large text outside the window.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) paragraph_height: f32,
+
+    /// Undo/redo for a large text, used instead of [`Self::undoer`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) window_undoer: Arc<Mutex<WindowUndoer>>,
+
+    /// Counts the changes to the text by the [`crate::TextEdit`],
+    /// so that e.g. the find bar only needs to search again when it changes.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) text_version: u64,
+
     /// When did the user last press a key or click on the `TextEdit`.
     /// Used to pause the cursor animation when typing.
     #[cfg_attr(feature = "serde", serde(skip))]
@@ -68,6 +98,25 @@ impl TextEditState {
         ctx.data_mut(|d| d.insert_persisted(id, self));
     }
 
+    /// All the selected ranges of characters: the one of [`Self::cursor`] first,
+    /// followed by any extra ones (e.g. from Alt+click or Ctrl+D).
+    pub fn char_ranges(&self) -> Vec<CCursorRange> {
+        self.cursor
+            .char_range()
+            .into_iter()
+            .chain(self.extra_cursors.iter().copied())
+            .collect()
+    }
+
+    /// Sets all the selected ranges of characters.
+    ///
+    /// The first one goes into [`Self::cursor`], and the rest become extra cursors.
+    pub fn set_char_ranges(&mut self, ranges: impl IntoIterator<Item = CCursorRange>) {
+        let mut ranges = ranges.into_iter();
+        self.cursor.set_char_range(ranges.next());
+        self.extra_cursors = ranges.collect();
+    }
+
     /// The currently selected range of characters.
     #[deprecated = "Use `self.cursor.char_range` instead"]
     pub fn ccursor_range(&self) -> Option<CCursorRange> {
@@ -96,6 +145,7 @@ impl TextEditState {
 
     pub fn clear_undoer(&mut self) {
         self.set_undoer(TextEditUndoer::default());
+        *self.window_undoer.lock() = WindowUndoer::default();
     }
 
     #[deprecated = "Use `self.cursor.range` instead"]
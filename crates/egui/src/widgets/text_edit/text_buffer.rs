@@ -20,12 +20,29 @@ use crate::text_selection::{
 /// an underlying buffer.
 ///
 /// Most likely you will use a [`String`] which implements [`TextBuffer`].
+/// For very large documents, use a [`super::TextRope`].
+///
+/// The text doesn't need to be stored in one piece: it is read through [`Self::chunks`].
+/// Implementors must provide [`Self::is_mutable`], [`Self::chunks`], [`Self::insert_text`]
+/// and [`Self::delete_char_range`]; everything else has a default.
 pub trait TextBuffer {
     /// Can this text be edited?
     fn is_mutable(&self) -> bool;
 
     /// Returns this buffer as a `str`.
-    fn as_str(&self) -> &str;
+    ///
+    /// # Panics
+    /// If the text is stored in several pieces.
+    #[deprecated = "Use `chunks`, `as_contiguous_str` or `text`, which also work for text stored in several pieces"]
+    fn as_str(&self) -> &str {
+        self.as_contiguous_str()
+            .expect("The text is stored in several pieces")
+    }
+
+    /// The text, in one or more pieces, in order.
+    ///
+    /// Buffers that store their text in one piece should return just that.
+    fn chunks(&self) -> Box<dyn Iterator<Item = &str> + '_>;
 
     /// Inserts text `text` into this buffer at character index `char_index`.
     ///
@@ -42,18 +59,125 @@ pub trait TextBuffer {
     /// `char_range` is a *character range*, not a byte range.
     fn delete_char_range(&mut self, char_range: Range<usize>);
 
+    /// Returns this buffer as a `str`, if the text is stored in one piece.
+    fn as_contiguous_str(&self) -> Option<&str> {
+        let mut chunks = self.chunks();
+        let first = chunks.next().unwrap_or_default();
+        chunks.next().is_none().then_some(first)
+    }
+
+    /// The whole text.
+    ///
+    /// This only copies the text if it is stored in several pieces.
+    fn text(&self) -> Cow<'_, str> {
+        match self.as_contiguous_str() {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Owned(self.chunks().collect()),
+        }
+    }
+
+    /// Is the text empty?
+    fn is_empty(&self) -> bool {
+        self.chunks().all(str::is_empty)
+    }
+
+    /// The number of characters in the text.
+    fn char_count(&self) -> usize {
+        self.chunks().map(|chunk| chunk.chars().count()).sum()
+    }
+
     /// Reads the given character range.
+    ///
+    /// # Panics
+    /// If the range spans several pieces of the text, see [`Self::char_range_cow`].
     fn char_range(&self, char_range: Range<usize>) -> &str {
-        slice_char_range(self.as_str(), char_range)
+        if let Some(text) = self.as_contiguous_str() {
+            return slice_char_range(text, char_range);
+        }
+
+        let mut chunk_start = 0;
+        for chunk in self.chunks() {
+            let chunk_end = chunk_start + chunk.chars().count();
+            if char_range.end <= chunk_end {
+                assert!(
+                    chunk_start <= char_range.start,
+                    "The character range spans several pieces of the text, use `char_range_cow`"
+                );
+                return slice_char_range(
+                    chunk,
+                    char_range.start - chunk_start..char_range.end - chunk_start,
+                );
+            }
+            chunk_start = chunk_end;
+        }
+        ""
+    }
+
+    /// Reads the given character range, even if it spans several pieces of the text.
+    ///
+    /// This only copies the text if the range spans several pieces.
+    fn char_range_cow(&self, char_range: Range<usize>) -> Cow<'_, str> {
+        if let Some(text) = self.as_contiguous_str() {
+            return Cow::Borrowed(slice_char_range(text, char_range));
+        }
+
+        let mut result = String::new();
+        let mut chunk_start = 0;
+        for chunk in self.chunks() {
+            let chunk_chars = chunk.chars().count();
+            let chunk_end = chunk_start + chunk_chars;
+            if char_range.start < chunk_end && chunk_start < char_range.end {
+                let start = char_range.start.saturating_sub(chunk_start);
+                let end = char_range.end.min(chunk_end) - chunk_start;
+                result.push_str(slice_char_range(chunk, start..end));
+            }
+            if char_range.end <= chunk_end {
+                break;
+            }
+            chunk_start = chunk_end;
+        }
+        Cow::Owned(result)
+    }
+
+    /// The number of paragraphs, i.e. one more than the number of newlines.
+    fn paragraph_count(&self) -> usize {
+        1 + self
+            .chunks()
+            .map(|chunk| chunk.matches('\n').count())
+            .sum::<usize>()
+    }
+
+    /// The character range of the given paragraph, excluding its newline.
+    ///
+    /// Returns an empty range at the end of the text for paragraphs past the end.
+    fn paragraph_char_range(&self, paragraph: usize) -> Range<usize> {
+        let mut start = (paragraph == 0).then_some(0);
+        let mut newlines = 0;
+        let mut char_index = 0;
+        for chunk in self.chunks() {
+            for c in chunk.chars() {
+                if c == '\n' {
+                    if let Some(start) = start {
+                        return start..char_index;
+                    }
+                    newlines += 1;
+                    if newlines == paragraph {
+                        start = Some(char_index + 1);
+                    }
+                }
+                char_index += 1;
+            }
+        }
+        start.unwrap_or(char_index)..char_index
     }
 
     fn byte_index_from_char_index(&self, char_index: usize) -> usize {
-        byte_index_from_char_index(self.as_str(), char_index)
+        byte_index_from_char_index(&self.text(), char_index)
     }
 
     /// Clears all characters in this buffer
     fn clear(&mut self) {
-        self.delete_char_range(0..self.as_str().len());
+        self.delete_char_range(0..self.char_count());
     }
 
     /// Replaces all contents of this string with `text`
@@ -64,7 +188,7 @@ pub trait TextBuffer {
 
     /// Clears all characters in this buffer and returns a string of the contents.
     fn take(&mut self) -> String {
-        let s = self.as_str().to_owned();
+        let s = self.text().into_owned();
         self.clear();
         s
     }
@@ -73,7 +197,7 @@ pub trait TextBuffer {
         if char_limit < usize::MAX {
             let mut new_string = text_to_insert;
             // Avoid subtract with overflow panic
-            let cutoff = char_limit.saturating_sub(self.as_str().chars().count());
+            let cutoff = char_limit.saturating_sub(self.char_count());
 
             new_string = match new_string.char_indices().nth(cutoff) {
                 None => new_string,
@@ -87,12 +211,12 @@ pub trait TextBuffer {
     }
 
     fn decrease_indentation(&mut self, ccursor: &mut CCursor) {
-        let line_start = find_line_start(self.as_str(), *ccursor);
+        let text = self.text();
+        let line_start = find_line_start(&text, *ccursor);
 
-        let remove_len = if self.as_str().chars().nth(line_start.index) == Some('\t') {
+        let remove_len = if text.chars().nth(line_start.index) == Some('\t') {
             Some(1)
-        } else if self
-            .as_str()
+        } else if text
             .chars()
             .skip(line_start.index)
             .take(TAB_SIZE)
@@ -102,6 +226,7 @@ pub trait TextBuffer {
         } else {
             None
         };
+        drop(text);
 
         if let Some(len) = remove_len {
             self.delete_char_range(line_start.index..(line_start.index + len));
@@ -139,12 +264,12 @@ pub trait TextBuffer {
     }
 
     fn delete_previous_word(&mut self, max_ccursor: CCursor) -> CCursor {
-        let min_ccursor = ccursor_previous_word(self.as_str(), max_ccursor);
+        let min_ccursor = ccursor_previous_word(&self.text(), max_ccursor);
         self.delete_selected_ccursor_range([min_ccursor, max_ccursor])
     }
 
     fn delete_next_word(&mut self, min_ccursor: CCursor) -> CCursor {
-        let max_ccursor = ccursor_next_word(self.as_str(), min_ccursor);
+        let max_ccursor = ccursor_next_word(&self.text(), min_ccursor);
         self.delete_selected_ccursor_range([min_ccursor, max_ccursor])
     }
 
@@ -190,8 +315,12 @@ impl TextBuffer for String {
         true
     }
 
-    fn as_str(&self) -> &str {
-        self.as_ref()
+    fn chunks(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(std::iter::once(self.as_str()))
+    }
+
+    fn as_contiguous_str(&self) -> Option<&str> {
+        Some(self)
     }
 
     fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
@@ -233,8 +362,12 @@ impl TextBuffer for Cow<'_, str> {
         true
     }
 
-    fn as_str(&self) -> &str {
-        self.as_ref()
+    fn chunks(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(std::iter::once(self.as_ref()))
+    }
+
+    fn as_contiguous_str(&self) -> Option<&str> {
+        Some(self)
     }
 
     fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
@@ -264,8 +397,12 @@ impl TextBuffer for &str {
         false
     }
 
-    fn as_str(&self) -> &str {
-        self
+    fn chunks(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(std::iter::once(*self))
+    }
+
+    fn as_contiguous_str(&self) -> Option<&str> {
+        Some(self)
     }
 
     fn insert_text(&mut self, _text: &str, _ch_idx: usize) -> usize {
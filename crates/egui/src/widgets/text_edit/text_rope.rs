@@ -0,0 +1,425 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::text_selection::text_cursor_state::{byte_index_from_char_index, slice_char_range};
+
+use super::TextBuffer;
+
+/// The text of a [`TextRope`] is stored in chunks of about this many bytes.
+const CHUNK_BYTES: usize = 4096;
+
+/// A [`TextBuffer`] for large texts, like log files.
+///
+/// The text is stored in many small chunks, so inserting or deleting text only touches
+/// the chunks around the edit, instead of moving the whole rest of the text like a [`String`] would.
+/// Finding the chunk of a character or paragraph takes `O(log n)`.
+///
+/// A [`crate::TextEdit`] showing a [`TextRope`] only lays out the paragraphs
+/// that are visible in the enclosing [`crate::ScrollArea`].
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let log_file_contents = String::new();
+/// let mut log = egui::TextRope::from(log_file_contents);
+/// egui::ScrollArea::vertical().show(ui, |ui| {
+///     ui.add(egui::TextEdit::multiline(&mut log).code_editor());
+/// });
+/// # });
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TextRope {
+    /// Never contains empty chunks.
+    chunks: Vec<Chunk>,
+
+    /// The number of characters in the chunks.
+    char_sums: PrefixSums,
+
+    /// The number of newlines in the chunks.
+    newline_sums: PrefixSums,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Chunk {
+    text: String,
+    num_chars: usize,
+    num_newlines: usize,
+}
+
+impl Chunk {
+    fn new(text: String) -> Self {
+        let num_chars = text.chars().count();
+        let num_newlines = text.bytes().filter(|&b| b == b'\n').count();
+        Self {
+            text,
+            num_chars,
+            num_newlines,
+        }
+    }
+
+    /// Call after changing the text.
+    fn recount(&mut self) {
+        *self = Self::new(std::mem::take(&mut self.text));
+    }
+}
+
+/// Sums of the first `n` of a list of counts, that stay quick to find when a count changes
+/// (a Fenwick tree).
+#[derive(Clone, Debug, Default)]
+struct PrefixSums {
+    /// `tree[j]` is the sum of the counts `j - lowest_bit(j)..j`. `tree[0]` is unused.
+    tree: Vec<usize>,
+}
+
+impl PrefixSums {
+    fn new(counts: impl Iterator<Item = usize>) -> Self {
+        let mut tree: Vec<usize> = std::iter::once(0).chain(counts).collect();
+        for j in 1..tree.len() {
+            let parent = j + lowest_bit(j);
+            if parent < tree.len() {
+                tree[parent] += tree[j];
+            }
+        }
+        Self { tree }
+    }
+
+    /// Change count `i` from `old` to `new`.
+    fn update(&mut self, i: usize, old: usize, new: usize) {
+        let mut j = i + 1;
+        while j < self.tree.len() {
+            self.tree[j] = self.tree[j] - old + new;
+            j += lowest_bit(j);
+        }
+    }
+
+    /// The sum of the first `n` counts.
+    fn sum(&self, n: usize) -> usize {
+        let mut sum = 0;
+        let mut j = n.min(self.tree.len().saturating_sub(1));
+        while j > 0 {
+            sum += self.tree[j];
+            j -= lowest_bit(j);
+        }
+        sum
+    }
+
+    fn total(&self) -> usize {
+        self.sum(usize::MAX)
+    }
+
+    /// The most counts whose sum is less than `target`, and their sum.
+    ///
+    /// So `i` is the first count where the sum reaches `target`, or the number of counts if it never does.
+    fn search(&self, target: usize) -> (usize, usize) {
+        let len = self.tree.len().saturating_sub(1);
+        let mut step = if len == 0 { 0 } else { 1 << len.ilog2() };
+        let (mut n, mut sum) = (0, 0);
+        while step > 0 {
+            if n + step <= len && sum + self.tree[n + step] < target {
+                n += step;
+                sum += self.tree[n];
+            }
+            step /= 2;
+        }
+        (n, sum)
+    }
+}
+
+fn lowest_bit(j: usize) -> usize {
+    j & j.wrapping_neg()
+}
+
+/// Split `text` into chunks of at most [`CHUNK_BYTES`], at character boundaries.
+fn split_into_chunks(text: &str) -> impl Iterator<Item = Chunk> + '_ {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = rest.len().min(CHUNK_BYTES);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(Chunk::new(chunk.to_owned()))
+    })
+}
+
+impl TextRope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn from_chunks(chunks: Vec<Chunk>) -> Self {
+        let mut rope = Self {
+            chunks,
+            ..Default::default()
+        };
+        rope.reindex();
+        rope
+    }
+
+    /// Call after adding or removing chunks.
+    fn reindex(&mut self) {
+        self.char_sums = PrefixSums::new(self.chunks.iter().map(|chunk| chunk.num_chars));
+        self.newline_sums = PrefixSums::new(self.chunks.iter().map(|chunk| chunk.num_newlines));
+    }
+
+    /// Call after changing the text of chunk `i`.
+    fn recount_chunk(&mut self, i: usize) {
+        let chunk = &mut self.chunks[i];
+        let (old_chars, old_newlines) = (chunk.num_chars, chunk.num_newlines);
+        chunk.recount();
+        self.char_sums.update(i, old_chars, chunk.num_chars);
+        self.newline_sums
+            .update(i, old_newlines, chunk.num_newlines);
+    }
+
+    /// Which chunk the character is in, and its index in that chunk.
+    ///
+    /// An index on the boundary between two chunks is placed at the end of the first one.
+    /// Returns `None` for indices past the end.
+    fn locate(&self, char_index: usize) -> Option<(usize, usize)> {
+        let (i, chunk_start) = self.char_sums.search(char_index);
+        (i < self.chunks.len()).then(|| (i, char_index - chunk_start))
+    }
+}
+
+impl From<&str> for TextRope {
+    fn from(text: &str) -> Self {
+        Self::from_chunks(split_into_chunks(text).collect())
+    }
+}
+
+impl From<String> for TextRope {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl std::fmt::Display for TextRope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.chunks
+            .iter()
+            .try_for_each(|chunk| f.write_str(&chunk.text))
+    }
+}
+
+impl TextBuffer for TextRope {
+    fn is_mutable(&self) -> bool {
+        true
+    }
+
+    fn chunks(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.chunks.iter().map(|chunk| chunk.text.as_str()))
+    }
+
+    fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+
+        if let Some((i, char_index)) = self.locate(char_index) {
+            let chunk = &mut self.chunks[i];
+            chunk
+                .text
+                .insert_str(byte_index_from_char_index(&chunk.text, char_index), text);
+            if chunk.text.len() <= 2 * CHUNK_BYTES {
+                self.recount_chunk(i);
+            } else {
+                let new_chunks: Vec<Chunk> = split_into_chunks(&chunk.text).collect();
+                self.chunks.splice(i..=i, new_chunks);
+                self.reindex();
+            }
+        } else {
+            self.chunks.extend(split_into_chunks(text));
+            self.reindex();
+        }
+
+        text.chars().count()
+    }
+
+    fn delete_char_range(&mut self, char_range: Range<usize>) {
+        assert!(char_range.start <= char_range.end);
+        let Some((first, offset)) = self.locate(char_range.start) else {
+            return;
+        };
+
+        let mut chunk_start = char_range.start - offset;
+        let mut emptied_chunk = false;
+        for i in first..self.chunks.len() {
+            if char_range.end <= chunk_start {
+                break;
+            }
+            let chunk = &mut self.chunks[i];
+            let chunk_end = chunk_start + chunk.num_chars;
+            if char_range.start < chunk_end {
+                let start = char_range.start.saturating_sub(chunk_start);
+                let end = char_range.end.min(chunk_end) - chunk_start;
+                let byte_start = byte_index_from_char_index(&chunk.text, start);
+                let byte_end = byte_index_from_char_index(&chunk.text, end);
+                chunk.text.drain(byte_start..byte_end);
+                emptied_chunk |= chunk.text.is_empty();
+                self.recount_chunk(i);
+            }
+            chunk_start = chunk_end;
+        }
+
+        if emptied_chunk {
+            self.chunks.retain(|chunk| !chunk.text.is_empty());
+            self.reindex();
+        }
+    }
+
+    fn as_contiguous_str(&self) -> Option<&str> {
+        // Even if there is only one chunk: the text is meant to be treated as large.
+        None
+    }
+
+    fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    fn char_count(&self) -> usize {
+        self.char_sums.total()
+    }
+
+    fn char_range_cow(&self, char_range: Range<usize>) -> Cow<'_, str> {
+        let mut result = String::new();
+        let Some((first, offset)) = self.locate(char_range.start) else {
+            return Cow::Owned(result);
+        };
+        let mut chunk_start = char_range.start - offset;
+        for chunk in &self.chunks[first..] {
+            if char_range.end <= chunk_start {
+                break;
+            }
+            let chunk_end = chunk_start + chunk.num_chars;
+            if char_range.start < chunk_end {
+                let start = char_range.start.saturating_sub(chunk_start);
+                let end = char_range.end.min(chunk_end) - chunk_start;
+                result.push_str(slice_char_range(&chunk.text, start..end));
+            }
+            chunk_start = chunk_end;
+        }
+        Cow::Owned(result)
+    }
+
+    fn paragraph_count(&self) -> usize {
+        1 + self.newline_sums.total()
+    }
+
+    fn paragraph_char_range(&self, paragraph: usize) -> Range<usize> {
+        // Skip the chunks before the one where the paragraph starts:
+        let (first_chunk, mut newlines) = self.newline_sums.search(paragraph);
+        let mut char_index = self.char_sums.sum(first_chunk);
+
+        let mut start = (paragraph == newlines).then_some(char_index);
+        for chunk in &self.chunks[first_chunk..] {
+            for c in chunk.text.chars() {
+                if c == '\n' {
+                    if let Some(start) = start {
+                        return start..char_index;
+                    }
+                    newlines += 1;
+                    if newlines == paragraph {
+                        start = Some(char_index + 1);
+                    }
+                }
+                char_index += 1;
+            }
+        }
+        start.unwrap_or(char_index)..char_index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_sums() {
+        let mut counts = [3, 0, 5, 1, 0, 0, 7, 2, 4, 1, 6];
+        let mut sums = PrefixSums::new(counts.iter().copied());
+        sums.update(2, 5, 8);
+        counts[2] = 8;
+        sums.update(9, 1, 0);
+        counts[9] = 0;
+
+        for n in 0..=counts.len() {
+            assert_eq!(sums.sum(n), counts[..n].iter().sum::<usize>());
+        }
+        assert_eq!(sums.total(), counts.iter().sum::<usize>());
+
+        for target in 0..=sums.total() + 1 {
+            let n = (0..=counts.len())
+                .take_while(|&n| n == 0 || counts[..n].iter().sum::<usize>() < target)
+                .last()
+                .unwrap_or(0);
+            assert_eq!(sums.search(target), (n, sums.sum(n)), "target {target}");
+        }
+        assert_eq!(PrefixSums::default().search(5), (0, 0));
+    }
+
+    #[test]
+    fn test_text_rope_edits() {
+        let line = "Ærøskøbing\n";
+        let text = line.repeat(1000);
+        let mut rope = TextRope::from(text.as_str());
+        let mut string = text.clone();
+        assert!(rope.chunks.len() > 1);
+
+        let many_lines = line.repeat(800);
+        for (char_index, insert) in [
+            (0, "a"),
+            (5, "🙂\n"),
+            (4000, many_lines.as_str()),
+            (20000, "z"),
+        ] {
+            assert_eq!(
+                rope.insert_text(insert, char_index),
+                string.insert_text(insert, char_index)
+            );
+            assert_eq!(rope.to_string(), string);
+            assert_eq!(rope.char_count(), string.char_count());
+            assert_eq!(rope.paragraph_count(), string.paragraph_count());
+        }
+
+        // Ranges within a single chunk can be borrowed:
+        assert_eq!(rope.char_range(3..100), string.char_range(3..100));
+
+        for char_range in [0..1, 3..4100, 100..100, 5000..9000] {
+            assert_eq!(
+                rope.char_range_cow(char_range.clone()),
+                string.char_range(char_range.clone())
+            );
+            rope.delete_char_range(char_range.clone());
+            string.delete_char_range(char_range);
+            assert_eq!(rope.to_string(), string);
+            assert_eq!(rope.char_count(), string.char_count());
+            assert_eq!(rope.paragraph_count(), string.paragraph_count());
+        }
+
+        rope.clear();
+        assert!(rope.is_empty());
+        assert_eq!(rope.char_count(), 0);
+    }
+
+    #[test]
+    fn test_text_rope_paragraphs() {
+        let text = "first\n\nthird line\n".repeat(500);
+        let rope = TextRope::from(text.as_str());
+        assert_eq!(rope.char_count(), text.chars().count());
+        assert_eq!(rope.paragraph_count(), text.as_str().paragraph_count());
+        assert_eq!(rope.paragraph_count(), 1501);
+
+        for paragraph in [0, 1, 2, 3, 700, 1499, 1500, 1501, 2000] {
+            assert_eq!(
+                rope.paragraph_char_range(paragraph),
+                text.as_str().paragraph_char_range(paragraph),
+                "paragraph {paragraph}"
+            );
+        }
+        assert_eq!(rope.paragraph_char_range(2), 7..17);
+        assert_eq!(rope.paragraph_char_range(1500), text.len()..text.len());
+    }
+}
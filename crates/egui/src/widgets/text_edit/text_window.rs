@@ -0,0 +1,673 @@
+//! Laying out only the visible part of a large [`TextBuffer`], like a [`super::TextRope`].
+//!
+//! The [`crate::TextEdit`] picks a window of whole paragraphs around the visible ones,
+//! and lays out and edits a copy of just that part of the text.
+//! The height of the paragraphs outside the window is estimated from their average length.
+
+use std::{cell::RefCell, ops::Range};
+
+use crate::{
+    text::{CCursor, CCursorRange},
+    text_selection::text_cursor_state::slice_char_range,
+    util::undoer::Undoer,
+    NumExt as _, Rangef,
+};
+
+use super::{TextBuffer, TextEditState};
+
+/// How many paragraphs to lay out above and below the visible ones,
+/// so that the window doesn't need to move on every bit of scrolling.
+const MARGIN_PARAGRAPHS: usize = 100;
+
+/// Which paragraphs to lay out, given the ones laid out last frame (`prev`)
+/// and the ones that should be visible now.
+///
+/// The previous window is kept as long as it covers the visible paragraphs.
+pub(crate) fn paragraphs_to_lay_out(
+    prev: Range<usize>,
+    visible: Range<usize>,
+    paragraph_count: usize,
+) -> Range<usize> {
+    let start = visible.start.min(paragraph_count - 1);
+    let end = visible.end.clamp(start + 1, paragraph_count);
+
+    if prev.start <= start && end <= prev.end && prev.end <= paragraph_count {
+        prev
+    } else {
+        start.saturating_sub(MARGIN_PARAGRAPHS)..(end + MARGIN_PARAGRAPHS).min(paragraph_count)
+    }
+}
+
+/// The estimated height of a paragraph of `buffer`, from the average number of characters in one.
+///
+/// `chars_per_row` is how many characters fit in the wrap width.
+pub(crate) fn paragraph_height(
+    buffer: &dyn TextBuffer,
+    row_height: f32,
+    chars_per_row: f32,
+) -> f32 {
+    let paragraph_count = buffer.paragraph_count();
+    let newlines = paragraph_count - 1;
+    let chars_per_paragraph = (buffer.char_count() - newlines) as f32 / paragraph_count as f32;
+    let rows = (chars_per_paragraph / chars_per_row.at_least(1.0)).ceil();
+    rows.at_least(1.0) * row_height
+}
+
+/// The paragraphs in `y_range`, measured from the top of the text.
+///
+/// `prev` are the paragraphs that were laid out last frame, `prev_height` high,
+/// and the other paragraphs are `paragraph_height` high.
+pub(crate) fn visible_paragraphs(
+    y_range: Rangef,
+    prev: &Range<usize>,
+    prev_height: f32,
+    paragraph_height: f32,
+) -> Range<usize> {
+    let prev_top = prev.start as f32 * paragraph_height;
+    let prev_bottom = prev_top + prev_height;
+    let paragraph_at = |y: f32| {
+        let paragraph = if y < prev_top {
+            y / paragraph_height
+        } else if y < prev_bottom {
+            prev.start as f32 + (y - prev_top) / prev_height * prev.len() as f32
+        } else {
+            prev.end as f32 + (y - prev_bottom) / paragraph_height
+        };
+        paragraph.at_least(0.0)
+    };
+    paragraph_at(y_range.min).floor() as usize..paragraph_at(y_range.max).ceil() as usize
+}
+
+/// The paragraph the character at `char_index` is in.
+pub(crate) fn paragraph_at(buffer: &dyn TextBuffer, char_index: usize) -> usize {
+    let mut paragraph = 0;
+    let mut chunk_start = 0;
+    for chunk in buffer.chunks() {
+        let num_chars = chunk.chars().count();
+        if char_index < chunk_start + num_chars {
+            let chars_before = slice_char_range(chunk, 0..char_index - chunk_start);
+            return paragraph + chars_before.matches('\n').count();
+        }
+        paragraph += chunk.matches('\n').count();
+        chunk_start += num_chars;
+    }
+    paragraph
+}
+
+/// The characters in the given paragraphs, excluding the newline after the last one.
+pub(crate) fn paragraphs_char_range(
+    buffer: &dyn TextBuffer,
+    paragraphs: Range<usize>,
+) -> Range<usize> {
+    let start = buffer.paragraph_char_range(paragraphs.start).start;
+    let end = buffer
+        .paragraph_char_range(paragraphs.end.saturating_sub(1).max(paragraphs.start))
+        .end;
+    start..end
+}
+
+/// Where the laid out window is in the whole buffer,
+/// and the cursors that are not in it.
+pub(crate) struct ParagraphWindow {
+    /// The paragraphs in the window.
+    pub paragraphs: Range<usize>,
+
+    /// The number of paragraphs in the whole buffer.
+    pub paragraph_count: usize,
+
+    /// The characters in the window, excluding the newline after the last paragraph.
+    pub char_range: Range<usize>,
+
+    /// The number of characters after [`Self::char_range`].
+    pub chars_after: usize,
+
+    /// The estimated height of each paragraph outside the window, see [`paragraph_height`].
+    pub paragraph_height: f32,
+
+    /// Cursor ranges (partly) outside the window, in buffer char indices.
+    pub outside_ranges: Vec<CCursorRange>,
+
+    /// Is the first of [`Self::outside_ranges`] the primary cursor range?
+    pub primary_outside: bool,
+}
+
+impl ParagraphWindow {
+    /// See [`paragraphs_to_lay_out`].
+    pub fn new(
+        buffer: &dyn TextBuffer,
+        prev: Range<usize>,
+        visible: Range<usize>,
+        paragraph_height: f32,
+    ) -> Self {
+        let paragraph_count = buffer.paragraph_count();
+        let paragraphs = paragraphs_to_lay_out(prev, visible, paragraph_count);
+        let char_range = paragraphs_char_range(buffer, paragraphs.clone());
+        Self {
+            chars_after: buffer.char_count() - char_range.end,
+            char_range,
+            paragraphs,
+            paragraph_count,
+            paragraph_height,
+            outside_ranges: Vec::new(),
+            primary_outside: false,
+        }
+    }
+
+    /// How far below the top of the text the window starts.
+    pub fn top(&self) -> f32 {
+        self.paragraphs.start as f32 * self.paragraph_height
+    }
+
+    /// The height of the whole text, given how tall the window is when laid out.
+    pub fn text_height(&self, window_height: f32) -> f32 {
+        let paragraphs_below = self.paragraph_count - self.paragraphs.end;
+        self.top() + window_height + paragraphs_below as f32 * self.paragraph_height
+    }
+
+    /// Make the cursor ranges of `state` relative to the start of the window.
+    ///
+    /// The ranges that are not in the window are set aside until [`Self::leave`].
+    pub fn enter(&mut self, state: &mut TextEditState) {
+        let Range { start, end } = self.char_range;
+        let inside = |ccursor: CCursor| (start..=end).contains(&ccursor.index);
+
+        let mut local_ranges = Vec::new();
+        for (i, range) in state.char_ranges().into_iter().enumerate() {
+            if inside(range.primary) && inside(range.secondary) {
+                local_ranges.push(CCursorRange {
+                    primary: range.primary - start,
+                    secondary: range.secondary - start,
+                });
+            } else {
+                self.primary_outside |= i == 0;
+                self.outside_ranges.push(range);
+            }
+        }
+        state.set_char_ranges(local_ranges);
+    }
+
+    /// Undo [`Self::enter`], after the `edits` of a [`TextWindow`].
+    pub fn leave(&self, state: &mut TextEditState, edits: &[WindowEdit]) {
+        let start = self.char_range.start;
+        let to_buffer = |ccursor: CCursor| {
+            // Each end of a range that is partly in the window moves with the text around it:
+            edits
+                .iter()
+                .fold(ccursor, |ccursor, edit| edit.move_cursor(ccursor))
+        };
+        let outside_ranges = self.outside_ranges.iter().map(|range| CCursorRange {
+            primary: to_buffer(range.primary),
+            secondary: to_buffer(range.secondary),
+        });
+        let local_ranges = state.char_ranges().into_iter().map(|range| CCursorRange {
+            primary: range.primary + start,
+            secondary: range.secondary + start,
+        });
+
+        if self.primary_outside && state.cursor.char_range().is_none() {
+            let outside_ranges: Vec<_> = outside_ranges.collect();
+            state.set_char_ranges(outside_ranges.into_iter().chain(local_ranges));
+        } else {
+            let local_ranges: Vec<_> = local_ranges.collect();
+            state.set_char_ranges(local_ranges.into_iter().chain(outside_ranges));
+        }
+    }
+}
+
+/// An edit of a [`TextWindow`], in char indices of the whole buffer:
+/// the characters in `deleted` were replaced by `inserted` new ones.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct WindowEdit {
+    deleted: Range<usize>,
+    inserted: usize,
+}
+
+impl WindowEdit {
+    /// Where a cursor ends up after this edit.
+    fn move_cursor(&self, ccursor: CCursor) -> CCursor {
+        if ccursor.index <= self.deleted.start {
+            ccursor
+        } else if self.deleted.end <= ccursor.index {
+            ccursor - self.deleted.len() + self.inserted
+        } else {
+            CCursor {
+                index: self.deleted.start,
+                ..ccursor
+            }
+        }
+    }
+}
+
+/// A copy of the text in a [`ParagraphWindow`], which passes on all edits to the buffer.
+///
+/// The copy is stored in one piece, so it can be laid out and edited like a [`String`].
+/// The edits are also written to a log, for [`ParagraphWindow::leave`].
+pub(crate) struct TextWindow<'t> {
+    text: String,
+    char_offset: usize,
+    buffer: &'t mut dyn TextBuffer,
+    edits: &'t RefCell<Vec<WindowEdit>>,
+}
+
+impl<'t> TextWindow<'t> {
+    pub fn new(
+        buffer: &'t mut dyn TextBuffer,
+        window: &ParagraphWindow,
+        edits: &'t RefCell<Vec<WindowEdit>>,
+    ) -> Self {
+        Self {
+            text: buffer
+                .char_range_cow(window.char_range.clone())
+                .into_owned(),
+            char_offset: window.char_range.start,
+            buffer,
+            edits,
+        }
+    }
+}
+
+impl TextBuffer for TextWindow<'_> {
+    fn is_mutable(&self) -> bool {
+        self.buffer.is_mutable()
+    }
+
+    fn chunks(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(std::iter::once(self.text.as_str()))
+    }
+
+    fn as_contiguous_str(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
+        let char_index = char_index.min(self.char_count());
+        let char_index = self.char_offset + char_index;
+        let inserted = self.buffer.insert_text(text, char_index);
+        self.text.insert_text(
+            slice_char_range(text, 0..inserted),
+            char_index - self.char_offset,
+        );
+        self.edits.borrow_mut().push(WindowEdit {
+            deleted: char_index..char_index,
+            inserted,
+        });
+        inserted
+    }
+
+    fn delete_char_range(&mut self, char_range: Range<usize>) {
+        let char_count = self.char_count();
+        let char_range = char_range.start.min(char_count)..char_range.end.min(char_count);
+        let deleted = self.char_offset + char_range.start..self.char_offset + char_range.end;
+        self.buffer.delete_char_range(deleted.clone());
+        self.text.delete_char_range(char_range);
+        self.edits.borrow_mut().push(WindowEdit {
+            deleted,
+            inserted: 0,
+        });
+    }
+
+    fn insert_text_at(&mut self, ccursor: &mut CCursor, text_to_insert: &str, char_limit: usize) {
+        // The limit is for the whole buffer, not just the window:
+        let chars_left = char_limit.saturating_sub(self.buffer.char_count());
+        let text_to_insert = match text_to_insert.char_indices().nth(chars_left) {
+            None => text_to_insert,
+            Some((idx, _)) => &text_to_insert[..idx],
+        };
+        ccursor.index += self.insert_text(text_to_insert, ccursor.index);
+    }
+}
+
+/// An undo point of a large text, see [`WindowUndoer`].
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct WindowUndoState {
+    /// All cursor ranges, primary first, in char indices of the whole buffer.
+    ranges: Vec<CCursorRange>,
+
+    /// The text of one part of the buffer,
+    /// which is everything but the first `chars_before` and the last `chars_after` characters.
+    text: String,
+    chars_before: usize,
+    chars_after: usize,
+}
+
+impl WindowUndoState {
+    fn new(buffer: &dyn TextBuffer, ranges: Vec<CCursorRange>, char_range: Range<usize>) -> Self {
+        Self {
+            ranges,
+            text: buffer.char_range_cow(char_range.clone()).into_owned(),
+            chars_before: char_range.start,
+            chars_after: buffer.char_count() - char_range.end,
+        }
+    }
+
+    /// The characters of `buffer` in the part of this state.
+    ///
+    /// `None` if the buffer is too short, because it was changed by something else than the [`crate::TextEdit`].
+    fn char_range_in(&self, buffer: &dyn TextBuffer) -> Option<Range<usize>> {
+        let end = buffer.char_count().checked_sub(self.chars_after)?;
+        (self.chars_before <= end).then_some(self.chars_before..end)
+    }
+}
+
+/// The undo history of a large text.
+///
+/// Only a window of the text is edited at a time, so each undo point only holds the text of one part of the buffer
+/// (usually the window) instead of a copy of all of it.
+/// Two adjacent undo points either hold the same part, or the same text of the whole buffer (in two parts),
+/// so that undoing and redoing only need to replace the part of the undo point.
+pub(crate) type WindowUndoer = Undoer<WindowUndoState>;
+
+/// The current state of `buffer` in the part of the latest undo point,
+/// if that is another part than the one from `chars_before` to `chars_after`.
+fn undo_checkpoint(
+    undoer: &WindowUndoer,
+    buffer: &dyn TextBuffer,
+    ranges: Vec<CCursorRange>,
+    chars_before: usize,
+    chars_after: usize,
+) -> Option<WindowUndoState> {
+    let latest = undoer.latest()?;
+    if (latest.chars_before, latest.chars_after) == (chars_before, chars_after) {
+        return None;
+    }
+    let char_range = latest.char_range_in(buffer)?;
+    Some(WindowUndoState::new(buffer, ranges, char_range))
+}
+
+impl ParagraphWindow {
+    fn undo_state(&self, ranges: Vec<CCursorRange>, text: String) -> WindowUndoState {
+        WindowUndoState {
+            ranges,
+            text,
+            chars_before: self.char_range.start,
+            chars_after: self.chars_after,
+        }
+    }
+
+    /// Call before editing the window, and pass the result to [`Self::feed_undoer`].
+    pub fn undo_checkpoint(
+        &self,
+        undoer: &WindowUndoer,
+        buffer: &dyn TextBuffer,
+        ranges: Vec<CCursorRange>,
+    ) -> Option<WindowUndoState> {
+        undo_checkpoint(
+            undoer,
+            buffer,
+            ranges,
+            self.char_range.start,
+            self.chars_after,
+        )
+    }
+
+    /// Call after editing the window, with the cursor ranges (in the whole buffer)
+    /// and the text of the window `before` and `after` the edits.
+    pub fn feed_undoer(
+        &self,
+        undoer: &mut WindowUndoer,
+        current_time: f64,
+        checkpoint: Option<WindowUndoState>,
+        before: (Vec<CCursorRange>, &str),
+        after: (Vec<CCursorRange>, String),
+    ) {
+        let after = self.undo_state(after.0, after.1);
+        match checkpoint {
+            None => undoer.feed_state(current_time, &after),
+            Some(checkpoint) if before.1 != after.text => {
+                // The edits are in another part of the buffer than the latest undo point.
+                // Record the text before them in both parts:
+                undoer.add_undo(&checkpoint);
+                undoer.add_undo(&self.undo_state(before.0, before.1.to_owned()));
+                undoer.feed_state(current_time, &after);
+            }
+            Some(checkpoint) => {
+                // No edits, so stick to the part of the latest undo point.
+                let after = WindowUndoState {
+                    ranges: after.ranges,
+                    ..checkpoint
+                };
+                undoer.feed_state(current_time, &after);
+            }
+        }
+    }
+}
+
+/// Add an undo point before editing `char_range` of a large text, from outside of the [`crate::TextEdit`].
+pub(crate) fn add_undo_before_edit(
+    undoer: &mut WindowUndoer,
+    buffer: &dyn TextBuffer,
+    ranges: Vec<CCursorRange>,
+    char_range: Range<usize>,
+) {
+    let chars_after = buffer.char_count() - char_range.end;
+    if let Some(checkpoint) = undo_checkpoint(
+        undoer,
+        buffer,
+        ranges.clone(),
+        char_range.start,
+        chars_after,
+    ) {
+        undoer.add_undo(&checkpoint);
+    }
+    undoer.add_undo(&WindowUndoState::new(buffer, ranges, char_range));
+}
+
+/// Undo (or redo) an edit of a large text, which may be outside of the window.
+///
+/// `ranges` are the current cursor ranges, in the whole buffer.
+/// Returns the cursor ranges to restore.
+pub(crate) fn undo(
+    buffer: &mut dyn TextBuffer,
+    undoer: &mut WindowUndoer,
+    ranges: Vec<CCursorRange>,
+    redo: bool,
+) -> Option<Vec<CCursorRange>> {
+    // The current state, in the part of the latest undo point:
+    let char_range = undoer.latest()?.char_range_in(buffer)?;
+    let current = WindowUndoState::new(buffer, ranges, char_range);
+
+    let target = if redo {
+        undoer.redo(&current)
+    } else {
+        undoer.undo(&current)
+    }?;
+    let char_range = target.char_range_in(buffer)?;
+    buffer.delete_char_range(char_range.clone());
+    buffer.insert_text(&target.text, char_range.start);
+    Some(target.ranges.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TextRope;
+
+    #[test]
+    fn test_paragraphs_to_lay_out() {
+        // The first frame:
+        let window = paragraphs_to_lay_out(0..0, 500..520, 10_000);
+        assert_eq!(window, 400..620);
+
+        // Scrolling a bit keeps the window:
+        assert_eq!(
+            paragraphs_to_lay_out(window.clone(), 510..530, 10_000),
+            window
+        );
+
+        // Scrolling further moves it:
+        assert_eq!(paragraphs_to_lay_out(window, 610..630, 10_000), 510..730);
+
+        // Near the ends:
+        assert_eq!(paragraphs_to_lay_out(0..0, 0..20, 50), 0..50);
+        assert_eq!(paragraphs_to_lay_out(0..0, 80..90, 50), 0..50);
+        assert_eq!(paragraphs_to_lay_out(0..0, 0..0, 1), 0..1);
+    }
+
+    #[test]
+    fn test_text_window_edits() {
+        let mut rope = TextRope::from("zero\none\ntwo\nthree\n");
+        assert_eq!(paragraph_at(&rope, 10), 2);
+
+        let mut window = ParagraphWindow::new(&rope, 1..3, 1..3, 10.0);
+        assert_eq!(window.paragraphs, 1..3);
+        assert_eq!(window.char_range, 5..12);
+        assert_eq!(window.chars_after, 7);
+
+        let mut state = TextEditState::default();
+        state.set_char_ranges([
+            CCursorRange::one(CCursor::new(6)),
+            CCursorRange::one(CCursor::new(15)),
+        ]);
+        window.enter(&mut state);
+        assert_eq!(
+            state.char_ranges(),
+            vec![CCursorRange::one(CCursor::new(1))]
+        );
+
+        let edits = RefCell::default();
+        let mut text_window = TextWindow::new(&mut rope, &window, &edits);
+        assert_eq!(text_window.text(), "one\ntwo");
+        text_window.insert_text("ne, and o", 1);
+        text_window.delete_char_range(0..1);
+        assert_eq!(text_window.text(), "ne, and one\ntwo");
+
+        state.set_char_ranges([CCursorRange::one(CCursor::new(9))]);
+        window.leave(&mut state, &edits.borrow());
+        assert_eq!(rope.to_string(), "zero\nne, and one\ntwo\nthree\n");
+        assert_eq!(
+            state.char_ranges(),
+            vec![
+                CCursorRange::one(CCursor::new(14)),
+                CCursorRange::one(CCursor::new(23)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_window_straddling_ranges() {
+        let mut rope = TextRope::from("zero\none\ntwo\nthree\n");
+        let mut window = ParagraphWindow::new(&rope, 1..3, 1..3, 10.0);
+        assert_eq!(window.char_range, 5..12);
+
+        let range = |a, b| CCursorRange::two(CCursor::new(a), CCursor::new(b));
+        let mut state = TextEditState::default();
+        state.set_char_ranges([
+            CCursorRange::one(CCursor::new(6)),
+            // Over the start and the end of the window:
+            range(2, 7),
+            range(10, 16),
+        ]);
+        window.enter(&mut state);
+        assert_eq!(
+            state.char_ranges(),
+            vec![CCursorRange::one(CCursor::new(1))]
+        );
+
+        let edits = RefCell::default();
+        let mut text_window = TextWindow::new(&mut rope, &window, &edits);
+        // Between the two ranges:
+        text_window.insert_text("XX", 3);
+        // In the first range:
+        text_window.delete_char_range(0..1);
+        assert_eq!(text_window.text(), "neXX\ntwo");
+
+        state.set_char_ranges([CCursorRange::one(CCursor::new(0))]);
+        window.leave(&mut state, &edits.borrow());
+        assert_eq!(rope.to_string(), "zero\nneXX\ntwo\nthree\n");
+        assert_eq!(
+            state.char_ranges(),
+            vec![
+                CCursorRange::one(CCursor::new(5)),
+                range(2, 6),
+                range(11, 17)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paragraph_heights() {
+        let short_lines = TextRope::from("short line\n".repeat(100).as_str());
+        assert_eq!(paragraph_height(&short_lines, 10.0, 50.0), 10.0);
+
+        // 120 characters in each paragraph take three rows of 50:
+        let long_lines = TextRope::from(format!("{}\n", "x".repeat(120)).repeat(100).as_str());
+        assert_eq!(paragraph_height(&long_lines, 10.0, 50.0), 30.0);
+
+        let window = ParagraphWindow::new(&long_lines, 10..20, 10..20, 30.0);
+        assert_eq!(window.paragraphs, 10..20);
+        assert_eq!(window.top(), 300.0);
+        assert_eq!(window.text_height(250.0), 300.0 + 250.0 + 81.0 * 30.0);
+
+        // Above, in and below the window laid out last frame:
+        let visible = |min, max| visible_paragraphs(Rangef::new(min, max), &(10..20), 250.0, 30.0);
+        assert_eq!(visible(0.0, 90.0), 0..3);
+        assert_eq!(visible(325.0, 400.0), 11..14);
+        assert_eq!(visible(520.0, 610.0), 18..22);
+
+        // The first frame:
+        assert_eq!(
+            visible_paragraphs(Rangef::new(0.0, 100.0), &(0..0), 0.0, 30.0),
+            0..4
+        );
+    }
+
+    #[test]
+    fn test_undo_in_several_windows() {
+        let mut original = String::new();
+        for i in 0..300 {
+            original.push_str(&format!("line {i}\n"));
+        }
+        let mut rope = TextRope::from(original.as_str());
+        let mut undoer = WindowUndoer::default();
+
+        // Shows the given paragraphs, and maybe edits them, like a frame of a `TextEdit`:
+        let mut frame = |rope: &mut TextRope, time: f64, paragraphs: Range<usize>, insert: &str| {
+            let window = ParagraphWindow::new(rope, paragraphs.clone(), paragraphs, 10.0);
+            let ranges = vec![CCursorRange::one(CCursor::new(window.char_range.start))];
+            let checkpoint = window.undo_checkpoint(&undoer, rope, ranges.clone());
+
+            let edits = RefCell::default();
+            let mut text_window = TextWindow::new(rope, &window, &edits);
+            let before = text_window.text().into_owned();
+            text_window.insert_text(insert, 0);
+            let after = text_window.text().into_owned();
+            window.feed_undoer(
+                &mut undoer,
+                time,
+                checkpoint,
+                (ranges.clone(), &before),
+                (ranges, after),
+            );
+        };
+
+        frame(&mut rope, 0.0, 0..10, "");
+        frame(&mut rope, 0.1, 0..10, "top ");
+        frame(&mut rope, 5.0, 0..10, "");
+        // Scroll down, and edit there:
+        frame(&mut rope, 6.0, 200..210, "");
+        frame(&mut rope, 7.0, 200..210, "bottom ");
+        let edited = rope.to_string();
+        assert!(edited.starts_with("top line 0\n"));
+        assert!(edited.contains("\nbottom line 200\n"));
+
+        let mut ranges = vec![CCursorRange::one(CCursor::new(
+            edited.find("bottom").unwrap(),
+        ))];
+        ranges = undo(&mut rope, &mut undoer, ranges, false).unwrap();
+        assert!(rope.to_string().starts_with("top line 0\n"));
+        assert!(!rope.to_string().contains("bottom"));
+
+        // Undo back to the original text, including the edit in the first window:
+        let mut steps = 1;
+        while let Some(undone) = undo(&mut rope, &mut undoer, ranges.clone(), false) {
+            ranges = undone;
+            steps += 1;
+        }
+        assert_eq!(rope.to_string(), original);
+
+        for _ in 0..steps {
+            ranges = undo(&mut rope, &mut undoer, ranges, true).unwrap();
+        }
+        assert_eq!(rope.to_string(), edited);
+    }
+}
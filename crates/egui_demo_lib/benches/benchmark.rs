@@ -131,12 +131,12 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             font_image_size,
             prepared_discs,
         );
-        let mut mesh = egui::epaint::Mesh::default();
+        let mut meshes = vec![];
         let text_shape = TextShape::new(egui::Pos2::ZERO, galley, text_color);
         c.bench_function("tessellate_text", |b| {
             b.iter(|| {
-                tessellator.tessellate_text(&text_shape, &mut mesh);
-                mesh.clear();
+                tessellator.tessellate_text(&text_shape, &mut meshes);
+                meshes.clear();
             });
         });
     }
@@ -0,0 +1,1081 @@
+use std::ops::Range;
+
+use egui::{
+    epaint, pos2,
+    text::{CCursor, CCursorRange},
+    text_edit::{TextEditOutput, TextEditState},
+    vec2, Align2, CursorIcon, Galley, Id, Key, Margin, Modifiers, Rect, Response, Sense, Shape,
+    TextBuffer, TextEdit, TextStyle, Ui, Widget,
+};
+
+use crate::syntax_highlighting::{highlight, CodeTheme};
+
+/// The brackets that are matched and folded, as `(open, close)`.
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// Space on either side of the line numbers.
+const GUTTER_PADDING: f32 = 4.0;
+
+/// How a [`CodeEditor`] finds the regions of lines that can be folded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Folding {
+    /// A line can be folded if the lines after it are indented further, like in Python.
+    Indentation,
+
+    /// A line can be folded if it opens a bracket that is closed on a later line, like in Rust or C.
+    ///
+    /// The lines between the two brackets are folded.
+    /// Brackets in strings and comments are counted too.
+    Syntax,
+}
+
+/// A multiline [`TextEdit`] for code, with syntax highlighting from a [`CodeTheme`].
+///
+/// In addition to what [`TextEdit::code_editor`] does, it has:
+/// * line numbers, in a gutter to the left of the code
+/// * auto-indentation: Enter keeps the indentation of the line
+/// * bracket matching: the bracket next to the text cursor and its match are highlighted
+/// * folding: click the arrow in the gutter to hide the lines of a block, see [`Folding`]
+/// * a highlight of the line with the text cursor
+///
+/// Editing a folded line, or right next to the folded lines, unfolds them.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let mut code = String::new();
+/// egui::ScrollArea::vertical().show(ui, |ui| {
+///     ui.add(egui_extras::CodeEditor::new(&mut code, "rs"));
+/// });
+/// # });
+/// ```
+#[must_use = "You should put this widget in a ui with `ui.add(widget);`"]
+pub struct CodeEditor<'t> {
+    code: &'t mut dyn TextBuffer,
+    language: String,
+    theme: Option<CodeTheme>,
+    id_salt: Option<Id>,
+    line_numbers: bool,
+    auto_indent: bool,
+    bracket_matching: bool,
+    highlight_current_line: bool,
+    folding: Option<Folding>,
+    desired_rows: usize,
+    desired_width: f32,
+}
+
+impl<'t> CodeEditor<'t> {
+    /// The `language` is passed on to [`crate::syntax_highlighting::highlight`], e.g. `"rs"` or `"py"`.
+    pub fn new(code: &'t mut dyn TextBuffer, language: impl Into<String>) -> Self {
+        Self {
+            code,
+            language: language.into(),
+            theme: None,
+            id_salt: None,
+            line_numbers: true,
+            auto_indent: true,
+            bracket_matching: true,
+            highlight_current_line: true,
+            folding: Some(Folding::Indentation),
+            desired_rows: 10,
+            desired_width: f32::INFINITY,
+        }
+    }
+
+    /// The colors of the code and around it.
+    ///
+    /// Default is [`CodeTheme::from_memory`].
+    #[inline]
+    pub fn theme(mut self, theme: CodeTheme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// A source for the unique [`Id`], e.g. `.id_salt("second_code_editor_field")` or `.id_salt(loop_index)`.
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Show the line numbers? Default is `true`.
+    #[inline]
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Keep the indentation of the line when pressing Enter? Default is `true`.
+    #[inline]
+    pub fn auto_indent(mut self, auto_indent: bool) -> Self {
+        self.auto_indent = auto_indent;
+        self
+    }
+
+    /// Highlight the bracket next to the text cursor, and the one matching it? Default is `true`.
+    #[inline]
+    pub fn bracket_matching(mut self, bracket_matching: bool) -> Self {
+        self.bracket_matching = bracket_matching;
+        self
+    }
+
+    /// Highlight the line with the text cursor? Default is `true`.
+    #[inline]
+    pub fn highlight_current_line(mut self, highlight_current_line: bool) -> Self {
+        self.highlight_current_line = highlight_current_line;
+        self
+    }
+
+    /// How to find the lines that can be folded, or `None` to not allow folding.
+    ///
+    /// Default is [`Folding::Indentation`].
+    #[inline]
+    pub fn folding(mut self, folding: impl Into<Option<Folding>>) -> Self {
+        self.folding = folding.into();
+        self
+    }
+
+    /// Set the number of rows to show by default. Default is `10`.
+    #[inline]
+    pub fn desired_rows(mut self, desired_rows: usize) -> Self {
+        self.desired_rows = desired_rows;
+        self
+    }
+
+    /// The width of the code, not counting the gutter. Default is to fill the available width.
+    #[inline]
+    pub fn desired_width(mut self, desired_width: f32) -> Self {
+        self.desired_width = desired_width;
+        self
+    }
+}
+
+impl Widget for CodeEditor<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        self.show(ui).response
+    }
+}
+
+impl CodeEditor<'_> {
+    /// Show the [`CodeEditor`], returning the [`TextEditOutput`] of its [`TextEdit`].
+    ///
+    /// Where the text cursor is and what is laid out in the galley
+    /// is in the code without the folded lines.
+    pub fn show(self, ui: &mut Ui) -> TextEditOutput {
+        let Self {
+            code,
+            language,
+            theme,
+            id_salt,
+            line_numbers,
+            auto_indent,
+            bracket_matching,
+            highlight_current_line,
+            folding,
+            desired_rows,
+            desired_width,
+        } = self;
+
+        let id = id_salt.map_or_else(
+            || ui.next_auto_id(),
+            |id_salt| ui.make_persistent_id(id_salt),
+        );
+        let theme = theme.unwrap_or_else(|| CodeTheme::from_memory(ui.ctx(), ui.style()));
+        let colors = theme.editor_colors(ui.ctx());
+        let mut state = CodeEditorState::load(ui.ctx(), id).unwrap_or_default();
+
+        // Work out which lines to hide:
+        let text = code.text();
+        let mut lines = Lines::new(&text);
+        let mut regions = folding.map_or_else(Vec::new, |folding| fold_regions(&text, folding));
+        let folds = update_folds(&mut state.folded, &lines, &regions);
+        let hidden: Vec<Range<usize>> = folds
+            .iter()
+            .map(|region| lines.end(region.header)..lines.end(region.last))
+            .collect();
+        drop(text);
+
+        if hidden != state.hidden {
+            if let Some(mut text_edit_state) = TextEdit::load_state(ui.ctx(), id) {
+                // The cursors are in the text we showed last frame, with other lines hidden.
+                let remap = |ccursor: CCursor| {
+                    let index = to_code_index(&state.hidden, ccursor.index, false);
+                    CCursor::new(to_folded_index(&hidden, index))
+                };
+                let ranges = text_edit_state
+                    .char_ranges()
+                    .into_iter()
+                    .map(|range| CCursorRange {
+                        primary: remap(range.primary),
+                        secondary: remap(range.secondary),
+                    });
+                text_edit_state.set_char_ranges(ranges);
+
+                // So are the undo states.
+                text_edit_state.clear_undoer();
+                TextEdit::store_state(ui.ctx(), id, text_edit_state);
+            }
+        }
+
+        let mut folded_text = FoldedText::new(code, hidden);
+
+        // Without any cursor, Enter is left to the `TextEdit`:
+        let auto_indent_state =
+            (auto_indent && folded_text.is_mutable() && ui.memory(|mem| mem.has_focus(id)))
+                .then(|| TextEdit::load_state(ui.ctx(), id))
+                .flatten()
+                .filter(|state| !state.char_ranges().is_empty());
+        let auto_indented = auto_indent_state.is_some_and(|state| {
+            let enter = ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Enter));
+            if enter {
+                insert_newline_with_indentation(ui.ctx(), id, state, &mut folded_text);
+            }
+            enter
+        });
+
+        let font_id = TextStyle::Monospace.resolve(ui.style());
+        let digit_width = ui.fonts(|f| f.glyph_width(&font_id, '0'));
+        let number_width = if line_numbers {
+            lines.count().to_string().len().max(2) as f32 * digit_width
+        } else {
+            0.0
+        };
+        let marker_width = if folding.is_some() {
+            ui.fonts(|f| f.row_height(&font_id))
+        } else {
+            0.0
+        };
+        let gutter_width = if line_numbers || folding.is_some() {
+            GUTTER_PADDING + number_width + GUTTER_PADDING + marker_width
+        } else {
+            0.0
+        };
+
+        let margin = Margin::symmetric(4, 2);
+        let where_to_put_background = ui.painter().add(Shape::Noop);
+        let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+            let mut layout_job = highlight(ui.ctx(), ui.style(), &theme, string, &language);
+            layout_job.wrap.max_width = wrap_width;
+            ui.fonts(|f| f.layout_job(layout_job))
+        };
+        let mut output = ui
+            .horizontal_top(|ui| {
+                ui.spacing_mut().item_spacing.x = 0.0;
+                ui.add_space(gutter_width);
+                TextEdit::multiline(&mut folded_text)
+                    .id(id)
+                    .code_editor()
+                    .frame(false)
+                    .margin(margin)
+                    .desired_rows(desired_rows)
+                    .desired_width(desired_width)
+                    .layouter(&mut layouter)
+                    .show(ui)
+            })
+            .inner;
+        if auto_indented {
+            output.response.mark_changed();
+        }
+
+        // Folds that were edited are unfolded:
+        let FoldedText { hidden, edits, .. } = folded_text;
+        if !edits.is_empty() {
+            for (deleted, inserted) in edits {
+                unfold_edited(&mut state.folded, deleted, inserted);
+            }
+            let text = code.text();
+            lines = Lines::new(&text);
+            regions = folding.map_or_else(Vec::new, |folding| fold_regions(&text, folding));
+        }
+
+        // Paint the frame, and what goes behind the code:
+        let code_rect = output.response.rect + margin;
+        let frame_rect = code_rect.with_min_x(code_rect.left() - gutter_width);
+        let visuals = ui.style().interact(&output.response);
+        let stroke = if output.response.has_focus() {
+            ui.visuals().selection.stroke
+        } else {
+            visuals.bg_stroke
+        };
+        let mut background = vec![Shape::from(epaint::RectShape::new(
+            frame_rect.expand(visuals.expansion),
+            visuals.rounding,
+            colors.background.unwrap_or(ui.visuals().extreme_bg_color),
+            stroke,
+        ))];
+
+        let galley = output.galley.clone();
+        let galley_offset = output.galley_pos.to_vec2();
+        let current_paragraph = output
+            .cursor_range
+            .map(|cursor_range| cursor_range.primary.pcursor.paragraph);
+
+        if bracket_matching {
+            if let Some(cursor_range) = &output.cursor_range {
+                let chars: Vec<char> = galley.text().chars().collect();
+                if let Some(pair) = bracket_pair_at(&chars, cursor_range.primary.ccursor.index) {
+                    for index in pair {
+                        let rect = glyph_rect(&galley, index).translate(galley_offset);
+                        background.push(Shape::rect_filled(rect, 2.0, colors.matching_bracket));
+                    }
+                }
+            }
+        }
+
+        // The rows of a paragraph all belong to the same line of code.
+        let visible_lines: Vec<usize> = (0..lines.count())
+            .filter(|&line| {
+                let start = lines.starts[line];
+                !hidden.iter().any(|range| range.contains(&start))
+            })
+            .collect();
+        let clip_rect = ui.clip_rect();
+        let number_right = frame_rect.left() + GUTTER_PADDING + number_width;
+        let mut toggled_fold = None;
+        let mut paragraph = 0;
+        let mut starts_paragraph = true;
+        for (row_index, row) in galley.rows.iter().enumerate() {
+            let row_rect = row.rect.translate(galley_offset);
+            let ends_paragraph = row.ends_with_newline || row_index + 1 == galley.rows.len();
+            let line = visible_lines.get(paragraph).copied();
+
+            if let (Some(line), true) = (line, clip_rect.y_range().intersects(row_rect.y_range())) {
+                let is_current = current_paragraph == Some(paragraph);
+                if is_current && highlight_current_line {
+                    let rect = Rect::from_x_y_ranges(code_rect.x_range(), row_rect.y_range());
+                    background.push(Shape::rect_filled(rect, 0.0, colors.line_highlight));
+                }
+
+                let is_folded = hidden.iter().any(|range| range.start == lines.end(line));
+
+                if starts_paragraph && line_numbers {
+                    ui.painter().text(
+                        pos2(number_right, row_rect.top()),
+                        Align2::RIGHT_TOP,
+                        (line + 1).to_string(),
+                        font_id.clone(),
+                        if is_current {
+                            colors.current_line_number
+                        } else {
+                            colors.line_number
+                        },
+                    );
+                }
+
+                let is_foldable = regions
+                    .binary_search_by_key(&line, |region| region.header)
+                    .is_ok();
+                if starts_paragraph && is_foldable {
+                    let rect = Rect::from_min_size(
+                        pos2(number_right + GUTTER_PADDING, row_rect.top()),
+                        vec2(marker_width, row_rect.height()),
+                    );
+                    let response = ui
+                        .interact(rect, id.with(("fold", line)), Sense::click())
+                        .on_hover_cursor(CursorIcon::PointingHand);
+                    if response.clicked() {
+                        toggled_fold = Some(line);
+                    }
+                    ui.painter().text(
+                        rect.center(),
+                        Align2::CENTER_CENTER,
+                        if is_folded { "⏵" } else { "⏷" },
+                        font_id.clone(),
+                        if response.hovered() || is_folded {
+                            colors.current_line_number
+                        } else {
+                            colors.line_number
+                        },
+                    );
+                }
+
+                if ends_paragraph && is_folded {
+                    ui.painter().text(
+                        pos2(row_rect.right() + digit_width, row_rect.top()),
+                        Align2::LEFT_TOP,
+                        "⋯",
+                        font_id.clone(),
+                        colors.line_number,
+                    );
+                }
+            }
+
+            starts_paragraph = row.ends_with_newline;
+            if row.ends_with_newline {
+                paragraph += 1;
+            }
+        }
+
+        ui.painter()
+            .set(where_to_put_background, Shape::Vec(background));
+
+        if let Some(line) = toggled_fold {
+            let start = lines.starts[line];
+            if let Some(index) = state.folded.iter().position(|fold| fold.start == start) {
+                state.folded.remove(index);
+            } else if let Ok(index) = regions.binary_search_by_key(&line, |region| region.header) {
+                let fold = start..lines.end(regions[index].last);
+                let index = state.folded.partition_point(|f| f.start < start);
+                state.folded.insert(index, fold);
+            }
+            ui.ctx().request_repaint();
+        }
+
+        state.hidden = hidden;
+        state.store(ui.ctx(), id);
+
+        output
+    }
+}
+
+/// What a [`CodeEditor`] remembers between frames.
+#[derive(Clone, Default)]
+struct CodeEditorState {
+    /// The folded regions, from the start of the line before the folded lines
+    /// to the end of the last folded line, as char indices into the code.
+    ///
+    /// In order, and may be nested.
+    folded: Vec<Range<usize>>,
+
+    /// The char ranges of the code that were hidden last frame,
+    /// which the cursors of the [`TextEdit`] take into account.
+    hidden: Vec<Range<usize>>,
+}
+
+impl CodeEditorState {
+    fn load(ctx: &egui::Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_temp(id.with("code_editor")))
+    }
+
+    fn store(self, ctx: &egui::Context, id: Id) {
+        ctx.data_mut(|d| d.insert_temp(id.with("code_editor"), self));
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Where each line of some text starts.
+struct Lines {
+    /// The char index of the start of each line.
+    starts: Vec<usize>,
+
+    char_count: usize,
+}
+
+impl Lines {
+    fn new(text: &str) -> Self {
+        let mut starts = vec![0];
+        let mut char_count = 0;
+        for c in text.chars() {
+            char_count += 1;
+            if c == '\n' {
+                starts.push(char_count);
+            }
+        }
+        Self { starts, char_count }
+    }
+
+    fn count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// The char index of the end of the line, before its newline.
+    fn end(&self, line: usize) -> usize {
+        self.starts
+            .get(line + 1)
+            .map_or(self.char_count, |next_start| next_start - 1)
+    }
+
+    /// The line the char index is in.
+    fn line_at(&self, char_index: usize) -> usize {
+        self.starts.partition_point(|&start| start <= char_index) - 1
+    }
+}
+
+/// Lines that can be folded: the ones after `header`, up to and including `last`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FoldRegion {
+    header: usize,
+    last: usize,
+}
+
+/// All the regions that can be folded, in order of their header line.
+///
+/// Each line is the header of at most one region.
+fn fold_regions(text: &str, folding: Folding) -> Vec<FoldRegion> {
+    let mut regions = Vec::new();
+    match folding {
+        Folding::Indentation => {
+            // The lines that are still open for more indented lines to follow, with their indentation:
+            let mut open: Vec<(usize, usize)> = Vec::new();
+            let mut last_non_blank = 0;
+            let mut close = |open: &mut Vec<(usize, usize)>, header: usize, last: usize| {
+                open.pop();
+                if header < last {
+                    regions.push(FoldRegion { header, last });
+                }
+            };
+            for (line, content) in text.split('\n').enumerate() {
+                if content.trim().is_empty() {
+                    continue; // Blank lines don't end a region.
+                }
+                let indentation = content.chars().take_while(|c| c.is_whitespace()).count();
+                while let Some(&(header, header_indentation)) = open.last() {
+                    if header_indentation < indentation {
+                        break;
+                    }
+                    close(&mut open, header, last_non_blank);
+                }
+                open.push((line, indentation));
+                last_non_blank = line;
+            }
+            while let Some(&(header, _)) = open.last() {
+                close(&mut open, header, last_non_blank);
+            }
+        }
+
+        Folding::Syntax => {
+            // The closing brackets we expect, and the lines of their opening ones:
+            let mut open: Vec<(char, usize)> = Vec::new();
+            let mut line = 0;
+            for c in text.chars() {
+                if c == '\n' {
+                    line += 1;
+                } else if let Some(&(_, close)) = BRACKETS.iter().find(|(open, _)| *open == c) {
+                    open.push((close, line));
+                } else if open.last().is_some_and(|&(close, _)| close == c) {
+                    let (_, header) = open.pop().unwrap_or_default();
+                    if header + 1 < line {
+                        regions.push(FoldRegion {
+                            header,
+                            last: line - 1,
+                        });
+                    }
+                }
+            }
+
+            // Of the regions with the same header line, keep the largest:
+            regions.sort_by_key(|region| (region.header, std::cmp::Reverse(region.last)));
+            regions.dedup_by_key(|region| region.header);
+        }
+    }
+    regions.sort_by_key(|region| region.header);
+    regions
+}
+
+/// Forget the `folded` regions that can no longer be folded, and update the extent of the others.
+///
+/// Returns the regions to hide, leaving out those inside other hidden regions.
+fn update_folds(
+    folded: &mut Vec<Range<usize>>,
+    lines: &Lines,
+    regions: &[FoldRegion],
+) -> Vec<FoldRegion> {
+    let mut outermost: Vec<FoldRegion> = Vec::new();
+    folded.retain_mut(|fold| {
+        let header = lines.line_at(fold.start.min(lines.char_count));
+        let Ok(index) = regions.binary_search_by_key(&header, |region| region.header) else {
+            return false;
+        };
+        if lines.starts[header] != fold.start {
+            return false;
+        }
+
+        let region = regions[index];
+        *fold = fold.start..lines.end(region.last);
+        if outermost.last().map_or(true, |outer| outer.last < header) {
+            outermost.push(region);
+        }
+        true
+    });
+    outermost
+}
+
+/// Unfold the `folded` regions touched by an edit of the code,
+/// and move the ones after it.
+fn unfold_edited(folded: &mut Vec<Range<usize>>, deleted: Range<usize>, inserted: usize) {
+    folded.retain_mut(|fold| {
+        if deleted.end < fold.start {
+            *fold = fold.start - deleted.len() + inserted..fold.end - deleted.len() + inserted;
+            true
+        } else {
+            fold.end < deleted.start
+        }
+    });
+}
+
+// ----------------------------------------------------------------------------
+
+/// The char index in the code of the char index `index` in the text with the `hidden` ranges left out.
+///
+/// An index right where some text is hidden is placed before that text, unless `after_hidden`.
+fn to_code_index(hidden: &[Range<usize>], index: usize, after_hidden: bool) -> usize {
+    let mut code_index = index;
+    for range in hidden {
+        if range.start < code_index || (after_hidden && range.start == code_index) {
+            code_index += range.len();
+        } else {
+            break;
+        }
+    }
+    code_index
+}
+
+/// The char index in the text with the `hidden` ranges left out of the char index `code_index` in the code.
+///
+/// An index in hidden text is placed where that text is hidden.
+fn to_folded_index(hidden: &[Range<usize>], code_index: usize) -> usize {
+    let mut index = code_index;
+    for range in hidden {
+        if range.end <= code_index {
+            index -= range.len();
+        } else {
+            if range.start < code_index {
+                index -= code_index - range.start;
+            }
+            break;
+        }
+    }
+    index
+}
+
+/// The code with the folded lines left out, which passes on all edits to the code.
+struct FoldedText<'t> {
+    text: String,
+
+    /// The char ranges of the code that are left out, in order.
+    hidden: Vec<Range<usize>>,
+
+    code: &'t mut dyn TextBuffer,
+
+    /// The edits of the code: the deleted char range, and how many chars were then inserted at its start.
+    edits: Vec<(Range<usize>, usize)>,
+}
+
+impl<'t> FoldedText<'t> {
+    fn new(code: &'t mut dyn TextBuffer, hidden: Vec<Range<usize>>) -> Self {
+        let text = code.text();
+        let mut folded_text = String::with_capacity(text.len());
+        let mut next_hidden = 0;
+        for (index, c) in text.chars().enumerate() {
+            while hidden
+                .get(next_hidden)
+                .is_some_and(|range| range.end <= index)
+            {
+                next_hidden += 1;
+            }
+            if !hidden
+                .get(next_hidden)
+                .is_some_and(|range| range.contains(&index))
+            {
+                folded_text.push(c);
+            }
+        }
+        drop(text);
+
+        Self {
+            text: folded_text,
+            hidden,
+            code,
+            edits: Vec::new(),
+        }
+    }
+}
+
+impl TextBuffer for FoldedText<'_> {
+    fn is_mutable(&self) -> bool {
+        self.code.is_mutable()
+    }
+
+    fn chunks(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(std::iter::once(self.text.as_str()))
+    }
+
+    fn as_contiguous_str(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
+        let char_index = char_index.min(self.char_count());
+        let code_index = to_code_index(&self.hidden, char_index, false);
+        let inserted = self.code.insert_text(text, code_index);
+        let inserted_text: String = text.chars().take(inserted).collect();
+        self.text.insert_text(&inserted_text, char_index);
+
+        for range in &mut self.hidden {
+            if code_index <= range.start {
+                *range = range.start + inserted..range.end + inserted;
+            }
+        }
+        self.edits.push((code_index..code_index, inserted));
+        inserted
+    }
+
+    fn delete_char_range(&mut self, char_range: Range<usize>) {
+        let char_count = self.char_count();
+        let char_range = char_range.start.min(char_count)..char_range.end.min(char_count);
+        if char_range.is_empty() {
+            return;
+        }
+
+        // Hidden text is only deleted along with text on both sides of it.
+        let start = to_code_index(&self.hidden, char_range.start, true);
+        let end = to_code_index(&self.hidden, char_range.end, false);
+        self.code.delete_char_range(start..end);
+        self.text.delete_char_range(char_range);
+
+        self.hidden.retain_mut(|range| {
+            if end <= range.start {
+                *range = range.start - (end - start)..range.end - (end - start);
+                true
+            } else {
+                range.end <= start
+            }
+        });
+        self.edits.push((start..end, 0));
+    }
+
+    fn clear(&mut self) {
+        let char_count = self.code.char_count();
+        self.code.clear();
+        self.text.clear();
+        self.hidden.clear();
+        self.edits.push((0..char_count, 0));
+    }
+
+    fn replace_with(&mut self, text: &str) {
+        // Only replace what changed (e.g. on undo), so the hidden text stays.
+        let old_char_count = self.char_count();
+        let new_char_count = text.chars().count();
+        let prefix = self
+            .text
+            .chars()
+            .zip(text.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = self
+            .text
+            .chars()
+            .rev()
+            .zip(text.chars().rev())
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(old_char_count - prefix)
+            .min(new_char_count - prefix);
+
+        self.delete_char_range(prefix..old_char_count - suffix);
+        let inserted: String = text
+            .chars()
+            .skip(prefix)
+            .take(new_char_count - prefix - suffix)
+            .collect();
+        self.insert_text(&inserted, prefix);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The spaces and tabs at the start of the line, up to `char_index`.
+fn indentation_at(text: &str, char_index: usize) -> &str {
+    let byte_index = text
+        .char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(byte_index, _)| byte_index);
+    let line_start = text[..byte_index].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..byte_index];
+    let indentation_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+    &line[..indentation_len]
+}
+
+/// Replace the selections of the [`TextEdit`] with a newline followed by the indentation of their line.
+fn insert_newline_with_indentation(
+    ctx: &egui::Context,
+    id: Id,
+    mut state: TextEditState,
+    text: &mut dyn TextBuffer,
+) {
+    let mut ranges = state.char_ranges();
+
+    // Go through the selections in order, keeping track of how much the earlier edits moved the text:
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_by_key(|&i| ranges[i].sorted()[0].index);
+    let mut added = 0;
+    let mut removed = 0;
+    for i in order {
+        let [min, max] = ranges[i].sorted();
+        let start = min.index + added - removed;
+        let end = max.index + added - removed;
+
+        let newline = format!("\n{}", indentation_at(&text.text(), start));
+        text.delete_char_range(start..end);
+        let inserted = text.insert_text(&newline, start);
+        ranges[i] = CCursorRange::one(CCursor::new(start + inserted));
+
+        added += inserted;
+        removed += end - start;
+    }
+
+    state.set_char_ranges(ranges);
+    TextEdit::store_state(ctx, id, state);
+}
+
+/// The char index of the bracket matching the one at `index`, if any.
+fn matching_bracket(chars: &[char], index: usize) -> Option<usize> {
+    let c = *chars.get(index)?;
+    let mut depth = 0;
+    if let Some(&(open, close)) = BRACKETS.iter().find(|(open, _)| *open == c) {
+        for (i, &c) in chars.iter().enumerate().skip(index) {
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+        }
+    } else if let Some(&(open, close)) = BRACKETS.iter().find(|(_, close)| *close == c) {
+        for i in (0..=index).rev() {
+            if chars[i] == close {
+                depth += 1;
+            } else if chars[i] == open {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The bracket just before the text cursor at `cursor`, or else just after it, and the one matching it.
+fn bracket_pair_at(chars: &[char], cursor: usize) -> Option<[usize; 2]> {
+    [cursor.checked_sub(1), Some(cursor)]
+        .into_iter()
+        .flatten()
+        .find_map(|index| Some([index, matching_bracket(chars, index)?]))
+}
+
+/// Where the char at `index` is in the `galley`.
+fn glyph_rect(galley: &Galley, index: usize) -> Rect {
+    let left = galley.pos_from_ccursor(CCursor::new(index));
+    let right = galley.pos_from_ccursor(CCursor::new(index + 1));
+    Rect::from_min_max(
+        left.min,
+        pos2(right.max.x.max(left.min.x + 1.0), left.max.y),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_regions() {
+        let code = "fn main() {\n    if x {\n        y();\n    }\n\n    z();\n}\n";
+        assert_eq!(
+            fold_regions(code, Folding::Indentation),
+            [
+                FoldRegion { header: 0, last: 5 },
+                FoldRegion { header: 1, last: 2 },
+            ]
+        );
+        assert_eq!(
+            fold_regions(code, Folding::Syntax),
+            [
+                FoldRegion { header: 0, last: 5 },
+                FoldRegion { header: 1, last: 2 },
+            ]
+        );
+
+        let python = "def f():\n    pass\n\n\nx = 1\n";
+        assert_eq!(
+            fold_regions(python, Folding::Indentation),
+            [FoldRegion { header: 0, last: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_folded_text() {
+        let mut code = String::from("a {\n    b\n}\nc");
+        let lines = Lines::new(&code);
+        let regions = fold_regions(&code, Folding::Syntax);
+        let mut folded: Vec<_> = std::iter::once(0..lines.end(1)).collect();
+        let folds = update_folds(&mut folded, &lines, &regions);
+        let hidden: Vec<_> = folds
+            .iter()
+            .map(|region| lines.end(region.header)..lines.end(region.last))
+            .collect();
+        assert_eq!(hidden, vec![(3..9)]);
+
+        let mut folded_text = FoldedText::new(&mut code, hidden);
+        assert_eq!(folded_text.text, "a {\n}\nc");
+        assert_eq!(to_code_index(&folded_text.hidden, 3, false), 3);
+        assert_eq!(to_code_index(&folded_text.hidden, 3, true), 9);
+        assert_eq!(to_folded_index(&folded_text.hidden, 6), 3);
+
+        folded_text.insert_text("x", 0);
+        folded_text.insert_text("!", 8);
+        assert_eq!(folded_text.text, "xa {\n}\nc!");
+        folded_text.replace_with("xa {\n};\nc!");
+        let FoldedText { hidden, edits, .. } = folded_text;
+        assert_eq!(code, "xa {\n    b\n};\nc!");
+        assert_eq!(hidden, vec![(4..10)]);
+
+        for (deleted, inserted) in edits {
+            unfold_edited(&mut folded, deleted, inserted);
+        }
+        assert!(folded.is_empty());
+    }
+
+    #[test]
+    fn test_brackets_and_indentation() {
+        let chars: Vec<char> = "f(a[0], (b))".chars().collect();
+        assert_eq!(matching_bracket(&chars, 1), Some(11));
+        assert_eq!(matching_bracket(&chars, 10), Some(8));
+        assert_eq!(bracket_pair_at(&chars, 4), Some([3, 5]));
+        assert_eq!(bracket_pair_at(&chars, 0), None);
+
+        assert_eq!(indentation_at("x\n\t  y", 6), "\t  ");
+        assert_eq!(indentation_at("x\n    y", 4), "  ");
+    }
+
+    /// A [`CodeEditor`] in a test harness, and what it showed last frame.
+    struct Editor {
+        code: String,
+        id: Id,
+        galley_pos: egui::Pos2,
+        galley: Option<std::sync::Arc<Galley>>,
+    }
+
+    fn editor(code: &str) -> egui_kittest::Harness<'static, Editor> {
+        let editor = Editor {
+            code: code.to_owned(),
+            id: Id::NULL,
+            galley_pos: egui::Pos2::ZERO,
+            galley: None,
+        };
+        let mut harness = egui_kittest::Harness::new_ui_state(
+            |ui, editor: &mut Editor| {
+                let output = CodeEditor::new(&mut editor.code, "rs").show(ui);
+                editor.id = output.response.id;
+                editor.galley_pos = output.galley_pos;
+                editor.galley = Some(output.galley);
+            },
+            editor,
+        );
+        harness.run();
+        harness
+    }
+
+    /// Focus the editor, with the text cursor at `char_index` of the shown text.
+    fn place_cursor(harness: &mut egui_kittest::Harness<'_, Editor>, char_index: usize) {
+        let id = harness.state().id;
+        let mut state = TextEdit::load_state(&harness.ctx, id).unwrap_or_default();
+        state.set_char_ranges([CCursorRange::one(CCursor::new(char_index))]);
+        TextEdit::store_state(&harness.ctx, id, state);
+        harness.ctx.memory_mut(|mem| mem.request_focus(id));
+        harness.run();
+    }
+
+    fn shown_text(harness: &egui_kittest::Harness<'_, Editor>) -> String {
+        harness.state().galley.as_ref().unwrap().text().to_owned()
+    }
+
+    /// The number of brackets highlighted by the bracket matching.
+    fn highlighted_brackets(harness: &egui_kittest::Harness<'_, Editor>) -> usize {
+        fn count(shape: &Shape, color: egui::Color32) -> usize {
+            match shape {
+                Shape::Vec(shapes) => shapes.iter().map(|shape| count(shape, color)).sum(),
+                Shape::Rect(rect) => usize::from(rect.fill == color),
+                _ => 0,
+            }
+        }
+        let ctx = &harness.ctx;
+        let color = CodeTheme::from_memory(ctx, &ctx.style())
+            .editor_colors(ctx)
+            .matching_bracket;
+        harness
+            .output()
+            .shapes
+            .iter()
+            .map(|clipped| count(&clipped.shape, color))
+            .sum()
+    }
+
+    #[test]
+    fn test_auto_indent() {
+        let mut harness = editor("fn main() {\n    x();\n}");
+        place_cursor(&mut harness, 20);
+        harness.press_key(Key::Enter);
+        harness.run();
+        harness
+            .input_mut()
+            .events
+            .push(egui::Event::Text("y();".to_owned()));
+        harness.run();
+        assert_eq!(harness.state().code, "fn main() {\n    x();\n    y();\n}");
+    }
+
+    #[test]
+    fn test_enter_without_cursor() {
+        let mut harness = editor("abc");
+        let id = harness.state().id;
+        harness.ctx.memory_mut(|mem| mem.request_focus(id));
+        harness.run();
+        let mut state = TextEdit::load_state(&harness.ctx, id).unwrap_or_default();
+        state.set_char_ranges([]);
+        TextEdit::store_state(&harness.ctx, id, state);
+
+        // The `TextEdit` still gets the key press, and puts the newline at the end:
+        harness.press_key(Key::Enter);
+        harness.run();
+        assert_eq!(harness.state().code, "abc\n");
+    }
+
+    #[test]
+    fn test_bracket_matching() {
+        let mut harness = editor("fn main() {\n    x();\n}");
+        place_cursor(&mut harness, 11);
+        assert_eq!(highlighted_brackets(&harness), 2);
+
+        place_cursor(&mut harness, 3);
+        assert_eq!(highlighted_brackets(&harness), 0);
+    }
+
+    #[test]
+    fn test_toggle_fold() {
+        let code = "fn main() {\n    x();\n}";
+        let mut harness = editor(code);
+        assert_eq!(shown_text(&harness), code);
+
+        // The fold marker is in the gutter, right next to the first line:
+        let galley = harness.state().galley.clone().unwrap();
+        let row_height = galley.rows[0].rect.height();
+        let marker = harness.state().galley_pos + vec2(-4.0 - row_height / 2.0, row_height / 2.0);
+        let click = |harness: &mut egui_kittest::Harness<'_, Editor>| {
+            harness
+                .input_mut()
+                .events
+                .push(egui::Event::PointerMoved(marker));
+            harness.run();
+            for pressed in [true, false] {
+                harness.input_mut().events.push(egui::Event::PointerButton {
+                    pos: marker,
+                    button: egui::PointerButton::Primary,
+                    pressed,
+                    modifiers: Modifiers::NONE,
+                });
+                harness.run();
+            }
+        };
+
+        click(&mut harness);
+        assert_eq!(shown_text(&harness), "fn main() {\n}");
+        assert_eq!(harness.state().code, code);
+
+        click(&mut harness);
+        assert_eq!(shown_text(&harness), code);
+    }
+}
@@ -9,6 +9,7 @@
 #![allow(clippy::float_cmp)]
 #![allow(clippy::manual_range_contains)]
 
+mod code_editor;
 #[cfg(feature = "chrono")]
 mod datepicker;
 
@@ -21,7 +22,11 @@ mod loaders;
 mod sizing;
 mod strip;
 mod table;
+mod table_edit;
+mod table_selection;
+mod table_tree;
 
+pub use crate::code_editor::{CodeEditor, Folding};
 #[cfg(feature = "chrono")]
 pub use crate::datepicker::DatePickerButton;
 
@@ -32,6 +37,9 @@ pub(crate) use crate::layout::StripLayout;
 pub use crate::sizing::Size;
 pub use crate::strip::*;
 pub use crate::table::*;
+pub use crate::table_edit::*;
+pub use crate::table_selection::*;
+pub use crate::table_tree::*;
 
 pub use loaders::install_image_loaders;
 
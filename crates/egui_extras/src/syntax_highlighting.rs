@@ -33,17 +33,6 @@ pub fn highlight(
     // performing it at a separate thread (ctx, ctx.style()) can be used and when ui is available
     // (ui.ctx(), ui.style()) can be used
 
-    impl egui::cache::ComputerMut<(&egui::FontId, &CodeTheme, &str, &str), LayoutJob> for Highlighter {
-        fn compute(
-            &mut self,
-            (font_id, theme, code, lang): (&egui::FontId, &CodeTheme, &str, &str),
-        ) -> LayoutJob {
-            self.highlight(font_id.clone(), theme, code, lang)
-        }
-    }
-
-    type HighlightCache = egui::cache::FrameCache<LayoutJob, Highlighter>;
-
     let font_id = style
         .override_font_id
         .clone()
@@ -56,10 +45,39 @@ pub fn highlight(
     })
 }
 
+impl egui::cache::ComputerMut<(&egui::FontId, &CodeTheme, &str, &str), LayoutJob> for Highlighter {
+    fn compute(
+        &mut self,
+        (font_id, theme, code, lang): (&egui::FontId, &CodeTheme, &str, &str),
+    ) -> LayoutJob {
+        self.highlight(font_id.clone(), theme, code, lang)
+    }
+}
+
+type HighlightCache = egui::cache::FrameCache<LayoutJob, Highlighter>;
+
 fn monospace_font_size(style: &egui::Style) -> f32 {
     TextStyle::Monospace.resolve(style).size
 }
 
+/// The colors of a [`crate::CodeEditor`] around the code, from its [`CodeTheme`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct EditorColors {
+    /// Behind the code, if the theme has its own background.
+    pub background: Option<egui::Color32>,
+
+    /// Behind the line with the text cursor.
+    pub line_highlight: egui::Color32,
+
+    pub line_number: egui::Color32,
+
+    /// The number of the line with the text cursor.
+    pub current_line_number: egui::Color32,
+
+    /// Behind the bracket next to the text cursor, and the one matching it.
+    pub matching_bracket: egui::Color32,
+}
+
 // ----------------------------------------------------------------------------
 
 #[cfg(not(feature = "syntect"))]
@@ -268,6 +286,16 @@ impl CodeTheme {
         }
     }
 
+    /// Uses the syntect themes that were loaded for [`highlight`].
+    pub(crate) fn editor_colors(&self, ctx: &egui::Context) -> EditorColors {
+        ctx.memory_mut(|mem| {
+            mem.caches
+                .cache::<HighlightCache>()
+                .computer()
+                .editor_colors(self)
+        })
+    }
+
     /// Show UI for changing the color theme.
     pub fn ui(&mut self, ui: &mut egui::Ui) {
         egui::widgets::global_theme_preference_buttons(ui);
@@ -318,6 +346,17 @@ impl CodeTheme {
         }
     }
 
+    pub(crate) fn editor_colors(&self, _ctx: &egui::Context) -> EditorColors {
+        let punctuation = self.formats[TokenType::Punctuation].color;
+        EditorColors {
+            background: None,
+            line_highlight: punctuation.gamma_multiply(0.08),
+            line_number: self.formats[TokenType::Comment].color,
+            current_line_number: punctuation,
+            matching_bracket: punctuation.gamma_multiply(0.25),
+        }
+    }
+
     /// Show UI for changing the color theme.
     pub fn ui(&mut self, ui: &mut egui::Ui) {
         ui.horizontal_top(|ui| {
@@ -468,17 +507,17 @@ impl Highlighter {
                 } else {
                     egui::Stroke::NONE
                 };
-                job.sections.push(LayoutSection {
-                    leading_space: 0.0,
-                    byte_range: as_byte_range(text, range),
-                    format: TextFormat {
+                job.sections.push(LayoutSection::new(
+                    0.0,
+                    as_byte_range(text, range),
+                    TextFormat {
                         font_id: theme.font_id.clone(),
                         color: text_color,
                         italics,
                         underline,
                         ..Default::default()
                     },
-                });
+                ));
             }
         }
 
@@ -486,6 +525,38 @@ impl Highlighter {
     }
 }
 
+#[cfg(feature = "syntect")]
+impl Highlighter {
+    /// The colors of the syntect theme around the code.
+    fn editor_colors(&self, theme: &CodeTheme) -> EditorColors {
+        let settings = &self.ts.themes[theme.syntect_theme.syntect_key_name()].settings;
+        let color = |c: syntect::highlighting::Color| {
+            egui::Color32::from_rgba_unmultiplied(c.r, c.g, c.b, c.a)
+        };
+        let foreground = settings.foreground.map_or(
+            if theme.dark_mode {
+                egui::Color32::LIGHT_GRAY
+            } else {
+                egui::Color32::DARK_GRAY
+            },
+            color,
+        );
+        EditorColors {
+            background: settings.background.map(color),
+            line_highlight: settings
+                .line_highlight
+                .map_or(foreground.gamma_multiply(0.08), color),
+            line_number: settings
+                .gutter_foreground
+                .map_or(foreground.gamma_multiply(0.5), color),
+            current_line_number: foreground,
+            matching_bracket: settings
+                .brackets_background
+                .map_or(foreground.gamma_multiply(0.25), color),
+        }
+    }
+}
+
 #[cfg(feature = "syntect")]
 fn as_byte_range(whole: &str, range: &str) -> std::ops::Range<usize> {
     let whole_start = whole.as_ptr() as usize;
@@ -5,12 +5,15 @@
 
 use egui::{
     scroll_area::{ScrollAreaOutput, ScrollBarVisibility},
-    Align, Id, NumExt as _, Rangef, Rect, Response, ScrollArea, Ui, Vec2, Vec2b,
+    Align, Align2, Id, NumExt as _, Rangef, Rect, Response, ScrollArea, Sense, TextStyle, Ui, Vec2,
+    Vec2b, WidgetText,
 };
 
 use crate::{
     layout::{CellDirection, CellSize, StripLayoutFlags},
-    StripLayout,
+    table_edit::{next_editable_cell, ActiveEdit, EditEnd},
+    table_tree::{is_node_open, visible_nodes, TreeCell},
+    CellEdit, CellEditor, CellValue, StripLayout, TableSelection, TableTreeNode,
 };
 
 // -----------------------------------------------------------------=----------
@@ -42,6 +45,12 @@ pub struct Column {
     /// If set, we should acurately measure the size of this column this frame
     /// so that we can correctly auto-size it. This is done as a `sizing_pass`.
     auto_size_this_frame: bool,
+
+    /// Clicking the header sorts by this column.
+    sortable: bool,
+
+    /// Can be hidden from the header context menu.
+    hideable: bool,
 }
 
 impl Column {
@@ -91,6 +100,8 @@ impl Column {
             resizable: None,
             clip: false,
             auto_size_this_frame: false,
+            sortable: false,
+            hideable: false,
         }
     }
 
@@ -152,6 +163,28 @@ impl Column {
         self
     }
 
+    /// Can the table be sorted by this column?
+    ///
+    /// If so, clicking the header cell of the column sorts by it (or reverses the sort),
+    /// and a sort indicator is shown in the header.
+    /// Use [`Table::sort`] to find out how to sort the rows.
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    /// Can the user hide this column from the context menu of the header?
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn hideable(mut self, hideable: bool) -> Self {
+        self.hideable = hideable;
+        self
+    }
+
     fn is_auto(&self) -> bool {
         match self.initial_width {
             InitialColumnSize::Automatic(_) => true,
@@ -176,6 +209,62 @@ fn to_sizing(columns: &[Column]) -> crate::sizing::Sizing {
     sizing
 }
 
+/// Which way a [`Table`] is sorted. See [`Column::sortable`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    /// The other direction.
+    #[inline]
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Ascending => Self::Descending,
+            Self::Descending => Self::Ascending,
+        }
+    }
+
+    /// Apply the direction to the result of comparing two rows in ascending order.
+    ///
+    /// ```
+    /// # use egui_extras::SortDirection;
+    /// let mut rows = vec![2, 3, 1];
+    /// rows.sort_by(|a, b| SortDirection::Descending.apply(a.cmp(b)));
+    /// assert_eq!(rows, [3, 2, 1]);
+    /// ```
+    #[inline]
+    pub fn apply(self, ordering: std::cmp::Ordering) -> std::cmp::Ordering {
+        match self {
+            Self::Ascending => ordering,
+            Self::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// How the user wants a [`Table`] to be sorted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ColumnSort {
+    /// The index of the column, in the order the columns were added to the [`TableBuilder`].
+    pub column: usize,
+
+    pub direction: SortDirection,
+}
+
+/// Where a visible column goes in a row.
+#[derive(Clone, Copy, Debug)]
+struct ColumnSlot {
+    /// Position among the visible columns, from left to right.
+    display_index: usize,
+
+    /// Distance from the left side of the row.
+    offset: f32,
+}
+
 // -----------------------------------------------------------------=----------
 
 struct TableScrollOptions {
@@ -244,17 +333,75 @@ impl Default for TableScrollOptions {
 ///     });
 /// # });
 /// ```
+///
+/// ### Sorting, reordering and hiding columns
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui_extras::{TableBuilder, Column};
+/// let mut files = vec![("b.txt", 30), ("a.txt", 10), ("c.txt", 20)];
+///
+/// let table = TableBuilder::new(ui)
+///     .column(Column::auto().sortable(true))
+///     .column(Column::remainder().sortable(true).hideable(true))
+///     .column_names(["Name", "Size"])
+///     .reorderable(true)
+///     .header(20.0, |mut header| {
+///         header.col(|ui| {
+///             ui.strong("Name");
+///         });
+///         header.col(|ui| {
+///             ui.strong("Size");
+///         });
+///     });
+///
+/// if let Some(sort) = table.sort() {
+///     files.sort_by(|a, b| {
+///         let ordering = if sort.column == 0 { a.0.cmp(b.0) } else { a.1.cmp(&b.1) };
+///         sort.direction.apply(ordering)
+///     });
+/// }
+///
+/// table.body(|mut body| {
+///     for (name, size) in &files {
+///         body.row(18.0, |mut row| {
+///             // Always add the cells in the original column order:
+///             row.col(|ui| {
+///                 ui.label(*name);
+///             });
+///             row.col(|ui| {
+///                 ui.label(size.to_string());
+///             });
+///         });
+///     }
+/// });
+/// # });
+/// ```
 pub struct TableBuilder<'a> {
     ui: &'a mut Ui,
     id_salt: Id,
     columns: Vec<Column>,
+    column_names: Vec<WidgetText>,
     striped: Option<bool>,
     resizable: bool,
+    reorderable: bool,
+    default_sort: Option<ColumnSort>,
+    selection: Option<&'a mut TableSelection>,
+    cell_text: Option<CellTextFn<'a>>,
+    editors: Vec<Option<CellEditor>>,
+    cell_value: Option<CellValueFn<'a>>,
+    cell_edits: Option<&'a mut Vec<CellEdit>>,
+    tree_column: usize,
     cell_layout: egui::Layout,
     scroll_options: TableScrollOptions,
     sense: egui::Sense,
 }
 
+/// Gets the text of a cell from the row and column index.
+type CellTextFn<'a> = Box<dyn Fn(usize, usize) -> String + 'a>;
+
+/// Gets the value of a cell to edit from the row and column index.
+type CellValueFn<'a> = Box<dyn Fn(usize, usize) -> CellValue + 'a>;
+
 impl<'a> TableBuilder<'a> {
     pub fn new(ui: &'a mut Ui) -> Self {
         let cell_layout = *ui.layout();
@@ -262,8 +409,17 @@ impl<'a> TableBuilder<'a> {
             ui,
             id_salt: Id::new("__table_state"),
             columns: Default::default(),
+            column_names: Default::default(),
             striped: None,
             resizable: false,
+            reorderable: false,
+            default_sort: None,
+            selection: None,
+            cell_text: None,
+            editors: Default::default(),
+            cell_value: None,
+            cell_edits: None,
+            tree_column: 0,
             cell_layout,
             scroll_options: Default::default(),
             sense: egui::Sense::hover(),
@@ -320,6 +476,159 @@ impl<'a> TableBuilder<'a> {
         self
     }
 
+    /// Let the user reorder the columns by dragging their header cells.
+    ///
+    /// Cells are still added in the original column order,
+    /// and the table moves them to where they are shown.
+    /// The new order is remembered, like the column widths.
+    ///
+    /// Default is `false`.
+    #[inline]
+    pub fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// The names of the columns, as shown in the context menu of the header.
+    ///
+    /// The context menu is shown if any column is [`Column::hideable`],
+    /// or if the table is [`Self::reorderable`].
+    /// Unnamed columns are called "Column 1", "Column 2", etc.
+    #[inline]
+    pub fn column_names(mut self, names: impl IntoIterator<Item = impl Into<WidgetText>>) -> Self {
+        self.column_names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// How to sort the table until the user clicks a [`Column::sortable`] header.
+    #[inline]
+    pub fn default_sort(mut self, column: usize, direction: SortDirection) -> Self {
+        self.default_sort = Some(ColumnSort { column, direction });
+        self
+    }
+
+    /// Let the user select rows or cells, by clicking and with the keyboard.
+    ///
+    /// The table gets keyboard focus when a cell is clicked,
+    /// and then scrolls to follow the keyboard cursor.
+    /// Use [`TableRow::is_selected`] to check if a row is selected while adding it.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui_extras::{Column, TableBuilder, TableSelection, TableSelectionMode};
+    /// # let mut selection = TableSelection::new(TableSelectionMode::Cells);
+    /// let data = vec![["a", "b"], ["c", "d"]];
+    /// TableBuilder::new(ui)
+    ///     .columns(Column::auto(), 2)
+    ///     .selection(&mut selection)
+    ///     .cell_text(|row, col| data[row][col].to_owned()) // for copying
+    ///     .body(|body| {
+    ///         body.rows(18.0, data.len(), |mut row| {
+    ///             for text in data[row.index()] {
+    ///                 row.col(|ui| {
+    ///                     ui.label(text);
+    ///                 });
+    ///             }
+    ///         });
+    ///     });
+    /// # });
+    /// ```
+    #[inline]
+    pub fn selection(mut self, selection: &'a mut TableSelection) -> Self {
+        self.selection = Some(selection);
+        self
+    }
+
+    /// The text of each cell, given the row and column index.
+    ///
+    /// Used to copy the [`Self::selection`] as tab-separated values with Ctrl/Cmd+C.
+    #[inline]
+    pub fn cell_text(mut self, cell_text: impl Fn(usize, usize) -> String + 'a) -> Self {
+        self.cell_text = Some(Box::new(cell_text));
+        self
+    }
+
+    /// Let the user edit the cells of the given column in place.
+    ///
+    /// Editing starts by double-clicking a cell, or by pressing Enter
+    /// when the table has keyboard focus (see [`Self::selection`]).
+    /// Enter or clicking elsewhere commits the edit, Escape cancels it,
+    /// and Tab (or Shift+Tab) commits and moves on to the next (or previous) editable cell.
+    ///
+    /// Committed edits are reported in [`Self::cell_edits`]. It is up to you to apply them.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui_extras::{CellEdit, CellEditor, CellValue, Column, TableBuilder};
+    /// # let mut names = vec!["Alice".to_owned(), "Bob".to_owned()];
+    /// # let mut ages = vec![32.0, 41.0];
+    /// let mut edits: Vec<CellEdit> = vec![];
+    /// TableBuilder::new(ui)
+    ///     .columns(Column::auto(), 2)
+    ///     .column_editor(0, CellEditor::Text)
+    ///     .column_editor(1, CellEditor::number())
+    ///     .cell_value(|row, col| match col {
+    ///         0 => CellValue::Text(names[row].clone()),
+    ///         _ => CellValue::Number(ages[row]),
+    ///     })
+    ///     .cell_edits(&mut edits)
+    ///     .body(|body| {
+    ///         body.rows(18.0, names.len(), |mut row| {
+    ///             let i = row.index();
+    ///             row.col(|ui| {
+    ///                 ui.label(&names[i]);
+    ///             });
+    ///             row.col(|ui| {
+    ///                 ui.label(ages[i].to_string());
+    ///             });
+    ///         });
+    ///     });
+    ///
+    /// for edit in edits {
+    ///     match edit.new {
+    ///         CellValue::Text(name) => names[edit.row] = name,
+    ///         CellValue::Number(age) => ages[edit.row] = age,
+    ///         CellValue::Bool(_) => {}
+    ///     }
+    /// }
+    /// # });
+    /// ```
+    #[inline]
+    pub fn column_editor(mut self, column: usize, editor: CellEditor) -> Self {
+        if self.editors.len() <= column {
+            self.editors.resize(column + 1, None);
+        }
+        self.editors[column] = Some(editor);
+        self
+    }
+
+    /// The value of each cell, given the row and column index, to start editing with.
+    ///
+    /// If not set, the [`Self::cell_text`] is used, converted to suit the [`Self::column_editor`].
+    #[inline]
+    pub fn cell_value(mut self, cell_value: impl Fn(usize, usize) -> CellValue + 'a) -> Self {
+        self.cell_value = Some(Box::new(cell_value));
+        self
+    }
+
+    /// Committed cell edits are pushed here, unless the value didn't change.
+    ///
+    /// See [`Self::column_editor`].
+    #[inline]
+    pub fn cell_edits(mut self, cell_edits: &'a mut Vec<CellEdit>) -> Self {
+        self.cell_edits = Some(cell_edits);
+        self
+    }
+
+    /// Which column of [`TableBody::tree_rows`] shows the disclosure triangles and the indentation.
+    ///
+    /// Default: `0`, the first column.
+    #[inline]
+    pub fn tree_column(mut self, column: usize) -> Self {
+        self.tree_column = column;
+        self
+    }
+
     /// Enable vertical scrolling in body (default: `true`)
     #[inline]
     pub fn vscroll(mut self, vscroll: bool) -> Self {
@@ -449,7 +758,7 @@ impl<'a> TableBuilder<'a> {
                 * self.ui.spacing().scroll.allocated_width()
     }
 
-    /// Reset all column widths.
+    /// Reset all column widths, the column order and visibility, and the sorting.
     pub fn reset(&self) {
         let state_id = self.ui.id().with(self.id_salt);
         TableState::reset(self.ui, state_id);
@@ -463,8 +772,17 @@ impl<'a> TableBuilder<'a> {
             ui,
             id_salt,
             mut columns,
+            column_names,
             striped,
             resizable,
+            reorderable,
+            default_sort,
+            selection,
+            cell_text,
+            editors,
+            cell_value,
+            cell_edits,
+            tree_column,
             cell_layout,
             scroll_options,
             sense,
@@ -483,35 +801,71 @@ impl<'a> TableBuilder<'a> {
 
         let state_id = ui.id().with(id_salt);
 
-        let (is_sizing_pass, state) =
-            TableState::load(ui, state_id, resizable, &columns, available_width);
+        let (is_sizing_pass, mut state) = TableState::load(
+            ui,
+            state_id,
+            resizable,
+            &columns,
+            available_width,
+            default_sort,
+        );
 
         let mut max_used_widths = vec![0.0; columns.len()];
         let table_top = ui.cursor().top();
 
+        let has_menu = reorderable || columns.iter().any(|c| c.hideable);
+        let mut header_sense = sense;
+        if has_menu || columns.iter().any(|c| c.sortable) {
+            header_sense |= Sense::click();
+        }
+        if reorderable {
+            header_sense |= Sense::drag();
+        }
+
+        let slots = state.column_slots(ui.spacing().item_spacing.x);
+        let mut header_responses = vec![None; columns.len()];
+
         let mut ui_builder = egui::UiBuilder::new();
         if is_sizing_pass {
             ui_builder = ui_builder.sizing_pass();
         }
         ui.scope_builder(ui_builder, |ui| {
-            let mut layout = StripLayout::new(ui, CellDirection::Horizontal, cell_layout, sense);
+            let mut layout =
+                StripLayout::new(ui, CellDirection::Horizontal, cell_layout, header_sense);
             let mut response: Option<Response> = None;
             add_header_row(TableRow {
                 layout: &mut layout,
                 columns: &columns,
                 widths: &state.column_widths,
+                slots: &slots,
                 max_used_widths: &mut max_used_widths,
                 row_index: 0,
                 col_index: 0,
+                last_display_index: None,
                 height,
                 striped: false,
                 hovered: false,
                 selected: false,
                 response: &mut response,
+                header_responses: Some(&mut header_responses),
+                tree: None,
+                selection: None,
+                interaction: &mut CellInteraction::default(),
             });
             layout.allocate_rect();
         });
 
+        if !is_sizing_pass {
+            state.header_interaction(
+                ui,
+                &columns,
+                &column_names,
+                reorderable,
+                has_menu,
+                &header_responses,
+            );
+        }
+
         Table {
             ui,
             table_top,
@@ -526,6 +880,12 @@ impl<'a> TableBuilder<'a> {
             cell_layout,
             scroll_options,
             sense,
+            selection,
+            cell_text,
+            editors,
+            cell_value,
+            cell_edits,
+            tree_column,
         }
     }
 
@@ -540,8 +900,17 @@ impl<'a> TableBuilder<'a> {
             ui,
             id_salt,
             columns,
+            column_names: _,
             striped,
             resizable,
+            reorderable: _,
+            default_sort,
+            selection,
+            cell_text,
+            editors,
+            cell_value,
+            cell_edits,
+            tree_column,
             cell_layout,
             scroll_options,
             sense,
@@ -551,8 +920,14 @@ impl<'a> TableBuilder<'a> {
 
         let state_id = ui.id().with(id_salt);
 
-        let (is_sizing_pass, state) =
-            TableState::load(ui, state_id, resizable, &columns, available_width);
+        let (is_sizing_pass, state) = TableState::load(
+            ui,
+            state_id,
+            resizable,
+            &columns,
+            available_width,
+            default_sort,
+        );
 
         let max_used_widths = vec![0.0; columns.len()];
         let table_top = ui.cursor().top();
@@ -571,6 +946,12 @@ impl<'a> TableBuilder<'a> {
             cell_layout,
             scroll_options,
             sense,
+            selection,
+            cell_text,
+            editors,
+            cell_value,
+            cell_edits,
+            tree_column,
         }
         .body(add_body_contents)
     }
@@ -581,11 +962,23 @@ impl<'a> TableBuilder<'a> {
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct TableState {
+    /// Indexed by column, in the order they were added to the [`TableBuilder`].
     column_widths: Vec<f32>,
 
     /// If known from previous frame
     #[cfg_attr(feature = "serde", serde(skip))]
     max_used_widths: Vec<f32>,
+
+    /// The columns, from left to right.
+    #[cfg_attr(feature = "serde", serde(default))]
+    column_order: Vec<usize>,
+
+    /// Indexed by column.
+    #[cfg_attr(feature = "serde", serde(default))]
+    hidden: Vec<bool>,
+
+    #[cfg_attr(feature = "serde", serde(default))]
+    sort: Option<ColumnSort>,
 }
 
 impl TableState {
@@ -596,6 +989,7 @@ impl TableState {
         resizable: bool,
         columns: &[Column],
         available_width: f32,
+        default_sort: Option<ColumnSort>,
     ) -> (bool, Self) {
         let rect = Rect::from_min_size(ui.available_rect_before_wrap().min, Vec2::ZERO);
         ui.ctx().check_for_id_clash(state_id, rect, "Table");
@@ -617,22 +1011,25 @@ impl TableState {
             Self {
                 column_widths: initial_widths,
                 max_used_widths: Default::default(),
+                column_order: Default::default(),
+                hidden: Default::default(),
+                sort: default_sort,
             }
         });
+        state.validate_columns();
 
         if !is_sizing_pass && state.max_used_widths.len() == columns.len() {
             // Make sure any non-resizable `remainder` columns are updated
             // to take up the remainder of the current available width.
             // Also handles changing item spacing.
+            let visible_columns: Vec<usize> = state.visible_columns().collect();
             let mut sizing = crate::sizing::Sizing::default();
-            for ((prev_width, max_used), column) in state
-                .column_widths
-                .iter()
-                .zip(&state.max_used_widths)
-                .zip(columns)
-            {
+            for &i in &visible_columns {
                 use crate::Size;
 
+                let column = &columns[i];
+                let prev_width = &state.column_widths[i];
+                let max_used = &state.max_used_widths[i];
                 let column_resizable = column.resizable.unwrap_or(resizable);
                 let size = if column_resizable {
                     // Resiable columns keep their width:
@@ -648,12 +1045,209 @@ impl TableState {
                 };
                 sizing.add(size);
             }
-            state.column_widths = sizing.to_lengths(available_width, ui.spacing().item_spacing.x);
+            let widths = sizing.to_lengths(available_width, ui.spacing().item_spacing.x);
+            for (i, width) in visible_columns.into_iter().zip(widths) {
+                state.column_widths[i] = width;
+            }
         }
 
         (is_sizing_pass, state)
     }
 
+    /// Make sure the column order and visibility match the number of columns.
+    fn validate_columns(&mut self) {
+        let num_columns = self.column_widths.len();
+
+        let mut is_permutation = self.column_order.len() == num_columns;
+        let mut seen = vec![false; num_columns];
+        for &i in &self.column_order {
+            if i >= num_columns || std::mem::replace(&mut seen[i], true) {
+                is_permutation = false;
+                break;
+            }
+        }
+        if !is_permutation {
+            self.column_order = (0..num_columns).collect();
+        }
+
+        if self.hidden.len() != num_columns || self.hidden.iter().all(|&hidden| hidden) {
+            self.hidden = vec![false; num_columns];
+        }
+
+        if self.sort.is_some_and(|sort| num_columns <= sort.column) {
+            self.sort = None;
+        }
+    }
+
+    /// The visible columns, from left to right.
+    fn visible_columns(&self) -> impl Iterator<Item = usize> + '_ {
+        self.column_order
+            .iter()
+            .copied()
+            .filter(|&i| !self.hidden[i])
+    }
+
+    /// Where each column goes in a row, or `None` for hidden columns.
+    fn column_slots(&self, spacing_x: f32) -> Vec<Option<ColumnSlot>> {
+        let mut slots = vec![None; self.column_widths.len()];
+        let mut offset = 0.0;
+        for (display_index, i) in self.visible_columns().enumerate() {
+            slots[i] = Some(ColumnSlot {
+                display_index,
+                offset,
+            });
+            offset += self.column_widths[i] + spacing_x;
+        }
+        slots
+    }
+
+    /// Move a column so that it is shown before the `visible_index`:th of the other visible columns.
+    fn move_column(&mut self, column: usize, visible_index: usize) {
+        self.column_order.retain(|&i| i != column);
+        let position = self
+            .column_order
+            .iter()
+            .enumerate()
+            .filter(|(_, &i)| !self.hidden[i])
+            .nth(visible_index)
+            .map_or(self.column_order.len(), |(position, _)| position);
+        self.column_order.insert(position, column);
+    }
+
+    /// Sorting, reordering and the context menu of the header cells.
+    fn header_interaction(
+        &mut self,
+        ui: &Ui,
+        columns: &[Column],
+        column_names: &[WidgetText],
+        reorderable: bool,
+        has_menu: bool,
+        header_responses: &[Option<Response>],
+    ) {
+        for (i, response) in header_responses.iter().enumerate() {
+            let Some(response) = response else {
+                continue;
+            };
+
+            if columns[i].sortable {
+                if response.clicked() {
+                    self.sort = Some(match self.sort {
+                        Some(sort) if sort.column == i => ColumnSort {
+                            column: i,
+                            direction: sort.direction.reversed(),
+                        },
+                        _ => ColumnSort {
+                            column: i,
+                            direction: SortDirection::Ascending,
+                        },
+                    });
+                }
+
+                if let Some(sort) = self.sort.filter(|sort| sort.column == i) {
+                    let icon = match sort.direction {
+                        SortDirection::Ascending => "⏶",
+                        SortDirection::Descending => "⏷",
+                    };
+                    ui.painter().text(
+                        response.rect.right_center(),
+                        Align2::RIGHT_CENTER,
+                        icon,
+                        TextStyle::Body.resolve(ui.style()),
+                        ui.visuals().strong_text_color(),
+                    );
+                }
+            }
+
+            if has_menu {
+                response
+                    .context_menu(|ui| self.column_menu(ui, columns, column_names, reorderable));
+            }
+        }
+
+        if !reorderable {
+            return;
+        }
+
+        let dragged = header_responses
+            .iter()
+            .enumerate()
+            .find_map(|(i, response)| {
+                response
+                    .as_ref()
+                    .filter(|r| r.dragged() || r.drag_stopped())
+                    .map(|r| (i, r))
+            });
+        let Some((dragged, response)) = dragged else {
+            return;
+        };
+        let Some(pointer) = ui.ctx().pointer_latest_pos() else {
+            return;
+        };
+
+        // Where to insert the dragged column among the other visible columns:
+        let others: Vec<Rect> = self
+            .visible_columns()
+            .filter(|&i| i != dragged)
+            .filter_map(|i| header_responses[i].as_ref().map(|r| r.rect))
+            .collect();
+        let target = others
+            .iter()
+            .take_while(|rect| rect.center().x < pointer.x)
+            .count();
+
+        if response.drag_stopped() {
+            self.move_column(dragged, target);
+        } else {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+            ui.painter().rect_filled(
+                response.rect,
+                egui::Rounding::ZERO,
+                ui.visuals().selection.bg_fill.gamma_multiply(0.5),
+            );
+
+            let half_spacing = 0.5 * ui.spacing().item_spacing.x;
+            let x = others.get(target).map_or_else(
+                || others.last().map_or(response.rect.left(), |r| r.right()) + half_spacing,
+                |r| r.left() - half_spacing,
+            );
+            ui.painter()
+                .vline(x, response.rect.y_range(), ui.visuals().selection.stroke);
+        }
+    }
+
+    fn column_menu(
+        &mut self,
+        ui: &mut Ui,
+        columns: &[Column],
+        column_names: &[WidgetText],
+        reorderable: bool,
+    ) {
+        let num_visible = self.visible_columns().count();
+        for i in self.column_order.clone() {
+            let name = column_names
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| format!("Column {}", i + 1).into());
+            let mut visible = !self.hidden[i];
+            let can_toggle = columns[i].hideable && !(visible && num_visible == 1);
+            if ui
+                .add_enabled(can_toggle, egui::Checkbox::new(&mut visible, name))
+                .changed()
+            {
+                self.hidden[i] = !visible;
+            }
+        }
+
+        if reorderable || self.hidden.iter().any(|&hidden| hidden) {
+            ui.separator();
+            if ui.button("Reset columns").clicked() {
+                self.column_order = (0..self.column_widths.len()).collect();
+                self.hidden = vec![false; self.column_widths.len()];
+                ui.close_menu();
+            }
+        }
+    }
+
     fn store(self, ui: &egui::Ui, state_id: egui::Id) {
         #![allow(clippy::needless_return)]
         #[cfg(feature = "serde")]
@@ -696,6 +1290,13 @@ pub struct Table<'a> {
     scroll_options: TableScrollOptions,
 
     sense: egui::Sense,
+
+    selection: Option<&'a mut TableSelection>,
+    cell_text: Option<CellTextFn<'a>>,
+    editors: Vec<Option<CellEditor>>,
+    cell_value: Option<CellValueFn<'a>>,
+    cell_edits: Option<&'a mut Vec<CellEdit>>,
+    tree_column: usize,
 }
 
 impl Table<'_> {
@@ -706,6 +1307,14 @@ impl Table<'_> {
         self.ui
     }
 
+    /// How the user wants the rows to be sorted, if at all.
+    ///
+    /// This is updated by clicking the header cells of [`Column::sortable`] columns,
+    /// so sort your rows after adding the header, but before adding the body.
+    pub fn sort(&self) -> Option<ColumnSort> {
+        self.state.sort
+    }
+
     /// Create table body after adding a header row
     pub fn body<F>(self, add_body_contents: F) -> ScrollAreaOutput<()>
     where
@@ -725,13 +1334,19 @@ impl Table<'_> {
             cell_layout,
             scroll_options,
             sense,
+            mut selection,
+            cell_text,
+            editors,
+            cell_value,
+            mut cell_edits,
+            tree_column,
         } = self;
 
         let TableScrollOptions {
             vscroll,
             drag_to_scroll,
             stick_to_bottom,
-            scroll_to_row,
+            mut scroll_to_row,
             scroll_offset_y,
             min_scrolled_height,
             max_scroll_height,
@@ -756,9 +1371,60 @@ impl Table<'_> {
             scroll_area = scroll_area.vertical_scroll_offset(scroll_offset_y);
         }
 
+        let slots = state.column_slots(ui.spacing().item_spacing.x);
+
+        let visible_columns: Vec<usize> = state.visible_columns().collect();
+        let selection_focus_id = state_id.with("__selection_focus");
+        let num_rows_id = state_id.with("__num_rows");
+        let edit_id = state_id.with("__cell_edit");
+        let mut sense = sense;
+        if let Some(selection) = selection.as_deref_mut() {
+            sense |= Sense::click();
+
+            let visible_columns = &visible_columns;
+            let cell_text = cell_text.as_ref().map(|cell_text| {
+                move |row, display_col: usize| cell_text(row, visible_columns[display_col])
+            });
+            let num_rows = ui.data(|d| d.get_temp(num_rows_id)).unwrap_or_default();
+            let num_cols = slots.iter().flatten().count();
+            let scroll_to_cursor = selection.keyboard_input(
+                ui,
+                selection_focus_id,
+                num_rows,
+                num_cols,
+                cell_text
+                    .as_ref()
+                    .map(|f| f as &dyn Fn(usize, usize) -> String),
+            );
+            if let Some(row) = scroll_to_cursor {
+                scroll_to_row = scroll_to_row.or(Some((row, None)));
+            }
+        }
+
+        if editors.iter().any(Option::is_some) {
+            sense |= Sense::click();
+        }
+
+        let editing = ui.data(|d| d.get_temp::<ActiveEdit>(edit_id));
+        if let Some(edit) = editing.as_ref().filter(|edit| !edit.was_shown()) {
+            // A new edit may be of a row that isn't in view, e.g. after pressing Tab in the last visible row:
+            scroll_to_row = scroll_to_row.or(Some((edit.row, None)));
+        }
+
+        let mut num_rows = 0;
+        let mut interaction = CellInteraction {
+            editing,
+            editors,
+            ..Default::default()
+        };
+
         let columns_ref = &columns;
         let widths_ref = &state.column_widths;
+        let slots_ref = &slots;
         let max_used_widths_ref = &mut max_used_widths;
+        let selection_ref = selection.as_deref();
+        let num_rows_ref = &mut num_rows;
+        let interaction_ref = &mut interaction;
 
         let scroll_area_out = scroll_area.show(ui, move |ui| {
             let mut scroll_to_y_range = None;
@@ -780,6 +1446,7 @@ impl Table<'_> {
                     layout,
                     columns: columns_ref,
                     widths: widths_ref,
+                    slots: slots_ref,
                     max_used_widths: max_used_widths_ref,
                     striped,
                     row_index: 0,
@@ -788,6 +1455,11 @@ impl Table<'_> {
                     scroll_to_y_range: &mut scroll_to_y_range,
                     hovered_row_index,
                     hovered_row_index_id,
+                    tree_id: self.state_id.with("__tree"),
+                    tree_column,
+                    selection: selection_ref,
+                    interaction: interaction_ref,
+                    num_rows: num_rows_ref,
                 });
 
                 if scroll_to_row.is_some() && scroll_to_y_range.is_none() {
@@ -804,16 +1476,125 @@ impl Table<'_> {
             }
         });
 
+        let CellInteraction {
+            clicked,
+            double_clicked,
+            editors,
+            mut editing,
+            mut edit_end,
+            edit_shown,
+        } = interaction;
+        let was_editing = editing.is_some();
+        let mut table_has_focus = false;
+
+        if let Some(selection) = selection.as_deref_mut() {
+            ui.data_mut(|d| d.insert_temp(num_rows_id, num_rows));
+
+            let response = ui.interact(
+                scroll_area_out.inner_rect,
+                selection_focus_id,
+                Sense::focusable_noninteractive(),
+            );
+            if let Some((row, col)) = clicked {
+                selection.click(row, col, ui.input(|i| i.modifiers));
+                response.request_focus();
+            }
+            table_has_focus = response.has_focus();
+        }
+
+        if editing.as_ref().is_some_and(ActiveEdit::was_shown) && !edit_shown {
+            // The row of the edit was scrolled out of view, or its column hidden:
+            edit_end = edit_end.or(Some(EditEnd::Commit));
+        }
+
+        if let (Some(edit), Some((row, display_col))) = (&editing, clicked) {
+            if (edit.row, Some(&edit.col)) != (row, visible_columns.get(display_col)) {
+                // Clicking another cell ends the edit:
+                edit_end = edit_end.or(Some(EditEnd::Commit));
+            }
+        }
+
+        let editable_columns: Vec<usize> = visible_columns
+            .iter()
+            .copied()
+            .filter(|&col| editors.get(col).is_some_and(Option::is_some))
+            .collect();
+        let mut start_edit = None;
+
+        if let Some(end) = edit_end {
+            if let Some(edit) = editing.take() {
+                let cell = (edit.row, edit.col);
+                if end != EditEnd::Cancel {
+                    if let Some(cell_edits) = &mut cell_edits {
+                        cell_edits.extend(edit.into_edit());
+                    }
+                }
+                if let EditEnd::CommitAndMove { forward } = end {
+                    start_edit = next_editable_cell(cell, forward, &editable_columns, num_rows);
+                }
+                if start_edit.is_none() && selection.is_some() {
+                    // Give the keyboard back to the table:
+                    ui.memory_mut(|mem| mem.request_focus(selection_focus_id));
+                }
+            }
+        }
+
+        if editing.is_none() && start_edit.is_none() {
+            start_edit = double_clicked;
+        }
+        if !was_editing
+            && start_edit.is_none()
+            && table_has_focus
+            && ui.input(|i| i.key_pressed(egui::Key::Enter))
+        {
+            // Edit the first editable cell at or after the keyboard cursor:
+            let cursor = selection.as_deref().and_then(TableSelection::cursor);
+            start_edit = cursor.and_then(|(row, display_col)| {
+                let col = visible_columns
+                    .iter()
+                    .skip(display_col)
+                    .find(|col| editable_columns.contains(col))?;
+                Some((row, *col))
+            });
+        }
+
+        if let Some((row, col)) = start_edit {
+            if let Some(Some(editor)) = editors.get(col) {
+                let value = if let Some(cell_value) = &cell_value {
+                    cell_value(row, col)
+                } else {
+                    CellValue::Text(cell_text.as_ref().map_or_else(String::new, |f| f(row, col)))
+                };
+                editing = Some(ActiveEdit::new(row, col, value, editor));
+
+                if let Some(selection) = &mut selection {
+                    if let Some(display_col) = visible_columns.iter().position(|&c| c == col) {
+                        selection.select(row, display_col);
+                    }
+                }
+                ui.ctx().request_repaint();
+            }
+        }
+
+        ui.data_mut(|d| {
+            if let Some(editing) = editing {
+                d.insert_temp(edit_id, editing);
+            } else {
+                d.remove::<ActiveEdit>(edit_id);
+            }
+        });
+
         let bottom = ui.min_rect().bottom();
 
         let spacing_x = ui.spacing().item_spacing.x;
         let mut x = cursor_position.x - spacing_x * 0.5;
-        for (i, column_width) in state.column_widths.iter_mut().enumerate() {
+        for (display_index, &i) in visible_columns.iter().enumerate() {
+            let column_width = &mut state.column_widths[i];
             let column = &columns[i];
             let column_is_resizable = column.resizable.unwrap_or(resizable);
             let width_range = column.width_range;
 
-            let is_last_column = i + 1 == columns.len();
+            let is_last_column = display_index + 1 == visible_columns.len();
             if is_last_column
                 && column.initial_width == InitialColumnSize::Remainder
                 && !ui.is_sizing_pass()
@@ -913,6 +1694,25 @@ impl Table<'_> {
     }
 }
 
+/// Clicks and edits in the cells of a [`TableBody`], shared between its rows.
+#[derive(Default)]
+struct CellInteraction {
+    /// The `(row, visible column)` of a clicked cell.
+    clicked: Option<(usize, usize)>,
+
+    /// The `(row, column)` of a double-clicked editable cell.
+    double_clicked: Option<(usize, usize)>,
+
+    /// Indexed by column.
+    editors: Vec<Option<CellEditor>>,
+
+    editing: Option<ActiveEdit>,
+    edit_end: Option<EditEnd>,
+
+    /// Was the cell being edited shown this pass?
+    edit_shown: bool,
+}
+
 /// The body of a table.
 ///
 /// Is created by calling `body` on a [`Table`] (after adding a header row) or [`TableBuilder`] (without a header row).
@@ -924,6 +1724,9 @@ pub struct TableBody<'a> {
     /// Current column widths.
     widths: &'a [f32],
 
+    /// Where each column goes, or `None` if hidden.
+    slots: &'a [Option<ColumnSlot>],
+
     /// Accumulated maximum used widths for each column.
     max_used_widths: &'a mut [f32],
 
@@ -942,6 +1745,18 @@ pub struct TableBody<'a> {
 
     /// Used to store the hovered row index between frames.
     hovered_row_index_id: egui::Id,
+
+    /// Where [`TableBody::tree_rows`] stores which nodes are expanded.
+    tree_id: egui::Id,
+
+    /// Where [`TableBody::tree_rows`] shows the disclosure triangles.
+    tree_column: usize,
+
+    selection: Option<&'a TableSelection>,
+    interaction: &'a mut CellInteraction,
+
+    /// Set to the total number of rows.
+    num_rows: &'a mut usize,
 }
 
 impl<'a> TableBody<'a> {
@@ -963,7 +1778,8 @@ impl<'a> TableBody<'a> {
         self.y_range.min - self.layout.rect.top()
     }
 
-    /// Return a vector containing all column widths for this table body.
+    /// Return a vector containing all column widths for this table body,
+    /// in the order the columns were added to the [`TableBuilder`].
     ///
     /// This is primarily meant for use with [`TableBody::heterogeneous_rows`] in cases where row
     /// heights are expected to according to the width of one or more cells -- for example, if text
@@ -983,14 +1799,20 @@ impl<'a> TableBody<'a> {
             layout: &mut self.layout,
             columns: self.columns,
             widths: self.widths,
+            slots: self.slots,
             max_used_widths: self.max_used_widths,
             row_index: self.row_index,
             col_index: 0,
+            last_display_index: None,
             height,
             striped: self.striped && self.row_index % 2 == 0,
             hovered: self.hovered_row_index == Some(self.row_index),
             selected: false,
             response: &mut response,
+            header_responses: None,
+            tree: None,
+            selection: self.selection,
+            interaction: self.interaction,
         });
         self.capture_hover_state(&response, self.row_index);
         let bottom_y = self.layout.cursor.y;
@@ -1000,6 +1822,7 @@ impl<'a> TableBody<'a> {
         }
 
         self.row_index += 1;
+        *self.num_rows = self.row_index;
     }
 
     /// Add many rows with same height.
@@ -1034,6 +1857,7 @@ impl<'a> TableBody<'a> {
     ) {
         let spacing = self.layout.ui.spacing().item_spacing;
         let row_height_with_spacing = row_height_sans_spacing + spacing.y;
+        *self.num_rows = total_rows;
 
         if let Some(scroll_to_row) = self.scroll_to_row {
             let scroll_to_row = scroll_to_row.at_most(total_rows.saturating_sub(1)) as f32;
@@ -1064,14 +1888,20 @@ impl<'a> TableBody<'a> {
                 layout: &mut self.layout,
                 columns: self.columns,
                 widths: self.widths,
+                slots: self.slots,
                 max_used_widths: self.max_used_widths,
                 row_index,
                 col_index: 0,
+                last_display_index: None,
                 height: row_height_sans_spacing,
                 striped: self.striped && (row_index + self.row_index) % 2 == 0,
                 hovered: self.hovered_row_index == Some(row_index),
                 selected: false,
                 response: &mut response,
+                header_responses: None,
+                tree: None,
+                selection: self.selection,
+                interaction: self.interaction,
             });
             self.capture_hover_state(&response, row_index);
         }
@@ -1127,6 +1957,7 @@ impl<'a> TableBody<'a> {
 
         // Skip the invisible rows, and populate the first non-virtual row.
         for (row_index, row_height) in &mut enumerated_heights {
+            *self.num_rows = row_index + 1;
             let old_cursor_y = cursor_y;
             cursor_y += (row_height + spacing.y) as f64;
 
@@ -1145,14 +1976,20 @@ impl<'a> TableBody<'a> {
                     layout: &mut self.layout,
                     columns: self.columns,
                     widths: self.widths,
+                    slots: self.slots,
                     max_used_widths: self.max_used_widths,
                     row_index,
                     col_index: 0,
+                    last_display_index: None,
                     height: row_height,
                     striped: self.striped && (row_index + self.row_index) % 2 == 0,
                     hovered: self.hovered_row_index == Some(row_index),
                     selected: false,
                     response: &mut response,
+                    header_responses: None,
+                    tree: None,
+                    selection: self.selection,
+                    interaction: self.interaction,
                 });
                 self.capture_hover_state(&response, row_index);
                 break;
@@ -1161,20 +1998,27 @@ impl<'a> TableBody<'a> {
 
         // populate visible rows:
         for (row_index, row_height) in &mut enumerated_heights {
+            *self.num_rows = row_index + 1;
             let top_y = cursor_y;
             let mut response: Option<Response> = None;
             add_row_content(TableRow {
                 layout: &mut self.layout,
                 columns: self.columns,
                 widths: self.widths,
+                slots: self.slots,
                 max_used_widths: self.max_used_widths,
                 row_index,
                 col_index: 0,
+                last_display_index: None,
                 height: row_height,
                 striped: self.striped && (row_index + self.row_index) % 2 == 0,
                 hovered: self.hovered_row_index == Some(row_index),
                 selected: false,
                 response: &mut response,
+                header_responses: None,
+                tree: None,
+                selection: self.selection,
+                interaction: self.interaction,
             });
             self.capture_hover_state(&response, row_index);
             cursor_y += (row_height + spacing.y) as f64;
@@ -1195,6 +2039,7 @@ impl<'a> TableBody<'a> {
         let mut height_below_visible: f64 = 0.0;
         for (row_index, row_height) in enumerated_heights {
             height_below_visible += (row_height + spacing.y) as f64;
+            *self.num_rows = row_index + 1;
 
             let top_y = cursor_y;
             cursor_y += (row_height + spacing.y) as f64;
@@ -1225,6 +2070,85 @@ impl<'a> TableBody<'a> {
         self.layout.skip_space(egui::vec2(0.0, height));
     }
 
+    /// Add rows of hierarchical data, which the user can expand and collapse.
+    ///
+    /// `nodes` is the whole tree in depth-first order, see [`TableTreeNode`].
+    /// Only the nodes that are not inside a collapsed node are shown,
+    /// and like with [`Self::rows`], only the visible ones of those are added.
+    ///
+    /// `add_row_content` gets the row and the index of its node.
+    /// Note that [`TableRow::index`] counts the shown rows, so it changes when a node is toggled.
+    ///
+    /// The [`TableBuilder::tree_column`] is indented by the depth of the node,
+    /// with a disclosure triangle if the node has children.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui_extras::{Column, TableBuilder, TableTreeNode};
+    /// let files = [("src", 0, 0), ("lib.rs", 1, 120), ("main.rs", 1, 40), ("README.md", 0, 10)];
+    /// let nodes: Vec<TableTreeNode> = files
+    ///     .iter()
+    ///     .map(|&(name, depth, _)| TableTreeNode::new(name, depth))
+    ///     .collect();
+    /// TableBuilder::new(ui)
+    ///     .columns(Column::auto(), 2)
+    ///     .body(|body| {
+    ///         body.tree_rows(18.0, &nodes, |mut row, node| {
+    ///             let (name, _, size) = files[node];
+    ///             row.col(|ui| {
+    ///                 ui.label(name);
+    ///             });
+    ///             row.col(|ui| {
+    ///                 ui.label(size.to_string());
+    ///             });
+    ///         });
+    ///     });
+    /// # });
+    /// ```
+    pub fn tree_rows(
+        self,
+        row_height_sans_spacing: f32,
+        nodes: &[TableTreeNode],
+        mut add_row_content: impl FnMut(TableRow<'_, '_>, usize),
+    ) {
+        let (tree_id, tree_column) = (self.tree_id, self.tree_column);
+        let visible = visible_nodes(nodes, |node| is_node_open(self.layout.ui, tree_id, node));
+        self.rows(row_height_sans_spacing, visible.len(), |mut row| {
+            let index = visible[row.index()];
+            row.tree = Some(TreeCell::new(
+                row.layout.ui,
+                tree_id,
+                tree_column,
+                nodes,
+                index,
+            ));
+            add_row_content(row, index);
+        });
+    }
+
+    /// Like [`Self::tree_rows`], but with a height per node, like [`Self::heterogeneous_rows`].
+    pub fn heterogeneous_tree_rows(
+        self,
+        nodes: &[TableTreeNode],
+        row_height: impl Fn(usize) -> f32,
+        mut add_row_content: impl FnMut(TableRow<'_, '_>, usize),
+    ) {
+        let (tree_id, tree_column) = (self.tree_id, self.tree_column);
+        let visible = visible_nodes(nodes, |node| is_node_open(self.layout.ui, tree_id, node));
+        let heights = visible.iter().map(|&index| row_height(index));
+        self.heterogeneous_rows(heights, |mut row| {
+            let index = visible[row.index()];
+            row.tree = Some(TreeCell::new(
+                row.layout.ui,
+                tree_id,
+                tree_column,
+                nodes,
+                index,
+            ));
+            add_row_content(row, index);
+        });
+    }
+
     // Capture the hover information for the just created row. This is used in the next render
     // to ensure that the entire row is highlighted.
     fn capture_hover_state(&self, response: &Option<Response>, row_index: usize) {
@@ -1249,12 +2173,17 @@ pub struct TableRow<'a, 'b> {
     layout: &'b mut StripLayout<'a>,
     columns: &'b [Column],
     widths: &'b [f32],
+    slots: &'b [Option<ColumnSlot>],
 
     /// grows during building with the maximum widths
     max_used_widths: &'b mut [f32],
 
     row_index: usize,
     col_index: usize,
+
+    /// Where the previous cell was shown.
+    last_display_index: Option<usize>,
+
     height: f32,
 
     striped: bool,
@@ -1262,6 +2191,15 @@ pub struct TableRow<'a, 'b> {
     selected: bool,
 
     response: &'b mut Option<Response>,
+
+    /// Only set for the header row.
+    header_responses: Option<&'b mut Vec<Option<Response>>>,
+
+    /// Only set for rows added with [`TableBody::tree_rows`].
+    tree: Option<TreeCell>,
+
+    selection: Option<&'b TableSelection>,
+    interaction: &'b mut CellInteraction,
 }
 
 impl TableRow<'_, '_> {
@@ -1289,6 +2227,27 @@ impl TableRow<'_, '_> {
             8.0 // anything will look wrong, so pick something that is obviously wrong
         };
 
+        let mut display_index = col_index;
+        match self.slots.get(col_index) {
+            Some(Some(slot)) => {
+                // Cells are added in column order, which may not be the order they are shown in:
+                let next_display_index = self.last_display_index.map_or(0, |i| i + 1);
+                if slot.display_index != next_display_index {
+                    self.layout.cursor.x = self.layout.rect.left() + slot.offset;
+                }
+                self.last_display_index = Some(slot.display_index);
+                display_index = slot.display_index;
+            }
+            Some(None) => {
+                // Hidden column:
+                let ui = &mut *self.layout.ui;
+                let rect = Rect::from_min_size(self.layout.cursor, Vec2::ZERO);
+                let id = ui.id().with(("__hidden_cell", self.row_index, col_index));
+                return (Rect::NOTHING, ui.interact(rect, id, Sense::hover()));
+            }
+            None => {}
+        }
+
         let width = CellSize::Absolute(width);
         let height = CellSize::Absolute(self.height);
 
@@ -1296,17 +2255,36 @@ impl TableRow<'_, '_> {
             clip,
             striped: self.striped,
             hovered: self.hovered,
-            selected: self.selected,
+            selected: self.selected
+                || self
+                    .selection
+                    .is_some_and(|s| s.is_cell_selected(self.row_index, display_index)),
             sizing_pass: auto_size_this_frame || self.layout.ui.is_sizing_pass(),
         };
 
-        let (used_rect, response) = self.layout.add(
-            flags,
-            width,
-            height,
-            egui::Id::new((self.row_index, col_index)),
-            add_cell_contents,
-        );
+        let row_index = self.row_index;
+        let id = egui::Id::new((row_index, col_index));
+        let interaction = &mut *self.interaction;
+        let editor = interaction.editors.get(col_index).and_then(Option::as_ref);
+        let is_editable = editor.is_some();
+        let edit = interaction
+            .editing
+            .as_mut()
+            .filter(|edit| (edit.row, edit.col) == (row_index, col_index));
+
+        let (used_rect, response) = if let (Some(edit), Some(editor)) = (edit, editor) {
+            let edit_end = &mut interaction.edit_end;
+            interaction.edit_shown = true;
+            self.layout.add(flags, width, height, id, |ui| {
+                *edit_end = edit.ui(ui, editor);
+            })
+        } else if let Some(tree) = self.tree.filter(|tree| tree.column == col_index) {
+            self.layout.add(flags, width, height, id, |ui| {
+                tree.ui(ui, add_cell_contents);
+            })
+        } else {
+            self.layout.add(flags, width, height, id, add_cell_contents)
+        };
 
         if let Some(max_w) = self.max_used_widths.get_mut(col_index) {
             *max_w = max_w.max(used_rect.width());
@@ -1318,6 +2296,19 @@ impl TableRow<'_, '_> {
                 .map_or(response.clone(), |r| r.union(response.clone())),
         );
 
+        if response.clicked() {
+            interaction.clicked = Some((row_index, display_index));
+        }
+        if is_editable && response.double_clicked() {
+            interaction.double_clicked = Some((row_index, col_index));
+        }
+
+        if let Some(header_responses) = &mut self.header_responses {
+            if let Some(header_response) = header_responses.get_mut(col_index) {
+                *header_response = Some(response.clone());
+            }
+        }
+
         (used_rect, response)
     }
 
@@ -1327,6 +2318,16 @@ impl TableRow<'_, '_> {
         self.selected = selected;
     }
 
+    /// Is this row selected, either with [`Self::set_selected`]
+    /// or in the [`TableBuilder::selection`]?
+    #[inline]
+    pub fn is_selected(&self) -> bool {
+        self.selected
+            || self
+                .selection
+                .is_some_and(|s| s.is_row_selected(self.row_index))
+    }
+
     /// Set the hovered highlight state for cells added after a call to this function.
     #[inline]
     pub fn set_hovered(&mut self, hovered: bool) {
@@ -1349,6 +2350,9 @@ impl TableRow<'_, '_> {
     }
 
     /// Returns the index of the column. Incremented after a column is added.
+    ///
+    /// This is the index in the order the columns were added to the [`TableBuilder`],
+    /// even if the user has reordered them.
     #[inline]
     pub fn col_index(&self) -> usize {
         self.col_index
@@ -1361,3 +2365,48 @@ impl Drop for TableRow<'_, '_> {
         self.layout.end_line();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_columns() {
+        let mut state = TableState {
+            column_widths: vec![10.0; 4],
+            max_used_widths: vec![],
+            column_order: vec![],
+            hidden: vec![],
+            sort: Some(ColumnSort {
+                column: 7,
+                direction: SortDirection::Ascending,
+            }),
+        };
+        state.validate_columns();
+        assert_eq!(state.column_order, [0, 1, 2, 3]);
+        assert_eq!(state.sort, None);
+
+        state.move_column(0, 2);
+        assert_eq!(state.column_order, [1, 2, 0, 3]);
+
+        state.move_column(3, 0);
+        assert_eq!(state.column_order, [3, 1, 2, 0]);
+
+        // Hidden columns are skipped when counting where to insert:
+        state.hidden[1] = true;
+        assert_eq!(state.visible_columns().collect::<Vec<_>>(), [3, 2, 0]);
+        state.move_column(0, 1);
+        assert_eq!(state.column_order, [3, 1, 0, 2]);
+
+        let slots = state.column_slots(2.0);
+        assert!(slots[1].is_none());
+        assert_eq!(
+            slots[0].map(|s| (s.display_index, s.offset)),
+            Some((1, 12.0))
+        );
+        assert_eq!(
+            slots[2].map(|s| (s.display_index, s.offset)),
+            Some((2, 24.0))
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use std::ops::RangeInclusive;
+
+use egui::{Key, Ui};
+
+/// How the cells of a column are edited. See [`crate::TableBuilder::column_editor`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum CellEditor {
+    /// A single-line [`egui::TextEdit`].
+    Text,
+
+    /// A [`egui::DragValue`], which can also be typed into.
+    Number {
+        speed: f64,
+        range: RangeInclusive<f64>,
+    },
+
+    /// A [`egui::ComboBox`] with these choices.
+    Choice(Vec<String>),
+
+    /// A checkbox.
+    Checkbox,
+}
+
+impl CellEditor {
+    /// A [`Self::Number`] without limits.
+    pub fn number() -> Self {
+        Self::Number {
+            speed: 1.0,
+            range: f64::NEG_INFINITY..=f64::INFINITY,
+        }
+    }
+
+    /// A [`Self::Choice`] between the given options.
+    pub fn choice(options: impl IntoIterator<Item = impl ToString>) -> Self {
+        Self::Choice(options.into_iter().map(|o| o.to_string()).collect())
+    }
+}
+
+/// The value of a cell, before or after editing.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum CellValue {
+    /// Edited with [`CellEditor::Text`] or [`CellEditor::Choice`].
+    Text(String),
+
+    /// Edited with [`CellEditor::Number`].
+    Number(f64),
+
+    /// Edited with [`CellEditor::Checkbox`].
+    Bool(bool),
+}
+
+impl std::fmt::Display for CellValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text(text) => text.fmt(f),
+            Self::Number(number) => number.fmt(f),
+            Self::Bool(value) => value.fmt(f),
+        }
+    }
+}
+
+impl CellValue {
+    /// Convert to the kind of value the editor works with.
+    fn convert_for(self, editor: &CellEditor) -> Self {
+        match editor {
+            CellEditor::Text | CellEditor::Choice(_) => match self {
+                Self::Text(text) => Self::Text(text),
+                other => Self::Text(other.to_string()),
+            },
+            CellEditor::Number { .. } => Self::Number(match self {
+                Self::Text(text) => text.trim().parse().unwrap_or_default(),
+                Self::Number(number) => number,
+                Self::Bool(value) => value as u8 as f64,
+            }),
+            CellEditor::Checkbox => Self::Bool(match self {
+                Self::Text(text) => text.trim().eq_ignore_ascii_case("true"),
+                Self::Number(number) => number != 0.0,
+                Self::Bool(value) => value,
+            }),
+        }
+    }
+}
+
+/// A committed edit of a cell. See [`crate::TableBuilder::cell_edits`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct CellEdit {
+    pub row: usize,
+
+    /// The index of the column, in the order the columns were added to the [`crate::TableBuilder`].
+    pub col: usize,
+
+    pub old: CellValue,
+    pub new: CellValue,
+}
+
+/// How editing a cell ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EditEnd {
+    Cancel,
+    Commit,
+
+    /// Commit, and start editing the next (or previous) editable cell.
+    CommitAndMove {
+        forward: bool,
+    },
+}
+
+/// The cell being edited. Stored between passes.
+#[derive(Clone, Debug)]
+pub(crate) struct ActiveEdit {
+    pub(crate) row: usize,
+    pub(crate) col: usize,
+    original: CellValue,
+    value: CellValue,
+
+    /// The editor should grab keyboard focus.
+    needs_focus: bool,
+}
+
+impl ActiveEdit {
+    pub(crate) fn new(row: usize, col: usize, value: CellValue, editor: &CellEditor) -> Self {
+        let value = value.convert_for(editor);
+        Self {
+            row,
+            col,
+            original: value.clone(),
+            value,
+            needs_focus: true,
+        }
+    }
+
+    /// Has the editor been shown yet?
+    pub(crate) fn was_shown(&self) -> bool {
+        !self.needs_focus
+    }
+
+    /// The edit, unless nothing changed.
+    pub(crate) fn into_edit(self) -> Option<CellEdit> {
+        (self.value != self.original).then_some(CellEdit {
+            row: self.row,
+            col: self.col,
+            old: self.original,
+            new: self.value,
+        })
+    }
+
+    /// Show the editor in the cell.
+    pub(crate) fn ui(&mut self, ui: &mut Ui, editor: &CellEditor) -> Option<EditEnd> {
+        let (escape, enter, tab, shift) = ui.input(|i| {
+            (
+                i.key_pressed(Key::Escape),
+                i.key_pressed(Key::Enter),
+                i.key_pressed(Key::Tab),
+                i.modifiers.shift,
+            )
+        });
+
+        let mut committed = false;
+        let response = match (editor, &mut self.value) {
+            (CellEditor::Text, CellValue::Text(text)) => {
+                ui.add(egui::TextEdit::singleline(text).desired_width(f32::INFINITY))
+            }
+            (CellEditor::Number { speed, range }, CellValue::Number(number)) => ui.add(
+                egui::DragValue::new(number)
+                    .speed(*speed)
+                    .range(range.clone()),
+            ),
+            (CellEditor::Choice(options), CellValue::Text(text)) => {
+                egui::ComboBox::from_id_salt("__cell_editor")
+                    .selected_text(text.as_str())
+                    .width(ui.available_width())
+                    .show_ui(ui, |ui| {
+                        for option in options {
+                            if ui.selectable_label(text == option, option).clicked() {
+                                text.clone_from(option);
+                                committed = true;
+                            }
+                        }
+                    })
+                    .response
+            }
+            (CellEditor::Checkbox, CellValue::Bool(value)) => {
+                let response = ui.checkbox(value, "");
+                committed = response.changed();
+                response
+            }
+            _ => {
+                // `ActiveEdit::new` makes sure this doesn't happen.
+                return Some(EditEnd::Cancel);
+            }
+        };
+
+        if std::mem::take(&mut self.needs_focus) {
+            response.request_focus();
+            return None;
+        }
+
+        if escape {
+            Some(EditEnd::Cancel)
+        } else if tab {
+            Some(EditEnd::CommitAndMove { forward: !shift })
+        } else if enter || committed || response.lost_focus() {
+            Some(EditEnd::Commit)
+        } else {
+            None
+        }
+    }
+}
+
+/// The next (or previous) cell to edit when pressing Tab,
+/// moving through the `editable` columns (in the order they are shown) and then on to the next row.
+pub(crate) fn next_editable_cell(
+    (row, col): (usize, usize),
+    forward: bool,
+    editable: &[usize],
+    num_rows: usize,
+) -> Option<(usize, usize)> {
+    let position = editable.iter().position(|&c| c == col)?;
+    if forward {
+        if let Some(&next) = editable.get(position + 1) {
+            Some((row, next))
+        } else {
+            (row + 1 < num_rows).then(|| (row + 1, editable[0]))
+        }
+    } else if 0 < position {
+        Some((row, editable[position - 1]))
+    } else {
+        (0 < row).then(|| (row - 1, editable[editable.len() - 1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{vec2, Event, Modifiers, MouseWheelUnit, Pos2, PointerButton};
+    use egui_kittest::{kittest::Queryable as _, Harness};
+
+    use crate::{Column, TableBuilder};
+
+    use super::*;
+
+    #[test]
+    fn convert_values() {
+        let number = CellValue::Text(" 4.5 ".to_owned()).convert_for(&CellEditor::number());
+        assert_eq!(number, CellValue::Number(4.5));
+        assert_eq!(
+            CellValue::Number(2.0).convert_for(&CellEditor::Text),
+            CellValue::Text("2".to_owned())
+        );
+        assert_eq!(
+            CellValue::Text("True".to_owned()).convert_for(&CellEditor::Checkbox),
+            CellValue::Bool(true)
+        );
+
+        let edit = ActiveEdit::new(1, 2, CellValue::Bool(false), &CellEditor::Checkbox);
+        assert_eq!(edit.into_edit(), None, "Unchanged values are not edits");
+    }
+
+    #[test]
+    fn tab_order() {
+        // Columns 3 and 1 are editable, shown in that order:
+        let editable = [3, 1];
+        assert_eq!(next_editable_cell((0, 3), true, &editable, 2), Some((0, 1)));
+        assert_eq!(next_editable_cell((0, 1), true, &editable, 2), Some((1, 3)));
+        assert_eq!(next_editable_cell((1, 1), true, &editable, 2), None);
+        assert_eq!(
+            next_editable_cell((1, 3), false, &editable, 2),
+            Some((0, 1))
+        );
+        assert_eq!(next_editable_cell((0, 3), false, &editable, 2), None);
+        assert_eq!(next_editable_cell((0, 0), true, &editable, 2), None);
+    }
+
+    /// The names in an editable table, and the edits made to them.
+    struct Names {
+        names: Vec<String>,
+        edits: Vec<CellEdit>,
+    }
+
+    /// A table of 30 names, of which only a few fit in view.
+    fn names_table() -> Harness<'static, Names> {
+        let names = Names {
+            names: (0..30).map(|i| format!("name {i}")).collect(),
+            edits: vec![],
+        };
+        // Short steps, so that two clicks make a double-click:
+        let mut harness = Harness::builder().with_step_dt(1.0 / 60.0).build_ui_state(
+            |ui, state: &mut Names| {
+                let mut edits = vec![];
+                let names = &state.names;
+                TableBuilder::new(ui)
+                    .column(Column::remainder())
+                    .column_editor(0, CellEditor::Text)
+                    .cell_value(|row, _| CellValue::Text(names[row].clone()))
+                    .max_scroll_height(100.0)
+                    .cell_edits(&mut edits)
+                    .body(|body| {
+                        body.rows(20.0, names.len(), |mut row| {
+                            let i = row.index();
+                            row.col(|ui| {
+                                ui.label(&names[i]);
+                            });
+                        });
+                    });
+                for edit in edits {
+                    if let CellValue::Text(name) = &edit.new {
+                        state.names[edit.row].clone_from(name);
+                    }
+                    state.edits.push(edit);
+                }
+            },
+            names,
+        );
+        harness.set_size(vec2(200.0, 150.0));
+        harness.run();
+        harness
+    }
+
+    /// A point in the cell of the `label`, to the right of the label itself.
+    fn cell_of(harness: &Harness<'_, Names>, label: &str) -> Pos2 {
+        let rect = harness.get_by_label(label).bounding_box().unwrap();
+        Pos2::new(rect.x1 as f32 + 20.0, (rect.y0 + rect.y1) as f32 / 2.0)
+    }
+
+    fn double_click(harness: &mut Harness<'_, Names>, pos: Pos2) {
+        harness.input_mut().events.push(Event::PointerMoved(pos));
+        harness.step();
+        for pressed in [true, false, true, false] {
+            harness.input_mut().events.push(Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed,
+                modifiers: Modifiers::NONE,
+            });
+            harness.step();
+        }
+        harness.run();
+    }
+
+    fn type_text(harness: &mut Harness<'_, Names>, text: &str) {
+        harness
+            .input_mut()
+            .events
+            .push(Event::Text(text.to_owned()));
+        harness.run();
+    }
+
+    #[test]
+    fn enter_commits_edit() {
+        let mut harness = names_table();
+        let pos = cell_of(&harness, "name 1");
+        double_click(&mut harness, pos);
+        type_text(&mut harness, "!");
+        assert!(harness.state().edits.is_empty(), "Not committed yet");
+
+        harness.press_key(Key::Enter);
+        harness.run();
+        assert_eq!(harness.state().names[1], "name 1!");
+        assert_eq!(
+            harness.state().edits,
+            vec![CellEdit {
+                row: 1,
+                col: 0,
+                old: CellValue::Text("name 1".to_owned()),
+                new: CellValue::Text("name 1!".to_owned()),
+            }]
+        );
+        harness.get_by_label("name 1!");
+    }
+
+    #[test]
+    fn escape_cancels_edit() {
+        let mut harness = names_table();
+        let pos = cell_of(&harness, "name 1");
+        double_click(&mut harness, pos);
+        type_text(&mut harness, "!");
+
+        harness.press_key(Key::Escape);
+        harness.run();
+        assert_eq!(harness.state().names[1], "name 1");
+        assert!(harness.state().edits.is_empty());
+
+        // The edit is over, so typing does nothing:
+        type_text(&mut harness, "?");
+        assert_eq!(harness.state().names[1], "name 1");
+    }
+
+    #[test]
+    fn edit_scrolled_out_of_view_is_committed() {
+        let mut harness = names_table();
+        let pos = cell_of(&harness, "name 0");
+        double_click(&mut harness, pos);
+        type_text(&mut harness, "!");
+
+        harness.input_mut().events.push(Event::MouseWheel {
+            unit: MouseWheelUnit::Point,
+            delta: vec2(0.0, -300.0),
+            modifiers: Modifiers::NONE,
+        });
+        // Let the scrolling animation finish:
+        harness.run_steps(60);
+        assert!(harness.query_by_label("name 0").is_none(), "Scrolled out of view");
+        assert_eq!(harness.state().names[0], "name 0!");
+        assert_eq!(harness.state().edits.len(), 1);
+    }
+}
@@ -0,0 +1,434 @@
+use std::ops::RangeInclusive;
+
+use egui::{Event, EventFilter, Id, Key, Modifiers, Ui};
+
+/// What the user can select in a [`crate::Table`]. See [`TableSelection`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TableSelectionMode {
+    /// At most one row at a time.
+    #[default]
+    SingleRow,
+
+    /// Any number of rows, using Ctrl/Cmd-click to toggle and Shift-click to select ranges.
+    MultiRow,
+
+    /// Rectangular ranges of cells.
+    Cells,
+}
+
+/// A rectangle of selected cells in a [`TableSelection`].
+///
+/// Columns are counted among the visible columns, from left to right,
+/// so they are only the same as the column indices if the user hasn't reordered or hidden any columns.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct CellRange {
+    pub rows: RangeInclusive<usize>,
+    pub cols: RangeInclusive<usize>,
+}
+
+impl CellRange {
+    #[inline]
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self.rows.contains(&row) && self.cols.contains(&col)
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.rows.start() <= other.rows.end()
+            && other.rows.start() <= self.rows.end()
+            && self.cols.start() <= other.cols.end()
+            && other.cols.start() <= self.cols.end()
+    }
+
+    /// The parts of `self` not covered by `other`.
+    fn subtract(&self, other: &Self) -> Vec<Self> {
+        if !self.intersects(other) {
+            return vec![self.clone()];
+        }
+
+        let mut pieces = vec![];
+        if self.rows.start() < other.rows.start() {
+            pieces.push(Self {
+                rows: *self.rows.start()..=other.rows.start() - 1,
+                cols: self.cols.clone(),
+            });
+        }
+        if other.rows.end() < self.rows.end() {
+            pieces.push(Self {
+                rows: other.rows.end() + 1..=*self.rows.end(),
+                cols: self.cols.clone(),
+            });
+        }
+        let rows =
+            *self.rows.start().max(other.rows.start())..=*self.rows.end().min(other.rows.end());
+        if self.cols.start() < other.cols.start() {
+            pieces.push(Self {
+                rows: rows.clone(),
+                cols: *self.cols.start()..=other.cols.start() - 1,
+            });
+        }
+        if other.cols.end() < self.cols.end() {
+            pieces.push(Self {
+                rows,
+                cols: other.cols.end() + 1..=*self.cols.end(),
+            });
+        }
+        pieces
+    }
+}
+
+/// The selected rows or cells of a [`crate::Table`], and the keyboard cursor.
+///
+/// Pass it to [`crate::TableBuilder::selection`] to let the user select by clicking,
+/// and by using the arrow keys, Home/End and Page Up/Down (with Shift to extend the selection).
+/// Ctrl/Cmd+A selects everything, and Ctrl/Cmd+C copies the selection
+/// if [`crate::TableBuilder::cell_text`] is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TableSelection {
+    mode: TableSelectionMode,
+    ranges: Vec<CellRange>,
+
+    /// Where Shift-selection starts from.
+    anchor: Option<(usize, usize)>,
+
+    /// Moved with the keyboard.
+    cursor: Option<(usize, usize)>,
+}
+
+impl TableSelection {
+    pub fn new(mode: TableSelectionMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn mode(&self) -> TableSelectionMode {
+        self.mode
+    }
+
+    /// The selected rectangles. In the row modes, these cover all columns.
+    #[inline]
+    pub fn ranges(&self) -> &[CellRange] {
+        &self.ranges
+    }
+
+    /// The `(row, column)` of the keyboard cursor.
+    ///
+    /// The column is always zero when selecting rows.
+    #[inline]
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        self.cursor
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+        self.anchor = None;
+        self.cursor = None;
+    }
+
+    /// Is any cell of the row selected?
+    pub fn is_row_selected(&self, row: usize) -> bool {
+        self.ranges.iter().any(|range| range.rows.contains(&row))
+    }
+
+    /// Is the cell selected? `col` is counted among the visible columns, see [`CellRange`].
+    pub fn is_cell_selected(&self, row: usize, col: usize) -> bool {
+        self.ranges.iter().any(|range| range.contains(row, col))
+    }
+
+    /// All rows with any selected cells, in order.
+    pub fn selected_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self
+            .ranges
+            .iter()
+            .flat_map(|range| range.rows.clone())
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+
+    /// Select only this row (and cell, when selecting cells), and move the cursor there.
+    pub fn select(&mut self, row: usize, col: usize) {
+        let cell = self.cell(row, col);
+        self.ranges = vec![self.range(cell, cell)];
+        self.anchor = Some(cell);
+        self.cursor = Some(cell);
+    }
+
+    /// When selecting rows, the column is ignored.
+    fn cell(&self, row: usize, col: usize) -> (usize, usize) {
+        match self.mode {
+            TableSelectionMode::SingleRow | TableSelectionMode::MultiRow => (row, 0),
+            TableSelectionMode::Cells => (row, col),
+        }
+    }
+
+    fn range(&self, a: (usize, usize), b: (usize, usize)) -> CellRange {
+        let rows = a.0.min(b.0)..=a.0.max(b.0);
+        let cols = match self.mode {
+            TableSelectionMode::SingleRow | TableSelectionMode::MultiRow => 0..=usize::MAX,
+            TableSelectionMode::Cells => a.1.min(b.1)..=a.1.max(b.1),
+        };
+        CellRange { rows, cols }
+    }
+
+    /// Select everything from the anchor to the given cell.
+    fn extend_to(&mut self, row: usize, col: usize, keep_other_ranges: bool) {
+        let cell = self.cell(row, col);
+        let anchor = self.anchor.unwrap_or(cell);
+        if !keep_other_ranges {
+            self.ranges.clear();
+        }
+        self.ranges.push(self.range(anchor, cell));
+        self.anchor = Some(anchor);
+        self.cursor = Some(cell);
+    }
+
+    fn toggle(&mut self, row: usize, col: usize) {
+        let cell = self.cell(row, col);
+        let range = self.range(cell, cell);
+        if self.is_cell_selected(cell.0, cell.1) {
+            self.ranges = self
+                .ranges
+                .iter()
+                .flat_map(|selected| selected.subtract(&range))
+                .collect();
+        } else {
+            self.ranges.push(range);
+        }
+        self.anchor = Some(cell);
+        self.cursor = Some(cell);
+    }
+
+    pub(crate) fn click(&mut self, row: usize, col: usize, modifiers: Modifiers) {
+        if self.mode == TableSelectionMode::SingleRow {
+            self.select(row, col);
+        } else if modifiers.shift {
+            self.extend_to(row, col, modifiers.command);
+        } else if modifiers.command {
+            self.toggle(row, col);
+        } else {
+            self.select(row, col);
+        }
+    }
+
+    /// Move the keyboard cursor, and select where it ends up.
+    pub(crate) fn move_cursor(&mut self, row: usize, col: usize, extend: bool) {
+        if extend && self.mode != TableSelectionMode::SingleRow {
+            self.extend_to(row, col, false);
+        } else {
+            self.select(row, col);
+        }
+    }
+
+    pub(crate) fn select_all(&mut self, num_rows: usize, num_cols: usize) {
+        if self.mode == TableSelectionMode::SingleRow || num_rows == 0 || num_cols == 0 {
+            return;
+        }
+        let range = self.range((0, 0), (num_rows - 1, num_cols - 1));
+        self.ranges = vec![range];
+        self.anchor = Some((0, 0));
+        self.cursor = Some(self.cell(num_rows - 1, num_cols - 1));
+    }
+
+    /// Handle keyboard input while the table with the given focus `id` has keyboard focus.
+    ///
+    /// `cell_text` is given the row and the visible column.
+    /// Returns the row to scroll to, if the cursor moved.
+    pub(crate) fn keyboard_input(
+        &mut self,
+        ui: &Ui,
+        id: Id,
+        num_rows: usize,
+        num_cols: usize,
+        cell_text: Option<&dyn Fn(usize, usize) -> String>,
+    ) -> Option<usize> {
+        if !ui.memory(|mem| mem.has_focus(id)) {
+            return None;
+        }
+
+        let event_filter = EventFilter {
+            horizontal_arrows: self.mode == TableSelectionMode::Cells,
+            vertical_arrows: true,
+            ..Default::default()
+        };
+        ui.memory_mut(|mem| mem.set_focus_lock_filter(id, event_filter));
+
+        const PAGE_SIZE: usize = 10;
+
+        let mut scroll_to_row = None;
+        for event in ui.input(|i| i.filtered_events(&event_filter)) {
+            match event {
+                Event::Copy => {
+                    if let Some(cell_text) = cell_text {
+                        ui.ctx()
+                            .copy_text(self.to_tsv(num_rows, num_cols, cell_text));
+                    }
+                }
+                Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } if 0 < num_rows && 0 < num_cols => {
+                    if key == Key::A && modifiers.command {
+                        self.select_all(num_rows, num_cols);
+                        continue;
+                    }
+
+                    let last_row = num_rows - 1;
+                    let last_col = num_cols - 1;
+                    let cells = self.mode == TableSelectionMode::Cells;
+                    let target = match self.cursor {
+                        None => matches!(
+                            key,
+                            Key::ArrowUp
+                                | Key::ArrowDown
+                                | Key::ArrowLeft
+                                | Key::ArrowRight
+                                | Key::PageUp
+                                | Key::PageDown
+                                | Key::Home
+                                | Key::End
+                        )
+                        .then_some((0, 0)),
+                        Some((row, col)) => {
+                            let (row, col) = (row.min(last_row), col.min(last_col));
+                            match key {
+                                Key::ArrowUp => Some((row.saturating_sub(1), col)),
+                                Key::ArrowDown => Some(((row + 1).min(last_row), col)),
+                                Key::ArrowLeft if cells => Some((row, col.saturating_sub(1))),
+                                Key::ArrowRight if cells => Some((row, (col + 1).min(last_col))),
+                                Key::PageUp => Some((row.saturating_sub(PAGE_SIZE), col)),
+                                Key::PageDown => Some(((row + PAGE_SIZE).min(last_row), col)),
+                                Key::Home => Some((0, col)),
+                                Key::End => Some((last_row, col)),
+                                _ => None,
+                            }
+                        }
+                    };
+
+                    if let Some((row, col)) = target {
+                        self.move_cursor(row, col, modifiers.shift);
+                        scroll_to_row = Some(row);
+                    }
+                }
+                _ => {}
+            }
+        }
+        scroll_to_row
+    }
+
+    /// The selection as tab-separated values, with one line per selected row.
+    ///
+    /// `cell_text` is given the row and the visible column.
+    /// Unselected cells within the selected columns are left empty.
+    pub(crate) fn to_tsv(
+        &self,
+        num_rows: usize,
+        num_cols: usize,
+        cell_text: impl Fn(usize, usize) -> String,
+    ) -> String {
+        if num_cols == 0 {
+            return String::new();
+        }
+        let Some(first_col) = self.ranges.iter().map(|r| *r.cols.start()).min() else {
+            return String::new();
+        };
+        let last_col = self
+            .ranges
+            .iter()
+            .map(|r| *r.cols.end())
+            .max()
+            .unwrap_or_default()
+            .min(num_cols - 1);
+
+        let mut lines = vec![];
+        for row in self.selected_rows() {
+            if num_rows <= row {
+                break;
+            }
+            let cells: Vec<String> = (first_col..=last_col)
+                .map(|col| {
+                    if self.is_cell_selected(row, col) {
+                        cell_text(row, col).replace(['\t', '\n'], " ")
+                    } else {
+                        String::new()
+                    }
+                })
+                .collect();
+            lines.push(cells.join("\t"));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_selection() {
+        let mut selection = TableSelection::new(TableSelectionMode::MultiRow);
+        selection.click(2, 5, Modifiers::NONE);
+        assert_eq!(selection.cursor(), Some((2, 0)));
+        assert!(selection.is_cell_selected(2, 3));
+
+        selection.click(5, 0, Modifiers::SHIFT);
+        assert_eq!(selection.selected_rows(), [2, 3, 4, 5]);
+
+        selection.click(3, 0, Modifiers::COMMAND);
+        assert_eq!(selection.selected_rows(), [2, 4, 5]);
+
+        selection.click(8, 0, Modifiers::COMMAND);
+        assert_eq!(selection.selected_rows(), [2, 4, 5, 8]);
+
+        selection.move_cursor(9, 0, false);
+        assert_eq!(selection.selected_rows(), [9]);
+
+        let mut single = TableSelection::new(TableSelectionMode::SingleRow);
+        single.click(1, 0, Modifiers::NONE);
+        single.click(4, 0, Modifiers::SHIFT);
+        assert_eq!(single.selected_rows(), [4]);
+    }
+
+    #[test]
+    fn cell_selection() {
+        let mut selection = TableSelection::new(TableSelectionMode::Cells);
+        selection.click(1, 1, Modifiers::NONE);
+        selection.move_cursor(3, 2, true);
+        assert_eq!(
+            selection.ranges(),
+            [CellRange {
+                rows: 1..=3,
+                cols: 1..=2
+            }]
+        );
+
+        // Punch a hole in the middle:
+        selection.click(2, 1, Modifiers::COMMAND);
+        assert!(!selection.is_cell_selected(2, 1));
+        assert!(selection.is_cell_selected(2, 2));
+        assert!(selection.is_cell_selected(1, 1));
+        assert!(selection.is_cell_selected(3, 1));
+
+        let tsv = selection.to_tsv(10, 4, |row, col| format!("{row}:{col}"));
+        assert_eq!(tsv, "1:1\t1:2\n\t2:2\n3:1\t3:2");
+
+        selection.select_all(2, 3);
+        assert_eq!(
+            selection.to_tsv(2, 3, |_, col| col.to_string()),
+            "0\t1\t2\n0\t1\t2"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use egui::{Id, Rect, Sense, Ui, UiBuilder, Vec2};
+
+/// A row of hierarchical data in a [`crate::Table`]. See [`crate::TableBody::tree_rows`].
+///
+/// The nodes of a tree are given as a flat list in depth-first order,
+/// where the children of a node directly follow it, one level deeper.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableTreeNode {
+    /// Used to store whether the node is expanded, so it should be unique within the table.
+    pub id: Id,
+
+    /// How many ancestors this node has.
+    pub depth: usize,
+
+    /// Is the node expanded before the user has toggled it?
+    pub default_open: bool,
+}
+
+impl TableTreeNode {
+    /// A collapsed node.
+    pub fn new(id_salt: impl std::hash::Hash, depth: usize) -> Self {
+        Self {
+            id: Id::new(id_salt),
+            depth,
+            default_open: false,
+        }
+    }
+
+    #[inline]
+    pub fn default_open(mut self, default_open: bool) -> Self {
+        self.default_open = default_open;
+        self
+    }
+}
+
+/// Does the node at `index` have any children?
+fn has_children(nodes: &[TableTreeNode], index: usize) -> bool {
+    nodes
+        .get(index + 1)
+        .is_some_and(|next| nodes[index].depth < next.depth)
+}
+
+/// The indices of the nodes that are not inside a collapsed node.
+pub(crate) fn visible_nodes(
+    nodes: &[TableTreeNode],
+    is_open: impl Fn(&TableTreeNode) -> bool,
+) -> Vec<usize> {
+    let mut visible = Vec::with_capacity(nodes.len());
+    let mut collapsed_depth = None;
+    for (index, node) in nodes.iter().enumerate() {
+        if let Some(depth) = collapsed_depth {
+            if depth < node.depth {
+                continue;
+            }
+            collapsed_depth = None;
+        }
+        visible.push(index);
+        if has_children(nodes, index) && !is_open(node) {
+            collapsed_depth = Some(node.depth);
+        }
+    }
+    visible
+}
+
+/// Is the node expanded? Stored by [`TreeCell::ui`].
+pub(crate) fn is_node_open(ui: &Ui, tree_id: Id, node: &TableTreeNode) -> bool {
+    ui.data_mut(|d| d.get_persisted(tree_id.with(node.id)))
+        .unwrap_or(node.default_open)
+}
+
+/// The tree column of a row shown with [`crate::TableBody::tree_rows`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TreeCell {
+    /// Which column to show the disclosure triangle in.
+    pub(crate) column: usize,
+
+    /// Where the open state is stored.
+    id: Id,
+
+    depth: usize,
+    has_children: bool,
+    open: bool,
+}
+
+impl TreeCell {
+    pub(crate) fn new(
+        ui: &Ui,
+        tree_id: Id,
+        column: usize,
+        nodes: &[TableTreeNode],
+        index: usize,
+    ) -> Self {
+        let node = &nodes[index];
+        Self {
+            column,
+            id: tree_id.with(node.id),
+            depth: node.depth,
+            has_children: has_children(nodes, index),
+            open: is_node_open(ui, tree_id, node),
+        }
+    }
+
+    /// Indent the cell, and show a disclosure triangle left of the contents if the node has children.
+    pub(crate) fn ui(self, ui: &mut Ui, add_cell_contents: impl FnOnce(&mut Ui)) {
+        let indent = self.depth as f32 * ui.spacing().indent;
+        let icon_width = ui.spacing().icon_width;
+        let max_rect = ui.max_rect();
+        let left = max_rect.left() + indent + icon_width + ui.spacing().item_spacing.x;
+        let contents_rect = max_rect.split_left_right_at_x(left.min(max_rect.right())).1;
+
+        if self.has_children {
+            let icon_rect = Rect::from_min_size(
+                max_rect.left_center() + Vec2::new(indent, -0.5 * icon_width),
+                Vec2::splat(icon_width),
+            );
+            let response = ui.interact(icon_rect, self.id, Sense::click());
+            if response.clicked() {
+                ui.data_mut(|d| d.insert_persisted(self.id, !self.open));
+            }
+            let openness = ui.ctx().animate_bool_responsive(self.id, self.open);
+            egui::collapsing_header::paint_default_icon(ui, openness, &response);
+        }
+
+        ui.scope_builder(
+            UiBuilder::new()
+                .max_rect(contents_rect)
+                .layout(*ui.layout()),
+            add_cell_contents,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapsed_nodes_hide_descendants() {
+        let nodes = [
+            TableTreeNode::new("a", 0),
+            TableTreeNode::new("a/b", 1),
+            TableTreeNode::new("a/b/c", 2),
+            TableTreeNode::new("a/d", 1),
+            TableTreeNode::new("e", 0),
+            TableTreeNode::new("e/f", 1),
+        ];
+        let open = |open: &[&str]| {
+            let ids: Vec<Id> = open.iter().map(Id::new).collect();
+            visible_nodes(&nodes, |node| ids.contains(&node.id))
+        };
+
+        assert_eq!(open(&[]), [0, 4]);
+        assert_eq!(open(&["a"]), [0, 1, 3, 4]);
+        assert_eq!(open(&["a", "a/b"]), [0, 1, 2, 3, 4]);
+        assert_eq!(open(&["a/b", "e"]), [0, 4, 5]);
+        assert!(
+            !has_children(&nodes, 3),
+            "a/d is followed by a shallower node"
+        );
+        assert!(!has_children(&nodes, 5), "the last node has no children");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app_kind::AppKind;
+#[cfg(feature = "wgpu")]
 use crate::wgpu::WgpuTestRenderer;
 use crate::{Harness, LazyRenderer, TestRenderer};
 use egui::{Pos2, Rect, Vec2};
@@ -87,6 +88,14 @@ impl<State> HarnessBuilder<State> {
         self.renderer(WgpuTestRenderer::from_setup(setup))
     }
 
+    /// Enable rendering on the CPU, using a [`crate::software::SoftwareTestRenderer`].
+    ///
+    /// This needs no GPU, so snapshots render the same on any machine.
+    #[cfg(feature = "software")]
+    pub fn software(self) -> Self {
+        self.renderer(crate::software::SoftwareTestRenderer::new())
+    }
+
     /// Create a new Harness with the given app closure and a state.
     ///
     /// The app closure will immediately be called once to create the initial ui.
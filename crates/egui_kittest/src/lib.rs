@@ -15,6 +15,8 @@ use std::time::Duration;
 
 mod app_kind;
 mod renderer;
+#[cfg(feature = "software")]
+pub mod software;
 #[cfg(feature = "wgpu")]
 mod texture_to_image;
 #[cfg(feature = "wgpu")]
@@ -402,6 +404,7 @@ impl<'a, State> Harness<'a, State> {
     ///
     /// # Errors
     /// Returns an error if the rendering fails.
+    #[cfg(any(feature = "wgpu", feature = "software", feature = "snapshot"))]
     pub fn render(&mut self) -> Result<image::RgbaImage, String> {
         self.renderer.render(&self.ctx, &self.output)
     }
@@ -1,4 +1,7 @@
-use egui::{Context, FullOutput, TexturesDelta};
+use egui::TexturesDelta;
+#[cfg(any(feature = "wgpu", feature = "software", feature = "snapshot"))]
+use egui::{Context, FullOutput};
+#[cfg(any(feature = "wgpu", feature = "software", feature = "snapshot"))]
 use image::RgbaImage;
 
 pub trait TestRenderer {
@@ -13,12 +16,14 @@ pub trait TestRenderer {
     ///
     /// # Errors
     /// Returns an error if the rendering fails.
+    #[cfg(any(feature = "wgpu", feature = "software", feature = "snapshot"))]
     fn render(&mut self, ctx: &Context, output: &FullOutput) -> Result<RgbaImage, String>;
 }
 
 /// A lazy renderer that initializes the renderer on the first render call.
 ///
-/// By default, this will create a wgpu renderer if the wgpu feature is enabled.
+/// By default, this will create a wgpu renderer if the wgpu feature is enabled,
+/// or else a software renderer if the software feature is enabled.
 pub enum LazyRenderer {
     Uninitialized {
         texture_ops: Vec<egui::TexturesDelta>,
@@ -33,7 +38,9 @@ impl Default for LazyRenderer {
     fn default() -> Self {
         #[cfg(feature = "wgpu")]
         return Self::new(crate::wgpu::WgpuTestRenderer::new);
-        #[cfg(not(feature = "wgpu"))]
+        #[cfg(all(feature = "software", not(feature = "wgpu")))]
+        return Self::new(crate::software::SoftwareTestRenderer::new);
+        #[cfg(not(any(feature = "wgpu", feature = "software")))]
         return Self::Uninitialized {
             texture_ops: Vec::new(),
             builder: None,
@@ -58,6 +65,7 @@ impl TestRenderer for LazyRenderer {
         }
     }
 
+    #[cfg(any(feature = "wgpu", feature = "software", feature = "snapshot"))]
     fn render(&mut self, ctx: &Context, output: &FullOutput) -> Result<RgbaImage, String> {
         match self {
             Self::Uninitialized {
@@ -66,7 +74,7 @@ impl TestRenderer for LazyRenderer {
             } => {
                 let mut renderer = build.take().ok_or({
                     "No default renderer available. \
-                    Enable the wgpu feature or set one via HarnessBuilder::renderer"
+                    Enable the wgpu or software feature or set one via HarnessBuilder::renderer"
                 })?();
                 for delta in texture_ops.drain(..) {
                     renderer.handle_delta(&delta);
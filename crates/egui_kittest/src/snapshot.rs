@@ -1,3 +1,4 @@
+#[cfg(any(feature = "wgpu", feature = "software"))]
 use crate::Harness;
 use image::ImageError;
 use std::fmt::Display;
@@ -334,7 +335,7 @@ pub fn image_snapshot(current: &image::RgbaImage, name: &str) {
     }
 }
 
-#[cfg(feature = "wgpu")]
+#[cfg(any(feature = "wgpu", feature = "software"))]
 impl<State> Harness<'_, State> {
     /// Render a image using the setup [`crate::TestRenderer`] and compare it to the snapshot
     /// with custom options.
@@ -430,7 +431,7 @@ impl<State> Harness<'_, State> {
 // Deprecated wgpu_snapshot functions
 // TODO(lucasmerlin): Remove in 0.32
 #[allow(clippy::missing_errors_doc)]
-#[cfg(feature = "wgpu")]
+#[cfg(any(feature = "wgpu", feature = "software"))]
 impl<State> Harness<'_, State> {
     #[deprecated(
         since = "0.31.0",
@@ -0,0 +1,418 @@
+//! A pure-Rust CPU rasterizer for [`egui::ClippedPrimitive`]s.
+//!
+//! This makes it possible to render snapshots on machines without a GPU
+//! (or without a software Vulkan driver like lavapipe).
+//!
+//! The rasterizer tries to mimic what `egui_glow` and `egui-wgpu` do:
+//! * vertex colors are interpolated in gamma space,
+//! * textures are stored in sRGB and filtered in linear space,
+//! * blending is premultiplied alpha into a gamma-space framebuffer.
+
+use egui::ecolor::{gamma_from_linear, linear_f32_from_gamma_u8};
+use egui::epaint::{ClippedPrimitive, ImageData, ImageDelta, Primitive, Vertex};
+use egui::{
+    Color32, ColorImage, Context, FullOutput, Pos2, Rect, TextureFilter, TextureId, TextureOptions,
+    TextureWrapMode, TexturesDelta,
+};
+
+/// A texture stored on the CPU.
+struct Texture {
+    size: [usize; 2],
+
+    /// Linear-space premultiplied RGBA.
+    pixels: Vec<[f32; 4]>,
+
+    options: TextureOptions,
+}
+
+impl Texture {
+    fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = wrap(x, self.size[0], self.options.wrap_mode);
+        let y = wrap(y, self.size[1], self.options.wrap_mode);
+        self.pixels[y * self.size[0] + x]
+    }
+
+    /// Sample the texture at the given uv coordinate, returning linear premultiplied RGBA.
+    fn sample(&self, uv: [f32; 2], filter: TextureFilter) -> [f32; 4] {
+        if self.size[0] == 0 || self.size[1] == 0 {
+            return [0.0; 4];
+        }
+
+        let x = uv[0] * self.size[0] as f32;
+        let y = uv[1] * self.size[1] as f32;
+
+        match filter {
+            TextureFilter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            TextureFilter::Linear => {
+                // Texel centers are at half-integer coordinates:
+                let x = x - 0.5;
+                let y = y - 0.5;
+                let x0 = x.floor();
+                let y0 = y.floor();
+                let tx = x - x0;
+                let ty = y - y0;
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                let c00 = self.texel(x0, y0);
+                let c10 = self.texel(x0 + 1, y0);
+                let c01 = self.texel(x0, y0 + 1);
+                let c11 = self.texel(x0 + 1, y0 + 1);
+
+                std::array::from_fn(|i| {
+                    let top = lerp(c00[i], c10[i], tx);
+                    let bottom = lerp(c01[i], c11[i], tx);
+                    lerp(top, bottom, ty)
+                })
+            }
+        }
+    }
+}
+
+fn wrap(coord: i64, size: usize, mode: TextureWrapMode) -> usize {
+    let size = size as i64;
+    match mode {
+        TextureWrapMode::ClampToEdge => coord.clamp(0, size - 1) as usize,
+        TextureWrapMode::Repeat => coord.rem_euclid(size) as usize,
+        TextureWrapMode::MirroredRepeat => {
+            let period = coord.rem_euclid(2 * size);
+            if period < size {
+                period as usize
+            } else {
+                (2 * size - 1 - period) as usize
+            }
+        }
+    }
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn linear_from_color32(color: Color32) -> [f32; 4] {
+    let [r, g, b, a] = color.to_array();
+    [
+        linear_f32_from_gamma_u8(r),
+        linear_f32_from_gamma_u8(g),
+        linear_f32_from_gamma_u8(b),
+        a as f32 / 255.0,
+    ]
+}
+
+/// A CPU rasterizer for the output of [`egui::Context::tessellate`].
+///
+/// Use [`Self::update_textures`] to keep the textures in sync,
+/// and [`Self::paint_primitives`] to render to a [`ColorImage`].
+///
+/// Paint callbacks ([`Primitive::Callback`]) are ignored.
+///
+/// Textures are sampled with their [`TextureOptions::magnification`], [`TextureOptions::minification`]
+/// and [`TextureOptions::wrap_mode`], but the [`TextureOptions::mipmap_mode`] is ignored:
+/// there are no mipmaps, so minified textures may look more aliased than on a GPU.
+///
+/// This also implements [`crate::TestRenderer`], so it can be used with
+/// [`crate::HarnessBuilder::renderer`] to render snapshots without a GPU.
+#[derive(Default)]
+pub struct SoftwareTestRenderer {
+    textures: egui::ahash::HashMap<TextureId, Texture>,
+
+    /// Textures to free before the next texture update.
+    ///
+    /// The [`TexturesDelta::free`] should be applied _after_ painting.
+    pending_free: Vec<TextureId>,
+}
+
+impl SoftwareTestRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the texture changes from a [`TexturesDelta`].
+    ///
+    /// Freed textures are kept around until the next call,
+    /// so that the frame producing the delta can still be painted.
+    ///
+    /// # Panics
+    /// If a partial update is outside the bounds of its texture.
+    pub fn update_textures(&mut self, delta: &TexturesDelta) {
+        for id in self.pending_free.drain(..) {
+            self.textures.remove(&id);
+        }
+
+        for (id, image_delta) in &delta.set {
+            self.set_texture(*id, image_delta);
+        }
+
+        self.pending_free.extend(delta.free.iter().copied());
+    }
+
+    fn set_texture(&mut self, id: TextureId, delta: &ImageDelta) {
+        let size = delta.image.size();
+        let pixels: Vec<[f32; 4]> = match &delta.image {
+            ImageData::Color(image) => image
+                .pixels
+                .iter()
+                .copied()
+                .map(linear_from_color32)
+                .collect(),
+            ImageData::Font(image) => image.srgba_pixels(None).map(linear_from_color32).collect(),
+        };
+
+        if let Some([x, y]) = delta.pos {
+            let Some(texture) = self.textures.get_mut(&id) else {
+                debug_assert!(false, "Partial update of unknown texture {id:?}");
+                return;
+            };
+            assert!(
+                x + size[0] <= texture.size[0] && y + size[1] <= texture.size[1],
+                "Partial texture update at {:?} of size {size:?} is outside the bounds of texture {id:?} of size {:?}",
+                [x, y],
+                texture.size
+            );
+            for (row, src) in pixels.chunks_exact(size[0].max(1)).enumerate() {
+                let offset = (y + row) * texture.size[0] + x;
+                texture.pixels[offset..offset + src.len()].copy_from_slice(src);
+            }
+            texture.options = delta.options;
+        } else {
+            let texture = Texture {
+                size,
+                pixels,
+                options: delta.options,
+            };
+            self.textures.insert(id, texture);
+        }
+    }
+
+    /// Rasterize the given primitives into a new image of the given size (in physical pixels).
+    ///
+    /// The image is cleared to [`Color32::TRANSPARENT`] first.
+    /// The resulting pixels are premultiplied, just like the framebuffer of a GPU painter.
+    pub fn paint_primitives(
+        &self,
+        primitives: &[ClippedPrimitive],
+        pixels_per_point: f32,
+        size_in_pixels: [usize; 2],
+    ) -> ColorImage {
+        let mut target = Framebuffer {
+            size: size_in_pixels,
+            pixels: vec![[0.0; 4]; size_in_pixels[0] * size_in_pixels[1]],
+        };
+
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            match primitive {
+                Primitive::Mesh(mesh) => {
+                    let Some(texture) = self.textures.get(&mesh.texture_id) else {
+                        continue;
+                    };
+                    let clip = scissor_rect(*clip_rect, pixels_per_point, size_in_pixels);
+                    if clip.is_empty() {
+                        continue;
+                    }
+                    for triangle in mesh.indices.chunks_exact(3) {
+                        let [a, b, c] = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+                        target.fill_triangle(texture, pixels_per_point, clip, [a, b, c]);
+                    }
+                }
+                Primitive::Callback(_) => {
+                    // We have no way of running a backend-specific callback on the CPU.
+                }
+            }
+        }
+
+        target.into_image()
+    }
+}
+
+impl crate::TestRenderer for SoftwareTestRenderer {
+    fn handle_delta(&mut self, delta: &TexturesDelta) {
+        self.update_textures(delta);
+    }
+
+    fn render(&mut self, ctx: &Context, output: &FullOutput) -> Result<image::RgbaImage, String> {
+        let pixels_per_point = ctx.pixels_per_point();
+        let size = ctx.screen_rect().size() * pixels_per_point;
+        let size_in_pixels = [size.x.round() as usize, size.y.round() as usize];
+
+        let primitives = ctx.tessellate(output.shapes.clone(), pixels_per_point);
+        let image = self.paint_primitives(&primitives, pixels_per_point, size_in_pixels);
+
+        image::RgbaImage::from_raw(
+            size_in_pixels[0] as u32,
+            size_in_pixels[1] as u32,
+            image
+                .pixels
+                .iter()
+                .flat_map(|color| color.to_array())
+                .collect(),
+        )
+        .ok_or_else(|| "Failed to create image".to_owned())
+    }
+}
+
+/// Integer pixel bounds, `min` inclusive and `max` exclusive.
+#[derive(Clone, Copy)]
+struct PixelRect {
+    min: [usize; 2],
+    max: [usize; 2],
+}
+
+impl PixelRect {
+    fn is_empty(&self) -> bool {
+        self.min[0] >= self.max[0] || self.min[1] >= self.max[1]
+    }
+}
+
+/// Same rounding as the scissor rectangles of the GPU painters.
+fn scissor_rect(clip_rect: Rect, pixels_per_point: f32, size_in_pixels: [usize; 2]) -> PixelRect {
+    let round = |v: f32, max: usize| ((v * pixels_per_point).round().max(0.0) as usize).min(max);
+    let min_x = round(clip_rect.min.x, size_in_pixels[0]);
+    let min_y = round(clip_rect.min.y, size_in_pixels[1]);
+    let max_x = round(clip_rect.max.x, size_in_pixels[0]).max(min_x);
+    let max_y = round(clip_rect.max.y, size_in_pixels[1]).max(min_y);
+    PixelRect {
+        min: [min_x, min_y],
+        max: [max_x, max_y],
+    }
+}
+
+/// Gamma-space premultiplied RGBA in 0-1.
+struct Framebuffer {
+    size: [usize; 2],
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Framebuffer {
+    fn fill_triangle(
+        &mut self,
+        texture: &Texture,
+        pixels_per_point: f32,
+        clip: PixelRect,
+        vertices: [&Vertex; 3],
+    ) {
+        let pos = vertices.map(|v| Pos2::new(v.pos.x, v.pos.y) * pixels_per_point);
+        let uv = vertices.map(|v| [v.uv.x, v.uv.y]);
+        let color = vertices.map(|v| v.color.to_array().map(|c| c as f32 / 255.0));
+
+        let area = edge(pos[0], pos[1], pos[2]);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+
+        // Bounding box, in pixels, limited by the clip rectangle:
+        let min_x = pos.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+        let min_y = pos.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_x = pos.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+        let max_y = pos.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+        let x_range = (min_x.floor().max(clip.min[0] as f32) as usize)
+            ..(max_x.ceil().max(0.0) as usize).min(clip.max[0]);
+        let y_range = (min_y.floor().max(clip.min[1] as f32) as usize)
+            ..(max_y.ceil().max(0.0) as usize).min(clip.max[1]);
+        if x_range.is_empty() || y_range.is_empty() {
+            return;
+        }
+
+        // The GPU picks minification or magnification based on the uv derivatives.
+        // They are constant over a triangle, so we can do it once.
+        let filter = {
+            let [w, h] = texture.size.map(|s| s as f32);
+            let du_dx = (uv[0][0] * (pos[1].y - pos[2].y)
+                + uv[1][0] * (pos[2].y - pos[0].y)
+                + uv[2][0] * (pos[0].y - pos[1].y))
+                / area;
+            let du_dy = (uv[0][0] * (pos[2].x - pos[1].x)
+                + uv[1][0] * (pos[0].x - pos[2].x)
+                + uv[2][0] * (pos[1].x - pos[0].x))
+                / area;
+            let dv_dx = (uv[0][1] * (pos[1].y - pos[2].y)
+                + uv[1][1] * (pos[2].y - pos[0].y)
+                + uv[2][1] * (pos[0].y - pos[1].y))
+                / area;
+            let dv_dy = (uv[0][1] * (pos[2].x - pos[1].x)
+                + uv[1][1] * (pos[0].x - pos[2].x)
+                + uv[2][1] * (pos[1].x - pos[0].x))
+                / area;
+            let texels_per_pixel =
+                f32::max((du_dx * w).hypot(dv_dx * h), (du_dy * w).hypot(dv_dy * h));
+            if texels_per_pixel > 1.0 {
+                texture.options.minification
+            } else {
+                texture.options.magnification
+            }
+        };
+
+        // Top-left fill rule, so that shared edges are only painted once:
+        let bias = |a: Pos2, b: Pos2| {
+            let is_top = a.y == b.y && (b.x - a.x) * area.signum() < 0.0;
+            let is_left = (b.y - a.y) * area.signum() > 0.0;
+            if is_top || is_left {
+                0.0
+            } else {
+                -f32::EPSILON
+            }
+        };
+        let biases = [
+            bias(pos[1], pos[2]),
+            bias(pos[2], pos[0]),
+            bias(pos[0], pos[1]),
+        ];
+
+        for y in y_range {
+            for x in x_range.clone() {
+                let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = edge(pos[1], pos[2], p) / area;
+                let w1 = edge(pos[2], pos[0], p) / area;
+                let w2 = edge(pos[0], pos[1], p) / area;
+                if w0 + biases[0] < 0.0 || w1 + biases[1] < 0.0 || w2 + biases[2] < 0.0 {
+                    continue;
+                }
+
+                let uv = [
+                    w0 * uv[0][0] + w1 * uv[1][0] + w2 * uv[2][0],
+                    w0 * uv[0][1] + w1 * uv[1][1] + w2 * uv[2][1],
+                ];
+                let texel = texture.sample(uv, filter);
+                let src: [f32; 4] = std::array::from_fn(|i| {
+                    let vertex_color = w0 * color[0][i] + w1 * color[1][i] + w2 * color[2][i];
+                    let texel_gamma = if i < 3 {
+                        gamma_from_linear(texel[i])
+                    } else {
+                        texel[i]
+                    };
+                    vertex_color * texel_gamma
+                });
+
+                let dst = &mut self.pixels[y * self.size[0] + x];
+                let inv_alpha = 1.0 - src[3];
+                for i in 0..4 {
+                    dst[i] = src[i] + dst[i] * inv_alpha;
+                }
+            }
+        }
+    }
+
+    fn into_image(self) -> ColorImage {
+        let pixels = self
+            .pixels
+            .into_iter()
+            .map(|[r, g, b, a]| {
+                let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+                Color32::from_rgba_premultiplied(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+            })
+            .collect();
+        ColorImage {
+            size: self.size,
+            pixels,
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `a, b, c`.
+#[inline]
+fn edge(a: Pos2, b: Pos2, c: Pos2) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
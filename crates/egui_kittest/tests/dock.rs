@@ -0,0 +1,122 @@
+use egui::{
+    DockArea, DockNode, DockSide, DockState, Event, Id, PointerButton, Pos2, TabViewer, Ui,
+};
+use egui_kittest::{kittest::Queryable, Harness};
+
+struct Viewer;
+
+impl TabViewer for Viewer {
+    type Tab = &'static str;
+
+    fn id(&mut self, tab: &mut Self::Tab) -> Id {
+        Id::new(*tab)
+    }
+
+    fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
+        (*tab).into()
+    }
+
+    fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
+        ui.label(format!("Contents of {tab}"));
+    }
+}
+
+fn leaf_tabs(node: &DockNode<&'static str>) -> Vec<Vec<&'static str>> {
+    match node {
+        DockNode::Leaf { tabs, .. } => vec![tabs.clone()],
+        DockNode::Split { children, .. } => {
+            let mut tabs = leaf_tabs(&children[0]);
+            tabs.extend(leaf_tabs(&children[1]));
+            tabs
+        }
+    }
+}
+
+fn tab_center(harness: &Harness<'_, DockState<&'static str>>, label: &str) -> Pos2 {
+    let rect = harness
+        .get_by_label(label)
+        .bounding_box()
+        .expect("The tab should have a bounding box");
+    Pos2::new(
+        (0.5 * (rect.x0 + rect.x1)) as f32,
+        (0.5 * (rect.y0 + rect.y1)) as f32,
+    )
+}
+
+fn drag(harness: &mut Harness<'_, DockState<&'static str>>, from: Pos2, to: Pos2) {
+    let modifiers = Default::default();
+    harness.input_mut().events.push(Event::PointerMoved(from));
+    harness.run();
+    harness.input_mut().events.push(Event::PointerButton {
+        pos: from,
+        button: PointerButton::Primary,
+        pressed: true,
+        modifiers,
+    });
+    harness.run();
+    for i in 1..=10 {
+        let pos = from.lerp(to, i as f32 / 10.0);
+        harness.input_mut().events.push(Event::PointerMoved(pos));
+        harness.run();
+    }
+    harness.input_mut().events.push(Event::PointerButton {
+        pos: to,
+        button: PointerButton::Primary,
+        pressed: false,
+        modifiers,
+    });
+    harness.run();
+}
+
+fn dock(state: DockState<&'static str>) -> Harness<'static, DockState<&'static str>> {
+    let mut harness = Harness::new_ui_state(
+        |ui, state: &mut DockState<&'static str>| {
+            DockArea::new("dock").show(ui, state, &mut Viewer);
+        },
+        state,
+    );
+    harness.run();
+    harness
+}
+
+#[test]
+fn drop_tab_on_the_tab_bar_of_another_group() {
+    let mut state = DockState::new(vec!["a", "b"]);
+    let first = match state.root() {
+        Some(DockNode::Leaf { id, .. }) => *id,
+        _ => unreachable!(),
+    };
+    state.split(first, DockSide::Right, vec!["c"]);
+    let mut harness = dock(state);
+
+    // Right of the tabs of the first group, but still in its tab bar:
+    let from = tab_center(&harness, "c");
+    let to = tab_center(&harness, "b") + egui::vec2(30.0, 0.0);
+    drag(&mut harness, from, to);
+
+    let state = harness.state();
+    assert_eq!(leaf_tabs(state.root().unwrap()), vec![vec!["a", "b", "c"]]);
+}
+
+#[test]
+fn drop_tab_near_the_bottom_of_another_group_splits_it() {
+    let mut state = DockState::new(vec!["a", "b"]);
+    let first = match state.root() {
+        Some(DockNode::Leaf { id, .. }) => *id,
+        _ => unreachable!(),
+    };
+    state.split(first, DockSide::Right, vec!["c", "d"]);
+    let mut harness = dock(state);
+
+    // In the middle of the bottom edge of the first group, which fills the left half:
+    let from = tab_center(&harness, "c");
+    let screen = harness.ctx.screen_rect();
+    let to = Pos2::new(0.25 * screen.width(), 0.9 * screen.height());
+    drag(&mut harness, from, to);
+
+    let state = harness.state();
+    assert_eq!(
+        leaf_tabs(state.root().unwrap()),
+        vec![vec!["a", "b"], vec!["c"], vec!["d"]]
+    );
+}
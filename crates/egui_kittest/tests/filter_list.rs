@@ -0,0 +1,65 @@
+use egui::{accesskit::Role, ComboBox, Event, Key, TextEdit};
+use egui_kittest::{kittest::Queryable, Harness};
+
+const FONTS: [&str; 5] = ["Arial", "Helvetica", "Times", "Courier", "Verdana"];
+
+fn type_text<State>(harness: &mut Harness<'_, State>, text: &str) {
+    harness
+        .input_mut()
+        .events
+        .push(Event::Text(text.to_owned()));
+    harness.run();
+}
+
+#[test]
+fn combo_box_filter_and_pick_with_keyboard() {
+    let mut harness = Harness::new_ui_state(
+        |ui, selected: &mut usize| {
+            ComboBox::from_label("Font")
+                .show_index_filtered(ui, selected, FONTS.len(), |i| FONTS[i]);
+        },
+        0,
+    );
+    harness.run();
+
+    harness
+        .get_by_role_and_label(Role::ComboBox, "Font")
+        .click();
+    harness.run();
+
+    // The filter has the focus, and only "Courier" and "Verdana" contain "er":
+    type_text(&mut harness, "ER");
+    assert!(harness.query_by_label("Arial").is_none());
+    harness.get_by_label("Courier");
+
+    harness.press_key(Key::ArrowDown);
+    harness.run();
+    harness.press_key(Key::Enter);
+    harness.run();
+    assert_eq!(*harness.state(), 4);
+
+    // The menu is closed:
+    assert!(harness.query_by_label("Courier").is_none());
+}
+
+#[test]
+fn pick_completion_with_keyboard() {
+    let mut harness = Harness::new_ui_state(
+        |ui, text: &mut String| {
+            TextEdit::singleline(text).show_with_completions(ui, &["apple", "apricot", "banana"]);
+        },
+        String::new(),
+    );
+    harness.run();
+
+    harness.get_by_role(Role::TextInput).focus();
+    harness.run();
+    type_text(&mut harness, "ap");
+    assert!(harness.query_by_label("banana").is_none());
+
+    harness.press_key(Key::ArrowDown);
+    harness.run();
+    harness.press_key(Key::Enter);
+    harness.run();
+    assert_eq!(harness.state(), "apricot");
+}
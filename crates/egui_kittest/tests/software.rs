@@ -0,0 +1,173 @@
+#![cfg(feature = "software")]
+
+use egui::{Color32, Pos2, Rect, Vec2};
+use egui_kittest::Harness;
+
+#[test]
+fn software_renderer_fills_rect() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(100.0, 100.0))
+        .software()
+        .build(|ctx| {
+            ctx.layer_painter(egui::LayerId::background()).rect_filled(
+                Rect::from_min_max(Pos2::new(10.0, 10.0), Pos2::new(50.0, 50.0)),
+                0.0,
+                Color32::RED,
+            );
+        });
+
+    let image = harness.render().unwrap();
+    assert_eq!(image.dimensions(), (100, 100));
+    assert_eq!(image.get_pixel(30, 30).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(80, 80).0, [0, 0, 0, 0]);
+}
+
+#[test]
+fn software_renderer_respects_clip_rect() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(100.0, 100.0))
+        .with_pixels_per_point(2.0)
+        .software()
+        .build(|ctx| {
+            let painter = ctx
+                .layer_painter(egui::LayerId::background())
+                .with_clip_rect(Rect::from_min_max(Pos2::ZERO, Pos2::new(25.0, 100.0)));
+            painter.rect_filled(
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(100.0, 100.0)),
+                0.0,
+                Color32::WHITE,
+            );
+        });
+
+    let image = harness.render().unwrap();
+    assert_eq!(image.dimensions(), (200, 200));
+    assert_eq!(image.get_pixel(49, 100).0, [255, 255, 255, 255]);
+    assert_eq!(image.get_pixel(50, 100).0, [0, 0, 0, 0]);
+}
+
+#[test]
+fn software_renderer_blends_premultiplied() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(10.0, 10.0))
+        .software()
+        .build(|ctx| {
+            let painter = ctx.layer_painter(egui::LayerId::background());
+            let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(10.0));
+            painter.rect_filled(rect, 0.0, Color32::WHITE);
+            painter.rect_filled(rect, 0.0, Color32::from_black_alpha(128));
+        });
+
+    let image = harness.render().unwrap();
+    let [r, g, b, a] = image.get_pixel(5, 5).0;
+    assert_eq!(a, 255);
+    assert_eq!((r, g), (b, b));
+    assert!(
+        (125..=129).contains(&r),
+        "Blending happens in gamma space, got {r}"
+    );
+}
+
+#[test]
+fn software_renderer_interpolates_vertex_colors() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(100.0, 10.0))
+        .software()
+        .build(|ctx| {
+            let mut mesh = egui::Mesh::default();
+            let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(100.0, 10.0));
+            mesh.colored_vertex(rect.left_top(), Color32::BLACK);
+            mesh.colored_vertex(rect.right_top(), Color32::WHITE);
+            mesh.colored_vertex(rect.left_bottom(), Color32::BLACK);
+            mesh.colored_vertex(rect.right_bottom(), Color32::WHITE);
+            mesh.add_triangle(0, 1, 2);
+            mesh.add_triangle(2, 1, 3);
+            ctx.layer_painter(egui::LayerId::background()).add(mesh);
+        });
+
+    let image = harness.render().unwrap();
+    let red_at = |x| image.get_pixel(x, 5).0[0];
+    assert!(red_at(0) < 5);
+    assert!((122..=133).contains(&red_at(50)), "got {}", red_at(50));
+    assert!(red_at(99) > 250);
+    assert!((0..99).all(|x| red_at(x) <= red_at(x + 1)));
+}
+
+#[test]
+fn software_renderer_samples_user_textures() {
+    let mut texture = None;
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(20.0, 20.0))
+        .software()
+        .build(|ctx| {
+            let texture = texture.get_or_insert_with(|| {
+                let image = egui::ColorImage {
+                    size: [2, 1],
+                    pixels: vec![Color32::BLUE, Color32::GREEN],
+                };
+                ctx.load_texture("two_texels", image, egui::TextureOptions::NEAREST)
+            });
+            ctx.layer_painter(egui::LayerId::background()).image(
+                texture.id(),
+                Rect::from_min_size(Pos2::ZERO, Vec2::splat(20.0)),
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        });
+
+    let image = harness.render().unwrap();
+    assert_eq!(image.get_pixel(5, 10).0, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(15, 10).0, [0, 255, 0, 255]);
+}
+
+#[test]
+fn software_renderer_draws_text() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(100.0, 30.0))
+        .software()
+        .build_ui(|ui| {
+            ui.label("Hello");
+        });
+
+    let image = harness.render().unwrap();
+    let covered = image.pixels().filter(|pixel| pixel.0[3] > 128).count();
+    assert!(
+        covered > 20,
+        "Only {covered} pixels were covered by the glyphs"
+    );
+}
+
+#[test]
+#[should_panic(expected = "outside the bounds")]
+fn software_renderer_rejects_out_of_bounds_texture_updates() {
+    use egui::{epaint::ImageDelta, ColorImage, TextureId, TexturesDelta};
+    use egui_kittest::software::SoftwareTestRenderer;
+
+    let id = TextureId::Managed(1);
+    let image = |size: [usize; 2]| ColorImage::new(size, Color32::WHITE);
+    let mut renderer = SoftwareTestRenderer::new();
+    renderer.update_textures(&TexturesDelta {
+        set: vec![(id, ImageDelta::full(image([4, 4]), Default::default()))],
+        free: vec![],
+    });
+    renderer.update_textures(&TexturesDelta {
+        set: vec![(
+            id,
+            ImageDelta::partial([3, 3], image([2, 2]), Default::default()),
+        )],
+        free: vec![],
+    });
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn software_renderer_snapshot() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(120.0, 40.0))
+        .software()
+        .build_ui(|ui| {
+            ui.label("Software");
+            let _ = ui.button("Button");
+        });
+
+    harness.snapshot("software_renderer");
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashSet, sync::Arc};
+
+use egui::{
+    text::CCursor, Event, Galley, Key, Modifiers, PointerButton, Pos2, TextBuffer, TextEdit,
+    TextRope, TextStyle, Vec2,
+};
+use egui_kittest::{kittest::Queryable, Harness};
+
+struct Editor<T> {
+    text: T,
+    dictionary: Option<HashSet<String>>,
+    galley_pos: Pos2,
+    galley: Option<Arc<Galley>>,
+}
+
+fn editor(text: &str) -> Harness<'static, Editor<String>> {
+    editor_with(text.to_owned(), None)
+}
+
+fn editor_with<T: TextBuffer + 'static>(
+    text: T,
+    dictionary: Option<HashSet<String>>,
+) -> Harness<'static, Editor<T>> {
+    let editor = Editor {
+        text,
+        dictionary,
+        galley_pos: Pos2::ZERO,
+        galley: None,
+    };
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 200.0))
+        .build_ui_state(
+            |ui, editor: &mut Editor<T>| {
+                // Monospace, so that the columns of a block selection line up:
+                let mut text_edit =
+                    TextEdit::multiline(&mut editor.text).font(TextStyle::Monospace);
+                if let Some(dictionary) = &editor.dictionary {
+                    text_edit = text_edit.spell_checker(dictionary);
+                }
+                let output = text_edit.show(ui);
+                editor.galley_pos = output.galley_pos;
+                editor.galley = Some(output.galley);
+            },
+            editor,
+        );
+    harness.run();
+    harness
+}
+
+/// The screen position of the cursor before the char at `char_index`, vertically centered in its row.
+fn char_pos<T>(harness: &Harness<'_, Editor<T>>, char_index: usize) -> Pos2 {
+    let editor = harness.state();
+    let galley = editor.galley.as_ref().expect("The text edit wasn't shown");
+    let rect = galley.pos_from_cursor(&galley.from_ccursor(CCursor::new(char_index)));
+    editor.galley_pos + rect.center().to_vec2()
+}
+
+fn pointer_button<T>(
+    harness: &mut Harness<'_, Editor<T>>,
+    button: PointerButton,
+    pos: Pos2,
+    pressed: bool,
+) {
+    let modifiers = harness.input().modifiers;
+    harness.input_mut().events.push(Event::PointerButton {
+        pos,
+        button,
+        pressed,
+        modifiers,
+    });
+}
+
+fn click<T>(harness: &mut Harness<'_, Editor<T>>, button: PointerButton, pos: Pos2) {
+    harness.input_mut().events.push(Event::PointerMoved(pos));
+    harness.run();
+    pointer_button(harness, button, pos, true);
+    harness.run();
+    pointer_button(harness, button, pos, false);
+    harness.run();
+}
+
+fn type_text<T>(harness: &mut Harness<'_, Editor<T>>, text: &str) {
+    harness
+        .input_mut()
+        .events
+        .push(Event::Text(text.to_owned()));
+    harness.run();
+}
+
+#[test]
+fn select_next_occurrence_and_undo() {
+    let mut harness = editor("foo bar foo baz foo");
+    let pos = char_pos(&harness, 1);
+    click(&mut harness, PointerButton::Primary, pos);
+
+    for _ in 0..3 {
+        harness.press_key_modifiers(Modifiers::COMMAND, Key::D);
+        harness.run();
+    }
+    type_text(&mut harness, "x");
+    assert_eq!(harness.state().text, "x bar x baz x");
+
+    // The edit of all the cursors is a single undo step:
+    harness.press_key_modifiers(Modifiers::COMMAND, Key::Z);
+    harness.run();
+    assert_eq!(harness.state().text, "foo bar foo baz foo");
+}
+
+#[test]
+fn select_next_occurrence_wraps_around() {
+    let mut harness = editor("ab ab ab");
+    let pos = char_pos(&harness, 4);
+    click(&mut harness, PointerButton::Primary, pos);
+
+    for _ in 0..4 {
+        harness.press_key_modifiers(Modifiers::COMMAND, Key::D);
+        harness.run();
+    }
+    type_text(&mut harness, "c");
+    assert_eq!(harness.state().text, "c c c");
+}
+
+#[test]
+fn alt_drag_selects_block() {
+    let mut harness = editor("abcd\nefgh\nijkl");
+    let from = char_pos(&harness, 1);
+    let to = char_pos(&harness, 13);
+
+    harness.input_mut().modifiers = Modifiers::ALT;
+    harness.input_mut().events.push(Event::PointerMoved(from));
+    harness.run();
+    pointer_button(&mut harness, PointerButton::Primary, from, true);
+    harness.run();
+    for t in [0.25, 0.5, 0.75, 1.0] {
+        harness
+            .input_mut()
+            .events
+            .push(Event::PointerMoved(from.lerp(to, t)));
+        harness.run();
+    }
+    pointer_button(&mut harness, PointerButton::Primary, to, false);
+    harness.run();
+    harness.input_mut().modifiers = Modifiers::NONE;
+
+    type_text(&mut harness, "X");
+    assert_eq!(harness.state().text, "aXd\neXh\niXl");
+
+    harness.press_key_modifiers(Modifiers::COMMAND, Key::Z);
+    harness.run();
+    assert_eq!(harness.state().text, "abcd\nefgh\nijkl");
+}
+
+#[test]
+fn alt_click_adds_carets() {
+    let mut harness = editor("abcd\nefgh\nijkl");
+    let pos = char_pos(&harness, 1);
+    click(&mut harness, PointerButton::Primary, pos);
+
+    harness.input_mut().modifiers = Modifiers::ALT;
+    for char_index in [6, 11] {
+        let pos = char_pos(&harness, char_index);
+        click(&mut harness, PointerButton::Primary, pos);
+    }
+    // Clicking a caret again doesn't add another one there:
+    let pos = char_pos(&harness, 6);
+    click(&mut harness, PointerButton::Primary, pos);
+    // …and other buttons don't add carets:
+    let pos = char_pos(&harness, 3);
+    click(&mut harness, PointerButton::Secondary, pos);
+    click(&mut harness, PointerButton::Middle, pos);
+    harness.input_mut().modifiers = Modifiers::NONE;
+
+    type_text(&mut harness, "X");
+    assert_eq!(harness.state().text, "aXbcd\neXfgh\niXjkl");
+}
+
+#[test]
+fn select_next_occurrence_is_multiline_only() {
+    let mut harness = Harness::new_ui_state(
+        |ui, text: &mut String| {
+            ui.text_edit_singleline(text);
+        },
+        "ab ab".to_owned(),
+    );
+    harness.run();
+
+    let text_edit = harness.get_by_role(egui::accesskit::Role::TextInput);
+    text_edit.focus();
+    harness.run();
+    harness.press_key_modifiers(Modifiers::COMMAND, Key::D);
+    harness.run();
+    harness.input_mut().events.push(Event::Text("c".to_owned()));
+    harness.run();
+    assert_eq!(harness.state(), "ab abc");
+}
+
+/// Right-click a misspelled word, pick a suggestion, and undo it.
+fn correct_spelling<T: TextBuffer + 'static>(text: T) {
+    let dictionary = ["hello", "help", "world"].map(String::from).into();
+    let mut harness = editor_with(text, Some(dictionary));
+    let pos = char_pos(&harness, 1);
+    // Focus the text edit, for the undo below:
+    click(&mut harness, PointerButton::Primary, pos);
+    click(&mut harness, PointerButton::Secondary, pos);
+
+    harness.get_by_label("Hello").click();
+    harness.run();
+    assert_eq!(harness.state().text.text(), "Hello world");
+
+    harness.press_key_modifiers(Modifiers::COMMAND, Key::Z);
+    harness.run();
+    assert_eq!(harness.state().text.text(), "Helo world");
+}
+
+#[test]
+fn correct_spelling_in_string() {
+    correct_spelling(String::from("Helo world"));
+}
+
+#[test]
+fn correct_spelling_in_rope() {
+    correct_spelling(TextRope::from("Helo world"));
+}
@@ -1,19 +1,236 @@
-use crate::{Rect, TextureId};
+use std::sync::Arc;
 
-/// Controls texturing of a [`crate::RectShape`].
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+use emath::{Pos2, Rect, TSTransform};
+
+use crate::{Color32, TextureId};
+
+/// Controls texturing and gradients of a filled shape,
+/// see [`crate::RectShape::brush`] and [`crate::Shape::with_brush`].
+///
+/// The gradient is shared, so a [`Brush`] is cheap to clone.
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Brush {
-    /// If the rect should be filled with a texture, which one?
+    /// If the shape should be filled with a texture, which one?
     ///
-    /// The texture is multiplied with [`crate::RectShape::fill`].
+    /// The texture is multiplied with the fill color of the shape.
     pub fill_texture_id: TextureId,
 
     /// What UV coordinates to use for the texture?
     ///
+    /// These are mapped onto the bounding rectangle of the shape.
+    ///
     /// To display a texture, set [`Self::fill_texture_id`],
     /// and set this to `Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0))`.
     ///
     /// Use [`Rect::ZERO`] to turn off texturing.
     pub uv: Rect,
+
+    /// If set, the shape is filled with this gradient.
+    ///
+    /// The gradient is multiplied with the fill color of the shape (and the texture, if any),
+    /// so you usually want to use [`Color32::WHITE`] as the fill color.
+    pub gradient: Option<Arc<Gradient>>,
+}
+
+impl Brush {
+    /// A brush with just a texture.
+    pub fn from_texture(fill_texture_id: TextureId, uv: Rect) -> Self {
+        Self {
+            fill_texture_id,
+            uv,
+            gradient: None,
+        }
+    }
+
+    /// A brush with just a gradient.
+    pub fn from_gradient(gradient: Gradient) -> Self {
+        Self {
+            fill_texture_id: TextureId::default(),
+            uv: Rect::ZERO,
+            gradient: Some(Arc::new(gradient)),
+        }
+    }
+
+    /// Move and scale the brush, e.g. together with the shape it fills.
+    ///
+    /// The texture coordinates are relative to the shape, so only the gradient is affected.
+    pub fn transform(&mut self, transform: TSTransform) {
+        if let Some(gradient) = &mut self.gradient {
+            Arc::make_mut(gradient).transform(transform);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The shape of a [`Gradient`].
+///
+/// All positions are in the same coordinate system as the shape being filled (usually screen points).
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GradientKind {
+    /// The color changes along the line from `start` (offset 0) to `end` (offset 1).
+    Linear { start: Pos2, end: Pos2 },
+
+    /// The color changes with the distance from `center`,
+    /// from offset 0 at the center to offset 1 at `radius`.
+    Radial { center: Pos2, radius: f32 },
+
+    /// The color changes with the angle around `center`, going clockwise (on screen)
+    /// a full turn, starting at `start_angle` (in radians, where 0 points to the right).
+    Conic { center: Pos2, start_angle: f32 },
+}
+
+/// A color gradient with any number of color stops.
+///
+/// Use it to fill a shape via [`Brush::gradient`], e.g. with [`crate::Shape::with_gradient`].
+///
+/// Colors are interpolated in gamma space, with premultiplied alpha.
+/// Outside the first and last stops, the color of the nearest stop is used.
+///
+/// ## Example:
+/// ```
+/// use epaint::{pos2, Color32, Gradient};
+///
+/// let gradient = Gradient::linear(
+///     pos2(0.0, 0.0),
+///     pos2(100.0, 0.0),
+///     vec![(0.0, Color32::RED), (0.5, Color32::YELLOW), (1.0, Color32::GREEN)],
+/// );
+/// assert_eq!(gradient.color_at(pos2(50.0, 20.0)), Color32::YELLOW);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Gradient {
+    pub kind: GradientKind,
+
+    /// Offsets (usually in the range `0..=1`) and their colors.
+    ///
+    /// Must be sorted by offset.
+    pub stops: Vec<(f32, Color32)>,
+}
+
+impl Gradient {
+    /// A linear gradient from `start` to `end`.
+    pub fn linear(start: Pos2, end: Pos2, stops: Vec<(f32, Color32)>) -> Self {
+        Self {
+            kind: GradientKind::Linear { start, end },
+            stops,
+        }
+    }
+
+    /// A radial gradient, going outwards from `center`.
+    pub fn radial(center: Pos2, radius: f32, stops: Vec<(f32, Color32)>) -> Self {
+        Self {
+            kind: GradientKind::Radial { center, radius },
+            stops,
+        }
+    }
+
+    /// A conic gradient, sweeping clockwise around `center`.
+    pub fn conic(center: Pos2, start_angle: f32, stops: Vec<(f32, Color32)>) -> Self {
+        Self {
+            kind: GradientKind::Conic {
+                center,
+                start_angle,
+            },
+            stops,
+        }
+    }
+
+    /// The gradient offset at the given position, before clamping to the stops.
+    pub fn offset_at(&self, pos: Pos2) -> f32 {
+        match self.kind {
+            GradientKind::Linear { start, end } => {
+                let dir = end - start;
+                let length_sq = dir.length_sq();
+                if length_sq > 0.0 {
+                    (pos - start).dot(dir) / length_sq
+                } else {
+                    0.0
+                }
+            }
+            GradientKind::Radial { center, radius } => {
+                if radius > 0.0 {
+                    pos.distance(center) / radius
+                } else {
+                    1.0
+                }
+            }
+            GradientKind::Conic {
+                center,
+                start_angle,
+            } => {
+                let angle = (pos - center).angle() - start_angle;
+                angle.rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU
+            }
+        }
+    }
+
+    /// The color of the gradient at the given offset.
+    pub fn color_at_offset(&self, t: f32) -> Color32 {
+        let Some(&(first_offset, first_color)) = self.stops.first() else {
+            return Color32::TRANSPARENT;
+        };
+        if t <= first_offset {
+            return first_color;
+        }
+        for window in self.stops.windows(2) {
+            let [(t0, c0), (t1, c1)] = [window[0], window[1]];
+            if t <= t1 {
+                return if t0 < t1 {
+                    c0.lerp_to_gamma(c1, (t - t0) / (t1 - t0))
+                } else {
+                    c1
+                };
+            }
+        }
+        self.stops.last().map_or(first_color, |&(_, color)| color)
+    }
+
+    /// The color of the gradient at the given position.
+    pub fn color_at(&self, pos: Pos2) -> Color32 {
+        self.color_at_offset(self.offset_at(pos))
+    }
+
+    /// Move and scale the gradient, e.g. together with the shape it fills.
+    pub fn transform(&mut self, transform: TSTransform) {
+        match &mut self.kind {
+            GradientKind::Linear { start, end } => {
+                *start = transform * *start;
+                *end = transform * *end;
+            }
+            GradientKind::Radial { center, radius } => {
+                *center = transform * *center;
+                *radius *= transform.scaling;
+            }
+            GradientKind::Conic { center, .. } => {
+                *center = transform * *center;
+            }
+        }
+    }
+
+    /// Does the color between these two positions change in a way that
+    /// linear interpolation (as done by the GPU between vertices) can't represent?
+    ///
+    /// Used by the tessellator to decide where to add more vertices.
+    pub(crate) fn needs_subdivision(&self, a: Pos2, b: Pos2) -> bool {
+        const TOLERANCE: i32 = 2; // out of 255
+
+        // Make sure we get the exact same answer for both directions of an edge:
+        let (a, b) = if (a.x, a.y) <= (b.x, b.y) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        let ca = self.color_at(a);
+        let cb = self.color_at(b);
+        [0.25, 0.5, 0.75].iter().any(|&t| {
+            let expected = ca.lerp_to_gamma(cb, t);
+            let actual = self.color_at(a + t * (b - a));
+            (0..4).any(|i| (i32::from(expected[i]) - i32::from(actual[i])).abs() > TOLERANCE)
+        })
+    }
 }
@@ -46,7 +46,7 @@ pub mod util;
 mod viewport;
 
 pub use self::{
-    brush::Brush,
+    brush::{Brush, Gradient, GradientKind},
     color::ColorMode,
     image::{ColorImage, FontImage, ImageData, ImageDelta},
     margin::Margin,
@@ -56,8 +56,9 @@ pub use self::{
     roundingf::Roundingf,
     shadow::Shadow,
     shapes::{
-        CircleShape, CubicBezierShape, EllipseShape, PaintCallback, PaintCallbackInfo, PathShape,
-        QuadraticBezierShape, RectShape, Shape, TextShape,
+        BrushedShape, CircleShape, CubicBezierShape, EllipseShape, FillRule, PaintCallback,
+        PaintCallbackInfo, PathShape, PolygonShape, QuadraticBezierShape, RectShape, Shape,
+        TextShape,
     },
     stats::PaintStats,
     stroke::{PathStroke, Stroke, StrokeKind},
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use crate::{
     color, CircleShape, Color32, ColorMode, CubicBezierShape, EllipseShape, Mesh, PathShape,
-    QuadraticBezierShape, RectShape, Shape, TextShape,
+    PolygonShape, QuadraticBezierShape, RectShape, Shape, TextShape,
 };
 
 /// Remember to handle [`Color32::PLACEHOLDER`] specially!
@@ -20,6 +20,10 @@ pub fn adjust_colors(
             }
         }
 
+        Shape::Brushed(brushed) => {
+            adjust_colors(&mut brushed.shape, adjust_color);
+        }
+
         Shape::LineSegment { stroke, points: _ } => {
             adjust_color(&mut stroke.color);
         }
@@ -30,6 +34,12 @@ pub fn adjust_colors(
             fill,
             stroke,
         })
+        | Shape::Polygon(PolygonShape {
+            contours: _,
+            fill_rule: _,
+            fill,
+            stroke,
+        })
         | Shape::QuadraticBezier(QuadraticBezierShape {
             points: _,
             closed: _,
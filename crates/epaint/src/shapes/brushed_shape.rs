@@ -0,0 +1,22 @@
+use crate::{Brush, Shape};
+
+/// A [`Shape`] whose fill is textured or a gradient, see [`Shape::with_brush`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrushedShape {
+    /// A [`Shape::Circle`], [`Shape::Ellipse`], [`Shape::Path`] or [`Shape::Polygon`].
+    ///
+    /// The brush is ignored for other shapes.
+    pub shape: Shape,
+
+    /// Multiplied with the fill color of the shape.
+    ///
+    /// Textures are mapped onto the bounding rectangle of the shape.
+    pub brush: Brush,
+}
+
+impl From<BrushedShape> for Shape {
+    #[inline(always)]
+    fn from(shape: BrushedShape) -> Self {
+        Self::Brushed(Box::new(shape))
+    }
+}
@@ -1,18 +1,22 @@
 mod bezier_shape;
+mod brushed_shape;
 mod circle_shape;
 mod ellipse_shape;
 mod paint_callback;
 mod path_shape;
+mod polygon_shape;
 mod rect_shape;
 mod shape;
 mod text_shape;
 
 pub use self::{
     bezier_shape::{CubicBezierShape, QuadraticBezierShape},
+    brushed_shape::BrushedShape,
     circle_shape::CircleShape,
     ellipse_shape::EllipseShape,
     paint_callback::{PaintCallback, PaintCallbackInfo},
     path_shape::PathShape,
+    polygon_shape::{FillRule, PolygonShape},
     rect_shape::RectShape,
     shape::Shape,
     text_shape::TextShape,
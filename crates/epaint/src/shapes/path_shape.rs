@@ -12,6 +12,8 @@ pub struct PathShape {
     pub closed: bool,
 
     /// Fill is only supported for convex polygons.
+    ///
+    /// Use [`PolygonShape`] for concave polygons.
     pub fill: Color32,
 
     /// Color and thickness of the line.
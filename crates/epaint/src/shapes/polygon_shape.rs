@@ -0,0 +1,96 @@
+use crate::*;
+
+/// Any number of closed contours, filled together according to a [`FillRule`].
+///
+/// Unlike [`PathShape`] the contours can be concave or self-intersecting,
+/// and later contours can cut holes into earlier ones.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct PolygonShape {
+    /// The closed contours, e.g. an outline followed by its holes.
+    pub contours: Vec<Vec<Pos2>>,
+
+    /// How to decide what is inside the contours.
+    pub fill_rule: FillRule,
+
+    /// Fill color.
+    pub fill: Color32,
+
+    /// Color and thickness of the outline of each contour.
+    pub stroke: PathStroke,
+}
+
+/// How to decide which parts of a [`PolygonShape`] are inside, for filling.
+///
+/// Matches the `fill-rule` property of SVG.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FillRule {
+    /// A point is inside if the contours wind around it a non-zero number of times.
+    ///
+    /// Holes must wind in the opposite direction of the outer contour.
+    #[default]
+    NonZero,
+
+    /// A point is inside if a ray from it crosses the contours an odd number of times.
+    ///
+    /// Holes can wind in any direction.
+    EvenOdd,
+}
+
+impl FillRule {
+    /// Is a point around which the contours wind `winding_number` times inside?
+    #[inline]
+    pub fn is_inside(self, winding_number: i32) -> bool {
+        match self {
+            Self::NonZero => winding_number != 0,
+            Self::EvenOdd => winding_number % 2 != 0,
+        }
+    }
+}
+
+impl PolygonShape {
+    /// A single polygon, which may be concave or self-intersecting.
+    #[inline]
+    pub fn new(points: Vec<Pos2>, fill: impl Into<Color32>, stroke: impl Into<PathStroke>) -> Self {
+        Self::multi(vec![points], FillRule::NonZero, fill, stroke)
+    }
+
+    /// Several closed contours filled together, e.g. a polygon with holes.
+    ///
+    /// Use [`FillRule::EvenOdd`] if you don't want to care about the winding order of the holes.
+    #[inline]
+    pub fn multi(
+        contours: Vec<Vec<Pos2>>,
+        fill_rule: FillRule,
+        fill: impl Into<Color32>,
+        stroke: impl Into<PathStroke>,
+    ) -> Self {
+        Self {
+            contours,
+            fill_rule,
+            fill: fill.into(),
+            stroke: stroke.into(),
+        }
+    }
+
+    /// The visual bounding rectangle (includes stroke width)
+    pub fn visual_bounding_rect(&self) -> Rect {
+        if self.fill == Color32::TRANSPARENT && self.stroke.is_empty() {
+            Rect::NOTHING
+        } else {
+            let mut rect = Rect::NOTHING;
+            for contour in &self.contours {
+                rect = rect.union(Rect::from_points(contour));
+            }
+            rect.expand(self.stroke.width / 2.0)
+        }
+    }
+}
+
+impl From<PolygonShape> for Shape {
+    #[inline(always)]
+    fn from(shape: PolygonShape) -> Self {
+        Self::Polygon(shape)
+    }
+}
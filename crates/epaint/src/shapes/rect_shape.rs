@@ -30,9 +30,9 @@ pub struct RectShape {
     /// The blur is currently implemented using a simple linear blur in sRGBA gamma space.
     pub blur_width: f32,
 
-    /// Controls texturing, if any.
+    /// Controls texturing and gradients, if any.
     ///
-    /// Since most rectangles do not have a texture, this is optional and in an `Arc`,
+    /// Since most rectangles do not have a texture or gradient, this is optional and in an `Arc`,
     /// so that [`RectShape`] is kept small..
     pub brush: Option<Arc<Brush>>,
 }
@@ -99,10 +99,16 @@ impl RectShape {
     /// Set the texture to use when painting this rectangle, if any.
     #[inline]
     pub fn with_texture(mut self, fill_texture_id: TextureId, uv: Rect) -> Self {
-        self.brush = Some(Arc::new(Brush {
-            fill_texture_id,
-            uv,
-        }));
+        self.brush = Some(Arc::new(Brush::from_texture(fill_texture_id, uv)));
+        self
+    }
+
+    /// Fill this rectangle with a gradient.
+    ///
+    /// The gradient is multiplied with [`Self::fill`], so you usually want that to be [`Color32::WHITE`].
+    #[inline]
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.brush = Some(Arc::new(Brush::from_gradient(gradient)));
         self
     }
 
@@ -7,12 +7,12 @@ use emath::{pos2, Align2, Pos2, Rangef, Rect, TSTransform, Vec2};
 use crate::{
     stroke::PathStroke,
     text::{FontId, Fonts, Galley},
-    Color32, Mesh, Rounding, Stroke, TextureId,
+    Brush, Color32, FillRule, Gradient, Mesh, Rounding, Stroke, TextureId,
 };
 
 use super::{
-    CircleShape, CubicBezierShape, EllipseShape, PaintCallback, PathShape, QuadraticBezierShape,
-    RectShape, TextShape,
+    BrushedShape, CircleShape, CubicBezierShape, EllipseShape, PaintCallback, PathShape,
+    PolygonShape, QuadraticBezierShape, RectShape, TextShape,
 };
 
 /// A paint primitive such as a circle or a piece of text.
@@ -45,6 +45,9 @@ pub enum Shape {
     /// The path can have a stroke and/or fill (if closed).
     Path(PathShape),
 
+    /// Any number of closed contours, which may be concave, self-intersecting or have holes.
+    Polygon(PolygonShape),
+
     /// Rectangle with optional outline and fill.
     Rect(RectShape),
 
@@ -68,6 +71,11 @@ pub enum Shape {
 
     /// Backend-specific painting.
     Callback(PaintCallback),
+
+    /// A shape filled with a texture or gradient, see [`Self::with_brush`].
+    ///
+    /// Boxed to minimize the size of [`Shape`].
+    Brushed(Box<BrushedShape>),
 }
 
 #[test]
@@ -255,6 +263,31 @@ impl Shape {
         Self::Path(PathShape::convex_polygon(points, fill, stroke))
     }
 
+    /// A polygon that may be concave or self-intersecting, filled using [`FillRule::NonZero`].
+    ///
+    /// Slower to tessellate than [`Self::convex_polygon`].
+    #[inline]
+    pub fn polygon(
+        points: Vec<Pos2>,
+        fill: impl Into<Color32>,
+        stroke: impl Into<PathStroke>,
+    ) -> Self {
+        Self::Polygon(PolygonShape::new(points, fill, stroke))
+    }
+
+    /// Several closed contours filled together, e.g. a polygon with holes.
+    ///
+    /// See [`PolygonShape::multi`].
+    #[inline]
+    pub fn multi_polygon(
+        contours: Vec<Vec<Pos2>>,
+        fill_rule: FillRule,
+        fill: impl Into<Color32>,
+        stroke: impl Into<PathStroke>,
+    ) -> Self {
+        Self::Polygon(PolygonShape::multi(contours, fill_rule, fill, stroke))
+    }
+
     #[inline]
     pub fn circle_filled(center: Pos2, radius: f32, fill_color: impl Into<Color32>) -> Self {
         Self::Circle(CircleShape::filled(center, radius, fill_color))
@@ -353,6 +386,42 @@ impl Shape {
         Self::mesh(mesh)
     }
 
+    /// Fill the shape with a texture and/or gradient.
+    ///
+    /// This works for [`Self::Rect`], [`Self::Circle`], [`Self::Ellipse`], [`Self::Path`],
+    /// [`Self::Polygon`] and the shapes in a [`Self::Vec`]. Other shapes are returned as they are.
+    ///
+    /// The brush is multiplied with the fill color, so you usually want that to be [`Color32::WHITE`].
+    pub fn with_brush(self, brush: Brush) -> Self {
+        match self {
+            Self::Vec(shapes) => Self::Vec(
+                shapes
+                    .into_iter()
+                    .map(|shape| shape.with_brush(brush.clone()))
+                    .collect(),
+            ),
+            Self::Rect(rect_shape) => Self::Rect(RectShape {
+                brush: Some(Arc::new(brush)),
+                ..rect_shape
+            }),
+            Self::Circle(_) | Self::Ellipse(_) | Self::Path(_) | Self::Polygon(_) => {
+                BrushedShape { shape: self, brush }.into()
+            }
+            Self::Brushed(brushed) => BrushedShape {
+                shape: brushed.shape,
+                brush,
+            }
+            .into(),
+            shape => shape,
+        }
+    }
+
+    /// Fill the shape with a gradient, see [`Self::with_brush`].
+    #[inline]
+    pub fn with_gradient(self, gradient: Gradient) -> Self {
+        self.with_brush(Brush::from_gradient(gradient))
+    }
+
     /// The visual bounding rectangle (includes stroke widths)
     pub fn visual_bounding_rect(&self) -> Rect {
         match self {
@@ -374,12 +443,14 @@ impl Shape {
                 }
             }
             Self::Path(path_shape) => path_shape.visual_bounding_rect(),
+            Self::Polygon(polygon_shape) => polygon_shape.visual_bounding_rect(),
             Self::Rect(rect_shape) => rect_shape.visual_bounding_rect(),
             Self::Text(text_shape) => text_shape.visual_bounding_rect(),
             Self::Mesh(mesh) => mesh.calc_bounds(),
             Self::QuadraticBezier(bezier) => bezier.visual_bounding_rect(),
             Self::CubicBezier(bezier) => bezier.visual_bounding_rect(),
             Self::Callback(custom) => custom.rect,
+            Self::Brushed(brushed) => brushed.shape.visual_bounding_rect(),
         }
     }
 }
@@ -392,6 +463,8 @@ impl Shape {
             mesh.texture_id
         } else if let Self::Rect(rect_shape) = self {
             rect_shape.fill_texture_id()
+        } else if let Self::Brushed(brushed) = self {
+            brushed.brush.fill_texture_id
         } else {
             crate::TextureId::default()
         }
@@ -447,10 +520,19 @@ impl Shape {
                 }
                 path_shape.stroke.width *= transform.scaling;
             }
+            Self::Polygon(polygon_shape) => {
+                for p in polygon_shape.contours.iter_mut().flatten() {
+                    *p = transform * *p;
+                }
+                polygon_shape.stroke.width *= transform.scaling;
+            }
             Self::Rect(rect_shape) => {
                 rect_shape.rect = transform * rect_shape.rect;
                 rect_shape.stroke.width *= transform.scaling;
                 rect_shape.rounding *= transform.scaling;
+                if let Some(brush) = &mut rect_shape.brush {
+                    Arc::make_mut(brush).transform(transform);
+                }
             }
             Self::Text(text_shape) => {
                 text_shape.pos = transform * text_shape.pos;
@@ -485,6 +567,10 @@ impl Shape {
             Self::Callback(shape) => {
                 shape.rect = transform * shape.rect;
             }
+            Self::Brushed(brushed) => {
+                brushed.shape.transform(transform);
+                brushed.brush.transform(transform);
+            }
         }
     }
 }
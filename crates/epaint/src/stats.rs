@@ -209,6 +209,14 @@ impl PaintStats {
             Shape::Path(path_shape) => {
                 self.shape_path += AllocInfo::from_slice(&path_shape.points);
             }
+            Shape::Brushed(brushed) => {
+                self.add(&brushed.shape);
+            }
+            Shape::Polygon(polygon_shape) => {
+                for contour in &polygon_shape.contours {
+                    self.shape_path += AllocInfo::from_slice(contour);
+                }
+            }
             Shape::Text(text_shape) => {
                 self.shape_text += AllocInfo::from_galley(&text_shape.galley);
 
@@ -7,9 +7,10 @@
 
 use crate::texture_atlas::PreparedDisc;
 use crate::{
-    color, emath, stroke, CircleShape, ClippedPrimitive, ClippedShape, Color32, CubicBezierShape,
-    EllipseShape, Mesh, PathShape, Primitive, QuadraticBezierShape, RectShape, Rounding, Shape,
-    Stroke, TextShape, TextureId, Vertex, WHITE_UV,
+    color, emath, stroke, Brush, BrushedShape, CircleShape, ClippedPrimitive, ClippedShape,
+    Color32, CubicBezierShape, EllipseShape, FillRule, Gradient, Mesh, PathShape, PolygonShape,
+    Primitive, QuadraticBezierShape, RectShape, Rounding, Shape, Stroke, TextShape, TextureId,
+    Vertex, WHITE_UV,
 };
 use emath::{pos2, remap, vec2, GuiRounding as _, NumExt, Pos2, Rect, Rot2, Vec2};
 
@@ -521,6 +522,49 @@ impl Path {
     ) {
         fill_closed_path_with_uv(feathering, &mut self.0, color, texture_id, uv_from_pos, out);
     }
+
+    /// Like [`Self::fill`] but with a [`Brush`] (texture and/or gradient).
+    ///
+    /// The texture coordinates of the brush are mapped onto `rect`,
+    /// which is usually the bounding rectangle of the shape.
+    pub fn fill_with_brush(
+        &mut self,
+        feathering: f32,
+        color: Color32,
+        brush: &Brush,
+        rect: Rect,
+        stroke: &PathStroke,
+        out: &mut Mesh,
+    ) {
+        let uv = brush.uv;
+        let uv_from_pos = |p: Pos2| {
+            pos2(
+                remap(p.x, rect.x_range(), uv.x_range()),
+                remap(p.y, rect.y_range(), uv.y_range()),
+            )
+        };
+        if let Some(gradient) = &brush.gradient {
+            fill_closed_path_with_gradient(
+                feathering,
+                &mut self.0,
+                color,
+                gradient,
+                brush.fill_texture_id,
+                uv_from_pos,
+                stroke,
+                out,
+            );
+        } else {
+            fill_closed_path_with_uv(
+                feathering,
+                &mut self.0,
+                color,
+                brush.fill_texture_id,
+                uv_from_pos,
+                out,
+            );
+        }
+    }
 }
 
 pub mod path {
@@ -914,6 +958,662 @@ fn fill_closed_path_with_uv(
     }
 }
 
+/// Like [`fill_closed_path_with_uv`] but with the color given by a [`Gradient`],
+/// multiplied by `color`.
+///
+/// The GPU can only interpolate colors linearly between vertices,
+/// so we add extra vertices along the edges and in the interior wherever that isn't good enough.
+#[allow(clippy::too_many_arguments)]
+fn fill_closed_path_with_gradient(
+    feathering: f32,
+    path: &mut [PathPoint],
+    color: Color32,
+    gradient: &Gradient,
+    texture_id: TextureId,
+    uv_from_pos: impl Fn(Pos2) -> Pos2,
+    stroke: &PathStroke,
+    out: &mut Mesh,
+) {
+    if color == Color32::TRANSPARENT || path.len() < 3 {
+        return;
+    }
+
+    if out.is_empty() {
+        out.texture_id = texture_id;
+    } else {
+        assert_eq!(
+            out.texture_id, texture_id,
+            "Mixing different `texture_id` in the same "
+        );
+    }
+
+    if cw_signed_area(path) < 0.0 {
+        // Wrong winding order - fix:
+        path.reverse();
+        for point in &mut *path {
+            point.normal = -point.normal;
+        }
+    }
+
+    let bbox = Rect::from_points(&path.iter().map(|p| p.pos).collect::<Vec<Pos2>>());
+
+    // Don't subdivide forever, e.g. across the discontinuity of a conic gradient:
+    let min_len = (bbox.size().max_elem() / 1024.0).max(0.5);
+
+    let get_stroke_color: Box<dyn Fn(Pos2) -> Color32> = match &stroke.color {
+        ColorMode::Solid(col) => Box::new(|_pos: Pos2| *col),
+        ColorMode::UV(fun) => {
+            let bbox = bbox.expand(feathering);
+            Box::new(move |pos: Pos2| fun(bbox, pos))
+        }
+    };
+    let fill_vertex = |pos: Pos2| Vertex {
+        pos,
+        uv: uv_from_pos(pos),
+        color: color * gradient.color_at(pos),
+    };
+
+    // Add points along the edges where needed:
+    let mut boundary: Vec<PathPoint> = Vec::with_capacity(path.len());
+    for (i, &p0) in path.iter().enumerate() {
+        let p1 = path[(i + 1) % path.len()];
+        boundary.push(p0);
+
+        let mut normal = (p1.pos - p0.pos).normalized().rot90();
+        if normal.dot(p0.normal + p1.normal) < 0.0 {
+            normal = -normal;
+        }
+        subdivide_gradient_edge(gradient, min_len, p0.pos, p1.pos, &mut |pos| {
+            boundary.push(PathPoint { pos, normal });
+        });
+    }
+
+    let inner_pos = |p: &PathPoint| p.pos - 0.5 * feathering * p.normal;
+
+    // Neighboring triangles share their vertices:
+    let mut vertex_at: ahash::HashMap<(u32, u32), u32> = Default::default();
+    let key = |pos: Pos2| (pos.x.to_bits(), pos.y.to_bits());
+
+    let n = boundary.len();
+    if feathering > 0.0 {
+        out.reserve_triangles(2 * n);
+        out.reserve_vertices(2 * n);
+        let idx_inner = out.vertices.len() as u32;
+        let idx_outer = idx_inner + 1;
+
+        let mut i0 = n as u32 - 1;
+        for i1 in 0..n as u32 {
+            let p1 = &boundary[i1 as usize];
+            let pos_inner = inner_pos(p1);
+            let pos_outer = p1.pos + 0.5 * feathering * p1.normal;
+
+            vertex_at.insert(key(pos_inner), idx_inner + i1 * 2);
+            out.vertices.push(fill_vertex(pos_inner));
+            out.vertices.push(Vertex {
+                pos: pos_outer,
+                uv: uv_from_pos(pos_outer),
+                color: get_stroke_color(pos_outer),
+            });
+            out.add_triangle(idx_inner + i1 * 2, idx_inner + i0 * 2, idx_outer + 2 * i0);
+            out.add_triangle(idx_outer + i0 * 2, idx_outer + i1 * 2, idx_inner + 2 * i1);
+            i0 = i1;
+        }
+    }
+
+    // The fill, as a triangle fan with each triangle subdivided as needed.
+    // The edges along the boundary must not be split, or we would get T-junctions with the feathering.
+    let p0 = inner_pos(&boundary[0]);
+    for i in 2..n {
+        let p1 = inner_pos(&boundary[i - 1]);
+        let p2 = inner_pos(&boundary[i]);
+        let splittable = [i == 2, false, i == n - 1].map(|is_boundary| !is_boundary);
+        subdivide_gradient_triangle(gradient, min_len, [p0, p1, p2], splittable, 0, &mut |tri| {
+            let [a, b, c] = tri.map(|pos| {
+                *vertex_at.entry(key(pos)).or_insert_with(|| {
+                    out.vertices.push(fill_vertex(pos));
+                    out.vertices.len() as u32 - 1
+                })
+            });
+            out.add_triangle(a, b, c);
+        });
+    }
+}
+
+/// Should the edge between these two points be split to represent the gradient well?
+///
+/// This only depends on the end points, so that neighboring triangles agree.
+fn should_split_gradient_edge(gradient: &Gradient, min_len: f32, a: Pos2, b: Pos2) -> bool {
+    a.distance(b) > min_len && gradient.needs_subdivision(a, b)
+}
+
+/// The same for `midpoint(a, b)` and `midpoint(b, a)`, so that neighboring triangles agree on it.
+fn midpoint(a: Pos2, b: Pos2) -> Pos2 {
+    pos2(0.5 * (a.x + b.x), 0.5 * (a.y + b.y))
+}
+
+/// Calls `add_point` for each point that needs to be inserted between `a` and `b`, in order.
+fn subdivide_gradient_edge(
+    gradient: &Gradient,
+    min_len: f32,
+    a: Pos2,
+    b: Pos2,
+    add_point: &mut dyn FnMut(Pos2),
+) {
+    if should_split_gradient_edge(gradient, min_len, a, b) {
+        let mid = midpoint(a, b);
+        subdivide_gradient_edge(gradient, min_len, a, mid, add_point);
+        add_point(mid);
+        subdivide_gradient_edge(gradient, min_len, mid, b, add_point);
+    }
+}
+
+/// Recursively split a triangle until the gradient can be linearly interpolated over it.
+///
+/// `splittable[i]` is for the edge from `tri[i]` to `tri[(i + 1) % 3]`.
+fn subdivide_gradient_triangle(
+    gradient: &Gradient,
+    min_len: f32,
+    tri: [Pos2; 3],
+    splittable: [bool; 3],
+    depth: usize,
+    add_triangle: &mut dyn FnMut([Pos2; 3]),
+) {
+    const MAX_DEPTH: usize = 16;
+
+    let split = [0, 1, 2].map(|i| {
+        splittable[i]
+            && depth < MAX_DEPTH
+            && should_split_gradient_edge(gradient, min_len, tri[i], tri[(i + 1) % 3])
+    });
+
+    let mid = |i: usize| midpoint(tri[i], tri[(i + 1) % 3]);
+    let mut recurse = |tri: [Pos2; 3], splittable: [bool; 3]| {
+        subdivide_gradient_triangle(gradient, min_len, tri, splittable, depth + 1, add_triangle);
+    };
+
+    match split.iter().filter(|&&s| s).count() {
+        0 => add_triangle(tri),
+        1 => {
+            // Rotate so that the edge to split is the first one:
+            let i = split.iter().position(|&s| s).unwrap_or_default();
+            let [a, b, c] = [tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]];
+            let m = mid(i);
+            recurse([a, m, c], [true, true, splittable[(i + 2) % 3]]);
+            recurse([m, b, c], [true, splittable[(i + 1) % 3], true]);
+        }
+        2 => {
+            // Rotate so that the edge not to split is the last one:
+            let i = (split.iter().position(|&s| !s).unwrap_or_default() + 1) % 3;
+            let [a, b, c] = [tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]];
+            let [m_ab, m_bc] = [mid(i), mid((i + 1) % 3)];
+            recurse([m_ab, b, m_bc], [true, true, true]);
+            recurse([a, m_ab, m_bc], [true, true, true]);
+            recurse([a, m_bc, c], [true, true, splittable[(i + 2) % 3]]);
+        }
+        _ => {
+            let [a, b, c] = tri;
+            let [m_ab, m_bc, m_ca] = [mid(0), mid(1), mid(2)];
+            recurse([a, m_ab, m_ca], [true, true, true]);
+            recurse([m_ab, b, m_bc], [true, true, true]);
+            recurse([m_ca, m_bc, c], [true, true, true]);
+            recurse([m_ab, m_bc, m_ca], [true, true, true]);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Filling arbitrary polygons:
+
+/// A non-horizontal edge of a polygon, used by [`fill_contours`].
+#[derive(Clone, Copy, Debug)]
+struct FillEdge {
+    /// The end with the smallest `y`.
+    top: Pos2,
+
+    /// The end with the largest `y`.
+    bottom: Pos2,
+
+    /// +1 if the contour goes downwards along this edge, -1 if it goes upwards.
+    winding: i32,
+}
+
+impl FillEdge {
+    fn x_at(&self, y: f32) -> f32 {
+        if y <= self.top.y {
+            self.top.x
+        } else if self.bottom.y <= y {
+            self.bottom.x
+        } else {
+            let t = (y - self.top.y) / (self.bottom.y - self.top.y);
+            emath::lerp(self.top.x..=self.bottom.x, t)
+        }
+    }
+
+    /// Normal pointing towards larger `x`.
+    fn right_normal(&self) -> Vec2 {
+        let dir = (self.bottom - self.top).normalized();
+        vec2(dir.y, -dir.x)
+    }
+}
+
+/// Fill any number of closed contours, which may be concave, self-intersecting and/or contain holes.
+///
+/// `rect` is the bounding rectangle of the contours, used for mapping the texture of the `brush`.
+///
+/// Like [`fill_closed_path`], the outline is feathered by a ring of vertices
+/// half the `feathering` inside and outside of it.
+/// The feathering fades out to the color of the `stroke`, or to transparent when there is a `brush`.
+#[allow(clippy::too_many_arguments)]
+fn fill_contours(
+    feathering: f32,
+    contours: &[&[Pos2]],
+    fill_rule: FillRule,
+    color: Color32,
+    brush: Option<&Brush>,
+    rect: Rect,
+    stroke: &PathStroke,
+    out: &mut Mesh,
+) {
+    if color == Color32::TRANSPARENT {
+        return;
+    }
+
+    let texture_id = brush.map_or(TextureId::default(), |brush| brush.fill_texture_id);
+    if out.is_empty() {
+        out.texture_id = texture_id;
+    } else {
+        assert_eq!(
+            out.texture_id, texture_id,
+            "Mixing different `texture_id` in the same "
+        );
+    }
+
+    let uv = brush.map_or(Rect::from_min_max(WHITE_UV, WHITE_UV), |brush| brush.uv);
+    let uv_from_pos = |p: Pos2| {
+        pos2(
+            remap(p.x, rect.x_range(), uv.x_range()),
+            remap(p.y, rect.y_range(), uv.y_range()),
+        )
+    };
+    let gradient = brush.and_then(|brush| brush.gradient.as_ref());
+
+    let outer_color = match (&stroke.color, brush) {
+        (ColorMode::Solid(stroke_color), None) => *stroke_color,
+        _ => Color32::TRANSPARENT,
+    };
+
+    let inner_vertex = |pos: Pos2| Vertex {
+        pos,
+        uv: uv_from_pos(pos),
+        color: gradient.map_or(color, |gradient| color * gradient.color_at(pos)),
+    };
+    let outer_vertex = |pos: Pos2| Vertex {
+        pos,
+        uv: uv_from_pos(pos),
+        color: outer_color,
+    };
+
+    // Don't subdivide forever, e.g. across the discontinuity of a conic gradient:
+    let min_len = (rect.size().max_elem() / 1024.0).max(0.5);
+
+    let add_quad = |out: &mut Mesh, [a, b, c, d]: [Pos2; 4]| {
+        if let Some(gradient) = gradient {
+            for tri in [[a, b, c], [a, c, d]] {
+                subdivide_gradient_triangle(gradient, min_len, tri, [true; 3], 0, &mut |tri| {
+                    let idx = out.vertices.len() as u32;
+                    out.vertices.extend(tri.map(inner_vertex));
+                    out.add_triangle(idx, idx + 1, idx + 2);
+                });
+            }
+        } else {
+            let idx = out.vertices.len() as u32;
+            out.vertices.extend([a, b, c, d].map(inner_vertex));
+            out.add_triangle(idx, idx + 1, idx + 2);
+            out.add_triangle(idx, idx + 2, idx + 3);
+        }
+    };
+
+    if feathering <= 0.0 {
+        let (trapezoids, _) = fill_trapezoids(contours, fill_rule);
+        for (top, bottom) in trapezoids {
+            add_quad(out, [top.min, top.max, bottom.max, bottom.min]);
+        }
+        return;
+    }
+
+    // Feather the outline of what is inside, and fill what is inside the feathering:
+    let (_, boundary) = fill_trapezoids(contours, fill_rule);
+    let mut path = Path::default();
+    let mut inner_contours = vec![];
+    for mut outline in outline_loops(&boundary) {
+        if let Some(gradient) = gradient {
+            let mut subdivided = Vec::with_capacity(outline.len());
+            for (i, &a) in outline.iter().enumerate() {
+                let b = outline[(i + 1) % outline.len()];
+                subdivided.push(a);
+                subdivide_gradient_edge(gradient, min_len, a, b, &mut |p| subdivided.push(p));
+            }
+            outline = subdivided;
+        }
+
+        path.clear();
+        path.add_line_loop(&outline);
+
+        let n = path.0.len() as u32;
+        out.reserve_triangles(2 * n as usize);
+        out.reserve_vertices(2 * n as usize);
+        let idx_inner = out.vertices.len() as u32;
+        let idx_outer = idx_inner + 1;
+
+        let mut inner_contour = Vec::with_capacity(outline.len());
+        let mut i0 = n - 1;
+        for i1 in 0..n {
+            let p1 = &path.0[i1 as usize];
+            let dm = 0.5 * feathering * p1.normal;
+            inner_contour.push(p1.pos - dm);
+            out.vertices.push(inner_vertex(p1.pos - dm));
+            out.vertices.push(outer_vertex(p1.pos + dm));
+            out.add_triangle(idx_inner + i1 * 2, idx_inner + i0 * 2, idx_outer + 2 * i0);
+            out.add_triangle(idx_outer + i0 * 2, idx_outer + i1 * 2, idx_inner + 2 * i1);
+            i0 = i1;
+        }
+        inner_contours.push(inner_contour);
+    }
+
+    // The outlines of holes wind the other way around, so they cancel out the outline around them:
+    let inner_contours: Vec<&[Pos2]> = inner_contours.iter().map(Vec::as_slice).collect();
+    let (trapezoids, _) = fill_trapezoids(&inner_contours, FillRule::NonZero);
+    for (top, bottom) in trapezoids {
+        add_quad(out, [top.min, top.max, bottom.max, bottom.min]);
+    }
+}
+
+/// Link the pieces of the outline from [`fill_trapezoids`] into closed loops.
+///
+/// The loops are oriented so that [`Path::add_line_loop`] gives normals pointing out of the fill.
+/// Points in the middle of straight lines are left out.
+fn outline_loops(boundary: &[(Pos2, Pos2, Vec2)]) -> Vec<Vec<Pos2>> {
+    // Where the ends of two pieces don't line up exactly because of rounding errors:
+    const EPSILON: f32 = 1e-2;
+
+    let key = |p: Pos2| (p.x.to_bits(), p.y.to_bits());
+
+    let segments: Vec<(Pos2, Pos2)> = boundary
+        .iter()
+        .filter(|(a, b, _)| a != b)
+        .map(|&(a, b, normal)| {
+            if 0.0 <= (b - a).rot90().dot(normal) {
+                (a, b)
+            } else {
+                (b, a)
+            }
+        })
+        .collect();
+
+    let mut starting_at: ahash::HashMap<(u32, u32), Vec<usize>> = Default::default();
+    for (i, (a, _)) in segments.iter().enumerate() {
+        starting_at.entry(key(*a)).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut loops = vec![];
+    for first in 0..segments.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+
+        let start = segments[first].0;
+        let mut points = vec![start];
+        let mut end = segments[first].1;
+        while start.distance(end) > EPSILON {
+            let next = starting_at
+                .get(&key(end))
+                .and_then(|candidates| candidates.iter().copied().find(|&i| !used[i]))
+                .or_else(|| {
+                    (0..segments.len())
+                        .find(|&i| !used[i] && segments[i].0.distance(end) <= EPSILON)
+                });
+            let Some(next) = next else {
+                break; // Should not happen, but close the loop anyway.
+            };
+            used[next] = true;
+
+            push_outline_point(&mut points, end);
+            end = segments[next].1;
+        }
+
+        // Where the loop closes may be in the middle of a straight line too:
+        push_outline_point(&mut points, start);
+        points.pop();
+        if let [first, second, .., last] = points.as_slice() {
+            if is_straight(*last, *first, *second) {
+                points.remove(0);
+            }
+        }
+        if 3 <= points.len() {
+            loops.push(points);
+        }
+    }
+    loops
+}
+
+/// Add a point to an outline, replacing the last point if it is in the middle of a straight line.
+fn push_outline_point(points: &mut Vec<Pos2>, p: Pos2) {
+    if let [.., a, b] = points.as_slice() {
+        if is_straight(*a, *b, p) {
+            points.pop();
+        }
+    }
+    points.push(p);
+}
+
+/// Is `b` in the middle of a straight line from `a` to `c`?
+fn is_straight(a: Pos2, b: Pos2, c: Pos2) -> bool {
+    let (ab, bc) = (b - a, c - b);
+    let cross = ab.x * bc.y - ab.y * bc.x;
+    ab.dot(bc) > 0.0 && cross.abs() <= 1e-4 * ab.length() * bc.length()
+}
+
+/// Split the inside of the contours into trapezoids with horizontal top and bottom sides.
+///
+/// The plane is cut into horizontal slabs at every vertex and every intersection of two edges,
+/// so that within a slab the edges never cross and can be sorted left to right.
+/// Walking the edges of a slab from left to right while summing their winding
+/// tells us which spans between them are inside.
+///
+/// Returns the top and bottom sides of each trapezoid,
+/// and each piece of the outline together with the normal pointing out of the fill.
+#[allow(clippy::type_complexity)]
+fn fill_trapezoids(
+    contours: &[&[Pos2]],
+    fill_rule: FillRule,
+) -> (Vec<(Span, Span)>, Vec<(Pos2, Pos2, Vec2)>) {
+    let mut trapezoids = vec![];
+    let mut boundary = vec![];
+
+    let mut edges = vec![];
+    for contour in contours {
+        if contour.len() < 3 {
+            continue;
+        }
+        for (i, &a) in contour.iter().enumerate() {
+            let b = contour[(i + 1) % contour.len()];
+            if !a.is_finite() || !b.is_finite() || a.y == b.y {
+                continue; // Horizontal edges don't change the winding number of a slab
+            }
+            edges.push(if a.y < b.y {
+                FillEdge {
+                    top: a,
+                    bottom: b,
+                    winding: 1,
+                }
+            } else {
+                FillEdge {
+                    top: b,
+                    bottom: a,
+                    winding: -1,
+                }
+            });
+        }
+    }
+    if edges.is_empty() {
+        return (trapezoids, boundary);
+    }
+
+    edges.sort_by(|a, b| a.top.y.total_cmp(&b.top.y));
+
+    let mut ys: Vec<f32> = edges.iter().flat_map(|e| [e.top.y, e.bottom.y]).collect();
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+
+    let mut active: Vec<FillEdge> = vec![];
+    let mut next_edge = 0;
+
+    // The inside spans along the bottom of the previous slab:
+    let mut prev_spans: Vec<Span> = vec![];
+    let mut top_spans: Vec<Span> = vec![];
+    let mut bottom_spans: Vec<Span> = vec![];
+
+    for window in ys.windows(2) {
+        let [y_min, y_max] = [window[0], window[1]];
+
+        active.retain(|edge| y_min < edge.bottom.y);
+        while next_edge < edges.len() && edges[next_edge].top.y <= y_min {
+            active.push(edges[next_edge]);
+            next_edge += 1;
+        }
+
+        let mut y0 = y_min;
+        while y0 < y_max {
+            // Find where the first two edges cross, if anywhere:
+            active.sort_by(|a, b| {
+                a.x_at(y0)
+                    .total_cmp(&b.x_at(y0))
+                    .then_with(|| a.x_at(y_max).total_cmp(&b.x_at(y_max)))
+            });
+            let mut y1 = y_max;
+            for pair in active.windows(2) {
+                let dx_top = pair[1].x_at(y0) - pair[0].x_at(y0);
+                let dx_bottom = pair[1].x_at(y_max) - pair[0].x_at(y_max);
+                if dx_bottom < 0.0 {
+                    let t = dx_top / (dx_top - dx_bottom);
+                    y1 = y1.min(emath::lerp(y0..=y_max, t));
+                }
+            }
+            if y1 <= y0 {
+                y1 = y_max; // Rounding errors
+            }
+
+            let y_mid = 0.5 * (y0 + y1);
+            active.sort_by(|a, b| a.x_at(y_mid).total_cmp(&b.x_at(y_mid)));
+
+            top_spans.clear();
+            bottom_spans.clear();
+            let mut winding = 0;
+            let mut span_start = None;
+            for edge in &active {
+                let was_inside = fill_rule.is_inside(winding);
+                winding += edge.winding;
+                let is_inside = fill_rule.is_inside(winding);
+                if was_inside == is_inside {
+                    continue;
+                }
+
+                let (top, bottom) = (pos2(edge.x_at(y0), y0), pos2(edge.x_at(y1), y1));
+                let normal = if is_inside {
+                    -edge.right_normal()
+                } else {
+                    edge.right_normal()
+                };
+                boundary.push((top, bottom, normal));
+
+                if let Some((start_top, start_bottom)) = span_start.take() {
+                    let top = Span::new(start_top, top);
+                    let bottom = Span::new(start_bottom, bottom);
+                    trapezoids.push((top, bottom));
+                    top_spans.push(top);
+                    bottom_spans.push(bottom);
+                } else {
+                    span_start = Some((top, bottom));
+                }
+            }
+
+            // Where the inside of this slab and the one above don't line up there is a horizontal boundary:
+            for span in span_difference(&prev_spans, &top_spans) {
+                boundary.push((span.min, span.max, Vec2::DOWN));
+            }
+            for span in span_difference(&top_spans, &prev_spans) {
+                boundary.push((span.min, span.max, Vec2::UP));
+            }
+
+            std::mem::swap(&mut prev_spans, &mut bottom_spans);
+            y0 = y1;
+        }
+    }
+
+    for span in prev_spans {
+        boundary.push((span.min, span.max, Vec2::DOWN));
+    }
+
+    (trapezoids, boundary)
+}
+
+/// A horizontal line segment, from `min` to `max`.
+#[derive(Clone, Copy, Debug)]
+struct Span {
+    min: Pos2,
+    max: Pos2,
+}
+
+impl Span {
+    fn new(a: Pos2, b: Pos2) -> Self {
+        if a.x <= b.x {
+            Self { min: a, max: b }
+        } else {
+            Self { min: b, max: a }
+        }
+    }
+}
+
+/// The parts of the sorted spans `a` that are not covered by the sorted spans `b`.
+///
+/// All spans are assumed to be at the same height.
+fn span_difference(a: &[Span], b: &[Span]) -> Vec<Span> {
+    // Ignore slivers from rounding errors:
+    const EPSILON: f32 = 1e-3;
+
+    let mut result = vec![];
+    let mut b = b.iter().peekable();
+    for span in a {
+        let mut x = span.min.x;
+        while let Some(other) = b.peek() {
+            if other.max.x <= x {
+                b.next();
+                continue;
+            }
+            if span.max.x <= other.min.x {
+                break;
+            }
+            if x + EPSILON < other.min.x {
+                result.push(Span::new(
+                    pos2(x, span.min.y),
+                    pos2(other.min.x, span.min.y),
+                ));
+            }
+            x = other.max.x;
+            if span.max.x <= x {
+                break;
+            }
+            b.next();
+        }
+        if x + EPSILON < span.max.x {
+            result.push(Span::new(pos2(x, span.min.y), span.max));
+        }
+    }
+    result
+}
+
 /// Translate a point along their normals according to the stroke kind.
 #[inline(always)]
 fn translate_stroke_point(p: &mut PathPoint, stroke: &PathStroke) {
@@ -1228,6 +1928,16 @@ fn mul_color(color: Color32, factor: f32) -> Color32 {
     color.gamma_multiply(factor)
 }
 
+/// The last of the meshes, if it has this texture, else a new mesh with it.
+fn mesh_with_texture(meshes: &mut Vec<Mesh>, texture_id: TextureId) -> &mut Mesh {
+    match meshes.last_mut() {
+        Some(mesh) if mesh.texture_id == texture_id => {}
+        Some(mesh) if mesh.is_empty() => mesh.texture_id = texture_id,
+        _ => meshes.push(Mesh::with_texture(texture_id)),
+    }
+    meshes.last_mut().expect("There is at least one mesh")
+}
+
 // ----------------------------------------------------------------------------
 
 /// Converts [`Shape`]s into triangles ([`Mesh`]).
@@ -1239,7 +1949,9 @@ fn mul_color(color: Color32, factor: f32) -> Color32 {
 pub struct Tessellator {
     pixels_per_point: f32,
     options: TessellationOptions,
-    font_tex_size: [usize; 2],
+
+    /// Texture and size of each page of the font atlas, see [`Self::set_font_pages`].
+    font_pages: Vec<Option<(TextureId, [usize; 2])>>,
 
     /// See [`crate::TextureAtlas::prepared_discs`].
     prepared_discs: Vec<PreparedDisc>,
@@ -1252,6 +1964,7 @@ pub struct Tessellator {
 
     scratchpad_points: Vec<Pos2>,
     scratchpad_path: Path,
+    scratchpad_meshes: Vec<Mesh>,
 }
 
 impl Tessellator {
@@ -1277,15 +1990,25 @@ impl Tessellator {
         Self {
             pixels_per_point,
             options,
-            font_tex_size,
+            font_pages: vec![Some((TextureId::default(), font_tex_size))],
             prepared_discs,
             feathering,
             clip_rect: Rect::EVERYTHING,
             scratchpad_points: Default::default(),
             scratchpad_path: Default::default(),
+            scratchpad_meshes: Default::default(),
         }
     }
 
+    /// Set the texture and size of each page of the font atlas.
+    ///
+    /// Use what [`crate::TextureAtlas::texture_pages`] returns.
+    /// By default there is only one page, of the size given to [`Self::new`].
+    /// Glyphs on pages that are `None` are not painted.
+    pub fn set_font_pages(&mut self, font_pages: Vec<Option<(TextureId, [usize; 2])>>) {
+        self.font_pages = font_pages;
+    }
+
     /// Set the [`Rect`] to use for culling.
     pub fn set_clip_rect(&mut self, clip_rect: Rect) {
         self.clip_rect = clip_rect;
@@ -1318,38 +2041,74 @@ impl Tessellator {
             return;
         }
 
-        let start_new_mesh = match out_primitives.last() {
-            None => true,
-            Some(output_clipped_primitive) => {
-                output_clipped_primitive.clip_rect != clip_rect
-                    || match &output_clipped_primitive.primitive {
-                        Primitive::Mesh(output_mesh) => {
-                            output_mesh.texture_id != shape.texture_id()
-                        }
-                        Primitive::Callback(_) => true,
-                    }
+        // Continue the last mesh if it has the same clip rectangle.
+        // A new mesh is started if the texture changes.
+        let mut meshes = std::mem::take(&mut self.scratchpad_meshes);
+        let continue_last_mesh = matches!(
+            out_primitives.last(),
+            Some(ClippedPrimitive {
+                clip_rect: last_clip_rect,
+                primitive: Primitive::Mesh(_),
+            }) if *last_clip_rect == clip_rect
+        );
+        if continue_last_mesh {
+            if let Some(ClippedPrimitive {
+                primitive: Primitive::Mesh(mesh),
+                ..
+            }) = out_primitives.pop()
+            {
+                meshes.push(mesh);
             }
-        };
-
-        if start_new_mesh {
-            out_primitives.push(ClippedPrimitive {
-                clip_rect,
-                primitive: Primitive::Mesh(Mesh::default()),
-            });
         }
 
-        let out = out_primitives.last_mut().unwrap();
+        self.clip_rect = clip_rect;
+        self.tessellate_shape_into_meshes(shape, &mut meshes);
 
-        if let Primitive::Mesh(out_mesh) = &mut out.primitive {
-            self.clip_rect = clip_rect;
-            self.tessellate_shape(shape, out_mesh);
-        } else {
-            unreachable!();
+        out_primitives.extend(meshes.drain(..).map(|mesh| ClippedPrimitive {
+            clip_rect,
+            primitive: Primitive::Mesh(mesh),
+        }));
+        self.scratchpad_meshes = meshes;
+    }
+
+    /// Tessellate a single [`Shape`] into [`Mesh`]es.
+    ///
+    /// Unlike [`Self::tessellate_shape`], this also paints the glyphs on other pages of the font atlas than the first.
+    /// A new mesh is started whenever the texture changes, so paint the meshes in order.
+    ///
+    /// This call can panic the given shape is of [`Shape::Callback`].
+    /// For that, use [`Self::tessellate_clipped_shape`] instead.
+    /// * `shape`: the shape to tessellate.
+    /// * `out`: triangles are appended to the last mesh, if it has the same texture.
+    pub fn tessellate_shape_into_meshes(&mut self, shape: Shape, out: &mut Vec<Mesh>) {
+        match shape {
+            Shape::Vec(vec) => {
+                for shape in vec {
+                    self.tessellate_shape_into_meshes(shape, out);
+                }
+            }
+            Shape::Text(text_shape) => {
+                if self.options.debug_paint_text_rects {
+                    let rect = text_shape.galley.rect.translate(text_shape.pos.to_vec2());
+                    self.tessellate_rect(
+                        &RectShape::stroke(rect.expand(0.5), 2.0, (0.5, Color32::GREEN)),
+                        mesh_with_texture(out, TextureId::default()),
+                    );
+                }
+                self.tessellate_text(&text_shape, out);
+            }
+            shape => {
+                let texture_id = shape.texture_id();
+                self.tessellate_shape(shape, mesh_with_texture(out, texture_id));
+            }
         }
     }
 
     /// Tessellate a single [`Shape`] into a [`Mesh`].
     ///
+    /// Only glyphs on the first page of the font atlas end up in `out` (with a warning for the others),
+    /// use [`Self::tessellate_shape_into_meshes`] for text that may use other pages.
+    ///
     /// This call can panic the given shape is of [`Shape::Vec`] or [`Shape::Callback`].
     /// For that, use [`Self::tessellate_clipped_shape`] instead.
     /// * `shape`: the shape to tessellate.
@@ -1391,6 +2150,9 @@ impl Tessellator {
             Shape::Path(path_shape) => {
                 self.tessellate_path(&path_shape, out);
             }
+            Shape::Polygon(polygon_shape) => {
+                self.tessellate_polygon(&polygon_shape, out);
+            }
             Shape::Rect(rect_shape) => {
                 self.tessellate_rect(&rect_shape, out);
             }
@@ -1402,7 +2164,7 @@ impl Tessellator {
                         out,
                     );
                 }
-                self.tessellate_text(&text_shape, out);
+                self.tessellate_text_into_mesh(&text_shape, out);
             }
             Shape::QuadraticBezier(quadratic_shape) => {
                 self.tessellate_quadratic_bezier(&quadratic_shape, out);
@@ -1411,6 +2173,30 @@ impl Tessellator {
             Shape::Callback(_) => {
                 panic!("Shape::Callback passed to Tessellator");
             }
+            Shape::Brushed(brushed) => {
+                self.tessellate_brushed(*brushed, out);
+            }
+        }
+    }
+
+    /// Tessellate a single [`BrushedShape`] into a [`Mesh`].
+    ///
+    /// * `brushed`: the shape to tessellate, and what to fill it with.
+    /// * `out`: triangles are appended to this.
+    pub fn tessellate_brushed(&mut self, brushed: BrushedShape, out: &mut Mesh) {
+        let BrushedShape { shape, brush } = brushed;
+        match shape {
+            Shape::Circle(circle) => self.tessellate_circle_with_brush(circle, Some(&brush), out),
+            Shape::Ellipse(ellipse) => {
+                self.tessellate_ellipse_with_brush(ellipse, Some(&brush), out);
+            }
+            Shape::Path(path_shape) => {
+                self.tessellate_path_with_brush(&path_shape, Some(&brush), out);
+            }
+            Shape::Polygon(polygon_shape) => {
+                self.tessellate_polygon_with_brush(&polygon_shape, Some(&brush), out);
+            }
+            shape => self.tessellate_shape(shape.with_brush(brush), out),
         }
     }
 
@@ -1419,6 +2205,15 @@ impl Tessellator {
     /// * `shape`: the circle to tessellate.
     /// * `out`: triangles are appended to this.
     pub fn tessellate_circle(&mut self, shape: CircleShape, out: &mut Mesh) {
+        self.tessellate_circle_with_brush(shape, None, out);
+    }
+
+    fn tessellate_circle_with_brush(
+        &mut self,
+        shape: CircleShape,
+        brush: Option<&Brush>,
+        out: &mut Mesh,
+    ) {
         let CircleShape {
             center,
             radius,
@@ -1439,7 +2234,7 @@ impl Tessellator {
             return;
         }
 
-        if self.options.prerasterized_discs && fill != Color32::TRANSPARENT {
+        if self.options.prerasterized_discs && fill != Color32::TRANSPARENT && brush.is_none() {
             let radius_px = radius * self.pixels_per_point;
             // strike the right balance between some circles becoming too blurry, and some too sharp.
             let cutoff_radius = radius_px * 2.0_f32.powf(0.25);
@@ -1466,8 +2261,20 @@ impl Tessellator {
         let path_stroke = PathStroke::from(stroke).outside();
         self.scratchpad_path.clear();
         self.scratchpad_path.add_circle(center, radius);
-        self.scratchpad_path
-            .fill(self.feathering, fill, &path_stroke, out);
+        if let Some(brush) = brush {
+            let rect = Rect::from_center_size(center, Vec2::splat(2.0 * radius));
+            self.scratchpad_path.fill_with_brush(
+                self.feathering,
+                fill,
+                brush,
+                rect,
+                &path_stroke,
+                out,
+            );
+        } else {
+            self.scratchpad_path
+                .fill(self.feathering, fill, &path_stroke, out);
+        }
         self.scratchpad_path
             .stroke_closed(self.feathering, &path_stroke, out);
     }
@@ -1477,6 +2284,15 @@ impl Tessellator {
     /// * `shape`: the ellipse to tessellate.
     /// * `out`: triangles are appended to this.
     pub fn tessellate_ellipse(&mut self, shape: EllipseShape, out: &mut Mesh) {
+        self.tessellate_ellipse_with_brush(shape, None, out);
+    }
+
+    fn tessellate_ellipse_with_brush(
+        &mut self,
+        shape: EllipseShape,
+        brush: Option<&Brush>,
+        out: &mut Mesh,
+    ) {
         let EllipseShape {
             center,
             radius,
@@ -1535,8 +2351,20 @@ impl Tessellator {
         let path_stroke = PathStroke::from(stroke).outside();
         self.scratchpad_path.clear();
         self.scratchpad_path.add_line_loop(&points);
-        self.scratchpad_path
-            .fill(self.feathering, fill, &path_stroke, out);
+        if let Some(brush) = brush {
+            let rect = Rect::from_center_size(center, 2.0 * radius);
+            self.scratchpad_path.fill_with_brush(
+                self.feathering,
+                fill,
+                brush,
+                rect,
+                &path_stroke,
+                out,
+            );
+        } else {
+            self.scratchpad_path
+                .fill(self.feathering, fill, &path_stroke, out);
+        }
         self.scratchpad_path
             .stroke_closed(self.feathering, &path_stroke, out);
     }
@@ -1622,6 +2450,15 @@ impl Tessellator {
     /// * `path_shape`: the path to tessellate.
     /// * `out`: triangles are appended to this.
     pub fn tessellate_path(&mut self, path_shape: &PathShape, out: &mut Mesh) {
+        self.tessellate_path_with_brush(path_shape, None, out);
+    }
+
+    fn tessellate_path_with_brush(
+        &mut self,
+        path_shape: &PathShape,
+        brush: Option<&Brush>,
+        out: &mut Mesh,
+    ) {
         if path_shape.points.len() < 2 {
             return;
         }
@@ -1653,8 +2490,20 @@ impl Tessellator {
                 closed,
                 "You asked to fill a path that is not closed. That makes no sense."
             );
-            self.scratchpad_path
-                .fill(self.feathering, *fill, stroke, out);
+            if let Some(brush) = brush {
+                let rect = Rect::from_points(points);
+                self.scratchpad_path.fill_with_brush(
+                    self.feathering,
+                    *fill,
+                    brush,
+                    rect,
+                    stroke,
+                    out,
+                );
+            } else {
+                self.scratchpad_path
+                    .fill(self.feathering, *fill, stroke, out);
+            }
         }
         let typ = if *closed {
             PathType::Closed
@@ -1665,6 +2514,62 @@ impl Tessellator {
             .stroke(self.feathering, typ, stroke, out);
     }
 
+    /// Tessellate a single [`PolygonShape`] into a [`Mesh`].
+    ///
+    /// * `polygon_shape`: the polygon to tessellate.
+    /// * `out`: triangles are appended to this.
+    pub fn tessellate_polygon(&mut self, polygon_shape: &PolygonShape, out: &mut Mesh) {
+        self.tessellate_polygon_with_brush(polygon_shape, None, out);
+    }
+
+    fn tessellate_polygon_with_brush(
+        &mut self,
+        polygon_shape: &PolygonShape,
+        brush: Option<&Brush>,
+        out: &mut Mesh,
+    ) {
+        if self.options.coarse_tessellation_culling
+            && !polygon_shape
+                .visual_bounding_rect()
+                .intersects(self.clip_rect)
+        {
+            return;
+        }
+
+        profiling::function_scope!();
+
+        let PolygonShape {
+            contours,
+            fill_rule,
+            fill,
+            stroke,
+        } = polygon_shape;
+
+        if *fill != Color32::TRANSPARENT {
+            let contours: Vec<&[Pos2]> = contours.iter().map(Vec::as_slice).collect();
+            let rect = Rect::from_points(&contours.concat());
+            fill_contours(
+                self.feathering,
+                &contours,
+                *fill_rule,
+                *fill,
+                brush,
+                rect,
+                stroke,
+                out,
+            );
+        }
+
+        for contour in contours {
+            if 2 <= contour.len() {
+                self.scratchpad_path.clear();
+                self.scratchpad_path.add_line_loop(contour);
+                self.scratchpad_path
+                    .stroke(self.feathering, PathType::Closed, stroke, out);
+            }
+        }
+    }
+
     /// Tessellate a single [`Rect`] into a [`Mesh`].
     ///
     /// * `rect`: the rectangle to tessellate.
@@ -1761,18 +2666,8 @@ impl Tessellator {
             let path_stroke = PathStroke::from(stroke).outside();
 
             if let Some(brush) = brush {
-                let crate::Brush {
-                    fill_texture_id,
-                    uv,
-                } = **brush;
-                // Textured
-                let uv_from_pos = |p: Pos2| {
-                    pos2(
-                        remap(p.x, rect.x_range(), uv.x_range()),
-                        remap(p.y, rect.y_range(), uv.y_range()),
-                    )
-                };
-                path.fill_with_uv(self.feathering, fill, fill_texture_id, uv_from_pos, out);
+                // Textured and/or gradient
+                path.fill_with_brush(self.feathering, fill, brush, rect, &path_stroke, out);
             } else {
                 // Untextured
                 path.fill(self.feathering, fill, &path_stroke, out);
@@ -1784,10 +2679,47 @@ impl Tessellator {
         self.feathering = old_feathering; // restore
     }
 
-    /// Tessellate a single [`TextShape`] into a [`Mesh`].
+    /// Tessellate the glyphs of a [`TextShape`] that are on the first page of the font atlas into `out`.
+    ///
+    /// A single mesh can't hold glyphs of other pages, so those are left out with a warning.
+    fn tessellate_text_into_mesh(&mut self, text_shape: &TextShape, out: &mut Mesh) {
+        let Some(Some((first_page_texture_id, _))) = self.font_pages.first().copied() else {
+            return;
+        };
+
+        let mut meshes = std::mem::take(&mut self.scratchpad_meshes);
+        self.tessellate_text(text_shape, &mut meshes);
+        let mut num_skipped_vertices = 0;
+        for mesh in meshes.drain(..) {
+            if mesh.texture_id == first_page_texture_id {
+                let index_offset = out.vertices.len() as u32;
+                out.indices
+                    .extend(mesh.indices.iter().map(|index| index + index_offset));
+                out.vertices.extend(mesh.vertices);
+            } else {
+                num_skipped_vertices += mesh.vertices.len();
+            }
+        }
+        self.scratchpad_meshes = meshes;
+
+        if num_skipped_vertices != 0 {
+            let warn = "epaint: WARNING: text on other pages of the font atlas than the first can't be tessellated into a single mesh. \
+                       Use Tessellator::tessellate_shape_into_meshes or Tessellator::tessellate_shapes instead.";
+            #[cfg(feature = "log")]
+            log::warn!("{warn}");
+            #[cfg(not(feature = "log"))]
+            println!("{warn}");
+        }
+    }
+
+    /// Tessellate a single [`TextShape`] into [`Mesh`]es, one for each page of the font atlas.
+    ///
+    /// Each mesh has the [`TextureId`] of its page. A new mesh is started whenever the page changes,
+    /// so paint the meshes in order.
+    ///
     /// * `text_shape`: the text to tessellate.
-    /// * `out`: triangles are appended to this.
-    pub fn tessellate_text(&mut self, text_shape: &TextShape, out: &mut Mesh) {
+    /// * `out`: triangles are appended to the last mesh, if it has the same texture.
+    pub fn tessellate_text(&mut self, text_shape: &TextShape, out: &mut Vec<Mesh>) {
         let TextShape {
             pos: galley_pos,
             galley,
@@ -1815,8 +2747,16 @@ impl Tessellator {
             println!("{warn}");
         }
 
-        out.vertices.reserve(galley.num_vertices);
-        out.indices.reserve(galley.num_indices);
+        let Some(Some((first_page_texture_id, first_page_size))) = self.font_pages.first().copied()
+        else {
+            return;
+        };
+        let uv_normalizer = |size: [usize; 2]| vec2(1.0 / size[0] as f32, 1.0 / size[1] as f32);
+        let first_page_uv_normalizer = uv_normalizer(first_page_size);
+
+        let mesh = mesh_with_texture(out, first_page_texture_id);
+        mesh.vertices.reserve(galley.num_vertices);
+        mesh.indices.reserve(galley.num_indices);
 
         // The contents of the galley are already snapped to pixel coordinates,
         // but we need to make sure the galley ends up on the start of a physical pixel:
@@ -1826,11 +2766,6 @@ impl Tessellator {
             *galley_pos
         };
 
-        let uv_normalizer = vec2(
-            1.0 / self.font_tex_size[0] as f32,
-            1.0 / self.font_tex_size[1] as f32,
-        );
-
         let rotator = Rot2::from_angle(*angle);
 
         for row in &galley.rows {
@@ -1850,59 +2785,90 @@ impl Tessellator {
                 continue;
             }
 
-            let index_offset = out.vertices.len() as u32;
+            let transform_vertex = |i: usize, vertex: &Vertex, uv_normalizer: Vec2| {
+                let Vertex { pos, uv, mut color } = *vertex;
 
-            out.indices.extend(
-                row.visuals
-                    .mesh
-                    .indices
-                    .iter()
-                    .map(|index| index + index_offset),
-            );
+                if let Some(override_text_color) = override_text_color {
+                    // Only override the glyph color (not background color, strike-through color, etc),
+                    // but decorations in the text color follow the glyphs.
+                    if row.visuals.glyph_vertex_range.contains(&i) || color == Color32::PLACEHOLDER
+                    {
+                        color = *override_text_color;
+                    }
+                } else if color == Color32::PLACEHOLDER {
+                    color = *fallback_color;
+                }
 
-            out.vertices.extend(
-                row.visuals
-                    .mesh
-                    .vertices
-                    .iter()
-                    .enumerate()
-                    .map(|(i, vertex)| {
-                        let Vertex { pos, uv, mut color } = *vertex;
-
-                        if let Some(override_text_color) = override_text_color {
-                            // Only override the glyph color (not background color, strike-through color, etc)
-                            if row.visuals.glyph_vertex_range.contains(&i) {
-                                color = *override_text_color;
-                            }
-                        } else if color == Color32::PLACEHOLDER {
-                            color = *fallback_color;
-                        }
-
-                        if *opacity_factor < 1.0 {
-                            color = color.gamma_multiply(*opacity_factor);
-                        }
-
-                        debug_assert!(color != Color32::PLACEHOLDER, "A placeholder color made it to the tessellator. You forgot to set a fallback color.");
-
-                        let offset = if *angle == 0.0 {
-                            pos.to_vec2()
-                        } else {
-                            rotator * pos.to_vec2()
-                        };
-
-                        Vertex {
-                            pos: galley_pos + offset,
-                            uv: (uv.to_vec2() * uv_normalizer).to_pos2(),
-                            color,
-                        }
-                    }),
-            );
+                if *opacity_factor < 1.0 {
+                    color = color.gamma_multiply(*opacity_factor);
+                }
+
+                debug_assert!(color != Color32::PLACEHOLDER, "A placeholder color made it to the tessellator. You forgot to set a fallback color.");
+
+                let offset = if *angle == 0.0 {
+                    pos.to_vec2()
+                } else {
+                    rotator * pos.to_vec2()
+                };
+
+                Vertex {
+                    pos: galley_pos + offset,
+                    uv: (uv.to_vec2() * uv_normalizer).to_pos2(),
+                    color,
+                }
+            };
+
+            let mesh = &row.visuals.mesh;
+            let page_vertex_ranges = &row.visuals.page_vertex_ranges;
+
+            if page_vertex_ranges.is_empty() {
+                let out = mesh_with_texture(out, first_page_texture_id);
+                let index_offset = out.vertices.len() as u32;
+
+                out.indices
+                    .extend(mesh.indices.iter().map(|index| index + index_offset));
+
+                out.vertices.extend(
+                    mesh.vertices
+                        .iter()
+                        .enumerate()
+                        .map(|(i, vertex)| transform_vertex(i, vertex, first_page_uv_normalizer)),
+                );
+            } else {
+                let page_of_vertex = |i: usize| {
+                    let range_index =
+                        page_vertex_ranges.partition_point(|(_, range)| range.end <= i);
+                    match page_vertex_ranges.get(range_index) {
+                        Some((page, range)) if range.contains(&i) => *page,
+                        _ => 0,
+                    }
+                };
+
+                // Each triangle goes in the mesh of the page its glyph is on:
+                for triangle in mesh.indices.chunks_exact(3) {
+                    let page = page_of_vertex(triangle[0] as usize);
+                    let Some(Some((texture_id, size))) = self.font_pages.get(page) else {
+                        continue; // The page was never uploaded
+                    };
+                    let out = mesh_with_texture(out, *texture_id);
+                    let index_offset = out.vertices.len() as u32;
+                    for &index in triangle {
+                        let i = index as usize;
+                        out.vertices.push(transform_vertex(
+                            i,
+                            &mesh.vertices[i],
+                            uv_normalizer(*size),
+                        ));
+                    }
+                    out.indices.extend(index_offset..index_offset + 3);
+                }
+            }
 
             if *underline != Stroke::NONE {
                 self.tessellate_line_segment(
                     [row_rect.left_bottom(), row_rect.right_bottom()],
                     *underline,
-                    out,
+                    mesh_with_texture(out, first_page_texture_id),
                 );
             }
         }
@@ -2129,6 +3095,12 @@ impl Tessellator {
 
                 Shape::Path(path_shape) => 32 < path_shape.points.len(),
 
+                Shape::Polygon(polygon_shape) => {
+                    32 < polygon_shape.contours.iter().map(Vec::len).sum::<usize>()
+                }
+
+                Shape::Brushed(brushed) => should_parallelize(&brushed.shape),
+
                 Shape::QuadraticBezier(_) | Shape::CubicBezier(_) | Shape::Ellipse(_) => true,
 
                 Shape::Noop
@@ -2141,7 +3113,7 @@ impl Tessellator {
             }
         }
 
-        let tessellated: Vec<(usize, Mesh)> = shapes
+        let tessellated: Vec<(usize, Vec<Mesh>)> = shapes
             .par_iter()
             .enumerate()
             .filter(|(_, clipped_shape)| should_parallelize(&clipped_shape.shape))
@@ -2149,15 +3121,15 @@ impl Tessellator {
                 profiling::scope!("tessellate_big_shape");
                 // TODO(emilk): reuse tessellator in a thread local
                 let mut tessellator = (*self).clone();
-                let mut mesh = Mesh::default();
-                tessellator.tessellate_shape(clipped_shape.shape.clone(), &mut mesh);
-                (index, mesh)
+                let mut meshes = vec![];
+                tessellator.tessellate_shape_into_meshes(clipped_shape.shape.clone(), &mut meshes);
+                (index, meshes)
             })
             .collect();
 
         profiling::scope!("distribute results", tessellated.len().to_string());
-        for (index, mesh) in tessellated {
-            shapes[index].shape = Shape::Mesh(mesh.into());
+        for (index, meshes) in tessellated {
+            shapes[index].shape = Shape::Vec(meshes.into_iter().map(Shape::mesh).collect());
         }
     }
 
@@ -2172,8 +3144,8 @@ impl Tessellator {
             .into_iter()
             .flat_map(|clipped_primitive| {
                 let mut clip_rect_mesh = Mesh::default();
-                self.tessellate_shape(
-                    Shape::rect_stroke(clipped_primitive.clip_rect, 0.0, stroke),
+                self.tessellate_rect(
+                    &RectShape::stroke(clipped_primitive.clip_rect, 0.0, stroke),
                     &mut clip_rect_mesh,
                 );
 
@@ -2221,6 +3193,140 @@ fn test_tessellator() {
     assert_eq!(primitives.len(), 2);
 }
 
+#[test]
+fn text_on_other_font_atlas_pages_keeps_paint_order() {
+    use crate::text::{FontDefinitions, FontId, Fonts};
+
+    let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+    let mut galley = fonts.layout_no_wrap("Hi".to_owned(), FontId::default(), Color32::WHITE);
+
+    // Pretend the glyphs are on the second page of the font atlas:
+    let row = &mut std::sync::Arc::make_mut(&mut galley).rows[0];
+    row.visuals.page_vertex_ranges = vec![(1, row.visuals.glyph_vertex_range.clone())];
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(10.0, 10.0));
+    let clipped_shapes = [
+        Shape::rect_filled(rect, 0.0, Color32::RED),
+        Shape::galley(pos2(0.0, 0.0), galley, Color32::WHITE),
+        Shape::rect_filled(rect, 0.0, Color32::BLUE),
+    ]
+    .into_iter()
+    .map(|shape| ClippedShape {
+        clip_rect: Rect::EVERYTHING,
+        shape,
+    })
+    .collect();
+
+    let second_page = TextureId::Managed(7);
+    let mut tessellator =
+        Tessellator::new(1.0, Default::default(), fonts.font_image_size(), vec![]);
+    tessellator.set_font_pages(vec![
+        Some((TextureId::default(), fonts.font_image_size())),
+        Some((second_page, [1024, 64])),
+    ]);
+    let textures: Vec<TextureId> = tessellator
+        .tessellate_shapes(clipped_shapes)
+        .iter()
+        .map(|primitive| match &primitive.primitive {
+            Primitive::Mesh(mesh) => mesh.texture_id,
+            Primitive::Callback(_) => unreachable!(),
+        })
+        .collect();
+    assert_eq!(
+        textures,
+        [TextureId::default(), second_page, TextureId::default()]
+    );
+}
+
+#[test]
+fn text_on_a_second_font_atlas_page_is_tessellated() {
+    use crate::text::{FontDefinitions, FontId, Fonts};
+
+    // Glyphs so big that they don't all fit on the first page of the atlas:
+    let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+    let text = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let font_id = FontId::proportional(300.0);
+    let galley = fonts.layout_no_wrap(text.to_owned(), font_id, Color32::WHITE);
+
+    let page_texture_id = |page: usize| TextureId::Managed(100 + page as u64);
+    let font_pages = {
+        let atlas = fonts.texture_atlas();
+        let mut atlas = atlas.lock();
+        assert!(
+            1 < atlas.num_pages(),
+            "The glyphs should need a second page"
+        );
+        for page in 1..atlas.num_pages() {
+            atlas.set_page_texture_id(page, page_texture_id(page));
+        }
+        atlas.texture_pages()
+    };
+
+    let mut tessellator =
+        Tessellator::new(1.0, Default::default(), fonts.font_image_size(), vec![]);
+    tessellator.set_font_pages(font_pages);
+    let mut meshes = vec![];
+    tessellator.tessellate_text(
+        &TextShape::new(pos2(0.0, 0.0), galley.clone(), Color32::WHITE),
+        &mut meshes,
+    );
+
+    let textures: Vec<TextureId> = meshes.iter().map(|mesh| mesh.texture_id).collect();
+    assert!(textures.contains(&TextureId::default()));
+    assert!(textures.contains(&page_texture_id(1)));
+
+    let num_indices: usize = meshes.iter().map(|mesh| mesh.indices.len()).sum();
+    let num_glyph_indices: usize = (galley.rows.iter())
+        .map(|row| row.visuals.mesh.indices.len())
+        .sum();
+    assert_eq!(
+        num_indices, num_glyph_indices,
+        "Every glyph should be tessellated"
+    );
+}
+
+#[test]
+fn decorations_in_text_color_follow_override_text_color() {
+    use crate::text::{FontDefinitions, FontId, Fonts, LayoutJob, TextDecoration, TextFormat};
+
+    let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+    let mut job = LayoutJob::default();
+    job.append(
+        "Hi",
+        0.0,
+        TextFormat {
+            font_id: FontId::default(),
+            color: Color32::PLACEHOLDER,
+            decorations: vec![TextDecoration::wavy_underline(Stroke::new(
+                1.0,
+                Color32::PLACEHOLDER,
+            ))],
+            ..Default::default()
+        },
+    );
+    let galley = fonts.layout_job(job);
+
+    let text_shape = TextShape::new(pos2(0.0, 0.0), galley, Color32::RED)
+        .with_override_text_color(Color32::BLUE);
+    let mut tessellator =
+        Tessellator::new(1.0, Default::default(), fonts.font_image_size(), vec![]);
+    let mut meshes = vec![];
+    tessellator.tessellate_text(&text_shape, &mut meshes);
+
+    let colors: Vec<Color32> = (meshes.iter())
+        .flat_map(|mesh| &mesh.vertices)
+        .map(|vertex| vertex.color)
+        .collect();
+    assert!(!colors.is_empty());
+    assert!(colors.contains(&Color32::BLUE));
+    assert!(
+        colors
+            .iter()
+            .all(|&color| color == Color32::BLUE || color == Color32::TRANSPARENT),
+        "The glyphs and the underline should be blue, except for their feathered edges"
+    );
+}
+
 #[test]
 fn path_bounding_box() {
     use crate::*;
@@ -2265,3 +3371,212 @@ fn path_bounding_box() {
         );
     }
 }
+
+#[test]
+fn gradient_fill_is_subdivided() {
+    use crate::*;
+
+    let center = pos2(50.0, 50.0);
+    let gradient = Gradient::radial(
+        center,
+        50.0,
+        vec![
+            (0.0, Color32::WHITE),
+            (0.5, Color32::RED),
+            (1.0, Color32::BLUE),
+        ],
+    );
+    let circle = Shape::circle_filled(center, 50.0, Color32::WHITE).with_gradient(gradient.clone());
+
+    let mut tessellator = Tessellator::new(1.0, Default::default(), [1024, 1024], vec![]);
+    let mut mesh = Mesh::default();
+    tessellator.tessellate_shape(circle, &mut mesh);
+    assert!(mesh.is_valid());
+
+    // Neighboring triangles should share their vertices:
+    let num_triangles = mesh.indices.len() / 3;
+    assert!(
+        mesh.vertices.len() < num_triangles,
+        "{} vertices for {num_triangles} triangles",
+        mesh.vertices.len()
+    );
+
+    // The interpolated color in the middle of each triangle should be close to the gradient:
+    for tri in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[tri[i] as usize]);
+        let centroid = pos2(
+            (a.pos.x + b.pos.x + c.pos.x) / 3.0,
+            (a.pos.y + b.pos.y + c.pos.y) / 3.0,
+        );
+        if centroid.distance(center) > 49.0 {
+            continue; // feathering
+        }
+        let expected = gradient.color_at(centroid);
+        for i in 0..4 {
+            let interpolated =
+                (u32::from(a.color[i]) + u32::from(b.color[i]) + u32::from(c.color[i])) as f32
+                    / 3.0;
+            assert!(
+                (interpolated - f32::from(expected[i])).abs() <= 8.0,
+                "Bad color at {centroid:?}: {interpolated} vs {expected:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn fill_rules() {
+    use crate::*;
+
+    fn filled_area(contours: &[&[Pos2]], fill_rule: FillRule) -> f32 {
+        let (trapezoids, _) = fill_trapezoids(contours, fill_rule);
+        trapezoids
+            .iter()
+            .map(|(top, bottom)| {
+                let width = (top.max.x - top.min.x) + (bottom.max.x - bottom.min.x);
+                0.5 * width * (bottom.min.y - top.min.y)
+            })
+            .sum()
+    }
+
+    let outer = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    let hole_cw = [
+        pos2(3.0, 3.0),
+        pos2(7.0, 3.0),
+        pos2(7.0, 7.0),
+        pos2(3.0, 7.0),
+    ];
+    let hole_ccw = [
+        pos2(3.0, 3.0),
+        pos2(3.0, 7.0),
+        pos2(7.0, 7.0),
+        pos2(7.0, 3.0),
+    ];
+
+    assert_eq!(filled_area(&[&outer], FillRule::NonZero), 100.0);
+    assert_eq!(filled_area(&[&outer, &hole_cw], FillRule::EvenOdd), 84.0);
+    assert_eq!(filled_area(&[&outer, &hole_ccw], FillRule::EvenOdd), 84.0);
+    assert_eq!(filled_area(&[&outer, &hole_ccw], FillRule::NonZero), 84.0);
+    assert_eq!(filled_area(&[&outer, &hole_cw], FillRule::NonZero), 100.0);
+
+    // A concave "L":
+    let l_shape = [
+        pos2(0.0, 0.0),
+        pos2(2.0, 0.0),
+        pos2(2.0, 8.0),
+        pos2(6.0, 8.0),
+        pos2(6.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    assert_eq!(filled_area(&[&l_shape], FillRule::NonZero), 28.0);
+
+    // A self-intersecting bow tie, made of two triangles with area 25 each:
+    let bow_tie = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(10.0, 0.0),
+        pos2(0.0, 10.0),
+    ];
+    let area = filled_area(&[&bow_tie], FillRule::NonZero);
+    assert!((area - 50.0).abs() < 1e-3, "area: {area}");
+
+    // A pentagram has a hole in the middle with the even-odd rule:
+    let star: Vec<Pos2> = (0..5)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * (2 * i) as f32 / 5.0;
+            pos2(50.0 + 40.0 * angle.sin(), 50.0 - 40.0 * angle.cos())
+        })
+        .collect();
+    let non_zero = filled_area(&[&star], FillRule::NonZero);
+    let even_odd = filled_area(&[&star], FillRule::EvenOdd);
+    assert!(even_odd < non_zero);
+
+    // The whole outline should be feathered, including the horizontal parts:
+    let (_, boundary) = fill_trapezoids(&[&outer, &hole_ccw], FillRule::NonZero);
+    let outline_len: f32 = boundary.iter().map(|(a, b, _)| a.distance(*b)).sum();
+    assert!((outline_len - 56.0).abs() < 1e-3, "outline: {outline_len}");
+
+    let loops = outline_loops(&boundary);
+    assert_eq!(loops.len(), 2);
+    assert!(loops.iter().all(|outline| outline.len() == 4));
+
+    let shape = PolygonShape::multi(
+        vec![outer.to_vec(), hole_cw.to_vec()],
+        FillRule::EvenOdd,
+        Color32::WHITE,
+        Stroke::new(1.0, Color32::RED),
+    );
+    let shape = Shape::from(shape).with_gradient(Gradient::linear(
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        vec![(0.0, Color32::BLACK), (1.0, Color32::WHITE)],
+    ));
+    let mut tessellator = Tessellator::new(1.0, Default::default(), [1024, 1024], vec![]);
+    let mut mesh = Mesh::default();
+    tessellator.tessellate_shape(shape, &mut mesh);
+    assert!(mesh.is_valid());
+    assert!(!mesh.is_empty());
+}
+
+#[test]
+fn feathered_polygon_keeps_its_area() {
+    use crate::*;
+
+    // The feathering fades out across the outline, so the coverage should add up to the area:
+    fn coverage(shape: &PolygonShape) -> f32 {
+        let mut tessellator = Tessellator::new(1.0, Default::default(), [1024, 1024], vec![]);
+        let mut mesh = Mesh::default();
+        tessellator.tessellate_polygon(shape, &mut mesh);
+        assert!(mesh.is_valid());
+        mesh.indices
+            .chunks_exact(3)
+            .map(|tri| {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[tri[i] as usize]);
+                let (ab, ac) = (b.pos - a.pos, c.pos - a.pos);
+                let area = 0.5 * (ab.x * ac.y - ab.y * ac.x).abs();
+                let alpha = [a, b, c]
+                    .iter()
+                    .map(|v| f32::from(v.color.a()) / 255.0)
+                    .sum::<f32>()
+                    / 3.0;
+                area * alpha
+            })
+            .sum()
+    }
+
+    // A concave "L" with an area of 2800:
+    let l_shape = vec![
+        pos2(0.0, 0.0),
+        pos2(20.0, 0.0),
+        pos2(20.0, 80.0),
+        pos2(60.0, 80.0),
+        pos2(60.0, 100.0),
+        pos2(0.0, 100.0),
+    ];
+    let area = coverage(&PolygonShape::new(l_shape, Color32::WHITE, Stroke::NONE));
+    assert!((area - 2800.0).abs() < 1.0, "area: {area}");
+
+    // A square with a square hole:
+    let contours = vec![
+        vec![
+            pos2(0.0, 0.0),
+            pos2(100.0, 0.0),
+            pos2(100.0, 100.0),
+            pos2(0.0, 100.0),
+        ],
+        vec![
+            pos2(30.0, 30.0),
+            pos2(70.0, 30.0),
+            pos2(70.0, 70.0),
+            pos2(30.0, 70.0),
+        ],
+    ];
+    let shape = PolygonShape::multi(contours, FillRule::EvenOdd, Color32::WHITE, Stroke::NONE);
+    let area = coverage(&shape);
+    assert!((area - 8400.0).abs() < 1.0, "area: {area}");
+}
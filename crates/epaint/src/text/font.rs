@@ -6,9 +6,13 @@ use emath::{vec2, GuiRounding, Vec2};
 use crate::{
     mutex::{Mutex, RwLock},
     text::FontTweak,
+    texture_atlas::AtlasSlot,
     TextureAtlas,
 };
 
+#[cfg(feature = "text_shaping")]
+use crate::text::FontData;
+
 // ----------------------------------------------------------------------------
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -26,12 +30,23 @@ pub struct UvRect {
 
     /// Bottom right corner (exclusive).
     pub max: [u16; 2],
+
+    /// Which page of the [`TextureAtlas`] the glyph is on.
+    pub page: u16,
 }
 
 impl UvRect {
     pub fn is_nothing(&self) -> bool {
         self.min == self.max
     }
+
+    /// Where the glyph is in the [`TextureAtlas`].
+    pub(crate) fn atlas_slot(&self) -> AtlasSlot {
+        AtlasSlot {
+            page: self.page,
+            min: self.min,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -80,16 +95,31 @@ pub struct FontImpl {
     pixels_per_point: f32,
     glyph_info_cache: RwLock<ahash::HashMap<char, GlyphInfo>>, // TODO(emilk): standard Mutex
     atlas: Arc<Mutex<TextureAtlas>>,
+
+    /// Extra stroke width for synthetic bold, in physical pixels.
+    embolden_in_pixels: f32,
+
+    /// The font file, parsed for shaping.
+    #[cfg(feature = "text_shaping")]
+    shaping_face: ShapingFace,
+
+    /// Shaped clusters that have been rasterized into the atlas.
+    #[cfg(feature = "text_shaping")]
+    cluster_cache: RwLock<ahash::HashMap<Vec<ClusterGlyph>, UvRect>>,
 }
 
 impl FontImpl {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         atlas: Arc<Mutex<TextureAtlas>>,
         pixels_per_point: f32,
         name: String,
         ab_glyph_font: ab_glyph::FontArc,
+        #[cfg(feature = "text_shaping")] font_data: Arc<FontData>,
+        #[cfg(feature = "text_shaping")] variations: &[([u8; 4], f32)],
         scale_in_pixels: f32,
         tweak: FontTweak,
+        embolden_in_pixels: f32,
     ) -> Self {
         assert!(scale_in_pixels > 0.0);
         assert!(pixels_per_point > 0.0);
@@ -130,6 +160,11 @@ impl FontImpl {
             pixels_per_point,
             glyph_info_cache: Default::default(),
             atlas,
+            embolden_in_pixels,
+            #[cfg(feature = "text_shaping")]
+            shaping_face: ShapingFace::parse(font_data, variations),
+            #[cfg(feature = "text_shaping")]
+            cluster_cache: Default::default(),
         }
     }
 
@@ -279,18 +314,10 @@ impl FontImpl {
             if glyph_width == 0 || glyph_height == 0 {
                 UvRect::default()
             } else {
-                let glyph_pos = {
-                    let atlas = &mut self.atlas.lock();
-                    let (glyph_pos, image) = atlas.allocate((glyph_width, glyph_height));
-                    glyph.draw(|x, y, v| {
-                        if 0.0 < v {
-                            let px = glyph_pos.0 + x as usize;
-                            let py = glyph_pos.1 + y as usize;
-                            image[(px, py)] = v;
-                        }
+                let (page, glyph_pos, glyph_width) =
+                    self.allocate_in_atlas(glyph_width, glyph_height, |put| {
+                        glyph.draw(|x, y, v| put(x as usize, y as usize, v));
                     });
-                    glyph_pos
-                };
 
                 let offset_in_pixels = vec2(bb.min.x, bb.min.y);
                 let offset =
@@ -303,15 +330,17 @@ impl FontImpl {
                         (glyph_pos.0 + glyph_width) as u16,
                         (glyph_pos.1 + glyph_height) as u16,
                     ],
+                    page,
                 }
             }
         });
         let uv_rect = uv_rect.unwrap_or_default();
 
-        let advance_width_in_points = self
+        let advance_width_in_points = (self
             .ab_glyph_font
             .as_scaled(self.scale_in_pixels as f32)
             .h_advance(glyph_id)
+            + self.embolden_in_pixels)
             / self.pixels_per_point;
 
         GlyphInfo {
@@ -320,6 +349,313 @@ impl FontImpl {
             uv_rect,
         }
     }
+
+    /// Allocate space in the atlas and let `draw` rasterize into it.
+    ///
+    /// `draw` is given a function to call with the coverage of each pixel,
+    /// which may be called several times for the same pixel.
+    ///
+    /// For synthetic bold, the coverage is smeared [`Self::embolden_in_pixels`] to the right,
+    /// which also makes the glyph wider.
+    ///
+    /// Returns the atlas page, the top left corner in it, and the resulting width.
+    fn allocate_in_atlas(
+        &self,
+        width: usize,
+        height: usize,
+        draw: impl FnOnce(&mut dyn FnMut(usize, usize, f32)),
+    ) -> (u16, (usize, usize), usize) {
+        let extra_width = self.embolden_in_pixels.ceil() as usize;
+
+        let atlas = &mut self.atlas.lock();
+        let (page, glyph_pos, image) = atlas.allocate((width + extra_width, height));
+
+        if extra_width == 0 {
+            draw(&mut |x, y, v| {
+                if 0.0 < v {
+                    let pixel = &mut image[(glyph_pos.0 + x, glyph_pos.1 + y)];
+                    *pixel = (*pixel + v).min(1.0);
+                }
+            });
+        } else {
+            let mut coverage = vec![0.0_f32; width * height];
+            draw(&mut |x, y, v| {
+                let pixel = &mut coverage[y * width + x];
+                *pixel = (*pixel + v).min(1.0);
+            });
+
+            let whole_pixels = self.embolden_in_pixels.floor() as usize;
+            let fraction = self.embolden_in_pixels.fract();
+            for y in 0..height {
+                let row = &coverage[y * width..(y + 1) * width];
+                let coverage_at = |x: usize, dx: usize| {
+                    x.checked_sub(dx)
+                        .and_then(|x| row.get(x))
+                        .copied()
+                        .unwrap_or_default()
+                };
+                for x in 0..width + extra_width {
+                    let v = (0..=whole_pixels)
+                        .map(|dx| coverage_at(x, dx))
+                        .fold(fraction * coverage_at(x, whole_pixels + 1), f32::max);
+                    if 0.0 < v {
+                        image[(glyph_pos.0 + x, glyph_pos.1 + y)] = v;
+                    }
+                }
+            }
+        }
+
+        (page as u16, glyph_pos, width + extra_width)
+    }
+
+    /// Forget these glyphs, which have been evicted from the atlas.
+    pub(crate) fn forget_glyphs(&self, evicted: &ahash::HashSet<AtlasSlot>) {
+        let is_evicted =
+            |uv_rect: &UvRect| !uv_rect.is_nothing() && evicted.contains(&uv_rect.atlas_slot());
+        self.glyph_info_cache
+            .write()
+            .retain(|_, glyph_info| !is_evicted(&glyph_info.uv_rect));
+        #[cfg(feature = "text_shaping")]
+        self.cluster_cache
+            .write()
+            .retain(|_, uv_rect| !is_evicted(uv_rect));
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// One glyph of a shaped cluster, positioned relative to the start of the cluster.
+///
+/// The offset is in physical pixels, quantized to [`CLUSTER_SUBPIXELS`] steps per pixel
+/// so that it can be used as a cache key.
+#[cfg(feature = "text_shaping")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ClusterGlyph {
+    id: u16,
+    x: i32,
+    y: i32,
+}
+
+#[cfg(feature = "text_shaping")]
+use shaping_face::ShapingFace;
+
+#[cfg(feature = "text_shaping")]
+mod shaping_face {
+    // The code generated by `self_cell!` trips this lint.
+    #![allow(clippy::mem_forget)]
+
+    use std::sync::Arc;
+
+    use crate::text::FontData;
+
+    type OptionalFace<'a> = Option<rustybuzz::Face<'a>>;
+
+    self_cell::self_cell!(
+        /// A [`rustybuzz::Face`] together with the font file it borrows.
+        ///
+        /// Parsing the font is much slower than shaping a short run of text, so we only do it once.
+        /// It is `None` if the font could not be parsed.
+        pub(super) struct ShapingFace {
+            owner: Arc<FontData>,
+
+            #[covariant]
+            dependent: OptionalFace,
+        }
+    );
+
+    impl ShapingFace {
+        pub(super) fn parse(font_data: Arc<FontData>, variations: &[([u8; 4], f32)]) -> Self {
+            Self::new(font_data, |font_data| {
+                let mut face = rustybuzz::Face::from_slice(&font_data.font, font_data.index)?;
+                let variations: Vec<rustybuzz::Variation> = variations
+                    .iter()
+                    .map(|&(tag, value)| rustybuzz::Variation {
+                        tag: rustybuzz::ttf_parser::Tag::from_bytes(&tag),
+                        value,
+                    })
+                    .collect();
+                face.set_variations(&variations);
+                Some(face)
+            })
+        }
+    }
+}
+
+#[cfg(feature = "text_shaping")]
+const CLUSTER_SUBPIXELS: f32 = 4.0;
+
+/// A grapheme cluster (one or more `char`s), as shaped by [`FontImpl::shape`].
+#[cfg(feature = "text_shaping")]
+#[derive(Clone, Debug)]
+pub(crate) struct ShapedCluster {
+    /// Which bytes of the shaped text this cluster covers.
+    pub byte_range: std::ops::Range<usize>,
+
+    /// Unit: points.
+    pub advance_width: f32,
+
+    /// All the glyphs of the cluster, rasterized together.
+    pub uv_rect: UvRect,
+
+    /// The font is missing a glyph for (some of) the cluster.
+    pub missing: bool,
+}
+
+#[cfg(feature = "text_shaping")]
+impl FontImpl {
+    /// Does the font have a glyph for this character?
+    ///
+    /// Unlike [`Self::glyph_info`] this does not allocate anything in the atlas.
+    fn has_codepoint(&self, c: char) -> bool {
+        use ab_glyph::Font as _;
+        !self.ignore_character(c) && self.ab_glyph_font.glyph_id(c).0 != 0
+    }
+
+    /// Shape a run of text that uses only this font, returning its clusters in logical order.
+    ///
+    /// The text should not contain any newlines, and should all be in the same direction.
+    pub(crate) fn shape(&self, text: &str, rtl: bool) -> Vec<ShapedCluster> {
+        use ab_glyph::Font as _;
+
+        let Some(face) = self.shaping_face.borrow_dependent() else {
+            return vec![ShapedCluster {
+                byte_range: 0..text.len(),
+                advance_width: 0.0,
+                uv_rect: UvRect::default(),
+                missing: true,
+            }];
+        };
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        buffer.set_direction(if rtl {
+            rustybuzz::Direction::RightToLeft
+        } else {
+            rustybuzz::Direction::LeftToRight
+        });
+        let output = rustybuzz::shape(face, &[], buffer);
+
+        // Same scale as `ab_glyph` uses for rasterization:
+        let pixels_per_unit = self.scale_in_pixels as f32 / self.ab_glyph_font.height_unscaled();
+
+        let infos = output.glyph_infos();
+        let positions = output.glyph_positions();
+
+        let mut clusters = Vec::new();
+        let mut glyphs = Vec::new();
+        let mut i = 0;
+        while i < infos.len() {
+            let cluster = infos[i].cluster;
+            let mut pen_x = 0.0;
+            let mut missing = false;
+            glyphs.clear();
+
+            while i < infos.len() && infos[i].cluster == cluster {
+                let (info, pos) = (&infos[i], &positions[i]);
+                missing |= info.glyph_id == 0;
+                let x = pen_x + pos.x_offset as f32 * pixels_per_unit;
+                let y = -pos.y_offset as f32 * pixels_per_unit;
+                glyphs.push(ClusterGlyph {
+                    id: info.glyph_id as u16,
+                    x: (x * CLUSTER_SUBPIXELS).round() as i32,
+                    y: (y * CLUSTER_SUBPIXELS).round() as i32,
+                });
+                pen_x += pos.x_advance as f32 * pixels_per_unit;
+                if pos.x_advance != 0 {
+                    pen_x += self.embolden_in_pixels;
+                }
+                i += 1;
+            }
+
+            let uv_rect = if missing {
+                UvRect::default()
+            } else {
+                self.cluster_uv_rect(&glyphs)
+            };
+
+            clusters.push(ShapedCluster {
+                byte_range: cluster as usize..cluster as usize, // end is set below
+                advance_width: pen_x / self.pixels_per_point,
+                uv_rect,
+                missing,
+            });
+        }
+
+        // Right-to-left text is shaped in visual order:
+        if rtl {
+            clusters.reverse();
+        }
+        for i in 0..clusters.len() {
+            clusters[i].byte_range.end = clusters
+                .get(i + 1)
+                .map_or(text.len(), |next| next.byte_range.start);
+        }
+
+        clusters
+    }
+
+    fn cluster_uv_rect(&self, glyphs: &[ClusterGlyph]) -> UvRect {
+        if let Some(uv_rect) = self.cluster_cache.read().get(glyphs) {
+            return *uv_rect;
+        }
+        let uv_rect = self.allocate_cluster(glyphs);
+        self.cluster_cache.write().insert(glyphs.to_vec(), uv_rect);
+        uv_rect
+    }
+
+    /// Rasterize several glyphs into one region of the atlas.
+    fn allocate_cluster(&self, glyphs: &[ClusterGlyph]) -> UvRect {
+        use ab_glyph::Font as _;
+
+        let outlines: Vec<ab_glyph::OutlinedGlyph> = glyphs
+            .iter()
+            .filter_map(|glyph| {
+                let position = ab_glyph::point(
+                    glyph.x as f32 / CLUSTER_SUBPIXELS,
+                    glyph.y as f32 / CLUSTER_SUBPIXELS,
+                );
+                let glyph = ab_glyph::GlyphId(glyph.id)
+                    .with_scale_and_position(self.scale_in_pixels as f32, position);
+                self.ab_glyph_font.outline_glyph(glyph)
+            })
+            .collect();
+
+        let Some(bounds) = outlines
+            .iter()
+            .map(|outline| outline.px_bounds())
+            .reduce(|a, b| ab_glyph::Rect {
+                min: ab_glyph::point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+                max: ab_glyph::point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+            })
+        else {
+            return UvRect::default();
+        };
+
+        let width = bounds.width() as usize;
+        let height = bounds.height() as usize;
+        if width == 0 || height == 0 {
+            return UvRect::default();
+        }
+
+        let (page, glyph_pos, width) = self.allocate_in_atlas(width, height, |put| {
+            for outline in &outlines {
+                let bb = outline.px_bounds();
+                let dx = (bb.min.x - bounds.min.x) as usize;
+                let dy = (bb.min.y - bounds.min.y) as usize;
+                outline.draw(|x, y, v| put(dx + x as usize, dy + y as usize, v));
+            }
+        });
+
+        let offset_in_pixels = vec2(bounds.min.x, bounds.min.y);
+        UvRect {
+            offset: offset_in_pixels / self.pixels_per_point + self.y_offset_in_points * Vec2::Y,
+            size: vec2(width as f32, height as f32) / self.pixels_per_point,
+            min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+            max: [(glyph_pos.0 + width) as u16, (glyph_pos.1 + height) as u16],
+            page,
+        }
+    }
 }
 
 type FontIndex = usize;
@@ -363,22 +699,38 @@ impl Font {
             glyph_info_cache: Default::default(),
         };
 
+        slf.replacement_glyph = slf.find_replacement_glyph();
+
+        slf
+    }
+
+    fn find_replacement_glyph(&mut self) -> (FontIndex, GlyphInfo) {
         const PRIMARY_REPLACEMENT_CHAR: char = '◻'; // white medium square
         const FALLBACK_REPLACEMENT_CHAR: char = '?'; // fallback for the fallback
 
-        let replacement_glyph = slf
-            .glyph_info_no_cache_or_fallback(PRIMARY_REPLACEMENT_CHAR)
-            .or_else(|| slf.glyph_info_no_cache_or_fallback(FALLBACK_REPLACEMENT_CHAR))
+        self.glyph_info_no_cache_or_fallback(PRIMARY_REPLACEMENT_CHAR)
+            .or_else(|| self.glyph_info_no_cache_or_fallback(FALLBACK_REPLACEMENT_CHAR))
             .unwrap_or_else(|| {
                 #[cfg(feature = "log")]
                 log::warn!(
                     "Failed to find replacement characters {PRIMARY_REPLACEMENT_CHAR:?} or {FALLBACK_REPLACEMENT_CHAR:?}. Will use empty glyph."
                 );
                 (0, GlyphInfo::default())
-            });
-        slf.replacement_glyph = replacement_glyph;
+            })
+    }
 
-        slf
+    /// Forget these glyphs, which have been evicted from the atlas.
+    ///
+    /// Call [`FontImpl::forget_glyphs`] first, so that they are rasterized again when next used.
+    pub(crate) fn forget_glyphs(&mut self, evicted: &ahash::HashSet<AtlasSlot>) {
+        let is_evicted = |glyph_info: &GlyphInfo| {
+            !glyph_info.uv_rect.is_nothing() && evicted.contains(&glyph_info.uv_rect.atlas_slot())
+        };
+        self.glyph_info_cache
+            .retain(|_, (_, glyph_info)| !is_evicted(glyph_info));
+        if is_evicted(&self.replacement_glyph.1) {
+            self.replacement_glyph = self.find_replacement_glyph();
+        }
     }
 
     pub fn preload_characters(&mut self, s: &str) {
@@ -487,6 +839,99 @@ impl Font {
     }
 }
 
+#[cfg(feature = "text_shaping")]
+impl Font {
+    /// Split a paragraph into runs of text that can each be shaped with a single [`FontImpl`].
+    ///
+    /// Each character uses the first font that supports it, except that characters
+    /// extending a cluster (combining marks, joiners, variation selectors, …)
+    /// stay with the font of the cluster they extend.
+    ///
+    /// Characters that we lay out ourselves (like `\t`) get a run of their own with no font.
+    pub(crate) fn shaping_runs(
+        &self,
+        text: &str,
+    ) -> Vec<(std::ops::Range<usize>, Option<Arc<FontImpl>>)> {
+        let mut runs: Vec<(std::ops::Range<usize>, Option<FontIndex>)> = vec![];
+        let mut prev_chr = None;
+
+        for (byte_index, chr) in text.char_indices() {
+            let byte_range = byte_index..byte_index + chr.len_utf8();
+
+            let font_index = if self.fonts.is_empty() || !is_shapable(chr) {
+                None
+            } else {
+                match runs.last() {
+                    Some(&(_, Some(current)))
+                        if extends_cluster(chr, prev_chr)
+                            && (is_default_ignorable(chr)
+                                || self.fonts[current].has_codepoint(chr)) =>
+                    {
+                        Some(current)
+                    }
+                    _ => Some(self.shaping_font_index(chr)),
+                }
+            };
+
+            match runs.last_mut() {
+                Some((range, Some(current))) if font_index == Some(*current) => {
+                    range.end = byte_range.end;
+                }
+                _ => runs.push((byte_range, font_index)),
+            }
+            prev_chr = Some(chr);
+        }
+
+        runs.into_iter()
+            .map(|(range, font_index)| (range, font_index.map(|i| self.fonts[i].clone())))
+            .collect()
+    }
+
+    /// The first font with a glyph for this character, found without rasterizing the glyph.
+    ///
+    /// Characters that none of the fonts have go with the font of the replacement glyph.
+    fn shaping_font_index(&self, c: char) -> FontIndex {
+        if let Some(&(font_index, _)) = self.glyph_info_cache.get(&c) {
+            return font_index;
+        }
+        (self.fonts.iter())
+            .position(|font_impl| {
+                font_impl.has_codepoint(c) || (invisible_char(c) && !font_impl.ignore_character(c))
+            })
+            .unwrap_or(self.replacement_glyph.0)
+    }
+}
+
+/// Characters that we lay out ourselves instead of sending them to the shaper.
+#[cfg(feature = "text_shaping")]
+fn is_shapable(c: char) -> bool {
+    !matches!(c, '\t' | '\r' | '\u{2009}')
+}
+
+/// Does this character belong to the same cluster as the previous one?
+#[cfg(feature = "text_shaping")]
+fn extends_cluster(c: char, prev: Option<char>) -> bool {
+    use unicode_properties::{GeneralCategoryGroup, UnicodeGeneralCategory as _};
+
+    const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+    prev == Some(ZERO_WIDTH_JOINER)
+        || c == ZERO_WIDTH_JOINER
+        || c == '\u{200C}' // ZERO WIDTH NON-JOINER
+        || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c) // Emoji skin tone modifiers
+        || ('\u{E0020}'..='\u{E007F}').contains(&c) // Tags, used in emoji flag sequences
+        || c.general_category_group() == GeneralCategoryGroup::Mark
+}
+
+/// Characters that are never visible on their own, so fonts often lack glyphs for them.
+#[cfg(feature = "text_shaping")]
+fn is_default_ignorable(c: char) -> bool {
+    invisible_char(c)
+        || ('\u{FE00}'..='\u{FE0F}').contains(&c) // Variation selectors
+        || ('\u{E0100}'..='\u{E01EF}').contains(&c) // Variation selectors supplement
+        || ('\u{E0020}'..='\u{E007F}').contains(&c) // Tags
+}
+
 /// Code points that will always be invisible (zero width).
 ///
 /// See also [`FontImpl::ignore_character`].
@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Weak},
+};
 
 use crate::{
     mutex::{Mutex, MutexGuard},
@@ -6,6 +9,7 @@ use crate::{
         font::{Font, FontImpl},
         Galley, LayoutJob,
     },
+    texture_atlas::AtlasSlot,
     TextureAtlas,
 };
 use emath::{NumExt as _, OrderedFloat};
@@ -24,7 +28,7 @@ pub struct FontId {
 
     /// What font family to use.
     pub family: FontFamily,
-    // TODO(emilk): weight (bold), italics, …
+    // TODO(emilk): italics, …
 }
 
 impl Default for FontId {
@@ -66,6 +70,36 @@ impl std::hash::Hash for FontId {
 
 // ----------------------------------------------------------------------------
 
+/// How thick the strokes of the text are, from 100 (thin) to 900 (black).
+///
+/// This uses the same scale as CSS and the `wght` axis of variable fonts.
+///
+/// See [`crate::text::TextFormat::weight`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const THIN: Self = Self(100);
+    pub const EXTRA_LIGHT: Self = Self(200);
+    pub const LIGHT: Self = Self(300);
+    pub const NORMAL: Self = Self(400);
+    pub const MEDIUM: Self = Self(500);
+    pub const SEMI_BOLD: Self = Self(600);
+    pub const BOLD: Self = Self(700);
+    pub const EXTRA_BOLD: Self = Self(800);
+    pub const BLACK: Self = Self(900);
+}
+
+impl Default for FontWeight {
+    #[inline]
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 /// Font of unknown size.
 ///
 /// Which style of font: [`Monospace`][`FontFamily::Monospace`], [`Proportional`][`FontFamily::Proportional`],
@@ -120,6 +154,9 @@ pub struct FontData {
 
     /// Extra scale and vertical tweak to apply to all text of this font.
     pub tweak: FontTweak,
+
+    /// Set with [`Self::variation`].
+    variations: Vec<([u8; 4], f32)>,
 }
 
 impl FontData {
@@ -128,6 +165,7 @@ impl FontData {
             font: std::borrow::Cow::Borrowed(font),
             index: 0,
             tweak: Default::default(),
+            variations: Vec::new(),
         }
     }
 
@@ -136,12 +174,84 @@ impl FontData {
             font: std::borrow::Cow::Owned(font),
             index: 0,
             tweak: Default::default(),
+            variations: Vec::new(),
         }
     }
 
     pub fn tweak(self, tweak: FontTweak) -> Self {
         Self { tweak, ..self }
     }
+
+    /// Set the value of one of the variation axes of a variable font, e.g. `*b"wdth"`.
+    ///
+    /// Axes that are not set keep their default value,
+    /// except `wght` which follows the weight of the text (see [`crate::text::TextFormat::weight`]).
+    ///
+    /// See also [`Self::variation_axes`].
+    pub fn variation(mut self, tag: [u8; 4], value: f32) -> Self {
+        self.variations.retain(|(t, _)| *t != tag);
+        self.variations.push((tag, value));
+        self
+    }
+
+    /// The values set with [`Self::variation`].
+    pub fn variations(&self) -> &[([u8; 4], f32)] {
+        &self.variations
+    }
+
+    /// The variation axes (`wght`, `wdth`, `slnt`, …) of a variable font.
+    ///
+    /// Empty for fonts that are not variable, or that fail to parse.
+    pub fn variation_axes(&self) -> Vec<FontVariationAxis> {
+        use ab_glyph::VariableFont as _;
+
+        ab_glyph::FontRef::try_from_slice_and_index(&self.font, self.index).map_or_else(
+            |_| Vec::new(),
+            |font| {
+                font.variations()
+                    .into_iter()
+                    .map(|axis| FontVariationAxis {
+                        tag: axis.tag,
+                        min: axis.min_value,
+                        default: axis.default_value,
+                        max: axis.max_value,
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// The nominal weight of the font, as declared in the font file.
+    #[cfg(feature = "font_weights")]
+    pub fn weight(&self) -> FontWeight {
+        self.face().map_or(FontWeight::NORMAL, |face| {
+            FontWeight(face.weight().to_number())
+        })
+    }
+
+    #[cfg(feature = "font_weights")]
+    fn face(&self) -> Option<ttf_parser::Face<'_>> {
+        ttf_parser::Face::parse(&self.font, self.index).ok()
+    }
+}
+
+/// One of the variation axes of a variable font.
+///
+/// See [`FontData::variation_axes`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FontVariationAxis {
+    /// E.g. `*b"wght"` (weight), `*b"wdth"` (width) or `*b"slnt"` (slant).
+    pub tag: [u8; 4],
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
+}
+
+impl FontVariationAxis {
+    /// The tag as a string, e.g. `"wght"`.
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(&self.tag).into_owned()
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -196,20 +306,170 @@ impl Default for FontTweak {
 
 // ----------------------------------------------------------------------------
 
-fn ab_glyph_font_from_font_data(name: &str, data: &FontData) -> ab_glyph::FontArc {
+fn ab_glyph_font_from_font_data(
+    name: &str,
+    data: &FontData,
+    variations: &[([u8; 4], f32)],
+) -> ab_glyph::FontArc {
+    use ab_glyph::VariableFont as _;
+
     match &data.font {
         std::borrow::Cow::Borrowed(bytes) => {
-            ab_glyph::FontRef::try_from_slice_and_index(bytes, data.index)
-                .map(ab_glyph::FontArc::from)
+            ab_glyph::FontRef::try_from_slice_and_index(bytes, data.index).map(|mut font| {
+                for (tag, value) in variations {
+                    font.set_variation(tag, *value);
+                }
+                ab_glyph::FontArc::from(font)
+            })
         }
         std::borrow::Cow::Owned(bytes) => {
-            ab_glyph::FontVec::try_from_vec_and_index(bytes.clone(), data.index)
-                .map(ab_glyph::FontArc::from)
+            ab_glyph::FontVec::try_from_vec_and_index(bytes.clone(), data.index).map(|mut font| {
+                for (tag, value) in variations {
+                    font.set_variation(tag, *value);
+                }
+                ab_glyph::FontArc::from(font)
+            })
         }
     }
     .unwrap_or_else(|err| panic!("Error parsing {name:?} TTF/OTF font file: {err}"))
 }
 
+/// What we need to know about a font file in order to pick the right face for a [`FontWeight`].
+#[derive(Clone, Debug)]
+struct FaceInfo {
+    /// The name of the typeface, shared by e.g. "Inter Regular" and "Inter Bold".
+    typeface: String,
+
+    /// The nominal weight of the font.
+    weight: FontWeight,
+
+    /// The range of the `wght` axis, for variable fonts.
+    weight_axis: Option<(f32, f32)>,
+
+    /// The values of all axes for each named instance (e.g. "Bold") of a variable font.
+    instances: Vec<Vec<([u8; 4], f32)>>,
+}
+
+impl FaceInfo {
+    fn new(name: &str, data: &FontData) -> Self {
+        let weight_axis = data
+            .variation_axes()
+            .into_iter()
+            .find(|axis| &axis.tag == b"wght")
+            .map(|axis| (axis.min, axis.max));
+
+        // Fonts without a family name are never faces of the same typeface:
+        let unique_typeface = || format!("\0{name}");
+
+        #[cfg(feature = "font_weights")]
+        {
+            let face = data.face();
+
+            let typeface = face
+                .as_ref()
+                .and_then(|face| {
+                    let names = face.names();
+                    [
+                        ttf_parser::name_id::TYPOGRAPHIC_FAMILY,
+                        ttf_parser::name_id::FAMILY,
+                    ]
+                    .into_iter()
+                    .find_map(|name_id| {
+                        names
+                            .into_iter()
+                            .filter(|name| name.name_id == name_id && name.is_unicode())
+                            .find_map(|name| name.to_string())
+                    })
+                })
+                .unwrap_or_else(unique_typeface);
+
+            Self {
+                typeface,
+                weight: data.weight(),
+                weight_axis,
+                instances: face.as_ref().map_or_else(Vec::new, named_instances),
+            }
+        }
+
+        #[cfg(not(feature = "font_weights"))]
+        Self {
+            typeface: unique_typeface(),
+            weight: FontWeight::NORMAL,
+            weight_axis,
+            instances: Vec::new(),
+        }
+    }
+
+    /// The weight closest to `weight` that this font can produce.
+    fn closest_weight(&self, weight: FontWeight) -> f32 {
+        let weight = weight.0 as f32;
+        match self.weight_axis {
+            Some((min, max)) => weight.clamp(min, max),
+            None => self.weight.0 as f32,
+        }
+    }
+
+    /// How to set the axes of a variable font for text of the given weight.
+    ///
+    /// Uses the named instance of that weight if there is one, so that e.g. its width matches too.
+    fn weight_variations(&self, weight: FontWeight) -> Option<Vec<([u8; 4], f32)>> {
+        self.weight_axis?;
+        let weight = self.closest_weight(weight);
+        let instance = self.instances.iter().find(|instance| {
+            instance
+                .iter()
+                .any(|(tag, value)| tag == b"wght" && (value - weight).abs() < 0.5)
+        });
+        Some(
+            instance
+                .cloned()
+                .unwrap_or_else(|| vec![(*b"wght", weight)]),
+        )
+    }
+}
+
+/// The values of all axes for each named instance of a variable font, from its `fvar` table.
+#[cfg(feature = "font_weights")]
+fn named_instances(face: &ttf_parser::Face<'_>) -> Vec<Vec<([u8; 4], f32)>> {
+    let Some(fvar) = face.raw_face().table(ttf_parser::Tag::from_bytes(b"fvar")) else {
+        return Vec::new();
+    };
+    let u16_at = |offset: usize| {
+        fvar.get(offset..offset + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    let fixed_at = |offset: usize| {
+        let bytes: [u8; 4] = fvar.get(offset..offset + 4)?.try_into().ok()?;
+        Some(i32::from_be_bytes(bytes) as f32 / 65536.0)
+    };
+
+    let (Some(axes_offset), Some(axis_count), Some(axis_size), Some(count), Some(size)) =
+        (u16_at(4), u16_at(8), u16_at(10), u16_at(12), u16_at(14))
+    else {
+        return Vec::new();
+    };
+    let tags: Option<Vec<[u8; 4]>> = (0..axis_count)
+        .map(|i| {
+            let offset = axes_offset + i * axis_size;
+            fvar.get(offset..offset + 4)?.try_into().ok()
+        })
+        .collect();
+    let Some(tags) = tags else {
+        return Vec::new();
+    };
+
+    // Each instance has a name id and flags, followed by the value of each axis:
+    let instances_offset = axes_offset + axis_count * axis_size;
+    (0..count)
+        .filter_map(|i| {
+            let coordinates_offset = instances_offset + i * size + 4;
+            (tags.iter().enumerate())
+                .map(|(axis, tag)| Some((*tag, fixed_at(coordinates_offset + 4 * axis)?)))
+                .collect()
+        })
+        .collect()
+}
+
 /// Describes the font data and the sizes to use.
 ///
 /// Often you would start with [`FontDefinitions::default()`] and then add/change the contents.
@@ -453,16 +713,39 @@ impl Fonts {
         if needs_recreate {
             let definitions = fonts_and_cache.fonts.definitions.clone();
 
+            let fonts = FontsImpl::new(pixels_per_point, max_texture_side, definitions);
+            fonts
+                .atlas
+                .lock()
+                .inherit(&mut fonts_and_cache.fonts.atlas.lock());
+
             *fonts_and_cache = FontsAndCache {
-                fonts: FontsImpl::new(pixels_per_point, max_texture_side, definitions),
+                fonts,
                 galley_cache: Default::default(),
             };
         }
 
-        fonts_and_cache.galley_cache.flush_cache();
+        let FontsAndCache {
+            fonts,
+            galley_cache,
+        } = &mut *fonts_and_cache;
+
+        galley_cache.flush_cache();
+
+        let evicted = {
+            let mut atlas = fonts.atlas.lock();
+            galley_cache.mark_glyphs_used(&mut atlas);
+            atlas.begin_pass()
+        };
+        if !evicted.is_empty() {
+            fonts.forget_atlas_glyphs(&evicted);
+        }
     }
 
     /// Call at the end of each frame (before painting) to get the change to the font texture since last call.
+    ///
+    /// This is only the first page of the font atlas.
+    /// Use [`TextureAtlas::take_page_deltas`] to get the changes to all pages.
     pub fn font_image_delta(&self) -> Option<crate::ImageDelta> {
         self.lock().fonts.atlas.lock().take_delta()
     }
@@ -626,7 +909,7 @@ pub struct FontsImpl {
     definitions: FontDefinitions,
     atlas: Arc<Mutex<TextureAtlas>>,
     font_impl_cache: FontImplCache,
-    sized_family: ahash::HashMap<(OrderedFloat<f32>, FontFamily), Font>,
+    sized_family: ahash::HashMap<(OrderedFloat<f32>, FontFamily, FontWeight), Font>,
 }
 
 impl FontsImpl {
@@ -673,19 +956,31 @@ impl FontsImpl {
 
     /// Get the right font implementation from size and [`FontFamily`].
     pub fn font(&mut self, font_id: &FontId) -> &mut Font {
+        self.font_with_weight(font_id, FontWeight::NORMAL)
+    }
+
+    /// Get the right font implementation from size, [`FontFamily`] and [`FontWeight`].
+    ///
+    /// Of the fonts in the family that are faces of the same typeface (e.g. "Inter Regular" and "Inter Bold"),
+    /// only the one closest to the requested weight is used.
+    /// Variable fonts are set to the requested weight.
+    /// If that is still not bold enough, the glyphs are emboldened synthetically.
+    pub fn font_with_weight(&mut self, font_id: &FontId, weight: FontWeight) -> &mut Font {
         let FontId { mut size, family } = font_id;
         size = size.at_least(0.1).at_most(2048.0);
 
         self.sized_family
-            .entry((OrderedFloat(size), family.clone()))
+            .entry((OrderedFloat(size), family.clone(), weight))
             .or_insert_with(|| {
                 let fonts = &self.definitions.families.get(family);
                 let fonts = fonts
                     .unwrap_or_else(|| panic!("FontFamily::{family:?} is not bound to any fonts"));
 
-                let fonts: Vec<Arc<FontImpl>> = fonts
-                    .iter()
-                    .map(|font_name| self.font_impl_cache.font_impl(size, font_name))
+                let fonts: Vec<Arc<FontImpl>> = self
+                    .font_impl_cache
+                    .faces_by_weight(fonts, weight)
+                    .into_iter()
+                    .map(|font_name| self.font_impl_cache.font_impl(size, &font_name, weight))
                     .collect();
 
                 Font::new(fonts)
@@ -713,6 +1008,17 @@ impl FontsImpl {
     fn row_height(&mut self, font_id: &FontId) -> f32 {
         self.font(font_id).row_height()
     }
+
+    /// Forget these glyphs, which have been evicted from the font atlas.
+    fn forget_atlas_glyphs(&mut self, evicted: &[AtlasSlot]) {
+        let evicted: ahash::HashSet<AtlasSlot> = evicted.iter().copied().collect();
+        for font_impl in self.font_impl_cache.cache.values() {
+            font_impl.forget_glyphs(&evicted);
+        }
+        for font in self.sized_family.values_mut() {
+            font.forget_glyphs(&evicted);
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -728,6 +1034,12 @@ struct GalleyCache {
     /// Frame counter used to do garbage collection on the cache
     generation: u32,
     cache: nohash_hasher::IntMap<u64, CachedGalley>,
+
+    /// The glyphs of each galley on other pages of the font atlas than the first (which are never evicted).
+    ///
+    /// The galleys are held weakly, so that a galley kept outside of the cache
+    /// keeps its glyphs from being evicted for as long as it lives.
+    atlas_glyphs: Vec<(Weak<Galley>, Vec<AtlasSlot>)>,
 }
 
 impl GalleyCache {
@@ -768,6 +1080,19 @@ impl GalleyCache {
             std::collections::hash_map::Entry::Vacant(entry) => {
                 let galley = super::layout(fonts, job.into());
                 let galley = Arc::new(galley);
+
+                let mut atlas_glyphs: Vec<AtlasSlot> = (galley.rows.iter())
+                    .flat_map(|row| row.glyphs.iter().map(|glyph| glyph.uv_rect))
+                    .filter(|uv_rect| uv_rect.page != 0 && !uv_rect.is_nothing())
+                    .map(|uv_rect| uv_rect.atlas_slot())
+                    .collect();
+                if !atlas_glyphs.is_empty() {
+                    atlas_glyphs.sort_unstable();
+                    atlas_glyphs.dedup();
+                    self.atlas_glyphs
+                        .push((Arc::downgrade(&galley), atlas_glyphs));
+                }
+
                 entry.insert(CachedGalley {
                     last_used: self.generation,
                     galley: galley.clone(),
@@ -781,6 +1106,17 @@ impl GalleyCache {
         self.cache.len()
     }
 
+    /// Keep the glyphs of all galleys that are still alive in the font atlas.
+    fn mark_glyphs_used(&mut self, atlas: &mut TextureAtlas) {
+        self.atlas_glyphs.retain(|(galley, glyphs)| {
+            let alive = galley.strong_count() > 0;
+            if alive {
+                atlas.mark_used(glyphs);
+            }
+            alive
+        });
+    }
+
     /// Must be called once per frame to clear the [`Galley`] cache.
     pub fn flush_cache(&mut self) {
         let current_generation = self.generation;
@@ -798,8 +1134,16 @@ struct FontImplCache {
     pixels_per_point: f32,
     ab_glyph_fonts: BTreeMap<String, (FontTweak, ab_glyph::FontArc)>,
 
-    /// Map font pixel sizes and names to the cached [`FontImpl`].
-    cache: ahash::HashMap<(u32, String), Arc<FontImpl>>,
+    /// The font files, needed for shaping and for applying variations.
+    font_data: BTreeMap<String, Arc<FontData>>,
+
+    faces: BTreeMap<String, FaceInfo>,
+
+    /// Variable fonts with their `wght` axis set to something else than the default.
+    weighted_ab_glyph_fonts: ahash::HashMap<(String, FontWeight), ab_glyph::FontArc>,
+
+    /// Map font pixel sizes, names and weights to the cached [`FontImpl`].
+    cache: ahash::HashMap<(u32, String, FontWeight), Arc<FontImpl>>,
 }
 
 impl FontImplCache {
@@ -812,20 +1156,66 @@ impl FontImplCache {
             .iter()
             .map(|(name, font_data)| {
                 let tweak = font_data.tweak;
-                let ab_glyph = ab_glyph_font_from_font_data(name, font_data);
+                let ab_glyph = ab_glyph_font_from_font_data(name, font_data, &font_data.variations);
                 (name.clone(), (tweak, ab_glyph))
             })
             .collect();
 
+        let faces = font_data
+            .iter()
+            .map(|(name, font_data)| (name.clone(), FaceInfo::new(name, font_data)))
+            .collect();
+
         Self {
             atlas,
             pixels_per_point,
             ab_glyph_fonts,
+            font_data: font_data.clone(),
+            faces,
+            weighted_ab_glyph_fonts: Default::default(),
             cache: Default::default(),
         }
     }
 
-    pub fn font_impl(&mut self, scale_in_points: f32, font_name: &str) -> Arc<FontImpl> {
+    fn face_info(&self, font_name: &str) -> &FaceInfo {
+        self.faces
+            .get(font_name)
+            .unwrap_or_else(|| panic!("No font data found for {font_name:?}"))
+    }
+
+    /// Of the fonts that are faces of the same typeface, put the ones that best match `weight` first.
+    ///
+    /// The other faces are kept as fallbacks, for the characters the best ones are missing.
+    /// The order of the typefaces is kept.
+    fn faces_by_weight(&self, font_names: &[String], weight: FontWeight) -> Vec<String> {
+        let distance = |font_name: &str| {
+            (self.face_info(font_name).closest_weight(weight) - weight.0 as f32).abs()
+        };
+
+        let mut typefaces: Vec<Vec<&String>> = vec![];
+        for font_name in font_names {
+            let typeface = &self.face_info(font_name).typeface;
+            if let Some(faces) = typefaces
+                .iter_mut()
+                .find(|faces| &self.face_info(faces[0]).typeface == typeface)
+            {
+                faces.push(font_name);
+            } else {
+                typefaces.push(vec![font_name]);
+            }
+        }
+        for faces in &mut typefaces {
+            faces.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+        }
+        typefaces.into_iter().flatten().cloned().collect()
+    }
+
+    pub fn font_impl(
+        &mut self,
+        scale_in_points: f32,
+        font_name: &str,
+        weight: FontWeight,
+    ) -> Arc<FontImpl> {
         use ab_glyph::Font as _;
 
         let (tweak, ab_glyph_font) = self
@@ -834,6 +1224,47 @@ impl FontImplCache {
             .unwrap_or_else(|| panic!("No font data found for {font_name:?}"))
             .clone();
 
+        let face = self.face_info(font_name);
+
+        // Text of normal weight uses the fonts as they are.
+        let weight_variations = if weight == FontWeight::NORMAL {
+            None
+        } else {
+            face.weight_variations(weight)
+        };
+
+        // If the font can't get bold enough, we fake it:
+        let missing_weight = if weight > FontWeight::NORMAL {
+            weight.0 as f32 - face.closest_weight(weight)
+        } else {
+            0.0
+        };
+        let embolden = if 100.0 <= missing_weight {
+            missing_weight / 300.0
+        } else {
+            0.0
+        };
+
+        let font_data = self.font_data[font_name].clone();
+        let mut variations = font_data.variations.clone();
+        let ab_glyph_font = if let Some(weight_variations) = weight_variations {
+            for (tag, value) in weight_variations {
+                // The values set by the user win, except for the weight:
+                if &tag == b"wght" {
+                    variations.retain(|(t, _)| *t != tag);
+                } else if variations.iter().any(|(t, _)| *t == tag) {
+                    continue;
+                }
+                variations.push((tag, value));
+            }
+            self.weighted_ab_glyph_fonts
+                .entry((font_name.to_owned(), weight))
+                .or_insert_with(|| ab_glyph_font_from_font_data(font_name, &font_data, &variations))
+                .clone()
+        } else {
+            ab_glyph_font
+        };
+
         let scale_in_pixels = self.pixels_per_point * scale_in_points;
 
         // Scale the font properly (see https://github.com/emilk/egui/issues/2068).
@@ -843,10 +1274,14 @@ impl FontImplCache {
         let font_scaling = ab_glyph_font.height_unscaled() / units_per_em;
         let scale_in_pixels = scale_in_pixels * font_scaling;
 
+        // Synthetic bold makes the strokes about 1/24 em thicker for going from normal to bold:
+        let embolden_in_pixels = embolden * scale_in_pixels * tweak.scale / 24.0;
+
         self.cache
             .entry((
                 (scale_in_pixels * tweak.scale).round() as u32,
                 font_name.to_owned(),
+                weight,
             ))
             .or_insert_with(|| {
                 Arc::new(FontImpl::new(
@@ -854,10 +1289,117 @@ impl FontImplCache {
                     self.pixels_per_point,
                     font_name.to_owned(),
                     ab_glyph_font,
+                    #[cfg(feature = "text_shaping")]
+                    font_data,
+                    #[cfg(feature = "text_shaping")]
+                    &variations,
                     scale_in_pixels,
                     tweak,
+                    embolden_in_pixels,
                 ))
             })
             .clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faces_of_other_weights_are_fallbacks() {
+        let mut definitions = FontDefinitions::default();
+        let ubuntu = definitions.font_data["Ubuntu-Light"].clone();
+        definitions
+            .font_data
+            .insert("Ubuntu-Light-copy".to_owned(), ubuntu);
+
+        let atlas = Arc::new(Mutex::new(TextureAtlas::new([1024, 32])));
+        let cache = FontImplCache::new(atlas, 1.0, &definitions.font_data);
+
+        // Two faces of the same typeface may cover different characters, so both are kept:
+        let names = ["Ubuntu-Light".to_owned(), "Ubuntu-Light-copy".to_owned()];
+        for weight in [FontWeight::LIGHT, FontWeight::NORMAL, FontWeight::BOLD] {
+            assert_eq!(cache.faces_by_weight(&names, weight), names);
+        }
+    }
+
+    #[test]
+    fn glyphs_of_living_galleys_are_not_evicted() {
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        fonts.texture_atlas().lock().set_max_unused_passes(2);
+
+        // Large glyphs, so that they don't all fit on the first page of the atlas:
+        let text = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        let layout = |size: f32| {
+            let font_id = FontId::proportional(size);
+            fonts.layout_no_wrap(text.to_owned(), font_id, crate::Color32::WHITE)
+        };
+        let glyphs_on_other_pages = |galley: &Galley| -> Vec<AtlasSlot> {
+            (galley.rows.iter())
+                .flat_map(|row| row.glyphs.iter().map(|glyph| glyph.uv_rect))
+                .filter(|uv_rect| uv_rect.page != 0 && !uv_rect.is_nothing())
+                .map(|uv_rect| uv_rect.atlas_slot())
+                .collect()
+        };
+
+        let first_page = layout(190.0);
+        assert!(glyphs_on_other_pages(&first_page).is_empty());
+        let dropped = glyphs_on_other_pages(&layout(200.0));
+        let kept_galley = layout(201.0);
+        let kept = glyphs_on_other_pages(&kept_galley);
+        assert!(!dropped.is_empty() && !kept.is_empty());
+
+        // The kept galley isn't used, but it is still alive:
+        for _ in 0..5 {
+            fonts.begin_pass(1.0, 1024);
+        }
+
+        let atlas = fonts.texture_atlas();
+        let atlas = atlas.lock();
+        assert!(kept.iter().all(|&slot| atlas.has_glyph(slot)));
+        assert!(!dropped.iter().any(|&slot| atlas.has_glyph(slot)));
+    }
+
+    #[test]
+    fn glyphs_are_rasterized_again_after_eviction() {
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        fonts.texture_atlas().lock().set_max_unused_passes(2);
+
+        let font_id = FontId::proportional(200.0);
+        let layout = |text: &str| {
+            fonts.layout_no_wrap(text.to_owned(), font_id.clone(), crate::Color32::WHITE)
+        };
+        let slots = |galley: &Galley| -> Vec<AtlasSlot> {
+            (galley.rows.iter())
+                .flat_map(|row| row.glyphs.iter().map(|glyph| glyph.uv_rect))
+                .filter(|uv_rect| !uv_rect.is_nothing())
+                .map(|uv_rect| uv_rect.atlas_slot())
+                .collect()
+        };
+
+        let latin = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        // Fill the first page, which is never evicted:
+        let _first_page = fonts.layout_no_wrap(
+            latin.to_owned(),
+            FontId::proportional(190.0),
+            crate::Color32::WHITE,
+        );
+        drop(layout(latin));
+        for _ in 0..5 {
+            fonts.begin_pass(1.0, 1024);
+        }
+
+        // Reuse the space of the evicted glyphs:
+        let other = layout("ΓΔΘΛΞΠΣΦΨΩαβγδεζηθλξπσφψωБГДЖЗИЙЛПФЦЧШЩЪЫЬЭЮЯбвгджзийлпфцчшщъыьэюя");
+        let latin = layout(latin);
+
+        let other = slots(&other);
+        let atlas = fonts.texture_atlas();
+        let atlas = atlas.lock();
+        for slot in slots(&latin) {
+            assert!(atlas.has_glyph(slot) || slot.page == 0);
+            assert!(!other.contains(&slot), "{slot:?} is used by another glyph");
+        }
+    }
+}
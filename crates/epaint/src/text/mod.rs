@@ -11,8 +11,8 @@ pub const TAB_SIZE: usize = 4;
 
 pub use {
     fonts::{
-        FontData, FontDefinitions, FontFamily, FontId, FontInsert, FontPriority, FontTweak, Fonts,
-        FontsImpl, InsertFontFamily,
+        FontData, FontDefinitions, FontFamily, FontId, FontInsert, FontPriority, FontTweak,
+        FontVariationAxis, FontWeight, Fonts, FontsImpl, InsertFontFamily,
     },
     text_layout::layout,
     text_layout_types::*,
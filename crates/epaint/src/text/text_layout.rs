@@ -1,11 +1,14 @@
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
 use emath::{pos2, vec2, Align, GuiRounding as _, NumExt, Pos2, Rect, Vec2};
 
 use crate::{stroke::PathStroke, text::font::Font, Color32, Mesh, Stroke, Vertex};
 
-use super::{FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals};
+use super::{
+    FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals, TextDecorationLine,
+    TextDecorationStyle,
+};
 
 // ----------------------------------------------------------------------------
 
@@ -52,19 +55,129 @@ struct Paragraph {
 
     /// In case of an empty paragraph ("\n"), use this as height.
     pub empty_paragraph_height: f32,
+
+    /// Is the base direction of this paragraph right-to-left?
+    pub is_rtl: bool,
 }
 
 impl Paragraph {
-    pub fn from_section_index(section_index_at_start: u32) -> Self {
+    pub fn from_section_index(section_index_at_start: u32, is_rtl: bool) -> Self {
         Self {
             cursor_x: 0.0,
             section_index_at_start,
             glyphs: vec![],
             empty_paragraph_height: 0.0,
+            is_rtl,
         }
     }
 }
 
+// ----------------------------------------------------------------------------
+
+/// The result of running the Unicode Bidirectional Algorithm on [`LayoutJob::text`].
+#[cfg(feature = "bidi")]
+struct BidiLevels {
+    /// One per byte of the text.
+    levels: Vec<unicode_bidi::Level>,
+
+    paragraphs: Vec<unicode_bidi::ParagraphInfo>,
+
+    /// Used for the empty paragraph after a trailing `\n`.
+    default_is_rtl: bool,
+}
+
+#[cfg(feature = "bidi")]
+impl BidiLevels {
+    /// Returns `None` if all the text is left-to-right.
+    fn new(job: &LayoutJob) -> Option<Self> {
+        use super::TextDirection;
+
+        let default_para_level = match job.base_direction {
+            TextDirection::Auto => None,
+            TextDirection::LeftToRight => Some(unicode_bidi::Level::ltr()),
+            TextDirection::RightToLeft => Some(unicode_bidi::Level::rtl()),
+        };
+
+        if job.base_direction != TextDirection::RightToLeft
+            && !job.text.chars().any(may_be_right_to_left)
+        {
+            return None; // Early-out for the common case
+        }
+
+        profiling::function_scope!();
+
+        let unicode_bidi::BidiInfo {
+            levels, paragraphs, ..
+        } = unicode_bidi::BidiInfo::new(&job.text, default_para_level);
+
+        Some(Self {
+            levels,
+            paragraphs,
+            default_is_rtl: job.base_direction == TextDirection::RightToLeft,
+        })
+    }
+
+    fn level(&self, byte_index: usize) -> u8 {
+        self.levels
+            .get(byte_index)
+            .map_or(0, |level| level.number())
+    }
+
+    /// Is the paragraph containing this byte right-to-left?
+    fn is_rtl_paragraph(&self, byte_index: usize) -> bool {
+        self.paragraphs
+            .iter()
+            .find(|paragraph| paragraph.range.contains(&byte_index))
+            .map_or(self.default_is_rtl, |paragraph| paragraph.level.is_rtl())
+    }
+}
+
+/// Without the `bidi` feature all text is laid out left-to-right.
+#[cfg(not(feature = "bidi"))]
+struct BidiLevels;
+
+#[cfg(not(feature = "bidi"))]
+#[allow(clippy::unused_self)]
+impl BidiLevels {
+    fn new(_job: &LayoutJob) -> Option<Self> {
+        None
+    }
+
+    fn level(&self, _byte_index: usize) -> u8 {
+        0
+    }
+
+    fn is_rtl_paragraph(&self, _byte_index: usize) -> bool {
+        false
+    }
+}
+
+/// Can this character start a right-to-left run?
+#[cfg(feature = "bidi")]
+fn may_be_right_to_left(chr: char) -> bool {
+    use unicode_bidi::BidiClass;
+
+    // Quick check: there are no right-to-left characters before Hebrew.
+    '\u{0590}' <= chr
+        && matches!(
+            unicode_bidi::bidi_class(chr),
+            BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+        )
+}
+
+/// Brackets etc are mirrored in right-to-left text.
+fn mirrored(chr: char, bidi_level: u8) -> char {
+    #[cfg(feature = "bidi")]
+    if bidi_level % 2 == 1 {
+        return unicode_bidi_mirroring::get_mirrored(chr).unwrap_or(chr);
+    }
+    #[cfg(not(feature = "bidi"))]
+    let _ = bidi_level;
+    chr
+}
+
+// ----------------------------------------------------------------------------
+
 /// Layout text into a [`Galley`].
 ///
 /// In most cases you should use [`crate::Fonts::layout_job`] instead
@@ -86,9 +199,19 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
 
     // For most of this we ignore the y coordinate:
 
-    let mut paragraphs = vec![Paragraph::from_section_index(0)];
+    let bidi = BidiLevels::new(&job);
+
+    let is_rtl = bidi.as_ref().is_some_and(|bidi| bidi.is_rtl_paragraph(0));
+    let mut paragraphs = vec![Paragraph::from_section_index(0, is_rtl)];
     for (section_index, section) in job.sections.iter().enumerate() {
-        layout_section(fonts, &job, section_index as u32, section, &mut paragraphs);
+        layout_section(
+            fonts,
+            &job,
+            bidi.as_ref(),
+            section_index as u32,
+            section,
+            &mut paragraphs,
+        );
     }
 
     let point_scale = PointScale::new(fonts.pixels_per_point());
@@ -121,6 +244,12 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
         }
     }
 
+    if bidi.is_some() {
+        for row in &mut rows {
+            reorder_row_visually(point_scale, row);
+        }
+    }
+
     // Calculate the Y positions and tessellate the text:
     galley_from_rows(point_scale, job, rows, elided)
 }
@@ -129,6 +258,7 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
 fn layout_section(
     fonts: &mut FontsImpl,
     job: &LayoutJob,
+    bidi: Option<&BidiLevels>,
     section_index: u32,
     section: &LayoutSection,
     out_paragraphs: &mut Vec<Paragraph>,
@@ -137,8 +267,10 @@ fn layout_section(
         leading_space,
         byte_range,
         format,
+        tag: _,
+        placeholder,
     } = section;
-    let font = fonts.font(&format.font_id);
+    let font = fonts.font_with_weight(&format.font_id, format.weight);
     let line_height = section
         .format
         .line_height
@@ -152,39 +284,301 @@ fn layout_section(
 
     paragraph.cursor_x += leading_space;
 
-    let mut last_glyph_id = None;
+    if let Some(placeholder) = placeholder {
+        layout_placeholder(
+            font,
+            job,
+            bidi,
+            section_index,
+            section,
+            placeholder,
+            paragraph,
+        );
+        return;
+    }
 
-    for chr in job.text[byte_range.clone()].chars() {
-        if job.break_on_newline && chr == '\n' {
-            out_paragraphs.push(Paragraph::from_section_index(section_index));
-            paragraph = out_paragraphs.last_mut().unwrap();
-            paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
-        } else {
-            let (font_impl, glyph_info) = font.font_impl_and_glyph_info(chr);
-            if let Some(font_impl) = font_impl {
-                if let Some(last_glyph_id) = last_glyph_id {
-                    paragraph.cursor_x += font_impl.pair_kerning(last_glyph_id, glyph_info.id);
-                    paragraph.cursor_x += extra_letter_spacing;
+    #[cfg(feature = "text_shaping")]
+    {
+        let mut segment_start = byte_range.start;
+        for (byte_index, chr) in job.text[byte_range.clone()].char_indices() {
+            let byte_index = byte_range.start + byte_index;
+            if job.break_on_newline && chr == '\n' {
+                layout_shaped_segment(
+                    font,
+                    &job.text,
+                    segment_start..byte_index,
+                    bidi,
+                    section_index,
+                    line_height,
+                    paragraph,
+                    extra_letter_spacing,
+                );
+                segment_start = byte_index + chr.len_utf8();
+
+                let is_rtl = bidi.is_some_and(|bidi| bidi.is_rtl_paragraph(segment_start));
+                out_paragraphs.push(Paragraph::from_section_index(section_index, is_rtl));
+                paragraph = out_paragraphs.last_mut().unwrap();
+                paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+            }
+        }
+        layout_shaped_segment(
+            font,
+            &job.text,
+            segment_start..byte_range.end,
+            bidi,
+            section_index,
+            line_height,
+            paragraph,
+            extra_letter_spacing,
+        );
+    }
+
+    #[cfg(not(feature = "text_shaping"))]
+    {
+        let mut last_glyph_id = None;
+
+        for (byte_index, chr) in job.text[byte_range.clone()].char_indices() {
+            let byte_index = byte_range.start + byte_index;
+            if job.break_on_newline && chr == '\n' {
+                let is_rtl = bidi.is_some_and(|bidi| bidi.is_rtl_paragraph(byte_index + 1));
+                out_paragraphs.push(Paragraph::from_section_index(section_index, is_rtl));
+                paragraph = out_paragraphs.last_mut().unwrap();
+                paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+            } else {
+                let bidi_level = bidi.map_or(0, |bidi| bidi.level(byte_index));
+
+                let (font_impl, glyph_info) =
+                    font.font_impl_and_glyph_info(mirrored(chr, bidi_level));
+                if let Some(font_impl) = font_impl {
+                    if let Some(last_glyph_id) = last_glyph_id {
+                        paragraph.cursor_x += font_impl.pair_kerning(last_glyph_id, glyph_info.id);
+                        paragraph.cursor_x += extra_letter_spacing;
+                    }
                 }
+
+                paragraph.glyphs.push(Glyph {
+                    chr,
+                    pos: pos2(paragraph.cursor_x, f32::NAN),
+                    advance_width: glyph_info.advance_width,
+                    line_height,
+                    font_impl_height: font_impl.map_or(0.0, |f| f.row_height()),
+                    font_impl_ascent: font_impl.map_or(0.0, |f| f.ascent()),
+                    font_height: font.row_height(),
+                    font_ascent: font.ascent(),
+                    uv_rect: glyph_info.uv_rect,
+                    section_index,
+                    is_cluster_continuation: false,
+                    bidi_level,
+                });
+
+                paragraph.cursor_x += glyph_info.advance_width;
+                paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
+                last_glyph_id = Some(glyph_info.id);
             }
+        }
+    }
+}
 
-            paragraph.glyphs.push(Glyph {
-                chr,
-                pos: pos2(paragraph.cursor_x, f32::NAN),
+/// Add a placeholder to the paragraph, as one invisible glyph.
+///
+/// Any further `char`s of the section become zero-width continuations of it.
+///
+/// Ignores the Y coordinate.
+fn layout_placeholder(
+    font: &Font,
+    job: &LayoutJob,
+    bidi: Option<&BidiLevels>,
+    section_index: u32,
+    section: &LayoutSection,
+    placeholder: &super::Placeholder,
+    paragraph: &mut Paragraph,
+) {
+    // Keep the baseline of the text, and grow the glyph to fit the placeholder:
+    let font_ascent = font.ascent();
+    let font_descent = section
+        .format
+        .line_height
+        .unwrap_or_else(|| font.row_height())
+        - font_ascent;
+    let ascent = font_ascent.max(placeholder.ascent());
+    let line_height = ascent + font_descent.max(placeholder.baseline_offset);
+
+    let min_x = paragraph.cursor_x;
+    let max_x = paragraph.cursor_x + placeholder.size.x;
+
+    for (i, (byte_index, chr)) in job.text[section.byte_range.clone()]
+        .char_indices()
+        .enumerate()
+    {
+        let is_cluster_continuation = 0 < i;
+        paragraph.glyphs.push(Glyph {
+            chr,
+            pos: pos2(
+                if is_cluster_continuation {
+                    max_x
+                } else {
+                    min_x
+                },
+                f32::NAN,
+            ),
+            advance_width: if is_cluster_continuation {
+                0.0
+            } else {
+                placeholder.size.x
+            },
+            line_height,
+            font_impl_height: line_height,
+            font_impl_ascent: ascent,
+            font_height: line_height,
+            font_ascent: ascent,
+            uv_rect: Default::default(),
+            section_index,
+            is_cluster_continuation,
+            bidi_level: bidi.map_or(0, |bidi| bidi.level(section.byte_range.start + byte_index)),
+        });
+    }
+
+    paragraph.cursor_x = font.round_to_pixel(max_x);
+}
+
+/// Shape a piece of a paragraph (with no newlines in it) and add the glyphs to the paragraph.
+///
+/// Ignores the Y coordinate.
+#[cfg(feature = "text_shaping")]
+#[allow(clippy::too_many_arguments)]
+fn layout_shaped_segment(
+    font: &mut Font,
+    text: &str,
+    byte_range: std::ops::Range<usize>,
+    bidi: Option<&BidiLevels>,
+    section_index: u32,
+    line_height: f32,
+    paragraph: &mut Paragraph,
+    extra_letter_spacing: f32,
+) {
+    /// A cluster, ready to be turned into [`Glyph`]s.
+    struct Cluster {
+        byte_range: std::ops::Range<usize>,
+        advance_width: f32,
+        uv_rect: super::font::UvRect,
+        font_impl_height: f32,
+        font_impl_ascent: f32,
+        bidi_level: u8,
+    }
+
+    /// Lay out one char at a time, like we do without shaping.
+    fn add_unshaped_chars(
+        font: &mut Font,
+        text: &str,
+        byte_range: std::ops::Range<usize>,
+        bidi_level: u8,
+        clusters: &mut Vec<Cluster>,
+    ) {
+        for (byte_index, chr) in text[byte_range.clone()].char_indices() {
+            let byte_index = byte_range.start + byte_index;
+            let (font_impl, glyph_info) = font.font_impl_and_glyph_info(mirrored(chr, bidi_level));
+            clusters.push(Cluster {
+                byte_range: byte_index..byte_index + chr.len_utf8(),
                 advance_width: glyph_info.advance_width,
-                line_height,
+                uv_rect: glyph_info.uv_rect,
                 font_impl_height: font_impl.map_or(0.0, |f| f.row_height()),
                 font_impl_ascent: font_impl.map_or(0.0, |f| f.ascent()),
+                bidi_level,
+            });
+        }
+    }
+
+    let mut clusters = vec![];
+
+    for (font_run, font_impl) in font.shaping_runs(&text[byte_range.clone()]) {
+        let font_run = byte_range.start + font_run.start..byte_range.start + font_run.end;
+
+        // Each run is shaped in one direction, so split it where the bidi level changes:
+        let mut runs: Vec<(std::ops::Range<usize>, u8)> = vec![];
+        for (byte_index, chr) in text[font_run.clone()].char_indices() {
+            let byte_index = font_run.start + byte_index;
+            let char_end = byte_index + chr.len_utf8();
+            let level = bidi.map_or(0, |bidi| bidi.level(byte_index));
+            match runs.last_mut() {
+                Some((run, run_level)) if *run_level == level => run.end = char_end,
+                _ => runs.push((byte_index..char_end, level)),
+            }
+        }
+
+        for (run, bidi_level) in runs {
+            let shaped = font_impl
+                .as_ref()
+                .map(|font_impl| font_impl.shape(&text[run.clone()], bidi_level % 2 == 1))
+                .unwrap_or_default();
+
+            if shaped.is_empty() {
+                add_unshaped_chars(font, text, run, bidi_level, &mut clusters);
+                continue;
+            }
+
+            let font_impl = font_impl.as_deref();
+            for cluster in shaped {
+                let cluster_range =
+                    run.start + cluster.byte_range.start..run.start + cluster.byte_range.end;
+                if cluster.missing {
+                    // Fall back to the replacement glyph, one per char:
+                    add_unshaped_chars(font, text, cluster_range, bidi_level, &mut clusters);
+                } else {
+                    clusters.push(Cluster {
+                        byte_range: cluster_range,
+                        advance_width: cluster.advance_width,
+                        uv_rect: cluster.uv_rect,
+                        font_impl_height: font_impl.map_or(0.0, |f| f.row_height()),
+                        font_impl_ascent: font_impl.map_or(0.0, |f| f.ascent()),
+                        bidi_level,
+                    });
+                }
+            }
+        }
+    }
+
+    for (cluster_index, cluster) in clusters.into_iter().enumerate() {
+        if 0 < cluster_index {
+            paragraph.cursor_x += extra_letter_spacing;
+        }
+
+        let cluster_min_x = paragraph.cursor_x;
+        let cluster_max_x = paragraph.cursor_x + cluster.advance_width;
+
+        for (i, chr) in text[cluster.byte_range].chars().enumerate() {
+            let is_cluster_continuation = 0 < i;
+            paragraph.glyphs.push(Glyph {
+                chr,
+                pos: pos2(
+                    if is_cluster_continuation {
+                        cluster_max_x
+                    } else {
+                        cluster_min_x
+                    },
+                    f32::NAN,
+                ),
+                advance_width: if is_cluster_continuation {
+                    0.0
+                } else {
+                    cluster.advance_width
+                },
+                line_height,
+                font_impl_height: cluster.font_impl_height,
+                font_impl_ascent: cluster.font_impl_ascent,
                 font_height: font.row_height(),
                 font_ascent: font.ascent(),
-                uv_rect: glyph_info.uv_rect,
+                uv_rect: if is_cluster_continuation {
+                    Default::default()
+                } else {
+                    cluster.uv_rect
+                },
                 section_index,
+                is_cluster_continuation,
+                bidi_level: cluster.bidi_level,
             });
-
-            paragraph.cursor_x += glyph_info.advance_width;
-            paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
-            last_glyph_id = Some(glyph_info.id);
         }
+
+        paragraph.cursor_x = font.round_to_pixel(cluster_max_x);
     }
 }
 
@@ -221,6 +615,8 @@ fn rows_from_paragraphs(
                     vec2(0.0, paragraph.empty_paragraph_height),
                 ),
                 ends_with_newline: !is_last_paragraph,
+                is_rtl: paragraph.is_rtl,
+                visual_order: Vec::new(),
             });
         } else {
             let paragraph_max_x = paragraph.glyphs.last().unwrap().max_x();
@@ -233,6 +629,8 @@ fn rows_from_paragraphs(
                     visuals: Default::default(),
                     rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                     ends_with_newline: !is_last_paragraph,
+                    is_rtl: paragraph.is_rtl,
+                    visual_order: Vec::new(),
                 });
             } else {
                 line_break(&paragraph, job, &mut rows, elided);
@@ -276,6 +674,8 @@ fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, e
                     visuals: Default::default(),
                     rect: rect_from_x_range(first_row_indentation..=first_row_indentation),
                     ends_with_newline: false,
+                    is_rtl: paragraph.is_rtl,
+                    visual_order: Vec::new(),
                 });
                 row_start_x += first_row_indentation;
                 first_row_indentation = 0.0;
@@ -300,6 +700,8 @@ fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, e
                     visuals: Default::default(),
                     rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                     ends_with_newline: false,
+                    is_rtl: paragraph.is_rtl,
+                    visual_order: Vec::new(),
                 });
 
                 // Start a new row:
@@ -339,6 +741,8 @@ fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, e
                 visuals: Default::default(),
                 rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                 ends_with_newline: false,
+                is_rtl: paragraph.is_rtl,
+                visual_order: Vec::new(),
             });
         }
     }
@@ -375,7 +779,7 @@ fn replace_last_glyph_with_overflow_character(
     if let Some(last_glyph) = row.glyphs.last() {
         let section_index = last_glyph.section_index;
         let section = &job.sections[section_index as usize];
-        let font = fonts.font(&section.format.font_id);
+        let font = fonts.font_with_weight(&section.format.font_id, section.format.weight);
         let line_height = row_height(section, font);
 
         let (_, last_glyph_info) = font.font_impl_and_glyph_info(last_glyph.chr);
@@ -403,11 +807,13 @@ fn replace_last_glyph_with_overflow_character(
             font_ascent: font.ascent(),
             uv_rect: replacement_glyph_info.uv_rect,
             section_index,
+            is_cluster_continuation: false,
+            bidi_level: u8::from(row.is_rtl),
         });
     } else {
         let section_index = row.section_index_at_start;
         let section = &job.sections[section_index as usize];
-        let font = fonts.font(&section.format.font_id);
+        let font = fonts.font_with_weight(&section.format.font_id, section.format.weight);
         let line_height = row_height(section, font);
 
         let x = 0.0; // TODO(emilk): heed paragraph leading_space 😬
@@ -425,6 +831,8 @@ fn replace_last_glyph_with_overflow_character(
             font_ascent: font.ascent(),
             uv_rect: replacement_glyph_info.uv_rect,
             section_index,
+            is_cluster_continuation: false,
+            bidi_level: u8::from(row.is_rtl),
         });
     }
 
@@ -449,7 +857,7 @@ fn replace_last_glyph_with_overflow_character(
 
         let section = &job.sections[last_glyph.section_index as usize];
         let extra_letter_spacing = section.format.extra_letter_spacing;
-        let font = fonts.font(&section.format.font_id);
+        let font = fonts.font_with_weight(&section.format.font_id, section.format.weight);
 
         if let Some(prev_glyph) = prev_glyph {
             let prev_glyph_id = font.font_impl_and_glyph_info(prev_glyph.chr).1.id;
@@ -588,6 +996,94 @@ fn halign_and_justify_row(
     row.rect.max.x = target_max_x;
 }
 
+/// Move the glyphs of a row to their visual positions, as per the Unicode Bidirectional Algorithm.
+///
+/// The glyphs stay in logical order in [`Row::glyphs`]; only their x positions change.
+/// The row keeps the same horizontal extent.
+///
+/// Ignores the Y coordinate.
+fn reorder_row_visually(point_scale: PointScale, row: &mut Row) {
+    // Trailing whitespace goes to the end of the row, as seen in the paragraph direction:
+    let paragraph_level = u8::from(row.is_rtl);
+    for glyph in row.glyphs.iter_mut().rev() {
+        if !glyph.chr.is_whitespace() {
+            break;
+        }
+        glyph.bidi_level = paragraph_level;
+    }
+
+    if row.glyphs.iter().all(|glyph| glyph.bidi_level == 0) {
+        return; // All left-to-right
+    }
+
+    // Each cluster is moved as one unit:
+    let clusters: Vec<usize> = (0..row.glyphs.len())
+        .filter(|&i| !row.glyphs[i].is_cluster_continuation)
+        .collect();
+    let widths: Vec<f32> = clusters
+        .iter()
+        .enumerate()
+        .map(|(i, &glyph_index)| {
+            if let Some(&next_glyph_index) = clusters.get(i + 1) {
+                // Include letter spacing, kerning and justification:
+                row.glyphs[next_glyph_index].pos.x - row.glyphs[glyph_index].pos.x
+            } else {
+                row.glyphs[glyph_index].advance_width
+            }
+        })
+        .collect();
+    let level = |cluster: usize| row.glyphs[clusters[cluster]].bidi_level;
+
+    // Reverse every run at each level, from the highest level down to the lowest odd level:
+    let mut visual_order: Vec<usize> = (0..clusters.len()).collect();
+    let max_level = (0..clusters.len()).map(level).max().unwrap_or(0);
+    let min_odd_level = (0..clusters.len())
+        .map(level)
+        .filter(|level| level % 2 == 1)
+        .min()
+        .unwrap_or(max_level + 1);
+    for reversal_level in (min_odd_level..=max_level).rev() {
+        let mut i = 0;
+        while i < visual_order.len() {
+            if level(visual_order[i]) < reversal_level {
+                i += 1;
+                continue;
+            }
+            let run_start = i;
+            while i < visual_order.len() && reversal_level <= level(visual_order[i]) {
+                i += 1;
+            }
+            visual_order[run_start..i].reverse();
+        }
+    }
+
+    let mut x = row.glyphs[0].pos.x;
+    for cluster in visual_order {
+        let first = clusters[cluster];
+        let end = clusters
+            .get(cluster + 1)
+            .copied()
+            .unwrap_or(row.glyphs.len());
+        let width = widths[cluster];
+        let advance_width = row.glyphs[first].advance_width;
+        let is_rtl = row.glyphs[first].is_rtl();
+
+        // Spacing goes after the glyph in reading order:
+        let min_x = if is_rtl {
+            point_scale.round_to_pixel(x + width - advance_width)
+        } else {
+            x
+        };
+        row.glyphs[first].pos.x = min_x;
+        for glyph in &mut row.glyphs[first + 1..end] {
+            // Continuations are placed at the end of the cluster, in reading order:
+            glyph.pos.x = if is_rtl { min_x } else { min_x + advance_width };
+        }
+
+        x += width;
+    }
+}
+
 /// Calculate the Y positions and tessellate the text.
 fn galley_from_rows(
     point_scale: PointScale,
@@ -640,6 +1136,9 @@ fn galley_from_rows(
     let mut num_indices = 0;
 
     for row in &mut rows {
+        if row.has_rtl() {
+            row.visual_order = row.calc_visual_order();
+        }
         row.visuals = tessellate_row(point_scale, &job, &format_summary, row);
         mesh_bounds = mesh_bounds.union(row.visuals.mesh_bounds);
         num_vertices += row.visuals.mesh.vertices.len();
@@ -687,6 +1186,10 @@ struct FormatSummary {
     any_background: bool,
     any_underline: bool,
     any_strikethrough: bool,
+
+    /// The different kinds of [`super::TextDecoration`] used,
+    /// and the most decorations of that kind in a single [`TextFormat`].
+    decoration_kinds: Vec<((TextDecorationLine, TextDecorationStyle), usize)>,
 }
 
 fn format_summary(job: &LayoutJob) -> FormatSummary {
@@ -695,6 +1198,16 @@ fn format_summary(job: &LayoutJob) -> FormatSummary {
         format_summary.any_background |= section.format.background != Color32::TRANSPARENT;
         format_summary.any_underline |= section.format.underline != Stroke::NONE;
         format_summary.any_strikethrough |= section.format.strikethrough != Stroke::NONE;
+        for decoration in &section.format.decorations {
+            let kind = (decoration.line, decoration.style);
+            let count = (section.format.decorations.iter())
+                .filter(|decoration| (decoration.line, decoration.style) == kind)
+                .count();
+            match (format_summary.decoration_kinds.iter_mut()).find(|(k, _)| *k == kind) {
+                Some((_, max_count)) => *max_count = (*max_count).max(count),
+                None => format_summary.decoration_kinds.push((kind, count)),
+            }
+        }
     }
     format_summary
 }
@@ -720,7 +1233,7 @@ fn tessellate_row(
 
     let glyph_index_start = mesh.indices.len();
     let glyph_vertex_start = mesh.vertices.len();
-    tessellate_glyphs(point_scale, job, row, &mut mesh);
+    let page_vertex_ranges = tessellate_glyphs(point_scale, job, row, &mut mesh);
     let glyph_vertex_end = mesh.vertices.len();
 
     if format_summary.any_underline {
@@ -741,6 +1254,26 @@ fn tessellate_row(
         });
     }
 
+    // A format can have several decorations of the same kind, e.g. two underlines of different colors:
+    for &((line, style), count) in &format_summary.decoration_kinds {
+        for nth in 0..count {
+            add_row_line(point_scale, row, &mut mesh, style, |glyph| {
+                let format = &job.sections[glyph.section_index as usize].format;
+                let Some(decoration) = (format.decorations.iter())
+                    .filter(|decoration| decoration.line == line && decoration.style == style)
+                    .nth(nth)
+                else {
+                    return (Stroke::NONE, f32::NAN);
+                };
+                let mut stroke = decoration.stroke;
+                if stroke.color == Color32::PLACEHOLDER {
+                    stroke.color = format.color;
+                }
+                (stroke, decoration_y(glyph, line, stroke))
+            });
+        }
+    }
+
     let mesh_bounds = mesh.calc_bounds();
 
     RowVisuals {
@@ -748,9 +1281,19 @@ fn tessellate_row(
         mesh_bounds,
         glyph_index_start,
         glyph_vertex_range: glyph_vertex_start..glyph_vertex_end,
+        page_vertex_ranges,
     }
 }
 
+/// Iterate over the glyphs of a row from left to right.
+///
+/// Rows with right-to-left text only yield the first glyph of each cluster.
+fn glyphs_in_visual_order(row: &Row) -> impl Iterator<Item = &Glyph> {
+    let visual_order = (!row.visual_order.is_empty()).then_some(row.visual_order.as_slice());
+    let len = visual_order.map_or(row.glyphs.len(), <[usize]>::len);
+    (0..len).map(move |i| &row.glyphs[visual_order.map_or(i, |order| order[i])])
+}
+
 /// Create background for glyphs that have them.
 /// Creates as few rectangular regions as possible.
 fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
@@ -769,7 +1312,7 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     let mut run_start = None;
     let mut last_rect = Rect::NAN;
 
-    for glyph in &row.glyphs {
+    for glyph in glyphs_in_visual_order(row) {
         let format = &job.sections[glyph.section_index as usize].format;
         let color = format.background;
         let rect = glyph.logical_rect();
@@ -796,10 +1339,20 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     end_run(run_start.take(), last_rect.right());
 }
 
-fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
+/// Returns the vertices of the glyphs on other pages of the font atlas than the first,
+/// see [`RowVisuals::page_vertex_ranges`].
+fn tessellate_glyphs(
+    point_scale: PointScale,
+    job: &LayoutJob,
+    row: &Row,
+    mesh: &mut Mesh,
+) -> Vec<(usize, Range<usize>)> {
+    let mut page_vertex_ranges: Vec<(usize, Range<usize>)> = vec![];
     for glyph in &row.glyphs {
         let uv_rect = glyph.uv_rect;
         if !uv_rect.is_nothing() {
+            let vertex_start = mesh.vertices.len();
+
             let mut left_top = glyph.pos + uv_rect.offset;
             left_top.x = point_scale.round_to_pixel(left_top.x);
             left_top.y = point_scale.round_to_pixel(left_top.y);
@@ -844,8 +1397,19 @@ fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh:
             } else {
                 mesh.add_rect_with_uv(rect, uv, color);
             }
+
+            let page = uv_rect.page as usize;
+            let vertex_end = mesh.vertices.len();
+            match page_vertex_ranges.last_mut() {
+                _ if page == 0 => {}
+                Some((last_page, range)) if *last_page == page && range.end == vertex_start => {
+                    range.end = vertex_end;
+                }
+                _ => page_vertex_ranges.push((page, vertex_start..vertex_end)),
+            }
         }
     }
+    page_vertex_ranges
 }
 
 /// Add a horizontal line over a row of glyphs with a stroke and y decided by a callback.
@@ -854,17 +1418,51 @@ fn add_row_hline(
     row: &Row,
     mesh: &mut Mesh,
     stroke_and_y: impl Fn(&Glyph) -> (Stroke, f32),
+) {
+    add_row_line(
+        point_scale,
+        row,
+        mesh,
+        TextDecorationStyle::Solid,
+        stroke_and_y,
+    );
+}
+
+/// Where to put a decoration line, relative to the baseline of the glyph.
+fn decoration_y(glyph: &Glyph, line: TextDecorationLine, stroke: Stroke) -> f32 {
+    let baseline = glyph.pos.y;
+    let descent = glyph.font_height - glyph.font_ascent;
+    match line {
+        TextDecorationLine::Under => baseline + 0.5 * descent,
+        TextDecorationLine::Over => baseline - glyph.font_ascent + 0.5 * stroke.width,
+        TextDecorationLine::Through => baseline - 0.3 * glyph.font_ascent,
+    }
+}
+
+/// Add horizontal lines of the given style to runs of glyphs with the same stroke and y.
+fn add_row_line(
+    point_scale: PointScale,
+    row: &Row,
+    mesh: &mut Mesh,
+    style: TextDecorationStyle,
+    stroke_and_y: impl Fn(&Glyph) -> (Stroke, f32),
 ) {
     let mut end_line = |start: Option<(Stroke, Pos2)>, stop_x: f32| {
         if let Some((stroke, start)) = start {
-            add_hline(point_scale, [start, pos2(stop_x, start.y)], stroke, mesh);
+            add_styled_hline(
+                point_scale,
+                [start, pos2(stop_x, start.y)],
+                stroke,
+                style,
+                mesh,
+            );
         }
     };
 
     let mut line_start = None;
     let mut last_right_x = f32::NAN;
 
-    for glyph in &row.glyphs {
+    for glyph in glyphs_in_visual_order(row) {
         let (stroke, y) = stroke_and_y(glyph);
 
         if stroke == Stroke::NONE {
@@ -886,6 +1484,62 @@ fn add_row_hline(
     end_line(line_start.take(), last_right_x);
 }
 
+fn add_styled_hline(
+    point_scale: PointScale,
+    [start, stop]: [Pos2; 2],
+    stroke: Stroke,
+    style: TextDecorationStyle,
+    mesh: &mut Mesh,
+) {
+    if stop.x <= start.x {
+        return; // e.g. a decorated zero-width glyph
+    }
+
+    // Make sure thin lines still show up as a pattern:
+    let width = stroke.width.at_least(1.0 / point_scale.pixels_per_point());
+
+    let add_dashes = |mesh: &mut Mesh, dash_length: f32, gap_length: f32| {
+        let mut x = start.x;
+        while x < stop.x {
+            let dash_end = (x + dash_length).at_most(stop.x);
+            add_hline(
+                point_scale,
+                [pos2(x, start.y), pos2(dash_end, start.y)],
+                stroke,
+                mesh,
+            );
+            x += dash_length + gap_length;
+        }
+    };
+
+    match style {
+        TextDecorationStyle::Solid => add_hline(point_scale, [start, stop], stroke, mesh),
+        TextDecorationStyle::Dashed => add_dashes(mesh, 3.0 * width, 2.0 * width),
+        TextDecorationStyle::Dotted => add_dashes(mesh, width, width),
+        TextDecorationStyle::Wavy => {
+            let amplitude = width;
+            let wavelength = 4.0 * amplitude + 2.0 * width;
+            let step = wavelength / 8.0;
+
+            let mut points = vec![];
+            let mut x = start.x;
+            loop {
+                let phase = std::f32::consts::TAU * (x - start.x) / wavelength;
+                points.push(pos2(x, start.y + amplitude * phase.sin()));
+                if stop.x <= x {
+                    break;
+                }
+                x = (x + step).at_most(stop.x);
+            }
+
+            let mut path = crate::tessellator::Path::default();
+            path.add_open_points(&points);
+            let feathering = 1.0 / point_scale.pixels_per_point();
+            path.stroke_open(feathering, &PathStroke::from(stroke), mesh);
+        }
+    }
+}
+
 fn add_hline(point_scale: PointScale, [start, stop]: [Pos2; 2], stroke: Stroke, mesh: &mut Mesh) {
     let antialiased = true;
 
@@ -942,6 +1596,13 @@ struct RowBreakCandidates {
 
 impl RowBreakCandidates {
     fn add(&mut self, index: usize, glyphs: &[Glyph]) {
+        if glyphs
+            .get(1)
+            .is_some_and(|glyph| glyph.is_cluster_continuation)
+        {
+            return; // Never break up a shaped cluster
+        }
+
         let chr = glyphs[0].chr;
         const NON_BREAKING_SPACE: char = '\u{A0}';
         if chr.is_whitespace() && chr != NON_BREAKING_SPACE {
@@ -1054,6 +1715,139 @@ mod tests {
         assert_eq!(galley.rows.len(), 1);
     }
 
+    #[test]
+    fn test_synthetic_bold() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let mut width = |weight| {
+            let text_format = TextFormat {
+                weight,
+                ..Default::default()
+            };
+            let job = LayoutJob::single_section("Hello world".into(), text_format);
+            layout(&mut fonts, job.into()).rect.width()
+        };
+
+        // The default fonts have no bold face, so bold is synthesized:
+        let normal = width(FontWeight::NORMAL);
+        let bold = width(FontWeight::BOLD);
+        assert!(normal < bold, "{normal} vs {bold}");
+        assert_eq!(normal, width(FontWeight::LIGHT), "Can't make it thinner");
+    }
+
+    #[test]
+    fn test_tagged_spans() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let mut job = LayoutJob::default();
+        job.append("Read the ", 0.0, TextFormat::default());
+        job.append_tagged("fine manual", 0.0, TextFormat::default(), 42);
+        job.append(" first.", 0.0, TextFormat::default());
+        job.wrap.max_width = 60.0;
+        let galley = layout(&mut fonts, job.into());
+        assert!(1 < galley.rows.len());
+
+        let rects = galley.tag_rects(42);
+        assert!(2 <= rects.len(), "The link should be wrapped: {rects:?}");
+        for rect in &rects {
+            assert_eq!(galley.tag_at(rect.center().to_vec2()), Some(42));
+        }
+        assert_eq!(galley.tag_at(vec2(1.0, 1.0)), None);
+        assert!(galley.tag_rects(7).is_empty());
+    }
+
+    #[test]
+    fn test_placeholder() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let size = vec2(30.0, 40.0);
+        let mut job = LayoutJob::default();
+        job.append("Look at this: ", 0.0, TextFormat::default());
+        let icon = job.append_placeholder(Placeholder::new(size), TextFormat::default());
+        job.append(" nice, right?", 0.0, TextFormat::default());
+        let galley = layout(&mut fonts, job.into());
+
+        assert_eq!(galley.rows.len(), 1);
+        let rect = galley.placeholder_rect(icon).unwrap();
+        assert_eq!(rect.size(), size);
+        assert!(
+            galley.rect.contains_rect(rect),
+            "{rect:?} {:?}",
+            galley.rect
+        );
+        assert!(40.0 < galley.rect.height());
+        assert_eq!(galley.placeholder_rects(), vec![(icon, rect)]);
+        assert_eq!(galley.placeholder_rect(0), None);
+
+        // It sits on the baseline:
+        let baseline = galley.rows[0].glyphs[0].pos.y;
+        assert_eq!(rect.bottom(), baseline);
+    }
+
+    #[test]
+    fn test_text_decorations() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let mut num_vertices = |decorations: Vec<TextDecoration>| {
+            let text_format = TextFormat {
+                decorations,
+                ..Default::default()
+            };
+            let job = LayoutJob::single_section("Hello world".into(), text_format);
+            let galley = layout(&mut fonts, job.into());
+            let row = &galley.rows[0];
+            (row.visuals.mesh.vertices.len(), row.visuals.mesh_bounds)
+        };
+
+        let stroke = Stroke::new(1.0, Color32::RED);
+        let (plain, plain_bounds) = num_vertices(vec![]);
+        let (solid, _) = num_vertices(vec![TextDecoration::new(
+            TextDecorationLine::Under,
+            TextDecorationStyle::Solid,
+            stroke,
+        )]);
+        let (dotted, _) = num_vertices(vec![TextDecoration::dotted_underline(stroke)]);
+        let (wavy, wavy_bounds) = num_vertices(vec![TextDecoration::wavy_underline(stroke)]);
+        let (over, over_bounds) = num_vertices(vec![TextDecoration::overline(stroke)]);
+
+        assert!(plain < solid);
+        assert!(solid < dotted, "Dotted lines consist of many segments");
+        assert!(solid < wavy);
+        assert!(plain < over);
+        assert!(plain_bounds.bottom() < wavy_bounds.bottom());
+        assert!(over_bounds.top() <= plain_bounds.top());
+
+        // Empty spans paint nothing rather than a degenerate path:
+        for style in [
+            TextDecorationStyle::Solid,
+            TextDecorationStyle::Dashed,
+            TextDecorationStyle::Dotted,
+            TextDecorationStyle::Wavy,
+        ] {
+            let mut mesh = Mesh::default();
+            let point = pos2(10.0, 10.0);
+            add_styled_hline(
+                PointScale::new(1.0),
+                [point, point],
+                stroke,
+                style,
+                &mut mesh,
+            );
+            assert!(mesh.is_empty());
+        }
+
+        // Decorations of the same kind are all painted:
+        let (two_solid, _) = num_vertices(vec![
+            TextDecoration::new(
+                TextDecorationLine::Under,
+                TextDecorationStyle::Solid,
+                stroke,
+            ),
+            TextDecoration::new(
+                TextDecorationLine::Under,
+                TextDecorationStyle::Solid,
+                Stroke::new(1.0, Color32::BLUE),
+            ),
+        ]);
+        assert_eq!(two_solid - solid, solid - plain);
+    }
+
     #[test]
     fn test_truncate_with_newline() {
         // No matter where we wrap, we should be appending the newline character.
@@ -1148,4 +1942,98 @@ mod tests {
         let row = &galley.rows[0];
         assert_eq!(row.rect.max.x, row.glyphs.last().unwrap().max_x());
     }
+
+    #[cfg(feature = "bidi")]
+    #[test]
+    fn test_bidi() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+
+        // Only rows with right-to-left text store their visual order:
+        let layout_job = LayoutJob::single_section("abc".into(), TextFormat::default());
+        let galley = layout(&mut fonts, layout_job.into());
+        let row = &galley.rows[0];
+        assert!(row.visual_order.is_empty());
+        assert_eq!(*row.visual_order(), [0, 1, 2]);
+
+        let layout_job = LayoutJob::single_section("abc אבג".into(), TextFormat::default());
+        let galley = layout(&mut fonts, layout_job.into());
+        let row = &galley.rows[0];
+        assert_eq!(*row.visual_order(), [0, 1, 2, 3, 6, 5, 4]);
+        let x = |i: usize| row.glyphs[i].pos.x;
+        assert!(!row.is_rtl);
+        assert!(x(0) < x(1) && x(1) < x(2) && x(2) < x(3));
+        assert!(x(3) < x(6) && x(6) < x(5) && x(5) < x(4));
+
+        // The base direction is taken from the first strong character:
+        let layout_job = LayoutJob::single_section("אבג abc".into(), TextFormat::default());
+        let galley = layout(&mut fonts, layout_job.into());
+        let row = &galley.rows[0];
+        let x = |i: usize| row.glyphs[i].pos.x;
+        assert!(row.is_rtl);
+        assert!(x(4) < x(5) && x(5) < x(6) && x(6) < x(3));
+        assert!(x(3) < x(2) && x(2) < x(1) && x(1) < x(0));
+
+        // …unless overridden:
+        let mut layout_job = LayoutJob::single_section("abc!".into(), TextFormat::default());
+        layout_job.base_direction = TextDirection::RightToLeft;
+        let galley = layout(&mut fonts, layout_job.into());
+        let row = &galley.rows[0];
+        let x = |i: usize| row.glyphs[i].pos.x;
+        assert!(row.is_rtl);
+        assert!(
+            x(3) < x(0) && x(0) < x(1) && x(1) < x(2),
+            "! goes to the left"
+        );
+    }
+
+    #[cfg(feature = "bidi")]
+    #[test]
+    fn test_bidi_cursor_movement() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let layout_job = LayoutJob::single_section("abc אבג".into(), TextFormat::default());
+        let galley = layout(&mut fonts, layout_job.into());
+
+        // Moving right visits every position on screen from left to right:
+        let mut cursor = galley.begin();
+        let mut xs = vec![galley.pos_from_cursor(&cursor).center().x];
+        for _ in 0..10 {
+            cursor = galley.cursor_right_one_character_visually(&cursor);
+            xs.push(galley.pos_from_cursor(&cursor).center().x);
+        }
+        xs.dedup();
+        assert_eq!(xs.len(), 8);
+        assert!(xs.windows(2).all(|w| w[0] < w[1]), "{xs:?}");
+
+        // …and back again:
+        for _ in 0..10 {
+            cursor = galley.cursor_left_one_character_visually(&cursor);
+        }
+        assert_eq!(cursor.ccursor.index, 0);
+    }
+
+    #[cfg(feature = "text_shaping")]
+    #[test]
+    fn test_shaped_cluster() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let layout_job = LayoutJob::single_section(
+            "ae\u{301}b".into(),
+            TextFormat::simple(FontId::monospace(12.0), Color32::WHITE),
+        );
+        let galley = layout(&mut fonts, layout_job.into());
+
+        let glyphs = &galley.rows[0].glyphs;
+        assert_eq!(glyphs.len(), 4, "Still one glyph per char");
+        assert!(glyphs[2].is_cluster_continuation);
+        assert_eq!(glyphs[2].advance_width, 0.0);
+
+        use crate::text::cursor::CCursor;
+
+        assert!(galley.is_inside_cluster(CCursor::new(2)));
+        let cursor = galley.from_ccursor(CCursor::new(1));
+        let cursor = galley.cursor_right_one_character(&cursor);
+        assert_eq!(
+            cursor.ccursor.index, 3,
+            "Should skip over the combining mark"
+        );
+    }
 }
@@ -1,15 +1,17 @@
 #![allow(clippy::derived_hash_with_manual_eq)] // We need to impl Hash for f32, but we don't implement Eq, which is fine
 #![allow(clippy::wrong_self_convention)] // We use `from_` to indicate conversion direction. It's non-diomatic, but makes sense in this context.
 
+use std::borrow::Cow;
 use std::ops::Range;
 use std::sync::Arc;
 
 use super::{
     cursor::{CCursor, Cursor, PCursor, RCursor},
     font::UvRect,
+    FontWeight,
 };
 use crate::{Color32, FontId, Mesh, Stroke};
-use emath::{pos2, vec2, Align, NumExt, OrderedFloat, Pos2, Rect, Vec2};
+use emath::{pos2, vec2, Align, NumExt, OrderedFloat, Pos2, Rangef, Rect, Vec2};
 
 /// Describes the task of laying out text.
 ///
@@ -78,6 +80,12 @@ pub struct LayoutJob {
     /// Justify text so that word-wrapped rows fill the whole [`TextWrapping::max_width`].
     pub justify: bool,
 
+    /// The base direction of each paragraph, used when laying out
+    /// mixed left-to-right and right-to-left text (e.g. English and Hebrew).
+    ///
+    /// Default: [`TextDirection::Auto`].
+    pub base_direction: TextDirection,
+
     /// Round output sizes using [`emath::GuiRounding`], to avoid rounding errors in layout code.
     pub round_output_to_gui: bool,
 }
@@ -93,6 +101,7 @@ impl Default for LayoutJob {
             break_on_newline: true,
             halign: Align::LEFT,
             justify: false,
+            base_direction: TextDirection::Auto,
             round_output_to_gui: true,
         }
     }
@@ -103,11 +112,11 @@ impl LayoutJob {
     #[inline]
     pub fn simple(text: String, font_id: FontId, color: Color32, wrap_width: f32) -> Self {
         Self {
-            sections: vec![LayoutSection {
-                leading_space: 0.0,
-                byte_range: 0..text.len(),
-                format: TextFormat::simple(font_id, color),
-            }],
+            sections: vec![LayoutSection::new(
+                0.0,
+                0..text.len(),
+                TextFormat::simple(font_id, color),
+            )],
             text,
             wrap: TextWrapping {
                 max_width: wrap_width,
@@ -122,11 +131,11 @@ impl LayoutJob {
     #[inline]
     pub fn simple_singleline(text: String, font_id: FontId, color: Color32) -> Self {
         Self {
-            sections: vec![LayoutSection {
-                leading_space: 0.0,
-                byte_range: 0..text.len(),
-                format: TextFormat::simple(font_id, color),
-            }],
+            sections: vec![LayoutSection::new(
+                0.0,
+                0..text.len(),
+                TextFormat::simple(font_id, color),
+            )],
             text,
             wrap: Default::default(),
             break_on_newline: false,
@@ -137,11 +146,7 @@ impl LayoutJob {
     #[inline]
     pub fn single_section(text: String, format: TextFormat) -> Self {
         Self {
-            sections: vec![LayoutSection {
-                leading_space: 0.0,
-                byte_range: 0..text.len(),
-                format,
-            }],
+            sections: vec![LayoutSection::new(0.0, 0..text.len(), format)],
             text,
             wrap: Default::default(),
             break_on_newline: true,
@@ -159,11 +164,36 @@ impl LayoutJob {
         let start = self.text.len();
         self.text += text;
         let byte_range = start..self.text.len();
-        self.sections.push(LayoutSection {
-            leading_space,
-            byte_range,
-            format,
-        });
+        self.sections
+            .push(LayoutSection::new(leading_space, byte_range, format));
+    }
+
+    /// Like [`Self::append`], but tags the section (see [`LayoutSection::tag`]).
+    ///
+    /// Use [`Galley::tag_at`] to find out which tag is under the mouse.
+    pub fn append_tagged(&mut self, text: &str, leading_space: f32, format: TextFormat, tag: u64) {
+        let start = self.text.len();
+        self.text += text;
+        let byte_range = start..self.text.len();
+        self.sections
+            .push(LayoutSection::new(leading_space, byte_range, format).with_tag(tag));
+    }
+
+    /// Add a [`Placeholder`], which will be wrapped like a glyph.
+    ///
+    /// The `format` decides the baseline alignment, background etc.
+    ///
+    /// Returns the index of the new section, for use with [`Galley::placeholder_rect`].
+    pub fn append_placeholder(&mut self, placeholder: Placeholder, format: TextFormat) -> usize {
+        self.append(Placeholder::TEXT, 0.0, format);
+        let section_index = self.sections.len() - 1;
+        self.sections[section_index].placeholder = Some(placeholder);
+        section_index
+    }
+
+    /// Does any section have a [`LayoutSection::tag`]?
+    pub fn has_tags(&self) -> bool {
+        self.sections.iter().any(|section| section.tag.is_some())
     }
 
     /// The height of the tallest font used in the job.
@@ -201,6 +231,7 @@ impl std::hash::Hash for LayoutJob {
             break_on_newline,
             halign,
             justify,
+            base_direction,
             round_output_to_gui,
         } = self;
 
@@ -211,12 +242,38 @@ impl std::hash::Hash for LayoutJob {
         break_on_newline.hash(state);
         halign.hash(state);
         justify.hash(state);
+        base_direction.hash(state);
         round_output_to_gui.hash(state);
     }
 }
 
 // ----------------------------------------------------------------------------
 
+/// The base direction of a paragraph of text.
+///
+/// Text is laid out using the Unicode Bidirectional Algorithm, so that
+/// right-to-left scripts (Hebrew, Arabic, …) can be mixed with left-to-right text.
+/// The base direction decides the order of the runs of different directions,
+/// and where neutral characters (punctuation, spaces) end up.
+///
+/// This needs the `bidi` feature. Without it, all text is laid out left-to-right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextDirection {
+    /// Use the direction of the first strong character of each paragraph,
+    /// falling back to left-to-right.
+    #[default]
+    Auto,
+
+    /// All paragraphs are left-to-right.
+    LeftToRight,
+
+    /// All paragraphs are right-to-left.
+    RightToLeft,
+}
+
+// ----------------------------------------------------------------------------
+
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LayoutSection {
@@ -227,6 +284,38 @@ pub struct LayoutSection {
     pub byte_range: Range<usize>,
 
     pub format: TextFormat,
+
+    /// An opaque tag given to this span of text, e.g. to identify a link.
+    ///
+    /// It does not affect the layout, but lets you find out which span is
+    /// hovered or clicked with [`Galley::tag_at`] and [`Galley::tag_rects`].
+    pub tag: Option<u64>,
+
+    /// If set, this section is not text, but empty space for an image or widget.
+    ///
+    /// See [`LayoutJob::append_placeholder`].
+    pub placeholder: Option<Placeholder>,
+}
+
+impl LayoutSection {
+    /// A section of text, without a [`Self::tag`] or [`Self::placeholder`].
+    #[inline]
+    pub fn new(leading_space: f32, byte_range: Range<usize>, format: TextFormat) -> Self {
+        Self {
+            leading_space,
+            byte_range,
+            format,
+            tag: None,
+            placeholder: None,
+        }
+    }
+
+    /// Give the section a [`Self::tag`].
+    #[inline]
+    pub fn with_tag(mut self, tag: u64) -> Self {
+        self.tag = Some(tag);
+        self
+    }
 }
 
 impl std::hash::Hash for LayoutSection {
@@ -236,10 +325,71 @@ impl std::hash::Hash for LayoutSection {
             leading_space,
             byte_range,
             format,
+            tag,
+            placeholder,
         } = self;
         OrderedFloat(*leading_space).hash(state);
         byte_range.hash(state);
         format.hash(state);
+        tag.hash(state);
+        placeholder.hash(state);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Space reserved in the text for an inline image or widget.
+///
+/// It is laid out and wrapped like a glyph,
+/// and [`Galley::placeholder_rect`] tells you where it ended up.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Placeholder {
+    /// The size of the image or widget, in points.
+    pub size: Vec2,
+
+    /// How far below the baseline of the text the bottom of the placeholder goes.
+    ///
+    /// At `0.0` it sits on the baseline, like a letter.
+    /// Positive values make it hang down, like the tail of a `g`.
+    pub baseline_offset: f32,
+}
+
+impl Placeholder {
+    /// The text of a placeholder section: the OBJECT REPLACEMENT CHARACTER.
+    pub const TEXT: &'static str = "\u{FFFC}";
+
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            baseline_offset: 0.0,
+        }
+    }
+
+    /// See [`Self::baseline_offset`].
+    #[inline]
+    pub fn baseline_offset(mut self, baseline_offset: f32) -> Self {
+        self.baseline_offset = baseline_offset;
+        self
+    }
+
+    /// How far above the baseline the top of the placeholder is.
+    #[inline]
+    pub fn ascent(&self) -> f32 {
+        self.size.y - self.baseline_offset
+    }
+}
+
+impl std::hash::Hash for Placeholder {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let Self {
+            size,
+            baseline_offset,
+        } = self;
+        OrderedFloat(size.x).hash(state);
+        OrderedFloat(size.y).hash(state);
+        OrderedFloat(*baseline_offset).hash(state);
     }
 }
 
@@ -272,12 +422,23 @@ pub struct TextFormat {
 
     pub background: Color32,
 
+    /// How bold the text is.
+    ///
+    /// See [`crate::text::FontsImpl::font_with_weight`] for how the font is chosen.
+    pub weight: FontWeight,
+
     pub italics: bool,
 
     pub underline: Stroke,
 
     pub strikethrough: Stroke,
 
+    /// Extra lines over, under or through the text, e.g. a wavy underline for errors.
+    ///
+    /// These are painted in addition to [`Self::underline`] and [`Self::strikethrough`],
+    /// and all of them are painted, even if several have the same line and style.
+    pub decorations: Vec<TextDecoration>,
+
     /// If you use a small font and [`Align::TOP`] you
     /// can get the effect of raised text.
     ///
@@ -299,9 +460,11 @@ impl Default for TextFormat {
             line_height: None,
             color: Color32::GRAY,
             background: Color32::TRANSPARENT,
+            weight: FontWeight::NORMAL,
             italics: false,
             underline: Stroke::NONE,
             strikethrough: Stroke::NONE,
+            decorations: Vec::new(),
             valign: Align::BOTTOM,
         }
     }
@@ -316,9 +479,11 @@ impl std::hash::Hash for TextFormat {
             line_height,
             color,
             background,
+            weight,
             italics,
             underline,
             strikethrough,
+            decorations,
             valign,
         } = self;
         font_id.hash(state);
@@ -328,13 +493,94 @@ impl std::hash::Hash for TextFormat {
         }
         color.hash(state);
         background.hash(state);
+        weight.hash(state);
         italics.hash(state);
         underline.hash(state);
         strikethrough.hash(state);
+        decorations.hash(state);
         valign.hash(state);
     }
 }
 
+// ----------------------------------------------------------------------------
+
+/// Where a [`TextDecoration`] goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextDecorationLine {
+    /// Below the baseline.
+    #[default]
+    Under,
+
+    /// Above the text.
+    Over,
+
+    /// Through the middle of lower-case letters.
+    Through,
+}
+
+/// How a [`TextDecoration`] is drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextDecorationStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+
+    /// A squiggly line, as often used for errors.
+    Wavy,
+}
+
+/// A line over, under or through a span of text.
+///
+/// Add it to [`TextFormat::decorations`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TextDecoration {
+    pub line: TextDecorationLine,
+
+    pub style: TextDecorationStyle,
+
+    /// Width and color of the line.
+    ///
+    /// If the color is [`Color32::PLACEHOLDER`], the text color is used.
+    pub stroke: Stroke,
+}
+
+impl TextDecoration {
+    pub fn new(
+        line: TextDecorationLine,
+        style: TextDecorationStyle,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self {
+            line,
+            style,
+            stroke: stroke.into(),
+        }
+    }
+
+    /// A wavy underline, as often used for errors.
+    pub fn wavy_underline(stroke: impl Into<Stroke>) -> Self {
+        Self::new(TextDecorationLine::Under, TextDecorationStyle::Wavy, stroke)
+    }
+
+    /// A dotted underline, as often used for hints.
+    pub fn dotted_underline(stroke: impl Into<Stroke>) -> Self {
+        Self::new(
+            TextDecorationLine::Under,
+            TextDecorationStyle::Dotted,
+            stroke,
+        )
+    }
+
+    /// A solid line above the text.
+    pub fn overline(stroke: impl Into<Stroke>) -> Self {
+        Self::new(TextDecorationLine::Over, TextDecorationStyle::Solid, stroke)
+    }
+}
+
 impl TextFormat {
     #[inline]
     pub fn simple(font_id: FontId, color: Color32) -> Self {
@@ -562,6 +808,18 @@ pub struct Row {
     /// so that text that ends with `\n` has an empty [`Row`] last.
     /// This also implies that the last [`Row`] in a [`Galley`] always has `ends_with_newline == false`.
     pub ends_with_newline: bool,
+
+    /// Is the base direction of the paragraph of this row right-to-left?
+    ///
+    /// See [`LayoutJob::base_direction`].
+    pub is_rtl: bool,
+
+    /// The index of the first glyph of each cluster, in visual (left-to-right) order.
+    ///
+    /// Only filled in for rows with right-to-left text.
+    /// If empty, the clusters are in logical order, see [`Self::visual_order`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub visual_order: Vec<usize>,
 }
 
 /// The tessellated output of a row.
@@ -586,6 +844,11 @@ pub struct RowVisuals {
     ///
     /// The glyph vertices comes after backgrounds (if any), but before any underlines and strikethrough.
     pub glyph_vertex_range: Range<usize>,
+
+    /// The vertices of glyphs on other pages of the [`crate::TextureAtlas`] than the first, with their page.
+    ///
+    /// Their uv coordinates are on that page. Empty if all glyphs are on the first page.
+    pub page_vertex_ranges: Vec<(usize, Range<usize>)>,
 }
 
 impl Default for RowVisuals {
@@ -595,11 +858,16 @@ impl Default for RowVisuals {
             mesh_bounds: Rect::NOTHING,
             glyph_index_start: 0,
             glyph_vertex_range: 0..0,
+            page_vertex_ranges: Vec::new(),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// One `char` of a laid out [`Row`].
+///
+/// Glyphs are made by the text layout. If you construct one yourself,
+/// end the struct literal with `..Default::default()`, so it keeps compiling when fields are added.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Glyph {
     /// The character this glyph represents.
@@ -635,6 +903,23 @@ pub struct Glyph {
 
     /// Index into [`LayoutJob::sections`]. Decides color etc.
     pub section_index: u32,
+
+    /// Is this `char` part of the same shaped cluster as the previous glyph?
+    ///
+    /// With text shaping, a cluster of several `char`s (e.g. a ligature, or a letter
+    /// followed by combining marks) is painted by its first glyph.
+    /// The rest have zero width, are placed at the end of the cluster,
+    /// and the cursor will never be placed before them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_cluster_continuation: bool,
+
+    /// The embedding level from the Unicode Bidirectional Algorithm.
+    ///
+    /// Even levels are left-to-right, odd levels are right-to-left.
+    /// [`Row::glyphs`] are always in logical order,
+    /// but [`Self::pos`] is the visual position.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bidi_level: u8,
 }
 
 impl Glyph {
@@ -648,6 +933,12 @@ impl Glyph {
         self.pos.x + self.advance_width
     }
 
+    /// Is this glyph part of a right-to-left run of text?
+    #[inline]
+    pub fn is_rtl(&self) -> bool {
+        self.bidi_level % 2 == 1
+    }
+
     /// Same y range for all characters with the same [`TextFormat`].
     #[inline]
     pub fn logical_rect(&self) -> Rect {
@@ -690,24 +981,104 @@ impl Row {
         self.rect.height()
     }
 
+    /// Does this row contain any right-to-left text?
+    pub fn has_rtl(&self) -> bool {
+        self.is_rtl || self.glyphs.iter().any(|glyph| glyph.is_rtl())
+    }
+
+    /// The index of the first glyph of each cluster, in visual (left-to-right) order.
+    pub fn visual_order(&self) -> Cow<'_, [usize]> {
+        if self.visual_order.is_empty() {
+            (0..self.glyphs.len())
+                .filter(|&i| !self.glyphs[i].is_cluster_continuation)
+                .collect()
+        } else {
+            Cow::Borrowed(&self.visual_order)
+        }
+    }
+
+    /// Sort the clusters by their position, for [`Self::visual_order`].
+    pub(crate) fn calc_visual_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.glyphs.len())
+            .filter(|&i| !self.glyphs[i].is_cluster_continuation)
+            .collect();
+        order.sort_by(|&a, &b| self.glyphs[a].pos.x.total_cmp(&self.glyphs[b].pos.x));
+        order
+    }
+
+    /// One past the last glyph of the cluster starting at the given glyph.
+    pub fn cluster_end(&self, glyph_index: usize) -> usize {
+        (glyph_index + 1..self.glyphs.len())
+            .find(|&i| !self.glyphs[i].is_cluster_continuation)
+            .unwrap_or(self.glyphs.len())
+    }
+
     /// Closest char at the desired x coordinate.
     /// Returns something in the range `[0, char_count_excluding_newline()]`.
     pub fn char_at(&self, desired_x: f32) -> usize {
-        for (i, glyph) in self.glyphs.iter().enumerate() {
+        let visual_order = self.visual_order();
+        for &i in visual_order.iter() {
+            let glyph = &self.glyphs[i];
             if desired_x < glyph.logical_rect().center().x {
-                return i;
+                // The cursor goes on the left side of this glyph:
+                return if glyph.is_rtl() {
+                    self.cluster_end(i)
+                } else {
+                    i
+                };
             }
         }
-        self.char_count_excluding_newline()
+
+        // Right of the last glyph:
+        match visual_order.last() {
+            Some(&i) if self.glyphs[i].is_rtl() => i,
+            _ => self.char_count_excluding_newline(),
+        }
     }
 
+    /// The x coordinate of the cursor placed before the given char.
     pub fn x_offset(&self, column: usize) -> f32 {
         if let Some(glyph) = self.glyphs.get(column) {
-            glyph.pos.x
+            if glyph.is_rtl() {
+                glyph.max_x()
+            } else {
+                glyph.pos.x
+            }
+        } else if let Some(last) = self.glyphs.last().filter(|glyph| glyph.is_rtl()) {
+            last.pos.x
         } else {
             self.rect.right()
         }
     }
+
+    /// The position of the cursor at the given column, as a boundary index into [`Self::visual_order`].
+    ///
+    /// `0` is the left edge of the row, `visual_order.len()` the right edge.
+    fn visual_boundary(&self, visual_order: &[usize], column: usize) -> usize {
+        let (glyph_index, is_after_glyph) = if column < self.glyphs.len() {
+            let mut cluster_start = column;
+            while 0 < cluster_start && self.glyphs[cluster_start].is_cluster_continuation {
+                cluster_start -= 1;
+            }
+            (cluster_start, false)
+        } else if let Some(last) = visual_order.iter().copied().max() {
+            (last, true)
+        } else {
+            return 0;
+        };
+
+        let visual_index = visual_order
+            .iter()
+            .position(|&i| i == glyph_index)
+            .unwrap_or_default();
+
+        // The cursor is on the right side of a glyph if it is after an LTR glyph, or before an RTL glyph:
+        if is_after_glyph != self.glyphs[glyph_index].is_rtl() {
+            visual_index + 1
+        } else {
+            visual_index
+        }
+    }
 }
 
 impl Galley {
@@ -880,6 +1251,88 @@ impl Galley {
     }
 }
 
+/// ## Tagged spans
+impl Galley {
+    /// The [`LayoutSection::tag`] of the glyph at the given position within the galley, if any.
+    pub fn tag_at(&self, pos: Vec2) -> Option<u64> {
+        let row = self
+            .rows
+            .iter()
+            .find(|row| row.min_y() <= pos.y && pos.y <= row.max_y())?;
+        let glyph = row
+            .glyphs
+            .iter()
+            .find(|glyph| glyph.pos.x <= pos.x && pos.x < glyph.max_x())?;
+        self.job.sections.get(glyph.section_index as usize)?.tag
+    }
+
+    /// The rectangles covered by the glyphs with the given [`LayoutSection::tag`],
+    /// relative to the galley.
+    ///
+    /// There is at least one rectangle per row the span is on,
+    /// and more if it is broken up by bidirectional text.
+    pub fn tag_rects(&self, tag: u64) -> Vec<Rect> {
+        let has_tag = |glyph: &Glyph| {
+            self.job
+                .sections
+                .get(glyph.section_index as usize)
+                .is_some_and(|section| section.tag == Some(tag))
+        };
+
+        let mut rects = vec![];
+        for row in &self.rows {
+            let mut visual_glyphs: Vec<&Glyph> = row.glyphs.iter().collect();
+            visual_glyphs.sort_by(|a, b| a.pos.x.total_cmp(&b.pos.x));
+
+            let mut run: Option<Rangef> = None;
+            for glyph in visual_glyphs {
+                let x_range = Rangef::new(glyph.pos.x, glyph.max_x());
+                if has_tag(glyph) {
+                    run = Some(run.map_or(x_range, |run| Rangef::new(run.min, x_range.max)));
+                } else if let Some(run) = run.take() {
+                    rects.push(Rect::from_x_y_ranges(run, row.rect.y_range()));
+                }
+            }
+            if let Some(run) = run {
+                rects.push(Rect::from_x_y_ranges(run, row.rect.y_range()));
+            }
+        }
+        rects
+    }
+}
+
+/// ## Placeholders
+impl Galley {
+    /// Where the [`Placeholder`] of the given section ended up, relative to the galley.
+    ///
+    /// Returns `None` if the section is not a placeholder,
+    /// or if it was elided (see [`TextWrapping::max_rows`]).
+    pub fn placeholder_rect(&self, section_index: usize) -> Option<Rect> {
+        let placeholder = self.job.sections.get(section_index)?.placeholder?;
+        self.rows
+            .iter()
+            .flat_map(|row| &row.glyphs)
+            .find(|glyph| {
+                glyph.section_index as usize == section_index && !glyph.is_cluster_continuation
+            })
+            .map(|glyph| {
+                let min = pos2(glyph.pos.x, glyph.pos.y - placeholder.ascent());
+                Rect::from_min_size(min, placeholder.size)
+            })
+    }
+
+    /// Where each [`Placeholder`] ended up, relative to the galley, with its section index.
+    pub fn placeholder_rects(&self) -> Vec<(usize, Rect)> {
+        self.job
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, section)| section.placeholder.is_some())
+            .filter_map(|(index, _)| Some((index, self.placeholder_rect(index)?)))
+            .collect()
+    }
+}
+
 /// ## Cursor positions
 impl Galley {
     /// Cursor to the first character.
@@ -1096,24 +1549,146 @@ impl Galley {
 
 /// ## Cursor positions
 impl Galley {
+    /// Is the glyph at this character index a continuation of a shaped cluster?
+    ///
+    /// The cursor should never be placed at such an index.
+    /// See [`Glyph::is_cluster_continuation`].
+    pub fn is_inside_cluster(&self, ccursor: CCursor) -> bool {
+        let mut index = ccursor.index;
+        for row in &self.rows {
+            if index < row.char_count_including_newline() {
+                return row
+                    .glyphs
+                    .get(index)
+                    .is_some_and(|glyph| glyph.is_cluster_continuation);
+            }
+            index -= row.char_count_including_newline();
+        }
+        false
+    }
+
     pub fn cursor_left_one_character(&self, cursor: &Cursor) -> Cursor {
         if cursor.ccursor.index == 0 {
             Default::default()
         } else {
-            let ccursor = CCursor {
+            let mut ccursor = CCursor {
                 index: cursor.ccursor.index,
                 prefer_next_row: true, // default to this when navigating. It is more often useful to put cursor at the begging of a row than at the end.
-            };
-            self.from_ccursor(ccursor - 1)
+            } - 1;
+            while ccursor.index > 0 && self.is_inside_cluster(ccursor) {
+                ccursor -= 1;
+            }
+            self.from_ccursor(ccursor)
         }
     }
 
     pub fn cursor_right_one_character(&self, cursor: &Cursor) -> Cursor {
-        let ccursor = CCursor {
+        let mut ccursor = CCursor {
             index: cursor.ccursor.index,
             prefer_next_row: true, // default to this when navigating. It is more often useful to put cursor at the begging of a row than at the end.
+        } + 1;
+        while self.is_inside_cluster(ccursor) {
+            ccursor += 1;
+        }
+        self.from_ccursor(ccursor)
+    }
+
+    /// Move the cursor one character to the left on screen.
+    ///
+    /// Same as [`Self::cursor_left_one_character`] for left-to-right text,
+    /// but for right-to-left text this moves forwards in the text.
+    pub fn cursor_left_one_character_visually(&self, cursor: &Cursor) -> Cursor {
+        self.cursor_move_visually(cursor, false)
+    }
+
+    /// Move the cursor one character to the right on screen.
+    ///
+    /// Same as [`Self::cursor_right_one_character`] for left-to-right text,
+    /// but for right-to-left text this moves backwards in the text.
+    pub fn cursor_right_one_character_visually(&self, cursor: &Cursor) -> Cursor {
+        self.cursor_move_visually(cursor, true)
+    }
+
+    fn cursor_move_visually(&self, cursor: &Cursor, rightwards: bool) -> Cursor {
+        let Some(row) = self.rows.get(cursor.rcursor.row) else {
+            return *cursor;
         };
-        self.from_ccursor(ccursor + 1)
+
+        if !row.has_rtl() {
+            return if rightwards {
+                self.cursor_right_one_character(cursor)
+            } else {
+                self.cursor_left_one_character(cursor)
+            };
+        }
+
+        let visual_order = row.visual_order();
+        let mut boundary = row.visual_boundary(&visual_order, cursor.rcursor.column);
+
+        loop {
+            // The glyph we are moving across, if we stay on this row:
+            let crossed = if rightwards {
+                visual_order.get(boundary).copied()
+            } else {
+                boundary
+                    .checked_sub(1)
+                    .and_then(|i| visual_order.get(i).copied())
+            };
+            let Some(glyph_index) = crossed else {
+                break;
+            };
+            if rightwards {
+                boundary += 1;
+            } else {
+                boundary -= 1;
+            }
+
+            // We end up on the far side of the glyph, which is its start or end depending on direction:
+            let to_cluster_end = rightwards != row.glyphs[glyph_index].is_rtl();
+            let column = if to_cluster_end {
+                row.cluster_end(glyph_index)
+            } else {
+                glyph_index
+            };
+
+            // Where two runs of different direction meet, the same column can be shown in two places.
+            // Make sure we pick a column that is actually shown at the new position:
+            let column = std::iter::once(column)
+                .chain((0..=row.glyphs.len()).filter(|&c| {
+                    row.glyphs
+                        .get(c)
+                        .map_or(true, |glyph| !glyph.is_cluster_continuation)
+                }))
+                .find(|&c| row.visual_boundary(&visual_order, c) == boundary);
+
+            if let Some(column) = column {
+                return self.from_rcursor(RCursor {
+                    row: cursor.rcursor.row,
+                    column,
+                });
+            }
+        }
+
+        // We are leaving the row:
+        let forwards = rightwards != row.is_rtl;
+        if forwards {
+            if cursor.rcursor.row + 1 < self.rows.len() {
+                self.from_rcursor(RCursor {
+                    row: cursor.rcursor.row + 1,
+                    column: 0,
+                })
+            } else {
+                *cursor
+            }
+        } else if 0 < cursor.rcursor.row {
+            let row = cursor.rcursor.row - 1;
+            self.from_rcursor(RCursor {
+                row,
+                column: self.rows[row].char_count_excluding_newline(),
+            })
+        } else {
+            *cursor
+        }
     }
 
     pub fn cursor_up_one_row(&self, cursor: &Cursor) -> Cursor {
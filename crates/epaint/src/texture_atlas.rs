@@ -1,6 +1,6 @@
 use emath::{remap_clamp, Rect};
 
-use crate::{FontImage, ImageDelta};
+use crate::{FontImage, ImageDelta, TextureId};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct Rectu {
@@ -52,11 +52,25 @@ pub struct PreparedDisc {
     pub uv: Rect,
 }
 
-/// Contains font data in an atlas, where each character occupied a small rectangle.
-///
-/// More characters can be added, possibly expanding the texture.
+/// Where a glyph is in a [`TextureAtlas`]: its page, and its top left corner on that page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct AtlasSlot {
+    pub page: u16,
+    pub min: [u16; 2],
+}
+
+/// A glyph on a page of a [`TextureAtlas`] that can be evicted.
+#[derive(Clone, Copy, Debug)]
+struct AtlasGlyph {
+    size: (usize, usize),
+
+    /// The last pass in which the glyph was used.
+    last_used: u64,
+}
+
+/// One texture of a [`TextureAtlas`].
 #[derive(Clone)]
-pub struct TextureAtlas {
+struct AtlasPage {
     image: FontImage,
 
     /// What part of the image that is dirty
@@ -67,28 +81,210 @@ pub struct TextureAtlas {
 
     row_height: usize,
 
+    /// The glyphs on this page, by their top left corner.
+    ///
+    /// Not kept for the first page, whose glyphs are never evicted.
+    glyphs: ahash::HashMap<[u16; 2], AtlasGlyph>,
+
+    /// The space of evicted glyphs, to be reused.
+    free: Vec<Rectu>,
+
+    /// Where the page is uploaded, if anywhere.
+    texture_id: Option<TextureId>,
+}
+
+impl AtlasPage {
+    fn new(size: [usize; 2]) -> Self {
+        Self {
+            image: FontImage::new(size),
+            dirty: Rectu::EVERYTHING,
+            cursor: (0, 0),
+            row_height: 0,
+            glyphs: Default::default(),
+            free: vec![],
+            texture_id: None,
+        }
+    }
+
+    fn max_height(&self) -> usize {
+        // the initial width is set to the max size
+        self.image.height().max(self.image.width())
+    }
+
+    fn fill_ratio(&self) -> f32 {
+        (self.cursor.1 + self.row_height) as f32 / self.max_height() as f32
+    }
+
+    /// The top left corner of where a `w` x `h` rectangle would go, if it fits.
+    fn next_pos(&self, (w, h): (usize, usize)) -> Option<(usize, usize)> {
+        let (x, y, row_height) = if self.cursor.0 + w > self.image.width() {
+            // New row:
+            (0, self.cursor.1 + self.row_height + PADDING, h)
+        } else {
+            (self.cursor.0, self.cursor.1, self.row_height.max(h))
+        };
+        (y + row_height <= self.max_height()).then_some((x, y))
+    }
+
+    fn allocate_at(&mut self, pos: (usize, usize), (w, h): (usize, usize)) {
+        if pos.1 != self.cursor.1 {
+            self.row_height = 0;
+        }
+        self.cursor = pos;
+        self.row_height = self.row_height.max(h);
+
+        if resize_to_min_height(&mut self.image, self.cursor.1 + self.row_height) {
+            self.dirty = Rectu::EVERYTHING;
+        }
+
+        self.cursor.0 += w + PADDING;
+
+        self.mark_dirty(pos, (w, h));
+    }
+
+    /// Put a `w` x `h` rectangle in the space of evicted glyphs, if it fits anywhere.
+    fn allocate_in_free_space(&mut self, (w, h): (usize, usize)) -> Option<(usize, usize)> {
+        let (index, _) = (self.free.iter().enumerate())
+            .filter(|(_, rect)| w <= rect.max_x - rect.min_x && h <= rect.max_y - rect.min_y)
+            .min_by_key(|(_, rect)| (rect.max_x - rect.min_x) * (rect.max_y - rect.min_y))?;
+        let rect = self.free.swap_remove(index);
+
+        // Keep what is left to the right of it:
+        if rect.min_x + w + PADDING < rect.max_x {
+            self.free.push(Rectu {
+                min_x: rect.min_x + w + PADDING,
+                ..rect
+            });
+        }
+
+        // …and below it:
+        if rect.min_y + h + PADDING < rect.max_y {
+            self.free.push(Rectu {
+                max_x: rect.min_x + w,
+                min_y: rect.min_y + h + PADDING,
+                ..rect
+            });
+        }
+
+        // Clear out the old glyph:
+        let pos = (rect.min_x, rect.min_y);
+        for y in pos.1..pos.1 + h {
+            let row = y * self.image.width();
+            self.image.pixels[row + pos.0..row + pos.0 + w].fill(0.0);
+        }
+
+        self.mark_dirty(pos, (w, h));
+        Some(pos)
+    }
+
+    fn mark_dirty(&mut self, pos: (usize, usize), (w, h): (usize, usize)) {
+        self.dirty.min_x = self.dirty.min_x.min(pos.0);
+        self.dirty.min_y = self.dirty.min_y.min(pos.1);
+        self.dirty.max_x = self.dirty.max_x.max(pos.0 + w);
+        self.dirty.max_y = self.dirty.max_y.max(pos.1 + h);
+    }
+
+    /// Forget the glyph with its top left corner here, and free its space.
+    ///
+    /// Returns the freed area.
+    fn evict(&mut self, min: [u16; 2]) -> usize {
+        let Some(glyph) = self.glyphs.remove(&min) else {
+            return 0;
+        };
+        let (x, y) = (min[0] as usize, min[1] as usize);
+        self.free.push(Rectu {
+            min_x: x,
+            min_y: y,
+            max_x: x + glyph.size.0,
+            max_y: y + glyph.size.1,
+        });
+        glyph.size.0 * glyph.size.1
+    }
+
+    fn free_area(&self) -> usize {
+        (self.free.iter())
+            .map(|rect| (rect.max_x - rect.min_x) * (rect.max_y - rect.min_y))
+            .sum()
+    }
+
+    fn take_delta(&mut self) -> Option<ImageDelta> {
+        let texture_options = TextureAtlas::texture_options();
+
+        let dirty = std::mem::replace(&mut self.dirty, Rectu::NOTHING);
+        if dirty == Rectu::NOTHING {
+            None
+        } else if dirty == Rectu::EVERYTHING {
+            Some(ImageDelta::full(self.image.clone(), texture_options))
+        } else {
+            let pos = [dirty.min_x, dirty.min_y];
+            let size = [dirty.max_x - dirty.min_x, dirty.max_y - dirty.min_y];
+            let region = self.image.region(pos, size);
+            Some(ImageDelta::partial(pos, region, texture_options))
+        }
+    }
+}
+
+/// On some low-precision GPUs (my old iPad) characters get muddled up
+/// if we don't add some empty pixels between the characters.
+/// On modern high-precision GPUs this is not needed.
+const PADDING: usize = 1;
+
+/// Contains font data in an atlas, where each character occupied a small rectangle.
+///
+/// More characters can be added, possibly expanding the texture.
+///
+/// When the first texture (page) is full, more pages are added, up to [`Self::set_max_pages`].
+/// Each page is its own texture.
+/// The first page is uploaded to [`TextureId::default`],
+/// and the integration needs to assign textures to the others (see [`Self::take_page_deltas`]).
+///
+/// Glyphs on the other pages that haven't been used for a while are evicted (see [`Self::set_max_unused_passes`]),
+/// as are the least recently used ones when we are running out of space.
+/// Their space is reused for new glyphs, and pages without any glyphs left are freed.
+#[derive(Clone)]
+pub struct TextureAtlas {
+    /// The first page is always there, the others may have been evicted.
+    pages: Vec<Option<AtlasPage>>,
+
+    /// Where we are allocating new rectangles.
+    current_page: usize,
+
     /// Set when someone requested more space than was available.
     overflowed: bool,
 
     /// pre-rasterized discs of radii `2^i`, where `i` is the index.
     discs: Vec<PrerasterizedDisc>,
+
+    /// Increased by [`Self::begin_pass`].
+    pass_nr: u64,
+
+    max_pages: usize,
+
+    max_unused_passes: u64,
+
+    /// Textures of pages that have been evicted.
+    freed_textures: Vec<TextureId>,
 }
 
 impl TextureAtlas {
     pub fn new(size: [usize; 2]) -> Self {
         assert!(size[0] >= 1024, "Tiny texture atlas");
+        let mut first_page = AtlasPage::new(size);
+        first_page.texture_id = Some(TextureId::default());
         let mut atlas = Self {
-            image: FontImage::new(size),
-            dirty: Rectu::EVERYTHING,
-            cursor: (0, 0),
-            row_height: 0,
+            pages: vec![Some(first_page)],
+            current_page: 0,
             overflowed: false,
             discs: vec![], // will be filled in below
+            pass_nr: 0,
+            max_pages: 8,
+            max_unused_passes: 300,
+            freed_textures: vec![],
         };
 
         // Make the top left pixel fully white for `WHITE_UV`, i.e. painting something with solid color:
-        let (pos, image) = atlas.allocate((1, 1));
-        assert_eq!(pos, (0, 0));
+        let (page, pos, image) = atlas.allocate((1, 1));
+        assert_eq!((page, pos), (0, (0, 0)));
         image[pos] = 1.0;
 
         // Allocate a series of anti-aliased discs used to render small filled circles:
@@ -105,7 +301,7 @@ impl TextureAtlas {
             }
             let hw = (r + 0.5).ceil() as i32;
             let w = (2 * hw + 1) as usize;
-            let ((x, y), image) = atlas.allocate((w, w));
+            let (_, (x, y), image) = atlas.allocate((w, w));
             for dx in -hw..=hw {
                 for dy in -hw..=hw {
                     let distance_to_center = ((dx * dx + dy * dy) as f32).sqrt();
@@ -129,8 +325,97 @@ impl TextureAtlas {
         atlas
     }
 
+    /// Keep the settings and textures of an atlas we are replacing.
+    ///
+    /// The textures of all but the first page of `old` will be freed.
+    pub(crate) fn inherit(&mut self, old: &mut Self) {
+        self.max_pages = old.max_pages;
+        self.max_unused_passes = old.max_unused_passes;
+        self.pass_nr = old.pass_nr;
+        self.freed_textures = std::mem::take(&mut old.freed_textures);
+        self.freed_textures
+            .extend(old.take_page_texture_ids().into_iter().skip(1));
+    }
+
+    fn first_page(&self) -> &AtlasPage {
+        self.pages[0]
+            .as_ref()
+            .expect("The first page is never evicted")
+    }
+
+    /// Size of the first page.
     pub fn size(&self) -> [usize; 2] {
-        self.image.size
+        self.first_page().image.size
+    }
+
+    /// Set how many pages (textures) the atlas may use.
+    ///
+    /// Default: 8.
+    pub fn set_max_pages(&mut self, max_pages: usize) {
+        // Glyphs store their page in a `u16`:
+        self.max_pages = max_pages.clamp(1, u16::MAX as usize + 1);
+    }
+
+    /// Glyphs that haven't been used for this many passes are evicted.
+    ///
+    /// The glyphs will be rasterized again if they are needed later.
+    ///
+    /// Default: 300.
+    pub fn set_max_unused_passes(&mut self, max_unused_passes: u64) {
+        self.max_unused_passes = max_unused_passes;
+    }
+
+    /// How many page slots are in use, including evicted pages.
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The size of the given page, unless it has been evicted.
+    pub fn page_size(&self, page: usize) -> Option<[usize; 2]> {
+        Some(self.pages.get(page)?.as_ref()?.image.size)
+    }
+
+    /// The texture and size of each page, for [`crate::Tessellator::set_font_pages`].
+    ///
+    /// Pages that have been evicted, or are not yet uploaded, are `None`.
+    pub fn texture_pages(&self) -> Vec<Option<(TextureId, [usize; 2])>> {
+        self.pages
+            .iter()
+            .map(|page| {
+                let page = page.as_ref()?;
+                Some((page.texture_id?, page.image.size))
+            })
+            .collect()
+    }
+
+    /// The texture the given page is uploaded to.
+    pub fn page_texture_id(&self, page: usize) -> Option<TextureId> {
+        self.pages.get(page)?.as_ref()?.texture_id
+    }
+
+    /// Tell the atlas where a page was uploaded to.
+    ///
+    /// The first page is assumed to be uploaded to [`TextureId::default`].
+    pub fn set_page_texture_id(&mut self, page: usize, texture_id: TextureId) {
+        if let Some(Some(page)) = self.pages.get_mut(page) {
+            page.texture_id = Some(texture_id);
+        }
+    }
+
+    /// Textures of pages that have been evicted since the last call, and should be freed.
+    pub fn take_freed_textures(&mut self) -> Vec<TextureId> {
+        std::mem::take(&mut self.freed_textures)
+    }
+
+    /// Take the textures of all pages, e.g. before dropping the atlas.
+    ///
+    /// The first one is the texture of the first page.
+    pub fn take_page_texture_ids(&mut self) -> Vec<TextureId> {
+        self.pages
+            .iter_mut()
+            .flatten()
+            .filter_map(|page| page.texture_id.take())
+            .collect()
     }
 
     /// Returns the locations and sizes of pre-rasterized discs (filled circles) in this atlas.
@@ -158,17 +443,13 @@ impl TextureAtlas {
             .collect()
     }
 
-    fn max_height(&self) -> usize {
-        // the initial width is set to the max size
-        self.image.height().max(self.image.width())
-    }
-
     /// When this get high, it might be time to clear and start over!
     pub fn fill_ratio(&self) -> f32 {
         if self.overflowed {
             1.0
         } else {
-            (self.cursor.1 + self.row_height) as f32 / self.max_height() as f32
+            let used_pages = self.pages.iter().flatten().map(AtlasPage::fill_ratio);
+            used_pages.sum::<f32>() / self.max_pages as f32
         }
     }
 
@@ -178,75 +459,223 @@ impl TextureAtlas {
         crate::textures::TextureOptions::LINEAR
     }
 
-    /// The full font atlas image.
+    /// The full image of the first page.
     #[inline]
     pub fn image(&self) -> &FontImage {
-        &self.image
+        &self.first_page().image
+    }
+
+    /// The full image of the given page, unless it has been evicted.
+    pub fn page_image(&self, page: usize) -> Option<&FontImage> {
+        Some(&self.pages.get(page)?.as_ref()?.image)
     }
 
-    /// Call to get the change to the image since last call.
+    /// Call to get the change to the image of the first page since last call.
+    ///
+    /// Use [`Self::take_page_deltas`] to get the changes to all pages.
     pub fn take_delta(&mut self) -> Option<ImageDelta> {
-        let texture_options = Self::texture_options();
+        self.pages[0].as_mut()?.take_delta()
+    }
 
-        let dirty = std::mem::replace(&mut self.dirty, Rectu::NOTHING);
-        if dirty == Rectu::NOTHING {
-            None
-        } else if dirty == Rectu::EVERYTHING {
-            Some(ImageDelta::full(self.image.clone(), texture_options))
-        } else {
-            let pos = [dirty.min_x, dirty.min_y];
-            let size = [dirty.max_x - dirty.min_x, dirty.max_y - dirty.min_y];
-            let region = self.image.region(pos, size);
-            Some(ImageDelta::partial(pos, region, texture_options))
+    /// Call to get the changes to all pages since last call.
+    ///
+    /// A new page will start with a full delta.
+    pub fn take_page_deltas(&mut self) -> Vec<(usize, ImageDelta)> {
+        self.pages
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, page)| Some((index, page.as_mut()?.take_delta()?)))
+            .collect()
+    }
+
+    /// Is there a glyph that can be evicted here?
+    #[cfg(test)]
+    pub(crate) fn has_glyph(&self, slot: AtlasSlot) -> bool {
+        (self.pages.get(slot.page as usize))
+            .and_then(Option::as_ref)
+            .is_some_and(|page| page.glyphs.contains_key(&slot.min))
+    }
+
+    /// Note that these glyphs are still in use.
+    pub(crate) fn mark_used(&mut self, slots: &[AtlasSlot]) {
+        for slot in slots {
+            if let Some(Some(page)) = self.pages.get_mut(slot.page as usize) {
+                if let Some(glyph) = page.glyphs.get_mut(&slot.min) {
+                    glyph.last_used = self.pass_nr;
+                }
+            }
         }
     }
 
-    /// Returns the coordinates of where the rect ended up,
-    /// and invalidates the region.
-    pub fn allocate(&mut self, (w, h): (usize, usize)) -> ((usize, usize), &mut FontImage) {
-        /// On some low-precision GPUs (my old iPad) characters get muddled up
-        /// if we don't add some empty pixels between the characters.
-        /// On modern high-precision GPUs this is not needed.
-        const PADDING: usize = 1;
+    /// Call at the start of each pass, before any glyphs are allocated,
+    /// but after marking the glyphs that are still in use with [`Self::mark_used`].
+    ///
+    /// Evicts glyphs that haven't been used in a while, and returns where they were.
+    /// Any references to these glyphs must be forgotten.
+    pub(crate) fn begin_pass(&mut self) -> Vec<AtlasSlot> {
+        self.pass_nr += 1;
+        let pass_nr = self.pass_nr;
+        let last_pass = pass_nr - 1;
+        let max_unused_passes = self.max_unused_passes;
+
+        // The first page has the discs and the white pixel, and its glyphs are never evicted.
+        let mut evicted = vec![];
+        for (index, page) in self.pages.iter_mut().enumerate().skip(1) {
+            let Some(page) = page else {
+                continue;
+            };
+            let unused: Vec<[u16; 2]> = (page.glyphs.iter())
+                .filter(|(_, glyph)| glyph.last_used + max_unused_passes < pass_nr)
+                .map(|(&min, _)| min)
+                .collect();
+            for min in unused {
+                page.evict(min);
+                evicted.push(AtlasSlot {
+                    page: index as u16,
+                    min,
+                });
+            }
+        }
 
-        assert!(
-            w <= self.image.width(),
-            "Tried to allocate a {} wide glyph in a {} wide texture atlas",
-            w,
-            self.image.width()
-        );
-        if self.cursor.0 + w > self.image.width() {
-            // New row:
-            self.cursor.0 = 0;
-            self.cursor.1 += self.row_height + PADDING;
-            self.row_height = 0;
+        // Make room if we will soon need more space, but have no page to spare:
+        let current_page_almost_full = self.pages[self.current_page]
+            .as_ref()
+            .is_some_and(|page| page.fill_ratio() > 0.8);
+        if current_page_almost_full && self.free_page_slot().is_none() {
+            let page_area = self.size()[0] * self.first_page().max_height();
+            let mut free_area: usize = self.pages.iter().flatten().map(AtlasPage::free_area).sum();
+
+            let mut least_recently_used: Vec<(u64, AtlasSlot)> = (self.pages.iter().enumerate())
+                .skip(1)
+                .filter_map(|(index, page)| Some((index as u16, page.as_ref()?)))
+                .flat_map(|(index, page)| {
+                    (page.glyphs.iter())
+                        .filter(|(_, glyph)| glyph.last_used < last_pass)
+                        .map(move |(&min, glyph)| (glyph.last_used, AtlasSlot { page: index, min }))
+                })
+                .collect();
+            least_recently_used.sort_unstable();
+
+            for (_, slot) in least_recently_used {
+                if page_area / 4 <= free_area {
+                    break;
+                }
+                if let Some(page) = &mut self.pages[slot.page as usize] {
+                    free_area += page.evict(slot.min);
+                    evicted.push(slot);
+                }
+            }
         }
 
-        self.row_height = self.row_height.max(h);
+        // Free the pages without any glyphs left:
+        for (index, page) in self.pages.iter_mut().enumerate().skip(1) {
+            if index != self.current_page && page.as_ref().is_some_and(|p| p.glyphs.is_empty()) {
+                if let Some(page) = page.take() {
+                    self.freed_textures.extend(page.texture_id);
+                }
+            }
+        }
+
+        evicted
+    }
+
+    /// Returns the page and coordinates of where the rect ended up,
+    /// and invalidates the region.
+    pub fn allocate(&mut self, (w, h): (usize, usize)) -> (usize, (usize, usize), &mut FontImage) {
+        let width = self.first_page().image.width();
+        assert!(
+            w <= width,
+            "Tried to allocate a {w} wide glyph in a {width} wide texture atlas"
+        );
 
-        let required_height = self.cursor.1 + self.row_height;
+        let current = self.pages[self.current_page]
+            .as_ref()
+            .and_then(|page| page.next_pos((w, h)));
+
+        if current.is_none() {
+            if let Some((index, pos)) = self.allocate_in_free_space((w, h)) {
+                let page = self.pages[index]
+                    .as_mut()
+                    .expect("Only existing pages have free space");
+                page.glyphs.insert(
+                    [pos.0 as u16, pos.1 as u16],
+                    AtlasGlyph {
+                        size: (w, h),
+                        last_used: self.pass_nr,
+                    },
+                );
+                return (index, pos, &mut page.image);
+            }
+        }
 
-        if required_height > self.max_height() {
+        let pos = if let Some(pos) = current {
+            pos
+        } else if let Some(index) = self.free_page_slot() {
+            // Start a new page:
+            #[cfg(feature = "log")]
+            log::debug!("epaint texture atlas: starting page {index}");
+
+            let initial_height = 64;
+            let page = AtlasPage::new([width, initial_height]);
+            if index == self.pages.len() {
+                self.pages.push(Some(page));
+            } else {
+                self.pages[index] = Some(page);
+            }
+            self.current_page = index;
+            (0, 0)
+        } else {
             // This is a bad place to be - we need to start reusing space :/
 
             #[cfg(feature = "log")]
             log::warn!("epaint texture atlas overflowed!");
 
-            self.cursor = (0, self.image.height() / 3); // Restart a bit down - the top of the atlas has too many important things in it
+            let page = self.pages[self.current_page]
+                .as_mut()
+                .expect("The current page is never evicted");
+            page.cursor = (0, page.image.height() / 3); // Restart a bit down - the top of the atlas has too many important things in it
+            page.row_height = 0;
             self.overflowed = true; // this will signal the user that we need to recreate the texture atlas next frame.
-        } else if resize_to_min_height(&mut self.image, required_height) {
-            self.dirty = Rectu::EVERYTHING;
+            (0, page.cursor.1)
+        };
+
+        let index = self.current_page;
+        let page = self.pages[index]
+            .as_mut()
+            .expect("The current page is never evicted");
+        page.allocate_at(pos, (w, h));
+        if index != 0 {
+            page.glyphs.insert(
+                [pos.0 as u16, pos.1 as u16],
+                AtlasGlyph {
+                    size: (w, h),
+                    last_used: self.pass_nr,
+                },
+            );
         }
 
-        let pos = self.cursor;
-        self.cursor.0 += w + PADDING;
+        (index, pos, &mut page.image)
+    }
 
-        self.dirty.min_x = self.dirty.min_x.min(pos.0);
-        self.dirty.min_y = self.dirty.min_y.min(pos.1);
-        self.dirty.max_x = self.dirty.max_x.max(pos.0 + w);
-        self.dirty.max_y = self.dirty.max_y.max(pos.1 + h);
+    /// Put a `w` x `h` rectangle where an evicted glyph used to be, if it fits anywhere.
+    fn allocate_in_free_space(
+        &mut self,
+        (w, h): (usize, usize),
+    ) -> Option<(usize, (usize, usize))> {
+        self.pages
+            .iter_mut()
+            .enumerate()
+            .find_map(|(index, page)| Some((index, page.as_mut()?.allocate_in_free_space((w, h))?)))
+    }
 
-        (pos, &mut self.image)
+    fn free_page_slot(&self) -> Option<usize> {
+        if let Some(index) = self.pages.iter().position(Option::is_none) {
+            Some(index)
+        } else if self.pages.len() < self.max_pages {
+            Some(self.pages.len())
+        } else {
+            None
+        }
     }
 }
 
@@ -262,3 +691,128 @@ fn resize_to_min_height(image: &mut FontImage, required_height: usize) -> bool {
         false
     }
 }
+
+#[cfg(test)]
+fn allocate_slot(atlas: &mut TextureAtlas, size: (usize, usize)) -> AtlasSlot {
+    let (page, (x, y), _) = atlas.allocate(size);
+    AtlasSlot {
+        page: page as u16,
+        min: [x as u16, y as u16],
+    }
+}
+
+#[test]
+fn unused_glyphs_are_evicted() {
+    let mut atlas = TextureAtlas::new([1024, 64]);
+    atlas.set_max_pages(3);
+    atlas.set_max_unused_passes(2);
+
+    // Fill up the second page, and start the third:
+    let mut glyphs = vec![];
+    loop {
+        let slot = allocate_slot(&mut atlas, (1000, 300));
+        if slot.page == 2 {
+            break;
+        } else if slot.page == 1 {
+            glyphs.push(slot);
+        }
+    }
+    let in_use = allocate_slot(&mut atlas, (1000, 300));
+    assert_eq!(in_use.page, 2);
+    assert_eq!(atlas.num_pages(), 3);
+
+    let deltas = atlas.take_page_deltas();
+    assert_eq!(
+        deltas.iter().map(|(page, _)| *page).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    atlas.set_page_texture_id(1, TextureId::Managed(1));
+    atlas.set_page_texture_id(2, TextureId::Managed(2));
+
+    let mut evicted = vec![];
+    for _ in 0..3 {
+        atlas.mark_used(&[in_use]);
+        evicted.extend(atlas.begin_pass());
+    }
+
+    // Only glyphs that weren't used, and their empty page is freed:
+    evicted.sort();
+    assert_eq!(evicted.len(), 4);
+    assert!(glyphs.iter().all(|glyph| evicted.contains(glyph)));
+    assert!(!evicted.contains(&in_use));
+    assert_eq!(atlas.page_size(1), None);
+    assert_eq!(atlas.take_freed_textures(), vec![TextureId::Managed(1)]);
+    assert_eq!(atlas.texture_pages()[1], None);
+    assert!(atlas.page_size(2).is_some());
+}
+
+#[test]
+fn space_of_least_recently_used_glyphs_is_reused() {
+    let mut atlas = TextureAtlas::new([1024, 64]);
+    atlas.set_max_pages(2);
+
+    // Fill up the second page:
+    let mut glyphs = vec![];
+    while glyphs.len() < 3 {
+        let slot = allocate_slot(&mut atlas, (1000, 300));
+        if slot.page == 1 {
+            glyphs.push(slot);
+        }
+    }
+
+    // Nothing is evicted while all glyphs were used in the last pass:
+    assert!(atlas.begin_pass().is_empty());
+
+    // We are out of space, so the glyph that wasn't used is evicted:
+    atlas.mark_used(&glyphs[..2]);
+    assert_eq!(atlas.begin_pass(), vec![glyphs[2]]);
+
+    // …and its space is reused:
+    let (page, pos, _) = atlas.allocate((900, 250));
+    assert_eq!(page, 1);
+    assert_eq!(pos, (glyphs[2].min[0] as usize, glyphs[2].min[1] as usize));
+}
+
+#[test]
+fn space_below_short_glyphs_is_reused() {
+    let mut atlas = TextureAtlas::new([1024, 64]);
+    atlas.set_max_pages(2);
+
+    // Fill up the second page, leaving no room for another row:
+    let mut glyphs = vec![];
+    while glyphs.len() < 3 {
+        let slot = allocate_slot(&mut atlas, (1000, 340));
+        if slot.page == 1 {
+            glyphs.push(slot);
+        }
+    }
+    assert!(atlas.begin_pass().is_empty());
+    atlas.mark_used(&glyphs[..2]);
+    assert_eq!(atlas.begin_pass(), vec![glyphs[2]]);
+    let (x, y) = (glyphs[2].min[0] as usize, glyphs[2].min[1] as usize);
+
+    // Glyphs of mixed heights share the space of the evicted one, without overlapping:
+    let mut rects: Vec<Rectu> = vec![];
+    for (w, h) in [(500, 100), (300, 40), (400, 60), (300, 50), (300, 100)] {
+        let (page, (min_x, min_y), _) = atlas.allocate((w, h));
+        assert_eq!(page, 1, "A {w}x{h} glyph should fit in the freed space");
+        let rect = Rectu {
+            min_x,
+            min_y,
+            max_x: min_x + w,
+            max_y: min_y + h,
+        };
+        assert!(x <= rect.min_x && rect.max_x <= x + 1000);
+        assert!(y <= rect.min_y && rect.max_y <= y + 340);
+        for other in &rects {
+            let apart = rect.max_x <= other.min_x
+                || other.max_x <= rect.min_x
+                || rect.max_y <= other.min_y
+                || other.max_y <= rect.min_y;
+            assert!(apart, "{rect:?} overlaps {other:?}");
+        }
+        rects.push(rect);
+    }
+    assert_eq!((rects[0].min_x, rects[0].min_y), (x, y));
+    assert_eq!(atlas.num_pages(), 2);
+}